@@ -16,7 +16,7 @@ use syn::{
 /// use nami::{Binding, binding};
 /// use nami_derive::Project;
 ///
-/// #[derive(Project)]
+/// #[derive(Clone, Project)]
 /// struct Person {
 ///     name: String,
 ///     age: u32,
@@ -29,7 +29,7 @@ use syn::{
 ///
 /// let projected = person_binding.project();
 /// projected.name.set("Bob".to_string());
-/// projected.age.set(25);
+/// projected.age.set(25u32);
 ///
 /// let person = person_binding.get();
 /// assert_eq!(person.name, "Bob");
@@ -205,6 +205,399 @@ fn derive_project_unit_struct(input: &DeriveInput) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Derive macro for implementing the `Variants` trait on enums.
+///
+/// This macro generates a fieldless `{Enum}Kind` enum naming the variants, and
+/// an `{Enum}Signal` extension trait for signals of the enum with an
+/// `is_{variant}()` signal for every variant, and an `as_{variant}()` signal of
+/// the fields of every variant that has some.
+///
+/// # Examples
+///
+/// ```rust
+/// use nami::{binding, Binding, Signal, SignalExt};
+///
+/// #[derive(Clone, nami::Variants)]
+/// enum Connection {
+///     Offline,
+///     Online { latency: u32 },
+/// }
+///
+/// let connection: Binding<Connection> = binding(Connection::Offline);
+/// let online = connection.is_online();
+/// let latency = connection.as_online();
+///
+/// connection.set(Connection::Online { latency: 20 });
+/// assert!(online.get());
+/// assert_eq!(latency.get(), Some(20));
+/// assert_eq!(connection.kind().get(), ConnectionKind::Online);
+/// ```
+#[proc_macro_derive(Variants)]
+pub fn derive_variants(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    variants(&input).into()
+}
+
+/// Expands `#[derive(Variants)]`, or a `compile_error!` for anything but an enum
+fn variants(input: &DeriveInput) -> proc_macro2::TokenStream {
+    match &input.data {
+        Data::Enum(data_enum) => derive_variants_enum(input, data_enum),
+        Data::Struct(_) => {
+            syn::Error::new_spanned(input, "Variants derive macro only supports enums")
+                .to_compile_error()
+        }
+        Data::Union(_) => {
+            syn::Error::new_spanned(input, "Variants derive macro only supports enums")
+                .to_compile_error()
+        }
+    }
+}
+
+fn derive_variants_enum(input: &DeriveInput, data: &syn::DataEnum) -> proc_macro2::TokenStream {
+    let enum_name = &input.ident;
+    let vis = &input.vis;
+    let kind_name = syn::Ident::new(&format!("{}Kind", enum_name), enum_name.span());
+    let trait_name = syn::Ident::new(&format!("{}Signal", enum_name), enum_name.span());
+
+    // Add lifetime bounds to generic parameters
+    let mut generics_with_static = input.generics.clone();
+    for param in &mut generics_with_static.params {
+        if let syn::GenericParam::Type(type_param) = param {
+            type_param.bounds.push(syn::parse_quote!('static));
+        }
+    }
+    let (impl_generics_with_static, ty_generics, where_clause) =
+        generics_with_static.split_for_impl();
+    let params = &generics_with_static.params;
+    let param_names: Vec<_> = input
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            syn::GenericParam::Type(type_param) => {
+                let ident = &type_param.ident;
+                quote! { #ident }
+            }
+            syn::GenericParam::Lifetime(lifetime_param) => {
+                let lifetime = &lifetime_param.lifetime;
+                quote! { #lifetime }
+            }
+            syn::GenericParam::Const(const_param) => {
+                let ident = &const_param.ident;
+                quote! { #ident }
+            }
+        })
+        .collect();
+    let where_predicates = where_clause.map(|where_clause| &where_clause.predicates);
+    let enum_type = quote! { #enum_name #ty_generics };
+
+    let kind_variants = data.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let doc = format!("The kind of `{}::{}`.", enum_name, variant_name);
+        quote! {
+            #[doc = #doc]
+            #variant_name
+        }
+    });
+
+    // Match each variant of the enum to its kind, ignoring the fields
+    let kind_arms = data.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        quote! {
+            #enum_name::#variant_name { .. } => #kind_name::#variant_name
+        }
+    });
+
+    let methods = data.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let snake_name = to_snake_case(&variant_name.to_string());
+        let is_name = syn::Ident::new(&format!("is_{}", snake_name), variant_name.span());
+        let is_doc = format!(
+            "Returns a signal of whether the value is `{}::{}`.",
+            enum_name, variant_name
+        );
+        let is_method = quote! {
+            #[doc = #is_doc]
+            fn #is_name(&self) -> nami::zip::Part<Self, bool> {
+                nami::zip::Part::new(self.clone(), |value| {
+                    matches!(value, #enum_name::#variant_name { .. })
+                })
+            }
+        };
+
+        // Bind the fields of the variant in declaration order
+        let (pattern, bindings, types): (_, Vec<syn::Ident>, Vec<&Type>) = match &variant.fields {
+            Fields::Unit => return is_method,
+            Fields::Named(fields) => {
+                let names: Vec<syn::Ident> = fields
+                    .named
+                    .iter()
+                    .filter_map(|field| field.ident.clone())
+                    .collect();
+                (
+                    quote! { #enum_name::#variant_name { #(#names),* } },
+                    names,
+                    fields.named.iter().map(|field| &field.ty).collect(),
+                )
+            }
+            Fields::Unnamed(fields) => {
+                let names: Vec<syn::Ident> = (0..fields.unnamed.len())
+                    .map(|index| syn::Ident::new(&format!("field{}", index), variant_name.span()))
+                    .collect();
+                (
+                    quote! { #enum_name::#variant_name(#(#names),*) },
+                    names,
+                    fields.unnamed.iter().map(|field| &field.ty).collect(),
+                )
+            }
+        };
+        let (payload_type, payload) = if types.len() == 1 {
+            (quote! { #(#types)* }, quote! { #(#bindings)* })
+        } else {
+            (quote! { (#(#types),*) }, quote! { (#(#bindings),*) })
+        };
+        let as_name = syn::Ident::new(&format!("as_{}", snake_name), variant_name.span());
+        let as_doc = format!(
+            "Returns a signal of the fields of `{}::{}`, or `None` for other variants.",
+            enum_name, variant_name
+        );
+        quote! {
+            #is_method
+
+            #[doc = #as_doc]
+            fn #as_name(
+                &self,
+            ) -> nami::map::Map<
+                Self,
+                fn(#enum_type) -> Option<#payload_type>,
+                Option<#payload_type>,
+            > {
+                let project: fn(#enum_type) -> Option<#payload_type> = |value| match value {
+                    #pattern => Some(#payload),
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                };
+                nami::map::Map::new(self.clone(), project)
+            }
+        }
+    });
+
+    let kind_doc = format!("The variants of [`{}`], without their fields.", enum_name);
+    let trait_doc = format!("Signals of the variants of a signal of [`{}`].", enum_name);
+
+    let expanded = quote! {
+        #[doc = #kind_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #kind_name {
+            #(#kind_variants,)*
+        }
+
+        impl #impl_generics_with_static nami::variant::Variants for #enum_type #where_clause {
+            type Kind = #kind_name;
+
+            fn kind(&self) -> Self::Kind {
+                match self {
+                    #(#kind_arms,)*
+                }
+            }
+        }
+
+        #[doc = #trait_doc]
+        #vis trait #trait_name<#params>: nami::Signal<Output = #enum_type> #where_clause {
+            #(#methods)*
+        }
+
+        impl<Source, #params> #trait_name<#(#param_names),*> for Source
+        where
+            Source: nami::Signal<Output = #enum_type>,
+            #where_predicates
+        {
+        }
+    };
+
+    expanded
+}
+
+/// Converts an UpperCamelCase identifier to snake_case
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Input structure for the `graph!` macro
+struct GraphInput {
+    definitions: Vec<(syn::Ident, Option<Type>, Expr)>,
+}
+
+impl Parse for GraphInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut definitions = Vec::new();
+        while !input.is_empty() {
+            let name: syn::Ident = input.parse()?;
+            let ty = if input.peek(Token![:]) {
+                input.parse::<Token![:]>()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+            input.parse::<Token![=]>()?;
+            let expr: Expr = input.parse()?;
+            definitions.push((name, ty, expr));
+            if !input.is_empty() {
+                input.parse::<Token![;]>()?;
+            }
+        }
+
+        Ok(GraphInput { definitions })
+    }
+}
+
+/// Function-like procedural macro describing a reactive graph as a list of definitions.
+///
+/// Each `name = expression;` defines a node, optionally with a type as in
+/// `name: Type = expression;`. An expression mentioning earlier nodes is computed from
+/// their values, while other expressions are used as they are and must be signals.
+/// Only bare names count as mentions, so `Type::name` or `value.name` do not refer
+/// to a node called `name`. The macro returns a struct with a field for every node,
+/// implementing `nami::graph::Graph` to name the nodes and their edges.
+///
+/// # Examples
+///
+/// ```rust
+/// use nami::{binding, graph, Binding, Signal};
+///
+/// let counter = graph! {
+///     count: Binding<i32> = binding(1);
+///     doubled = count * 2;
+/// };
+///
+/// counter.count.set(5);
+/// assert_eq!(counter.doubled.get(), 10);
+/// ```
+#[proc_macro]
+pub fn graph(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as GraphInput);
+
+    let mut names: Vec<&syn::Ident> = Vec::new();
+    let mut statements = Vec::new();
+    let mut edges = Vec::new();
+    for (name, ty, expr) in &input.definitions {
+        if names.contains(&name) {
+            return syn::Error::new_spanned(name, format!("`{}` is defined twice", name))
+                .to_compile_error()
+                .into();
+        }
+
+        // Earlier nodes mentioned by the expression, in definition order
+        let tokens = quote! { #expr };
+        let dependencies: Vec<&syn::Ident> = names
+            .iter()
+            .copied()
+            .filter(|node| mentions(tokens.clone(), node))
+            .collect();
+        for dependency in &dependencies {
+            edges.push((dependency.to_string(), name.to_string()));
+        }
+
+        let value = match dependencies.len() {
+            0 => quote! { #expr },
+            1 => {
+                let dependency = dependencies[0];
+                quote! {
+                    nami::SignalExt::map(#dependency.clone(), |#dependency| #expr)
+                }
+            }
+            2..=8 => quote! {
+                nami::SignalExt::map(
+                    (#(#dependencies.clone()),*),
+                    |(#(#dependencies),*)| #expr
+                )
+            },
+            _ => {
+                return syn::Error::new_spanned(
+                    expr,
+                    "Too many nodes in one definition, maximum 8 supported",
+                )
+                .to_compile_error()
+                .into()
+            }
+        };
+        let ty = ty.as_ref().map(|ty| quote! { : #ty });
+        statements.push(quote! { let #name #ty = #value; });
+        names.push(name);
+    }
+
+    let params: Vec<syn::Ident> = (0..names.len())
+        .map(|index| syn::Ident::new(&format!("N{}", index), proc_macro2::Span::call_site()))
+        .collect();
+    let name_strings = names.iter().map(|name| name.to_string());
+    let (edges_from, edges_to): (Vec<_>, Vec<_>) = edges.into_iter().unzip();
+
+    let expanded = quote! {
+        {
+            #(#statements)*
+
+            struct __NamiGraph<#(#params),*> {
+                #(#names: #params,)*
+            }
+
+            impl<#(#params: nami::Signal),*> nami::graph::Graph for __NamiGraph<#(#params),*> {
+                fn nodes(&self) -> nami::__Vec<nami::graph::Node> {
+                    nami::__Vec::from([
+                        #(nami::graph::Node::new(#name_strings, &self.#names),)*
+                    ])
+                }
+
+                fn edges(&self) -> nami::__Vec<(&'static str, &'static str)> {
+                    nami::__Vec::from([#((#edges_from, #edges_to),)*])
+                }
+            }
+
+            __NamiGraph { #(#names),* }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Returns whether `tokens` mention the variable `name`, ignoring field and method names
+/// and segments of longer paths such as `Type::name` or `name::item`
+fn mentions(tokens: proc_macro2::TokenStream, name: &syn::Ident) -> bool {
+    use proc_macro2::{Spacing, TokenTree};
+
+    let is_punct = |token: Option<&TokenTree>, ch: char, joint: bool| {
+        matches!(token, Some(TokenTree::Punct(punct))
+            if punct.as_char() == ch && (!joint || punct.spacing() == Spacing::Joint))
+    };
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    tokens.iter().enumerate().any(|(i, token)| match token {
+        TokenTree::Ident(ident) => {
+            let previous = |back: usize| i.checked_sub(back).and_then(|j| tokens.get(j));
+            let after_dot = is_punct(previous(1), '.', false);
+            let after_path = is_punct(previous(1), ':', false) && is_punct(previous(2), ':', true);
+            let before_path = is_punct(tokens.get(i + 1), ':', true);
+            ident == name && !after_dot && !after_path && !before_path
+        }
+        TokenTree::Group(group) => mentions(group.stream(), name),
+        _ => false,
+    })
+}
+
 /// Input structure for the `s!` macro
 struct SInput {
     format_str: LitStr,
@@ -528,3 +921,45 @@ fn analyze_format_string(format_str: &str) -> (bool, bool, usize, Vec<String>) {
 
     (has_positional, has_named, positional_count, named_vars)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mentions_count(tokens: proc_macro2::TokenStream) -> bool {
+        mentions(
+            tokens,
+            &syn::Ident::new("count", proc_macro2::Span::call_site()),
+        )
+    }
+
+    #[test]
+    fn test_variants_rejects_non_enums() {
+        let inputs: [DeriveInput; 2] = [
+            syn::parse_quote! { struct Point { x: i32, y: i32 } },
+            syn::parse_quote! { union Bits { int: u32, float: f32 } },
+        ];
+        for input in &inputs {
+            let expanded = variants(input).to_string();
+            assert!(expanded.contains("compile_error !"), "{expanded}");
+            assert!(expanded.contains("only supports enums"), "{expanded}");
+        }
+    }
+
+    #[test]
+    fn test_mentions_bare_names() {
+        assert!(mentions_count(quote! { count * 2 }));
+        assert!(mentions_count(quote! { f(count) }));
+        assert!(mentions_count(quote! { Point { x: count, y: 0 } }));
+        assert!(mentions_count(quote! { [1, count].len() }));
+    }
+
+    #[test]
+    fn test_mentions_ignores_fields_and_paths() {
+        assert!(!mentions_count(quote! { total.count }));
+        assert!(!mentions_count(quote! { items.count() }));
+        assert!(!mentions_count(quote! { Foo::count }));
+        assert!(!mentions_count(quote! { Foo::count(1) }));
+        assert!(!mentions_count(quote! { count::MAX }));
+    }
+}
@@ -0,0 +1,64 @@
+use nami::{binding, Binding, Signal, SignalExt};
+use nami_derive::Variants;
+
+#[derive(Debug, Clone, PartialEq, Variants)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Segment(i32, i32),
+    Rect { width: u32, height: u32 },
+}
+
+#[test]
+fn test_unit_tuple_and_struct_variants() {
+    let shape: Binding<Shape> = binding(Shape::Empty);
+    let empty = shape.is_empty();
+    let circle = shape.as_circle();
+    let segment = shape.as_segment();
+    let rect = shape.as_rect();
+
+    assert!(empty.get());
+    assert_eq!(shape.clone().kind().get(), ShapeKind::Empty);
+    assert_eq!((circle.get(), segment.get(), rect.get()), (None, None, None));
+
+    shape.set(Shape::Circle(1.5));
+    assert!(!empty.get());
+    assert!(shape.is_circle().get());
+    assert_eq!(circle.get(), Some(1.5));
+
+    shape.set(Shape::Segment(-1, 4));
+    assert_eq!(shape.clone().kind().get(), ShapeKind::Segment);
+    assert_eq!(segment.get(), Some((-1, 4)));
+    assert_eq!(circle.get(), None);
+
+    shape.set(Shape::Rect {
+        width: 3,
+        height: 2,
+    });
+    assert!(shape.is_rect().get());
+    assert_eq!(rect.get(), Some((3, 2)));
+    assert_eq!(segment.get(), None);
+}
+
+#[test]
+fn test_variant_signals_notify_only_when_they_change() {
+    let shape: Binding<Shape> = binding(Shape::Empty);
+    let rect = shape.is_rect();
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let _guard = {
+        let seen = seen.clone();
+        rect.watch(move |context| seen.borrow_mut().push(context.value))
+    };
+
+    shape.set(Shape::Circle(1.0));
+    shape.set(Shape::Rect {
+        width: 1,
+        height: 1,
+    });
+    shape.set(Shape::Rect {
+        width: 2,
+        height: 1,
+    });
+    shape.set(Shape::Empty);
+    assert_eq!(*seen.borrow(), [true, false]);
+}
@@ -0,0 +1,184 @@
+//! # Accessibility announcements
+//!
+//! Screen readers read out *announcements*: short messages about changes that
+//! are not otherwise focused, such as "3 results" or "Saved". An
+//! [`Announcer`] collects them from the whole app into one queue, consumed by
+//! the bridge to the platform's accessibility API.
+//!
+//! Components push announcements directly with [`Announcer::announce`], or tie
+//! them to reactive state: [`Announcer::announce_when`] announces a message
+//! each time a condition becomes true, and [`Announcer::announce_changes`]
+//! announces each new text of a signal.
+//!
+//! Announcements have a [`Politeness`], like ARIA live regions: assertive ones
+//! are queued before polite ones. A message equal to one already queued is not
+//! queued again, but raises the politeness of the queued one if needed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, a11y::{Announcer, Politeness}};
+//!
+//! let announcer = Announcer::new();
+//! let saving: Binding<bool> = binding(false);
+//! let _guard = announcer.announce_when(saving.clone(), "Saving", Politeness::Polite);
+//!
+//! saving.set(true);
+//! announcer.announce("Connection lost", Politeness::Assertive);
+//! announcer.announce("Saving", Politeness::Polite);
+//!
+//! let spoken: Vec<String> = announcer.take().into_iter().map(|a| a.message).collect();
+//! assert_eq!(spoken, ["Connection lost", "Saving"]);
+//! assert!(announcer.pending().get().is_empty());
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::{cell::Cell, cmp::Reverse};
+
+use crate::{Computed, Container, CustomBinding, Signal, SignalExt, watcher::Context};
+
+/// How urgently an announcement should be read, like ARIA live regions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Politeness {
+    /// Read when the screen reader is idle.
+    Polite,
+    /// Read as soon as possible, interrupting the current speech.
+    Assertive,
+}
+
+/// A message for screen readers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Announcement {
+    /// The text to read.
+    pub message: String,
+    /// How urgently to read it.
+    pub politeness: Politeness,
+}
+
+/// A queue of announcements for screen readers.
+///
+/// All clones share the same queue. See the [module documentation](self) for
+/// how announcements are queued.
+#[derive(Debug, Clone, Default)]
+pub struct Announcer {
+    queue: Container<Vec<Announcement>>,
+}
+
+impl Announcer {
+    /// Creates an announcer with an empty queue.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues `message` with `politeness`.
+    pub fn announce(&self, message: impl Into<String>, politeness: Politeness) {
+        let message = message.into();
+        let mut queue = self.queue.get();
+        if let Some(queued) = queue.iter_mut().find(|queued| queued.message == message) {
+            if queued.politeness >= politeness {
+                return;
+            }
+            queued.politeness = politeness;
+        } else {
+            queue.push(Announcement {
+                message,
+                politeness,
+            });
+        }
+        // Stable, so announcements of the same politeness stay in order.
+        queue.sort_by_key(|announcement| Reverse(announcement.politeness));
+        self.queue.set(queue);
+    }
+
+    /// Announces `message` each time `condition` becomes true.
+    ///
+    /// Returns a guard that stops announcing when dropped.
+    pub fn announce_when<S>(
+        &self,
+        condition: S,
+        message: impl Into<String>,
+        politeness: Politeness,
+    ) -> S::Guard
+    where
+        S: Signal<Output = bool>,
+    {
+        let announcer = self.clone();
+        let message = message.into();
+        let previous = Cell::new(condition.get());
+        condition.watch(move |context: Context<bool>| {
+            if context.value && !previous.get() {
+                announcer.announce(message.clone(), politeness);
+            }
+            previous.set(context.value);
+        })
+    }
+
+    /// Announces each new text of `text`, skipping empty ones.
+    ///
+    /// Returns a guard that stops announcing when dropped.
+    pub fn announce_changes<S>(&self, text: S, politeness: Politeness) -> S::Guard
+    where
+        S: Signal<Output = String>,
+    {
+        let announcer = self.clone();
+        text.watch(move |context: Context<String>| {
+            if !context.value.is_empty() {
+                announcer.announce(context.value, politeness);
+            }
+        })
+    }
+
+    /// Returns a signal of the queued announcements, most urgent first.
+    #[must_use]
+    pub fn pending(&self) -> Computed<Vec<Announcement>> {
+        self.queue.clone().computed()
+    }
+
+    /// Removes and returns the queued announcements, most urgent first.
+    #[must_use]
+    pub fn take(&self) -> Vec<Announcement> {
+        let queue = self.queue.get();
+        if !queue.is_empty() {
+            self.queue.set(Vec::new());
+        }
+        queue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+    use alloc::{format, string::ToString};
+
+    #[test]
+    fn test_announcer_deduplicates_and_raises_politeness() {
+        let announcer = Announcer::new();
+        let results: Binding<i32> = binding(0);
+        let _guard = announcer.announce_changes(
+            results.clone().map(|n| format!("{n} results")),
+            Politeness::Polite,
+        );
+
+        results.set(3);
+        announcer.announce("Offline", Politeness::Polite);
+        results.set(3);
+        announcer.announce("3 results", Politeness::Assertive);
+
+        assert_eq!(
+            announcer.take(),
+            [
+                Announcement {
+                    message: "3 results".to_string(),
+                    politeness: Politeness::Assertive,
+                },
+                Announcement {
+                    message: "Offline".to_string(),
+                    politeness: Politeness::Polite,
+                },
+            ]
+        );
+        assert!(announcer.take().is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! # Graphs on their own thread
+//!
+//! The signals of this crate cannot cross threads. A [`GraphHandle`] gives
+//! other threads access to a graph anyway, by running it on a thread of its
+//! own and sending it requests through a mailbox:
+//!
+//! - [`read`](GraphHandle::read) runs a closure on the graph and waits for its
+//!   result, such as the value of a binding.
+//! - [`write`](GraphHandle::write) runs a closure on the graph without
+//!   waiting, such as setting a binding.
+//! - [`subscribe`](GraphHandle::subscribe) selects a signal of the graph and
+//!   returns a channel receiving its current value, then every change. Dropping
+//!   the receiver ends the subscription.
+//!
+//! Requests run one at a time, in the order they were sent. The graph is built
+//! on its thread, so it may hold `Rc`-based state, and it is dropped there once
+//! every handle is dropped. Unlike the [`sync`](crate::sync) signals, which
+//! share state behind locks, nothing of the graph is shared: only the
+//! requests and their results cross threads.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt, actor::GraphHandle};
+//!
+//! struct Counter {
+//!     count: Binding<i32>,
+//! }
+//!
+//! let graph = GraphHandle::spawn(|| Counter { count: binding(0) });
+//! let labels = graph
+//!     .subscribe(|counter| counter.count.clone().map(|n| format!("{n} clicks")))
+//!     .unwrap();
+//!
+//! let clicker = graph.clone();
+//! std::thread::spawn(move || clicker.write(|counter| counter.count.set(2)).unwrap())
+//!     .join()
+//!     .unwrap();
+//!
+//! assert_eq!(graph.read(|counter| counter.count.get()).unwrap(), 2);
+//! assert_eq!(labels.recv().unwrap(), "0 clicks");
+//! assert_eq!(labels.recv().unwrap(), "2 clicks");
+//! ```
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, ops::ControlFlow};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::{
+    Signal,
+    watcher::{BoxWatcherGuard, Context, WhileGuard},
+};
+
+/// The subscriptions of a graph, kept on its thread.
+type Subscriptions = Vec<WhileGuard<BoxWatcherGuard>>;
+
+/// A request run on the thread of a graph.
+type Request<G> = Box<dyn FnOnce(&G, &mut Subscriptions) + Send>;
+
+/// An error returned when the thread of a graph has stopped, because building
+/// the graph or running a request panicked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disconnected;
+
+impl fmt::Display for Disconnected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the graph thread has stopped")
+    }
+}
+
+impl core::error::Error for Disconnected {}
+
+/// A handle sending requests to a graph running on its own thread.
+///
+/// All clones send requests to the same graph, which is dropped once every
+/// handle is. See the [module documentation](self) for how it behaves.
+pub struct GraphHandle<G> {
+    sender: Sender<Request<G>>,
+}
+
+impl<G> Clone for GraphHandle<G> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<G> fmt::Debug for GraphHandle<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphHandle").finish_non_exhaustive()
+    }
+}
+
+impl<G: 'static> GraphHandle<G> {
+    /// Spawns a thread running the graph returned by `build`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread cannot be spawned.
+    pub fn spawn(build: impl FnOnce() -> G + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel::<Request<G>>();
+        std::thread::spawn(move || {
+            let graph = build();
+            // Declared after the graph, so subscriptions are dropped first.
+            let mut subscriptions = Subscriptions::new();
+            for request in receiver {
+                request(&graph, &mut subscriptions);
+                subscriptions.retain(|subscription| !subscription.is_stopped());
+            }
+        });
+        Self { sender }
+    }
+
+    /// Runs `f` on the graph and waits for its result.
+    ///
+    /// Must not be called from the thread of the graph, such as from a
+    /// request, as it would wait for itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the thread of the graph has stopped.
+    pub fn read<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&G) -> R + Send + 'static,
+    ) -> Result<R, Disconnected> {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        self.send(move |graph, _| {
+            let _ = sender.send(f(graph));
+        })?;
+        receiver.recv().map_err(|_| Disconnected)
+    }
+
+    /// Runs `f` on the graph, without waiting for it to run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the thread of the graph has stopped.
+    pub fn write(&self, f: impl FnOnce(&G) + Send + 'static) -> Result<(), Disconnected> {
+        self.send(move |graph, _| f(graph))
+    }
+
+    /// Subscribes to the signal of the graph returned by `select`.
+    ///
+    /// The returned receiver gets the current value of the signal, then every
+    /// value it is notified with. Dropping it ends the subscription on the next
+    /// change.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the thread of the graph has stopped.
+    pub fn subscribe<S>(
+        &self,
+        select: impl FnOnce(&G) -> S + Send + 'static,
+    ) -> Result<Receiver<S::Output>, Disconnected>
+    where
+        S: Signal,
+        S::Output: Send,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.send(move |graph, subscriptions| {
+            let signal = select(graph);
+            if sender.send(signal.get()).is_err() {
+                return;
+            }
+            subscriptions.push(WhileGuard::new(
+                |watcher| -> BoxWatcherGuard { Box::new(signal.watch(watcher)) },
+                move |context: Context<S::Output>| {
+                    if sender.send(context.value).is_ok() {
+                        ControlFlow::Continue(())
+                    } else {
+                        ControlFlow::Break(())
+                    }
+                },
+            ));
+        })?;
+        Ok(receiver)
+    }
+
+    fn send(
+        &self,
+        request: impl FnOnce(&G, &mut Subscriptions) + Send + 'static,
+    ) -> Result<(), Disconnected> {
+        self.sender
+            .send(Box::new(request))
+            .map_err(|_| Disconnected)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+
+    #[test]
+    fn test_requests_run_in_order_on_the_graph_thread() {
+        let graph = GraphHandle::spawn(|| -> Binding<i32> { binding(1) });
+        let values = graph.subscribe(Clone::clone).unwrap();
+        let graph_thread = graph.read(|_| std::thread::current().id()).unwrap();
+        assert_ne!(graph_thread, std::thread::current().id());
+
+        graph.write(|count| count.set(2)).unwrap();
+        assert_eq!(values.recv().unwrap(), 1);
+        assert_eq!(values.recv().unwrap(), 2);
+
+        // Dropped subscriptions stop on the next change.
+        drop(values);
+        graph.write(|count| count.set(3)).unwrap();
+        assert_eq!(graph.read(Signal::watcher_count).unwrap(), Some(0));
+    }
+}
@@ -0,0 +1,269 @@
+//! # Animated values
+//!
+//! [`spring`] derives a value that follows a target signal with spring
+//! physics, instead of jumping to it. The value is advanced by a clock signal
+//! of elapsed time, typically updated once per frame, so the animation stays
+//! inside the reactive graph and is deterministic in tests.
+//!
+//! When the target changes while the value is still moving, the spring keeps
+//! its current velocity and bends towards the new target, which feels natural
+//! for interactive UIs such as dragging or flinging. Once the value comes to
+//! rest at the target, [`SpringValue::is_settled`] turns `true`, and clock
+//! ticks no longer notify until the target changes again.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use nami::{binding, Binding, Signal, animation::{Spring, spring}};
+//!
+//! let target: Binding<f64> = binding(0.0);
+//! let clock: Binding<Duration> = binding(Duration::ZERO);
+//! let offset = spring(target.clone(), clock.clone(), Spring::new(170.0, 26.0));
+//! let settled = offset.is_settled();
+//!
+//! target.set(100.0);
+//! clock.set(Duration::from_millis(16));
+//! assert!(offset.get() > 0.0 && offset.get() < 100.0);
+//! assert!(!settled.get());
+//!
+//! for frame in 2..120 {
+//!     clock.set(Duration::from_millis(frame * 16));
+//! }
+//! assert!((offset.get() - 100.0).abs() < 1e-9);
+//! assert!(settled.get());
+//! ```
+
+use alloc::rc::Rc;
+use core::{any::Any, cell::RefCell, fmt, time::Duration};
+
+use crate::{Computed, Container, CustomBinding, NodeId, Signal, SignalExt, watcher::Context};
+
+/// The longest step of the simulation, to keep it stable.
+const STEP: Duration = Duration::from_nanos(1_000_000_000 / 240);
+
+/// The longest time simulated in one clock tick, so a long pause between
+/// ticks does not stall the thread.
+const MAX_ELAPSED: Duration = Duration::from_secs(1);
+
+/// The parameters of a spring, acting on a unit mass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    /// How strongly the value is pulled towards the target.
+    pub stiffness: f64,
+    /// How strongly the velocity is slowed down.
+    pub damping: f64,
+    /// The distance to the target and the speed below which the value comes
+    /// to rest at the target.
+    pub rest_threshold: f64,
+}
+
+impl Spring {
+    /// Creates a spring with `stiffness` and `damping`, resting within a
+    /// thousandth of the target.
+    #[must_use]
+    pub const fn new(stiffness: f64, damping: f64) -> Self {
+        Self {
+            stiffness,
+            damping,
+            rest_threshold: 1e-3,
+        }
+    }
+
+    /// Creates a critically damped spring with `stiffness`, reaching the
+    /// target as fast as possible without overshooting it.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn critically_damped(stiffness: f64) -> Self {
+        Self::new(stiffness, 2.0 * stiffness.sqrt())
+    }
+
+    /// Returns this spring resting within `threshold` of the target.
+    #[must_use]
+    pub const fn with_rest_threshold(mut self, threshold: f64) -> Self {
+        self.rest_threshold = threshold;
+        self
+    }
+}
+
+/// A value following a target with spring physics.
+///
+/// Created by [`spring`]. All clones share the same value.
+#[derive(Clone)]
+pub struct SpringValue {
+    position: Container<f64>,
+    settled: Container<bool>,
+    motion: Rc<RefCell<Motion>>,
+    _guard: Rc<dyn Any>,
+}
+
+impl fmt::Debug for SpringValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpringValue")
+            .field("position", &self.position.get())
+            .field("motion", &self.motion.borrow())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+struct Motion {
+    target: f64,
+    velocity: f64,
+    time: Duration,
+}
+
+/// Returns a value following `target` with `spring` physics, advanced by
+/// `clock`, a signal of the elapsed time.
+///
+/// The value starts at rest at the current target. See the
+/// [module documentation](self) for how it behaves.
+pub fn spring<T, C>(target: T, clock: C, spring: Spring) -> SpringValue
+where
+    T: Signal<Output = f64>,
+    C: Signal<Output = Duration>,
+{
+    let position = Container::new(target.get());
+    let settled = Container::new(true);
+    let motion = Rc::new(RefCell::new(Motion {
+        target: target.get(),
+        velocity: 0.0,
+        time: clock.get(),
+    }));
+
+    let target_guard = {
+        let settled = settled.clone();
+        let motion = motion.clone();
+        target.watch(move |context: Context<f64>| {
+            // The velocity is kept, so the value bends towards the new target.
+            motion.borrow_mut().target = context.value;
+            if settled.get() {
+                settled.set(false);
+            }
+        })
+    };
+    let clock_guard = {
+        let (position, settled, motion) = (position.clone(), settled.clone(), motion.clone());
+        clock.watch(move |context: Context<Duration>| {
+            let next = {
+                let mut motion = motion.borrow_mut();
+                let elapsed = context.value.saturating_sub(motion.time);
+                motion.time = context.value;
+                if settled.get() {
+                    return;
+                }
+                motion.advance(position.get(), elapsed, &spring)
+            };
+            if let Some(next) = next {
+                position.set(next);
+            } else {
+                let target = motion.borrow().target;
+                position.set(target);
+                settled.set(true);
+            }
+        })
+    };
+
+    SpringValue {
+        position,
+        settled,
+        motion,
+        _guard: Rc::new((target_guard, clock_guard)),
+    }
+}
+
+impl Motion {
+    /// Advances `position` by `elapsed`, returning the new position, or `None`
+    /// if it came to rest at the target.
+    fn advance(&mut self, mut position: f64, elapsed: Duration, spring: &Spring) -> Option<f64> {
+        let mut remaining = elapsed.min(MAX_ELAPSED);
+        while !remaining.is_zero() {
+            let step = remaining.min(STEP);
+            remaining -= step;
+            let step = step.as_secs_f64();
+            let acceleration = spring
+                .stiffness
+                .mul_add(self.target - position, -spring.damping * self.velocity);
+            self.velocity = acceleration.mul_add(step, self.velocity);
+            position = self.velocity.mul_add(step, position);
+        }
+        let resting = (self.target - position).abs() < spring.rest_threshold
+            && self.velocity.abs() < spring.rest_threshold;
+        if resting {
+            self.velocity = 0.0;
+            None
+        } else {
+            Some(position)
+        }
+    }
+}
+
+impl SpringValue {
+    /// Returns a signal of whether the value is at rest at the target.
+    #[must_use]
+    pub fn is_settled(&self) -> Computed<bool> {
+        self.settled.clone().computed()
+    }
+
+    /// Returns the current velocity, in units per second.
+    #[must_use]
+    pub fn velocity(&self) -> f64 {
+        self.motion.borrow().velocity
+    }
+}
+
+impl Signal for SpringValue {
+    type Output = f64;
+    type Guard = <Container<f64> as Signal>::Guard;
+
+    fn get(&self) -> Self::Output {
+        self.position.get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.position.watch(watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.position.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.position.watcher_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_spring_carries_velocity_to_a_new_target() {
+        let target: Binding<f64> = binding(0.0);
+        let clock: Binding<Duration> = binding(Duration::ZERO);
+        let value = spring(
+            target.clone(),
+            clock.clone(),
+            Spring::critically_damped(100.0),
+        );
+
+        target.set(10.0);
+        clock.set(Duration::from_millis(100));
+        let velocity = value.velocity();
+        assert!(velocity > 0.0);
+
+        // Reversing the target does not reset the velocity.
+        target.set(0.0);
+        assert!((value.velocity() - velocity).abs() < f64::EPSILON);
+        clock.set(Duration::from_millis(116));
+        assert!(value.velocity() > 0.0 && value.velocity() < velocity);
+
+        for frame in 8..200 {
+            clock.set(Duration::from_millis(frame * 16));
+        }
+        assert!(value.get().abs() < f64::EPSILON);
+        assert!(value.is_settled().get());
+    }
+}
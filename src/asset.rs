@@ -0,0 +1,315 @@
+//! # Derived assets
+//!
+//! Asset pipelines, such as thumbnails or waveform previews, derive an expensive
+//! value asynchronously from a key that follows the UI, like the selected file.
+//! [`AssetCache::derive`] turns a signal of keys into a signal of the asset for
+//! the current key:
+//!
+//! - While the asset for a key is loading, the signal reads as `None`.
+//! - When the key changes, a load still running for the previous key is
+//!   cancelled, so a stale asset never replaces the current one.
+//! - Loaded assets are cached, so returning to a key shows its asset at once.
+//!   The cache is bounded by a byte-size budget: once the assets exceed it, the
+//!   least recently used ones are evicted.
+//!
+//! A cache can be shared by several derived assets, such as every thumbnail of
+//! a grid. [`derived_asset`] is a shorthand for an asset of bytes with a cache
+//! of its own.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use nami::{binding, Binding, Signal, asset::AssetCache};
+//!
+//! async fn render_thumbnail(path: String) -> Vec<u8> {
+//!     // Decode and scale the image...
+//!     # path.into_bytes()
+//! }
+//!
+//! // Keep up to 16 MiB of thumbnails.
+//! let cache = AssetCache::new(16 << 20, |thumbnail: &Vec<u8>| thumbnail.len());
+//! let selected: Binding<String> = binding("cat.png");
+//! let thumbnail = cache.derive(selected.clone(), render_thumbnail);
+//!
+//! assert_eq!(thumbnail.get(), None); // Loading.
+//! ```
+
+use alloc::{collections::BTreeMap, rc::Rc};
+use core::{cell::RefCell, fmt, future::Future};
+
+use executor_core::{DefaultExecutor, LocalExecutor};
+
+use crate::{
+    Container, CustomBinding, Signal,
+    held::{Held, held_signal},
+    watcher::Context,
+};
+
+/// A cache of loaded assets bounded by their total size.
+///
+/// All clones share the same assets.
+pub struct AssetCache<K, V> {
+    inner: Rc<RefCell<CacheInner<K, V>>>,
+    size: Rc<dyn Fn(&V) -> usize>,
+}
+
+struct CacheInner<K, V> {
+    budget: usize,
+    used: usize,
+    tick: u64,
+    entries: BTreeMap<K, Entry<V>>,
+    /// Keys ordered by their last access, oldest first.
+    recency: BTreeMap<u64, K>,
+}
+
+struct Entry<V> {
+    asset: V,
+    size: usize,
+    tick: u64,
+}
+
+impl<K, V> Clone for AssetCache<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            size: self.size.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V> fmt::Debug for AssetCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.borrow();
+        f.debug_struct("AssetCache")
+            .field("budget", &inner.budget)
+            .field("used", &inner.used)
+            .field("keys", &inner.entries.keys())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, V> AssetCache<K, V>
+where
+    K: Ord + Clone + 'static,
+    V: Clone + 'static,
+{
+    /// Creates an empty cache retaining assets up to a total of `budget`, as
+    /// measured by `size`, typically in bytes.
+    pub fn new(budget: usize, size: impl Fn(&V) -> usize + 'static) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(CacheInner {
+                budget,
+                used: 0,
+                tick: 0,
+                entries: BTreeMap::new(),
+                recency: BTreeMap::new(),
+            })),
+            size: Rc::new(size),
+        }
+    }
+
+    /// Returns the cached asset for `key`, marking it as the most recently used.
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut inner = self.inner.borrow_mut();
+        inner.tick += 1;
+        let tick = inner.tick;
+        let entry = inner.entries.get_mut(key)?;
+        let previous = core::mem::replace(&mut entry.tick, tick);
+        let asset = entry.asset.clone();
+        inner.recency.remove(&previous);
+        inner.recency.insert(tick, key.clone());
+        Some(asset)
+    }
+
+    /// Caches `asset` for `key`, evicting the least recently used assets until
+    /// the total size fits the budget.
+    ///
+    /// An asset larger than the whole budget is not cached.
+    pub fn insert(&self, key: K, asset: V) {
+        let size = (self.size)(&asset);
+        let mut inner = self.inner.borrow_mut();
+        inner.remove(&key);
+        if size > inner.budget {
+            return;
+        }
+        inner.tick += 1;
+        let tick = inner.tick;
+        inner.used += size;
+        inner.recency.insert(tick, key.clone());
+        inner.entries.insert(key, Entry { asset, size, tick });
+        while inner.used > inner.budget {
+            let Some((_, oldest)) = inner.recency.first_key_value() else {
+                break;
+            };
+            let oldest = oldest.clone();
+            inner.remove(&oldest);
+        }
+    }
+
+    /// Returns the total size of the cached assets.
+    #[must_use]
+    pub fn used(&self) -> usize {
+        self.inner.borrow().used
+    }
+
+    /// Returns the number of cached assets.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.borrow().entries.len()
+    }
+
+    /// Returns `true` if no assets are cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().entries.is_empty()
+    }
+
+    /// Removes the asset for `key`, so it is loaded again when next needed.
+    pub fn invalidate(&self, key: &K) {
+        self.inner.borrow_mut().remove(key);
+    }
+
+    /// Returns a signal of the asset for the current key of `key`, loading
+    /// missing assets with `load` on the default executor.
+    ///
+    /// See the [module documentation](self) for how it behaves.
+    pub fn derive<S, F, Fut>(&self, key: S, load: F) -> DerivedAsset<V>
+    where
+        S: Signal<Output = K>,
+        F: Fn(K) -> Fut + 'static,
+        Fut: Future<Output = V> + 'static,
+    {
+        self.derive_with_executor(DefaultExecutor, key, load)
+    }
+
+    /// Like [`derive`](Self::derive), but loads assets on `executor`.
+    pub fn derive_with_executor<E, S, F, Fut>(
+        &self,
+        executor: E,
+        key: S,
+        load: F,
+    ) -> DerivedAsset<V>
+    where
+        E: LocalExecutor + 'static,
+        S: Signal<Output = K>,
+        F: Fn(K) -> Fut + 'static,
+        Fut: Future<Output = V> + 'static,
+    {
+        let container = Container::new(None);
+        let loading: RefCell<Option<E::Task<()>>> = RefCell::new(None);
+        let show = {
+            let (cache, container) = (self.clone(), container.clone());
+            Rc::new(move |key: K| {
+                // Cancel the load for the previous key.
+                drop(loading.take());
+                if let Some(asset) = cache.get(&key) {
+                    container.set(Some(asset));
+                    return;
+                }
+                if container.get().is_some() {
+                    container.set(None);
+                }
+                let asset = load(key.clone());
+                let (cache, container) = (cache.clone(), container.clone());
+                let task = executor.spawn(async move {
+                    let asset = asset.await;
+                    cache.insert(key, asset.clone());
+                    container.set(Some(asset));
+                });
+                loading.replace(Some(task));
+            })
+        };
+        show(key.get());
+        let guard = key.watch(move |context: Context<K>| show(context.value));
+        DerivedAsset {
+            held: Held::new(container, guard),
+        }
+    }
+}
+
+impl<K: Ord, V> CacheInner<K, V> {
+    fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.used -= entry.size;
+            self.recency.remove(&entry.tick);
+        }
+    }
+}
+
+/// The default budget of [`derived_asset`], 64 MiB.
+pub const DEFAULT_BUDGET: usize = 64 << 20;
+
+/// Returns a signal of the bytes loaded by `load` for the current key of `key`,
+/// cached up to `budget` bytes.
+///
+/// This is a shorthand for [`AssetCache::derive`] with a cache of its own,
+/// measuring assets by their length.
+pub fn derived_asset<S, V, F, Fut>(key: S, budget: usize, load: F) -> DerivedAsset<V>
+where
+    S: Signal,
+    S::Output: Ord + Clone,
+    V: AsRef<[u8]> + Clone + 'static,
+    F: Fn(S::Output) -> Fut + 'static,
+    Fut: Future<Output = V> + 'static,
+{
+    AssetCache::new(budget, |asset: &V| asset.as_ref().len()).derive(key, load)
+}
+
+/// A signal of an asset derived from a signal of keys, created by
+/// [`AssetCache::derive`].
+///
+/// It reads as `None` while the asset for the current key is loading.
+#[derive(Clone)]
+pub struct DerivedAsset<V: Clone + 'static> {
+    held: Held<Option<V>>,
+}
+
+impl<V: Clone + fmt::Debug + 'static> fmt::Debug for DerivedAsset<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.held.debug(f, "DerivedAsset", "asset")
+    }
+}
+
+held_signal!(DerivedAsset<V> => Option<V>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding, test_executor::Manual};
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn test_derived_asset_caches_within_budget() {
+        let executor = Manual::default();
+        let cache = AssetCache::new(4, |asset: &Vec<u8>| asset.len());
+        let selected: Binding<u8> = binding(1);
+        let loads = Rc::new(RefCell::new(Vec::new()));
+        let asset = {
+            let loads = loads.clone();
+            cache.derive_with_executor(executor.clone(), selected.clone(), move |key: u8| {
+                loads.borrow_mut().push(key);
+                async move { vec![key; 2] }
+            })
+        };
+
+        assert_eq!(asset.get(), None);
+        executor.run();
+        assert_eq!(asset.get(), Some(vec![1, 1]));
+
+        // The load for 2 is cancelled before it runs.
+        selected.set(2);
+        selected.set(3);
+        executor.run();
+        assert_eq!(asset.get(), Some(vec![3, 3]));
+        assert!(cache.get(&2).is_none());
+
+        selected.set(1);
+        assert_eq!(asset.get(), Some(vec![1, 1]));
+        assert_eq!(*loads.borrow(), [1, 2, 3]);
+
+        cache.insert(4, vec![4; 2]);
+        assert_eq!((cache.len(), cache.used()), (2, 4));
+        assert!(cache.get(&3).is_none());
+    }
+}
@@ -0,0 +1,207 @@
+//! # Batched writes
+//!
+//! [`batch`] runs a closure in which writes to bindings change their value
+//! right away, but defer notifying watchers until the closure returns. Each
+//! written binding then notifies its watchers once, with its final value, so
+//! updating several bindings in a row, or one binding several times, does not
+//! run watchers for the intermediate states.
+//!
+//! Deferred notifications run in the order the bindings were first written,
+//! as a single notification: a signal combining several written bindings, such
+//! as a [`zip`](crate::SignalExt::zip) of them, notifies its watchers once, with
+//! all the new values.
+//! Like [`set_many`](crate::binding::set_many), a batch guarantees that no
+//! watcher observes a mix of old and new values, but the writes can be
+//! interleaved with arbitrary code, such as reads and conditionals.
+//!
+//! Batches cover bindings holding their own value and
+//! [`CellBinding`](crate::cell::CellBinding)s. Reactive collections notify
+//! immediately, since their notifications describe each individual change.
+//! Batches only affect the current thread, and can be nested: the outermost
+//! batch notifies when it returns.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::{cell::RefCell, rc::Rc};
+//! use nami::{binding, Binding, Signal, SignalExt, batch::batch};
+//!
+//! let first: Binding<String> = binding("Ada");
+//! let last: Binding<String> = binding("Lovelace");
+//! let full = first.clone().zip(last.clone()).map(|(f, l)| format!("{f} {l}"));
+//!
+//! let seen = Rc::new(RefCell::new(Vec::new()));
+//! let _guard = {
+//!     let seen = seen.clone();
+//!     full.watch(move |context| seen.borrow_mut().push(context.value))
+//! };
+//!
+//! batch(|| {
+//!     first.set("Grace");
+//!     last.set("Hopper");
+//!     first.set("Rear Admiral Grace");
+//! });
+//!
+//! // One notification, which never saw "Grace Lovelace".
+//! assert_eq!(*seen.borrow(), ["Rear Admiral Grace Hopper"]);
+//! ```
+
+use alloc::{boxed::Box, vec::Vec};
+use core::cell::RefCell;
+
+use crate::NodeId;
+
+/// The notifications deferred by the running batches of a thread.
+struct Batch {
+    /// The number of nested batches running.
+    depth: usize,
+    /// The written nodes, with how to notify their watchers, in write order.
+    queue: Vec<(NodeId, Box<dyn FnOnce()>)>,
+}
+
+std::thread_local! {
+    static BATCH: RefCell<Batch> = const {
+        RefCell::new(Batch {
+            depth: 0,
+            queue: Vec::new(),
+        })
+    };
+}
+
+/// Runs `f` in a batch and returns its result.
+///
+/// Writes in `f` notify watchers once `f` returns, once per written binding.
+/// See the [module documentation](self) for the details. If `f` panics, the
+/// deferred notifications are discarded, while the written values are kept.
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    struct Exit;
+
+    impl Drop for Exit {
+        fn drop(&mut self) {
+            let discarded = BATCH.with(|batch| {
+                let mut batch = batch.borrow_mut();
+                batch.depth -= 1;
+                if batch.depth == 0 && std::thread::panicking() {
+                    core::mem::take(&mut batch.queue)
+                } else {
+                    Vec::new()
+                }
+            });
+            // Released outside the borrow, since dropping a notification may
+            // drop the last handle to a binding.
+            drop(discarded);
+        }
+    }
+
+    let outermost = BATCH.with(|batch| {
+        let mut batch = batch.borrow_mut();
+        batch.depth += 1;
+        batch.depth == 1
+    });
+    let result = {
+        let _exit = Exit;
+        f()
+    };
+    if outermost {
+        let queue = BATCH.with(|batch| core::mem::take(&mut batch.borrow_mut().queue));
+        crate::signal::joined(queue.into_iter().map(|(_, notify)| notify));
+    }
+    result
+}
+
+/// Returns `true` if a batch is running on the current thread.
+#[must_use]
+pub fn is_batching() -> bool {
+    BATCH.with(|batch| batch.borrow().depth > 0)
+}
+
+/// Defers notifying the watchers of the node identified by `id` to the end of
+/// the running batch, returning whether a batch is running.
+///
+/// `notify` is only called for the first write, and returns a function
+/// notifying the watchers with the value the node holds when it runs.
+pub(crate) fn defer(id: NodeId, notify: impl FnOnce() -> Box<dyn FnOnce()>) -> bool {
+    BATCH.with(|batch| {
+        let mut batch = batch.borrow_mut();
+        if batch.depth == 0 {
+            return false;
+        }
+        if !batch.queue.iter().any(|(queued, _)| *queued == id) {
+            batch.queue.push((id, notify()));
+        }
+        crate::signal::advance_silent_generation();
+        true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, Signal, SignalExt, binding, cell::CellBinding};
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_batch_notifies_each_binding_once() {
+        let a: Binding<i32> = binding(0);
+        let b = CellBinding::new(0);
+        let notified = Rc::new(RefCell::new(Vec::new()));
+        let _guards = {
+            let (seen_a, seen_b) = (notified.clone(), notified.clone());
+            (
+                a.watch(move |context| seen_a.borrow_mut().push(("a", context.value))),
+                b.watch(move |context| seen_b.borrow_mut().push(("b", context.value))),
+            )
+        };
+
+        let inner_ran = Rc::new(Cell::new(false));
+        let sum = batch(|| {
+            b.set(1);
+            a.set(1);
+            batch(|| {
+                a.set(2);
+                inner_ran.set(true);
+            });
+            b.set(3);
+            assert!(notified.borrow().is_empty());
+            a.get() + b.get()
+        });
+
+        assert_eq!(sum, 5);
+        assert!(inner_ran.get() && !is_batching());
+        assert_eq!(*notified.borrow(), [("b", 3), ("a", 2)]);
+    }
+
+    #[test]
+    fn test_batch_notifies_combined_signals_once() {
+        let first: Binding<&str> = binding("Ada");
+        let last: Binding<&str> = binding("Lovelace");
+        let full = first
+            .clone()
+            .zip(last.clone())
+            .map(|(first, last)| alloc::format!("{first} {last}"));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            full.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        batch(|| {
+            first.set("Grace");
+            last.set("Hopper");
+        });
+        assert_eq!(*seen.borrow(), ["Grace Hopper"]);
+
+        // Later writes are new notifications again.
+        first.set("Admiral Grace");
+        last.set("Murray Hopper");
+        assert_eq!(
+            *seen.borrow(),
+            [
+                "Grace Hopper",
+                "Admiral Grace Hopper",
+                "Admiral Grace Murray Hopper"
+            ]
+        );
+    }
+}
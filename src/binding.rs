@@ -4,23 +4,29 @@
 //! Unlike read-only signals, bindings can be modified and will notify watchers of changes.
 
 use core::{
-    any::{Any, type_name},
+    any::Any,
     cell::RefCell,
     fmt::Debug,
+    hash::{Hash, Hasher},
     marker::PhantomData,
-    ops::{Add, AddAssign, Deref, DerefMut, Not, RangeBounds},
+    ops::{AddAssign, Deref, DerefMut, Not, RangeBounds},
+    str::FromStr,
 };
 
-use alloc::{boxed::Box, rc::Rc, vec::Vec};
+use alloc::{
+    boxed::Box,
+    rc::Rc,
+    string::{String, ToString},
+    vec::Vec,
+};
 use async_channel::{Sender, unbounded};
 use executor_core::{DefaultExecutor, LocalExecutor};
 
 use crate::{
-    Computed, Signal,
+    Computed, NodeId, Signal,
+    held::{Held, held_signal},
     map::Map,
-    utils::add,
     watcher::{BoxWatcherGuard, Context, Metadata, WatcherManager},
-    zip::Zip,
 };
 
 /// The `CustomBinding` trait represents a computable value that can also be set.
@@ -32,13 +38,21 @@ pub trait CustomBinding: Signal {
     ///
     /// This will typically trigger notifications to any watchers.
     fn set(&self, value: Self::Output);
+
+    /// Calls `f` with a reference to the current value, or returns `None` if
+    /// the value cannot be read right now, such as while it is being modified.
+    ///
+    /// By default, `f` is called with the result of [`get`](Signal::get).
+    fn try_peek<R>(&self, f: impl FnOnce(&Self::Output) -> R) -> Option<R> {
+        Some(f(&self.get()))
+    }
 }
 
 /// A `Binding<T>` represents a mutable value of type `T` that can be observed.
 ///
 /// Bindings provide a reactive way to work with values. When a binding's value
 /// changes, it can notify watchers that have registered interest in the value.
-pub struct Binding<T: 'static>(Box<dyn BindingImpl<Output = T>>);
+pub struct Binding<T: 'static>(Rc<dyn BindingImpl<Output = T>>);
 
 /// Internal trait that defines the operations required to implement a binding.
 ///
@@ -48,7 +62,12 @@ trait BindingImpl: crate::signal::ComputedImpl {
     /// Sets a new value
     fn set(&self, value: Self::Output);
 
-    fn cloned_binding(&self) -> Binding<Self::Output>;
+    /// Returns the concrete binding, for downcasting.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Calls `f` with a reference to the value, returning `false` without
+    /// calling it if the value is being modified.
+    fn peek_value(&self, f: &mut dyn FnMut(&Self::Output)) -> bool;
 }
 
 impl<T: CustomBinding + Clone + 'static> BindingImpl for T {
@@ -56,14 +75,29 @@ impl<T: CustomBinding + Clone + 'static> BindingImpl for T {
         <T as CustomBinding>::set(self, value);
     }
 
-    fn cloned_binding(&self) -> Binding<Self::Output> {
-        Binding::custom(self.clone())
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn peek_value(&self, f: &mut dyn FnMut(&Self::Output)) -> bool {
+        <T as CustomBinding>::try_peek(self, f).is_some()
     }
 }
 
-impl<T> Debug for Binding<T> {
+/// Formats the node id, value type, current value and watcher count.
+///
+/// Values of primitive types and strings are shown as usual; other values are
+/// shown as their type name, since `T` is not required to implement `Debug`.
+/// A value being modified, such as from inside [`update`](Binding::update), is
+/// shown as `<borrowed>`.
+impl<T: 'static> Debug for Binding<T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.write_str(type_name::<Self>())
+        self.try_peek(|value| {
+            crate::debug::fmt_node(f, "Binding", self, Some(&crate::debug::Value(value)))
+        })
+        .unwrap_or_else(|| {
+            crate::debug::fmt_node(f, "Binding", self, Some(&format_args!("<borrowed>")))
+        })
     }
 }
 
@@ -115,6 +149,181 @@ pub fn binding<T: 'static + Clone>(value: impl Into<T>) -> Binding<T> {
     Binding::container(value.into())
 }
 
+/// Creates a typed binding backed by the text of `text`, such as a text field.
+///
+/// The typed binding holds the value the text last parsed to, or `T::default()`
+/// if it has not parsed yet, and only notifies its watchers when the text
+/// parses: while it does not, the typed binding is left untouched. Setting the
+/// typed binding writes the value's [`Display`](core::fmt::Display) form back
+/// to `text`.
+///
+/// Also returns a signal of the current parse error, which is `None` while the
+/// text parses.
+///
+/// # Example
+///
+/// ```
+/// use nami::{binding, Binding, Signal, binding::parsed_binding};
+///
+/// let field: Binding<String> = binding("12");
+/// let (quantity, error) = parsed_binding::<u32>(&field);
+/// assert_eq!(quantity.get(), 12);
+///
+/// field.set("12x");
+/// assert_eq!(quantity.get(), 12);
+/// assert!(error.get().is_some());
+///
+/// quantity.set(7u32);
+/// assert_eq!(field.get(), "7");
+/// assert!(error.get().is_none());
+/// ```
+#[must_use]
+pub fn parsed_binding<T>(text: &Binding<String>) -> (Binding<T>, Computed<Option<T::Err>>)
+where
+    T: FromStr + ToString + Default + Clone + 'static,
+    T::Err: 'static,
+{
+    let container = Container::new(text.get().parse::<T>().unwrap_or_default());
+    let guard = {
+        let container = container.clone();
+        text.watch(move |context: Context<String>| {
+            if let Ok(value) = context.value.parse::<T>() {
+                CustomBinding::set(&container, value);
+            }
+        })
+    };
+    let value = Binding::custom(Parsed {
+        held: Held::new(container, guard),
+        text: text.clone(),
+    });
+    let error = Computed::new(Map::new(text.clone(), |text: String| {
+        text.parse::<T>().err()
+    }));
+    (value, error)
+}
+
+/// The typed binding created by [`parsed_binding`].
+#[derive(Clone)]
+struct Parsed<T: Clone + 'static> {
+    held: Held<T>,
+    text: Binding<String>,
+}
+
+held_signal!(Parsed<T> => T);
+
+impl<T: Clone + ToString + 'static> CustomBinding for Parsed<T> {
+    fn set(&self, value: T) {
+        self.text.set(value.to_string());
+    }
+}
+
+/// Creates a writable view of `source` through a pair of conversions.
+///
+/// Reading the view returns `forward` of the value of `source`, and its
+/// watchers are notified whenever `source` changes. Setting the view writes
+/// `backward` of the new value to `source`. `backward` may return an `Option`,
+/// in which case writes it maps to `None` leave `source` unchanged, such as
+/// text that does not parse.
+///
+/// # Example
+///
+/// ```
+/// use nami::{binding, Binding, binding::bimap};
+///
+/// let count: Binding<i32> = binding(3);
+/// let text = bimap(&count, |n: i32| n.to_string(), |text: String| text.parse().ok());
+/// assert_eq!(text.get(), "3");
+///
+/// text.set("42");
+/// assert_eq!(count.get(), 42);
+///
+/// text.set("forty-two");
+/// assert_eq!(count.get(), 42);
+/// ```
+#[must_use]
+pub fn bimap<T, U, R>(
+    source: &Binding<T>,
+    forward: impl Fn(T) -> U + 'static,
+    backward: impl Fn(U) -> R + 'static,
+) -> Binding<U>
+where
+    T: 'static,
+    U: 'static,
+    R: Into<Option<T>>,
+{
+    Binding::mapping(source, forward, move |source, value| {
+        if let Some(value) = backward(value).into() {
+            source.set(value);
+        }
+    })
+}
+
+/// A pending value for a binding, to be set together with others by [`set_many`].
+///
+/// Created by [`Binding::assign`].
+pub struct Assignment(Pending);
+
+/// How an assignment is carried out.
+enum Pending {
+    /// Updates a container silently, returning how to notify its watchers.
+    Stage(Box<dyn FnOnce() -> Option<Publish>>),
+    /// Sets any other binding.
+    Set(Publish),
+}
+
+/// Notifies the watchers of a staged assignment, or sets a binding.
+type Publish = Box<dyn FnOnce()>;
+
+impl Debug for Assignment {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Assignment").finish_non_exhaustive()
+    }
+}
+
+/// Sets several bindings at once, so that no watcher observes a mix of old and
+/// new values.
+///
+/// All bindings holding their own value are updated before any watcher is
+/// notified, then other bindings, such as those created by
+/// [`Binding::mapping`], are set in order. With `std`, the assignments run in
+/// a [`batch`](crate::batch::batch), so every written binding notifies its
+/// watchers once all of them are set, and a signal combining several of them
+/// notifies once.
+///
+/// # Example
+///
+/// ```
+/// use nami::{binding, Binding, Signal, binding::set_many};
+///
+/// let width: Binding<i32> = binding(1);
+/// let height: Binding<i32> = binding(1);
+/// let unit: Binding<String> = binding("px");
+/// let area = nami::utils::add(width.clone(), height.clone());
+///
+/// let _guard = area.watch(|context| assert_eq!(context.value, 7));
+/// set_many([width.assign(3), height.assign(4), unit.assign("em")]);
+/// ```
+pub fn set_many(assignments: impl IntoIterator<Item = Assignment>) {
+    let run = || {
+        let (mut publish, mut set) = (Vec::new(), Vec::new());
+        for Assignment(pending) in assignments {
+            match pending {
+                Pending::Stage(stage) => publish.extend(stage()),
+                Pending::Set(assign) => set.push(assign),
+            }
+        }
+        // Other bindings are set once every container holds its new value, so
+        // their watchers never see an old one.
+        for run in set.into_iter().chain(publish) {
+            run();
+        }
+    };
+    #[cfg(feature = "std")]
+    crate::batch::batch(run);
+    #[cfg(not(feature = "std"))]
+    run();
+}
+
 impl<T> Binding<Vec<T>> {
     /// Adds a value to the end of the vector and notifies watchers.
     ///
@@ -170,22 +379,6 @@ impl<T> Binding<Vec<T>> {
     }
 }
 
-impl<T, C2> Add<C2> for Binding<T>
-where
-    C2: Signal,
-    T: Add<C2::Output> + 'static,
-{
-    type Output = Map<
-        Zip<Self, C2>,
-        fn((T, <C2 as Signal>::Output)) -> <T as Add<<C2 as Signal>::Output>>::Output,
-        <T as Add<<C2 as Signal>::Output>>::Output,
-    >;
-
-    fn add(self, rhs: C2) -> Self::Output {
-        add(self, rhs)
-    }
-}
-
 /// A guard that provides mutable access to a binding's value.
 ///
 /// When dropped, it will update the binding with the modified value.
@@ -232,7 +425,7 @@ impl<T: 'static> Drop for BindingMutGuard<'_, T> {
 impl<T: 'static> Binding<T> {
     /// Creates a binding that uses a custom implementation of the `CustomBinding` trait.
     pub fn custom(custom: impl CustomBinding<Output = T> + Clone + 'static) -> Self {
-        Self(Box::new(custom))
+        Self(Rc::new(custom))
     }
 
     /// Gets the current value of the binding.
@@ -246,8 +439,27 @@ impl<T: 'static> Binding<T> {
     where
         T: Clone,
     {
-        let any = &self.0 as &dyn Any;
-        any.downcast_ref()
+        self.0.as_any().downcast_ref()
+    }
+
+    /// Creates an assignment of `value` to this binding, for [`set_many`].
+    #[must_use]
+    pub fn assign(&self, value: impl Into<T>) -> Assignment
+    where
+        T: Clone,
+    {
+        let value = value.into();
+        Assignment(if let Some(container) = self.as_container() {
+            let container = container.clone();
+            Pending::Stage(Box::new(move || {
+                container
+                    .stage(|current| *current = value)
+                    .then(|| Box::new(move || container.publish()) as Publish)
+            }))
+        } else {
+            let binding = self.clone();
+            Pending::Set(Box::new(move || binding.set(value)))
+        })
     }
 
     /// Gets mutable access to the binding's value through a guard.
@@ -261,24 +473,69 @@ impl<T: 'static> Binding<T> {
     ///
     /// This is a convenience method that handles getting the value, modifying it,
     /// and then setting it back, all while properly handling notifications.
+    /// Same as [`update`](Self::update).
     pub fn handle(&self, handler: impl FnOnce(&mut T))
+    where
+        T: Clone,
+    {
+        self.update(handler);
+    }
+
+    /// Modifies the value in place with `f`, then notifies watchers.
+    ///
+    /// For bindings holding their own value, `f` mutates the stored value
+    /// directly, so large values such as long `Vec`s or strings are not cloned
+    /// to change them. Other bindings, such as those created by
+    /// [`mapping`](Self::mapping), are read, modified and set back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nami::{binding, Binding};
+    ///
+    /// let log: Binding<Vec<String>> = binding(Vec::new());
+    /// log.update(|lines| lines.push("started".to_string()));
+    /// assert_eq!(log.peek(Vec::len), 1);
+    /// ```
+    pub fn update(&self, f: impl FnOnce(&mut T))
     where
         T: Clone,
     {
         if let Some(container) = self.as_container() {
-            {
-                let mut value = container.value.borrow_mut();
-                handler(&mut value);
-            }
-            container.watchers.notify(|| self.get(), &Metadata::new());
+            container.modify(f);
         } else {
             let mut temp = self.get();
 
-            handler(&mut temp);
+            f(&mut temp);
             self.set(temp);
         }
     }
 
+    /// Calls `f` with a reference to the current value, returning its result.
+    ///
+    /// For bindings holding their own value, the value is borrowed rather than
+    /// cloned, so `f` must not write to this binding. Other bindings call `f`
+    /// with a computed value.
+    pub fn peek<R>(&self, f: impl FnOnce(&T) -> R) -> R
+    where
+        T: Clone,
+    {
+        if let Some(container) = self.as_container() {
+            f(&container.value.borrow())
+        } else {
+            f(&self.get())
+        }
+    }
+
+    /// Like [`peek`](Self::peek), but returns `None` instead of panicking if
+    /// the value is being modified.
+    pub(crate) fn try_peek<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        let mut f = Some(f);
+        let mut result = None;
+        self.0.peek_value(&mut |value| result = f.take().map(|f| f(value)));
+        result
+    }
+
     /// Sets the binding to a new value with automatic type conversion.
     ///
     /// Accepts any value that implements `Into<T>`, providing the same ergonomic
@@ -320,6 +577,7 @@ impl<T: 'static> Binding<T> {
             binding: source.clone(),
             getter: Rc::new(getter),
             setter: Rc::new(setter),
+            id: NodeId::next(),
             _marker: PhantomData,
         })
     }
@@ -650,7 +908,7 @@ impl<T> Binding<Option<T>> {
             self,
             {
                 let equal = equal.clone();
-                move |value| value.as_ref().filter(|value| **value == equal).is_some()
+                move |value| value.as_ref().is_some_and(|value| *value == equal)
             },
             move |binding, value| {
                 if value {
@@ -814,10 +1072,66 @@ where
     }
 }
 
+impl<T: 'static> Binding<T> {
+    /// Returns `true` if both bindings are handles to the same node.
+    ///
+    /// This is the identity that `==` and [`Hash`] use. Custom bindings that do
+    /// not report a [`node_id`](Signal::node_id) are equal to their clones,
+    /// which share the custom implementation, but not to other bindings
+    /// created from it.
+    ///
+    /// ```rust
+    /// use nami::{binding, Binding};
+    ///
+    /// let a: Binding<i32> = binding(1);
+    /// let b: Binding<i32> = binding(1);
+    /// assert!(a.ptr_eq(&a.clone()));
+    /// assert!(!a.ptr_eq(&b));
+    /// ```
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (Signal::node_id(self), Signal::node_id(other)) {
+            (Some(id), Some(other)) => id == other,
+            (None, None) => Rc::ptr_eq(&self.0, &other.0),
+            _ => false,
+        }
+    }
+}
+
+/// Bindings are equal if they are handles to the same node, regardless of their
+/// values.
+///
+/// ```rust
+/// use std::collections::HashSet;
+/// use nami::{binding, Binding};
+///
+/// let a: Binding<i32> = binding(1);
+/// let b: Binding<i32> = binding(1);
+/// let subscribed: HashSet<Binding<i32>> = [a.clone(), a.clone(), b].into();
+/// assert_eq!(subscribed.len(), 2);
+/// assert!(subscribed.contains(&a));
+/// ```
+impl<T: 'static> PartialEq for Binding<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl<T: 'static> Eq for Binding<T> {}
+
+impl<T: 'static> Hash for Binding<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match Signal::node_id(self) {
+            Some(id) => id.hash(state),
+            None => Rc::as_ptr(&self.0).cast::<()>().hash(state),
+        }
+    }
+}
+
 impl<T> Clone for Binding<T> {
-    /// Creates a clone of this binding.
+    /// Creates a clone of this binding, sharing its implementation.
     fn clone(&self) -> Self {
-        self.0.cloned_binding()
+        Self(self.0.clone())
     }
 }
 
@@ -831,6 +1145,8 @@ pub struct Container<T: 'static + Clone> {
     value: Rc<RefCell<T>>,
     /// Manager for watchers that are interested in changes to the value
     watchers: WatcherManager<T>,
+    /// The identity shared by all clones of this container
+    id: NodeId,
 }
 
 impl<T: 'static + Clone + Default> Default for Container<T> {
@@ -845,8 +1161,90 @@ impl<T: 'static + Clone> Container<T> {
         Self {
             value: Rc::new(RefCell::new(value)),
             watchers: WatcherManager::default(),
+            id: NodeId::next(),
+        }
+    }
+
+    /// Returns `true` if both containers share the same value and watchers.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.value, &other.value)
+    }
+
+    /// Registers `waker` to be woken by the next change, without registering
+    /// a watcher.
+    pub fn add_waker(&self, waker: &core::task::Waker) {
+        self.watchers.add_waker(waker);
+    }
+
+    /// Calls `f` with a reference to the value, or returns `None` if the value
+    /// is being modified.
+    pub(crate) fn try_peek<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        self.value.try_borrow().ok().map(|value| f(&value))
+    }
+
+    /// Replaces the value without notifying watchers, returning the old value.
+    pub(crate) fn replace_silently(&self, value: T) -> T {
+        crate::signal::advance_generation();
+        crate::signal::advance_silent_generation();
+        self.value.replace(value)
+    }
+
+    /// Modifies the value in place, then reports the change and notifies
+    /// watchers, unless inside a [`sandbox`](crate::what_if::sandbox).
+    pub(crate) fn modify(&self, modify: impl FnOnce(&mut T)) {
+        if self.stage(modify) {
+            self.publish();
         }
     }
+
+    /// Modifies the value in place without notifying watchers, returning
+    /// whether the change should be published.
+    fn stage(&self, modify: impl FnOnce(&mut T)) -> bool {
+        #[cfg(feature = "std")]
+        let sandboxed = crate::what_if::record(self.id, || {
+            let (container, original) = (self.clone(), self.get());
+            Box::new(move || {
+                container.replace_silently(original);
+            })
+        });
+        #[cfg(not(feature = "std"))]
+        let sandboxed = false;
+        crate::signal::advance_generation();
+        if sandboxed {
+            crate::signal::advance_silent_generation();
+        }
+        modify(&mut self.value.borrow_mut());
+        !sandboxed
+    }
+
+    /// Reports the current value and notifies watchers, at the end of the
+    /// running [`batch`](crate::batch::batch) if any.
+    fn publish(&self) {
+        #[cfg(feature = "std")]
+        if crate::batch::defer(self.id, || {
+            let container = self.clone();
+            Box::new(move || container.publish())
+        }) {
+            return;
+        }
+        // Cloned on first use, so a write nobody observes clones nothing.
+        let value = core::cell::OnceCell::new();
+        #[cfg(feature = "devtools")]
+        crate::devtools::report(self.id, || value.get_or_init(|| self.get()));
+        let notify = || {
+            self.watchers.notify(
+                || value.get_or_init(|| self.get()).clone(),
+                &Metadata::new(),
+            );
+        };
+        #[cfg(feature = "std")]
+        crate::limits::propagate(self.id, || {
+            crate::trace::write(self.id, core::any::type_name::<T>(), notify);
+        });
+        #[cfg(not(feature = "std"))]
+        notify();
+    }
 }
 
 impl<T: 'static + Clone> Signal for Container<T> {
@@ -862,14 +1260,35 @@ impl<T: 'static + Clone> Signal for Container<T> {
     fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
         Box::new(self.watchers.register_as_guard(watcher))
     }
+
+    /// Registers a watcher that is only notified of changes whose metadata
+    /// `filter` accepts, checked before the value is cloned.
+    fn watch_filtered(
+        &self,
+        filter: impl Fn(&Metadata) -> bool + 'static,
+        watcher: impl Fn(Context<Self::Output>) + 'static,
+    ) -> Self::Guard {
+        Box::new(self.watchers.register_filtered(filter, watcher))
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        Some(self.id)
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        Some(self.watchers.len())
+    }
 }
 
 impl<T: 'static + Clone> CustomBinding for Container<T> {
     /// Sets a new value and notifies watchers.
     fn set(&self, value: T) {
-        self.value.replace(value.clone());
-        self.watchers
-            .notify(move || value.clone(), &Metadata::new());
+        self.modify(|current| *current = value);
+    }
+
+    /// Borrows the value instead of cloning it.
+    fn try_peek<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        Self::try_peek(self, f)
     }
 }
 
@@ -884,8 +1303,32 @@ impl<T: 'static> Signal for Binding<T> {
 
     /// Registers a watcher to be notified when the binding's value changes.
     fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        #[cfg(feature = "std")]
+        let watcher = crate::trace::traced(watcher);
         Box::new(self.0.add_watcher(Box::new(watcher)))
     }
+
+    /// Registers a watcher that is only notified of changes whose metadata
+    /// `filter` accepts.
+    fn watch_filtered(
+        &self,
+        filter: impl Fn(&Metadata) -> bool + 'static,
+        watcher: impl Fn(Context<Self::Output>) + 'static,
+    ) -> Self::Guard {
+        #[cfg(feature = "std")]
+        let watcher = crate::trace::traced(watcher);
+        self.0
+            .add_watcher_filtered(Box::new(filter), Box::new(watcher))
+    }
+
+    /// Returns the identity of the underlying node, such as its container.
+    fn node_id(&self) -> Option<NodeId> {
+        self.0.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.0.watcher_count()
+    }
 }
 
 /// A mapping between one binding type and another.
@@ -899,6 +1342,8 @@ struct Mapping<Input: 'static, Output, Getter, Setter> {
     getter: Rc<Getter>,
     /// Function to convert from output type back to input type
     setter: Rc<Setter>,
+    /// The identity shared by all clones of this mapping
+    id: NodeId,
     /// Phantom data to keep track of the Output type parameter
     _marker: PhantomData<Output>,
 }
@@ -909,6 +1354,7 @@ impl<Input, Output, Getter, Setter> Clone for Mapping<Input, Output, Getter, Set
             binding: self.binding.clone(),
             getter: self.getter.clone(),
             setter: self.setter.clone(),
+            id: self.id,
             _marker: PhantomData,
         }
     }
@@ -939,6 +1385,9 @@ where
             watcher(Context::new(getter(value), metadata));
         })
     }
+    fn node_id(&self) -> Option<NodeId> {
+        Some(self.id)
+    }
 }
 
 impl<Input, Output, Getter, Setter> CustomBinding for Mapping<Input, Output, Getter, Setter>
@@ -954,11 +1403,9 @@ where
     }
 }
 
-// Reduce once heap allocate
 impl<T> From<Binding<T>> for Computed<T> {
     fn from(val: Binding<T>) -> Self {
-        let boxed = val.0 as Box<_>;
-        Self(boxed)
+        Self(Box::new(val))
     }
 }
 
@@ -966,6 +1413,7 @@ impl<T> From<Binding<T>> for Computed<T> {
 mod tests {
     use super::*;
     use alloc::{string::String, vec, vec::Vec};
+    use core::cell::Cell;
 
     #[test]
     fn test_binding_into_conversion() {
@@ -1019,4 +1467,215 @@ mod tests {
         number.set(100i64); // Direct i64
         assert_eq!(number.get(), 100i64);
     }
+
+    #[test]
+    fn test_node_identity() {
+        let a: Binding<i32> = binding(1);
+        let b: Binding<i32> = binding(1);
+        let computed: Computed<i32> = a.clone().into();
+        let boxed = Computed::new(a.clone());
+
+        assert!(a.node_id().is_some());
+        assert_eq!(computed.node_id(), a.node_id());
+        assert!(computed.ptr_eq(&boxed));
+        assert!(!a.ptr_eq(&b));
+
+        let positive = a.filter(|value| *value > 0);
+        assert_eq!(positive, positive.clone());
+        assert_ne!(positive, a);
+
+        let doubled = Computed::new(crate::map::map(a, |value: i32| value * 2));
+        assert_eq!(doubled.node_id(), None);
+        assert!(!doubled.ptr_eq(&doubled.clone()));
+    }
+    #[test]
+    fn test_parsed_binding_round_trip() {
+        let field: Binding<String> = binding("x");
+        let (value, error) = parsed_binding::<f64>(&field);
+        assert!((value.get() - 0.0).abs() < f64::EPSILON);
+        assert!(error.get().is_some());
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            value.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        field.set("1.5");
+        field.set("1.5e");
+        value.set(2.25);
+        assert_eq!(*seen.borrow(), [1.5, 2.25]);
+        assert_eq!(field.get(), "2.25");
+        assert!(error.get().is_none());
+    }
+
+    #[test]
+    fn test_bimap_writes_back_to_source() {
+        let celsius: Binding<i32> = binding(0);
+        let fahrenheit = bimap(&celsius, |c: i32| c * 9 / 5 + 32, |f: i32| (f - 32) * 5 / 9);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            fahrenheit.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        fahrenheit.set(212);
+        celsius.set(-40);
+        assert_eq!(celsius.get(), -40);
+        assert_eq!(*seen.borrow(), [212, -40]);
+    }
+
+    #[test]
+    fn test_update_and_peek_avoid_cloning() {
+        #[derive(Default)]
+        struct Counted(Rc<Cell<usize>>);
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                self.0.set(self.0.get() + 1);
+                Self(self.0.clone())
+            }
+        }
+
+        // A devtools hook would clone the value to report it.
+        #[cfg(feature = "devtools")]
+        let _hook = crate::devtools::TestHook::install(None);
+        let clones = Rc::new(Cell::new(0));
+        let value: Binding<(Counted, i32)> = binding((Counted(clones.clone()), 0));
+        value.update(|(_, n)| *n += 1);
+        assert_eq!(value.peek(|(_, n)| *n), 1);
+        assert_eq!(clones.get(), 0);
+
+        let doubled = Binding::mapping(
+            &value,
+            |(_, n)| n * 2,
+            |value, n: i32| {
+                value.update(|(_, current)| *current = n / 2);
+            },
+        );
+        doubled.update(|n| *n += 2);
+        assert_eq!(value.peek(|(_, n)| *n), 2);
+    }
+
+    #[test]
+    fn test_debug_while_borrowed() {
+        let count: Binding<i32> = binding(3);
+        let rendered = RefCell::new(alloc::string::String::new());
+        count.update(|_| *rendered.borrow_mut() = alloc::format!("{count:?}"));
+        assert!(rendered.borrow().contains("value: <borrowed>"));
+        assert!(alloc::format!("{count:?}").contains("value: 3"));
+    }
+
+    #[test]
+    fn test_debug_of_non_clone_values() {
+        /// A value that cannot be cloned, read from a shared cell.
+        struct Token(i32);
+
+        #[derive(Clone)]
+        struct Tokens(Rc<Cell<i32>>);
+
+        impl Signal for Tokens {
+            type Output = Token;
+            type Guard = ();
+
+            fn get(&self) -> Token {
+                Token(self.0.get())
+            }
+
+            fn watch(&self, _: impl Fn(Context<Token>) + 'static) {}
+        }
+
+        impl CustomBinding for Tokens {
+            fn set(&self, value: Token) {
+                self.0.set(value.0);
+            }
+        }
+
+        let tokens = Binding::custom(Tokens(Rc::new(Cell::new(1))));
+        tokens.set(Token(2));
+        assert_eq!(tokens.get().0, 2);
+        let rendered = alloc::format!("{tokens:?}");
+        assert!(rendered.starts_with("Binding {"), "{rendered}");
+        assert!(rendered.contains("Token>"), "{rendered}");
+    }
+
+    #[test]
+    fn test_custom_binding_equals_its_clone() {
+        #[derive(Clone)]
+        struct Cells(Rc<Cell<i32>>);
+
+        impl Signal for Cells {
+            type Output = i32;
+            type Guard = ();
+
+            fn get(&self) -> i32 {
+                self.0.get()
+            }
+
+            fn watch(&self, _: impl Fn(Context<i32>) + 'static) {}
+        }
+
+        impl CustomBinding for Cells {
+            fn set(&self, value: i32) {
+                self.0.set(value);
+            }
+        }
+
+        let cells = Cells(Rc::new(Cell::new(0)));
+        let first = Binding::custom(cells.clone());
+        let second = Binding::custom(cells);
+        assert_eq!(first.node_id(), None);
+        assert!(first.clone() == first);
+        assert!(first.clone().ptr_eq(&first));
+        assert!(first != second);
+    }
+
+    #[test]
+    fn test_debug_rendering() {
+        #[derive(Clone)]
+        struct Opaque;
+
+        let count: Binding<i32> = binding(3);
+        let _guard = count.watch(|_| {});
+        let id = count.node_id().map_or(0, NodeId::get);
+        assert_eq!(
+            alloc::format!("{count:?}"),
+            alloc::format!("Binding {{ node: #{id}, type: \"i32\", value: 3, watchers: 1 }}")
+        );
+
+        let opaque: Binding<Opaque> = Binding::container(Opaque);
+        assert!(alloc::format!("{opaque:?}").contains("value: <nami::binding::tests::"));
+
+        let doubled = crate::map::map(count, |value: i32| value * 2);
+        let rendered = alloc::format!("{doubled:#?}");
+        assert!(rendered.starts_with("Map {\n    type: \"i32\",\n    f: \"nami::binding::tests::"));
+        assert!(rendered.contains("source: Binding {\n        node: #"));
+        assert!(
+            alloc::format!("{:?}", Computed::new(doubled))
+                .starts_with("Computed { type: \"i32\" }")
+        );
+    }
+
+    #[test]
+    fn test_set_many_is_atomic() {
+        let a: Binding<i32> = binding(1);
+        let b: Binding<i32> = binding(2);
+        let label: Binding<String> = binding("");
+        let shouted = Binding::mapping(
+            &label,
+            |text: String| text.to_uppercase(),
+            |label, text: String| label.set(text.to_lowercase()),
+        );
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            let label = label.clone();
+            crate::zip::zip(a.clone(), b.clone())
+                .watch(move |context| seen.borrow_mut().push((context.value, label.get())))
+        };
+
+        set_many([a.assign(10), shouted.assign("NEW"), b.assign(20)]);
+        assert_eq!(*seen.borrow(), [((10, 20), "new".to_string())]);
+        assert_eq!(label.get(), "new");
+    }
 }
@@ -0,0 +1,189 @@
+//! # Shared byte buffers
+//!
+//! Binary frames, such as images or audio, are expensive to copy at every step
+//! of a pipeline. [`Bytes`] is a reference-counted, immutable byte buffer:
+//! cloning it, which every signal read and notification does, only bumps a
+//! reference count, and [slicing](Bytes::slice) it shares the same allocation.
+//!
+//! [`slice`] derives a signal of a window of a buffer signal, following both the
+//! buffer and a signal of the range.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, bytes::{self, Bytes}};
+//!
+//! let frame: Binding<Bytes> = binding(Bytes::from(vec![0_u8, 1, 2, 3, 4, 5]));
+//! let window: Binding<core::ops::Range<usize>> = binding(1..3);
+//! let visible = bytes::slice(frame.clone(), window.clone());
+//!
+//! assert_eq!(&*visible.get(), [1, 2]);
+//! window.set(4..10);
+//! assert_eq!(&*visible.get(), [4, 5]);
+//! assert!(visible.get().shares_buffer(&frame.get()));
+//! ```
+
+use alloc::{rc::Rc, vec::Vec};
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Bound, Deref, Range, RangeBounds},
+};
+
+use crate::{
+    Signal,
+    map::{Map, map},
+    zip::{Zip, zip},
+};
+
+/// A reference-counted, immutable byte buffer.
+///
+/// Clones and slices share the same allocation.
+#[derive(Clone)]
+pub struct Bytes {
+    buffer: Rc<[u8]>,
+    range: Range<usize>,
+}
+
+impl Bytes {
+    /// Creates an empty buffer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from(Rc::<[u8]>::from([]))
+    }
+
+    /// Returns a buffer of the bytes within `range`, sharing this buffer's
+    /// allocation.
+    ///
+    /// The range is clamped to the length of this buffer.
+    #[must_use]
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Self {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        }
+        .min(len);
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        }
+        .clamp(start, len);
+        Self {
+            buffer: self.buffer.clone(),
+            range: self.range.start + start..self.range.start + end,
+        }
+    }
+
+    /// Returns `true` if both buffers are views of the same allocation.
+    #[must_use]
+    pub fn shares_buffer(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.buffer, &other.buffer)
+    }
+}
+
+impl Default for Bytes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer[self.range.clone()]
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<Rc<[u8]>> for Bytes {
+    fn from(buffer: Rc<[u8]>) -> Self {
+        Self {
+            range: 0..buffer.len(),
+            buffer,
+        }
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::from(Rc::<[u8]>::from(bytes))
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self::from(Rc::<[u8]>::from(bytes))
+    }
+}
+
+impl fmt::Debug for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bytes").field("len", &self.len()).finish()
+    }
+}
+
+impl PartialEq for Bytes {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Bytes {}
+
+impl Hash for Bytes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+/// Returns a signal of the bytes of `source` within the range from `range`.
+///
+/// The range is clamped to the length of the buffer, and the result shares the
+/// buffer's allocation.
+#[allow(clippy::type_complexity)]
+pub fn slice<S, R>(source: S, range: R) -> Map<Zip<S, R>, fn((Bytes, Range<usize>)) -> Bytes, Bytes>
+where
+    S: Signal<Output = Bytes>,
+    R: Signal<Output = Range<usize>>,
+{
+    map(zip(source, range), |(bytes, range)| bytes.slice(range))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+    use alloc::vec;
+
+    #[test]
+    fn test_slices_share_the_buffer() {
+        let frame: Binding<Bytes> = binding(Bytes::from(vec![1_u8, 2, 3, 4]));
+        let window: Binding<Range<usize>> = binding(0..2);
+        let head = slice(frame.clone(), window.clone());
+
+        let seen = Rc::new(core::cell::RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            head.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        window.set(1..3);
+        frame.set(Bytes::from(&[9_u8][..]));
+
+        let seen = seen.borrow();
+        assert_eq!(
+            seen.iter().map(|b| b.to_vec()).collect::<Vec<_>>(),
+            [vec![2, 3], vec![]]
+        );
+        assert_eq!(seen[0].slice(1..), Bytes::from(vec![3]));
+        assert!(seen[1].shares_buffer(&frame.get()));
+    }
+}
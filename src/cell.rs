@@ -0,0 +1,201 @@
+//! # Cell bindings
+//!
+//! [`CellBinding`] is a binding specialized for small [`Copy`] values such as
+//! numbers and flags. It stores its value in a [`Cell`] rather than a
+//! `RefCell`, so reading and writing it neither tracks borrows nor clones.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{Binding, Signal, cell::CellBinding};
+//!
+//! let volume = CellBinding::new(0.5_f32);
+//! let _guard = volume.watch(|context| assert!(context.value > 0.5));
+//! volume.set(0.75);
+//! assert!((volume.get() - 0.75).abs() < f32::EPSILON);
+//!
+//! // Convert it when an API expects a `Binding`.
+//! let binding: Binding<f32> = volume.into();
+//! assert!((binding.get() - 0.75).abs() < f32::EPSILON);
+//! ```
+
+use alloc::{boxed::Box, rc::Rc};
+use core::{cell::Cell, fmt};
+
+use crate::{
+    Binding, CustomBinding, NodeId, Signal,
+    watcher::{BoxWatcherGuard, Context, Metadata, WatcherManager},
+};
+
+/// A binding of a [`Copy`] value, without `RefCell` or cloning overhead.
+///
+/// All clones share the same value and watchers.
+#[derive(Clone)]
+pub struct CellBinding<T: Copy + 'static> {
+    value: Rc<Cell<T>>,
+    watchers: WatcherManager<T>,
+    id: NodeId,
+}
+
+impl<T: Copy + Default + 'static> Default for CellBinding<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy + fmt::Debug + 'static> fmt::Debug for CellBinding<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CellBinding")
+            .field("node", &self.id)
+            .field("value", &self.value.get())
+            .field("watchers", &self.watchers.len())
+            .finish()
+    }
+}
+
+impl<T: Copy + 'static> CellBinding<T> {
+    /// Creates a binding holding `value`.
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Rc::new(Cell::new(value)),
+            watchers: WatcherManager::new(),
+            id: NodeId::next(),
+        }
+    }
+
+    /// Returns the current value.
+    #[must_use]
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    /// Sets the value and notifies watchers, unless inside a
+    /// [`sandbox`](crate::what_if::sandbox).
+    pub fn set(&self, value: impl Into<T>) {
+        self.replace(value);
+    }
+
+    /// Sets the value and notifies watchers, returning the previous value.
+    pub fn replace(&self, value: impl Into<T>) -> T {
+        #[cfg(feature = "std")]
+        let sandboxed = crate::what_if::record(self.id, || {
+            let (cell, original) = (self.value.clone(), self.value.get());
+            Box::new(move || {
+                crate::signal::advance_generation();
+                crate::signal::advance_silent_generation();
+                cell.set(original);
+            })
+        });
+        #[cfg(not(feature = "std"))]
+        let sandboxed = false;
+        let value = value.into();
+        crate::signal::advance_generation();
+        let previous = self.value.replace(value);
+        if sandboxed {
+            crate::signal::advance_silent_generation();
+        } else {
+            self.publish(value);
+        }
+        previous
+    }
+
+    /// Registers `waker` to be woken by the next change, without registering
+    /// a watcher.
+    pub fn add_waker(&self, waker: &core::task::Waker) {
+        self.watchers.add_waker(waker);
+    }
+
+    /// Sets the value to the result of `f` applied to the current value, and
+    /// notifies watchers.
+    pub fn update(&self, f: impl FnOnce(T) -> T) {
+        self.set(f(self.get()));
+    }
+
+    /// Reports `value` and notifies watchers, at the end of the running
+    /// [`batch`](crate::batch::batch) if any.
+    fn publish(&self, value: T) {
+        #[cfg(feature = "std")]
+        if crate::batch::defer(self.id, || {
+            let cell = self.clone();
+            Box::new(move || cell.publish(cell.get()))
+        }) {
+            return;
+        }
+        #[cfg(feature = "devtools")]
+        crate::devtools::report(self.id, || &value);
+        let notify = || self.watchers.notify(|| value, &Metadata::new());
+        #[cfg(feature = "std")]
+        crate::limits::propagate(self.id, || {
+            crate::trace::write(self.id, core::any::type_name::<T>(), notify);
+        });
+        #[cfg(not(feature = "std"))]
+        notify();
+    }
+}
+
+impl<T: Copy + 'static> Signal for CellBinding<T> {
+    type Output = T;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> Self::Output {
+        self.value.get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        Box::new(self.watchers.register_as_guard(watcher))
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        Some(self.id)
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        Some(self.watchers.len())
+    }
+}
+
+impl<T: Copy + 'static> CustomBinding for CellBinding<T> {
+    fn set(&self, value: T) {
+        self.replace(value);
+    }
+}
+
+impl<T: Copy + 'static> From<CellBinding<T>> for Binding<T> {
+    fn from(cell: CellBinding<T>) -> Self {
+        Self::custom(cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_cell_binding_notifies_and_rolls_back() {
+        let count = CellBinding::new(1);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            count.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        count.update(|n| n + 1);
+        assert_eq!(count.replace(5), 2);
+
+        let binding: Binding<i32> = count.clone().into();
+        binding.set(7);
+        assert_eq!(binding.node_id(), count.node_id());
+
+        #[cfg(feature = "std")]
+        {
+            let inside = crate::what_if::sandbox(|| {
+                count.set(100);
+                count.get()
+            });
+            assert_eq!((inside, count.get()), (100, 7));
+        }
+        assert_eq!(*seen.borrow(), [2, 5, 7]);
+    }
+}
@@ -0,0 +1,399 @@
+//! # Reactive configuration for command-line tools
+//!
+//! This module loads a typed configuration from a JSON file and command-line
+//! overrides, and exposes it as a signal. With `--watch-config`, the file is
+//! reloaded whenever it changes on disk, so long-running tools such as dev servers
+//! can adapt without a restart.
+//!
+//! [`Args`] recognizes the following arguments and keeps everything else for the
+//! application to parse:
+//!
+//! - `--config <path>`: the JSON file to load.
+//! - `--watch-config`: reload the file when it changes.
+//! - `--set <path>=<value>`: override a value, using [`json`](crate::json) paths
+//!   such as `server.port`. The value is parsed as JSON, or used as a string if it
+//!   is not valid JSON. Overrides are reapplied after every reload.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use nami::{Signal, SignalExt, cli::{Args, ReactiveConfig}};
+//!
+//! #[derive(Clone, PartialEq, serde::Deserialize)]
+//! struct Config {
+//!     port: u16,
+//!     verbose: bool,
+//! }
+//!
+//! // my-server --config server.json --watch-config --set verbose=true
+//! let args = Args::from_env().expect("invalid arguments");
+//! let config: ReactiveConfig<Config> = ReactiveConfig::load(&args).expect("invalid config");
+//!
+//! let verbose = config.clone().map(|config| config.verbose);
+//! let _guard = verbose.watch(|ctx| println!("verbose logging: {}", ctx.value));
+//! ```
+
+use alloc::{
+    rc::Rc,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{any::Any, fmt, time::Duration};
+use std::{ffi::OsString, fs, path::PathBuf, time::SystemTime};
+
+use async_io::Timer;
+use executor_core::{DefaultExecutor, LocalExecutor};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    Container, CustomBinding, NodeId, Signal,
+    json::{Path, PathError, ReactiveJson},
+    watcher::Context,
+};
+
+/// How often a watched configuration file is checked for changes.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Configuration-related command-line arguments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    config: Option<PathBuf>,
+    watch: bool,
+    overrides: Vec<(Path, Value)>,
+    rest: Vec<OsString>,
+}
+
+impl Args {
+    /// Parses the arguments of the current process, skipping the program name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a recognized argument is malformed.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::parse(std::env::args_os().skip(1))
+    }
+
+    /// Parses `args`, which should not include the program name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a recognized argument is missing its value or has an
+    /// invalid override path.
+    pub fn parse(args: impl IntoIterator<Item = impl Into<OsString>>) -> Result<Self, ConfigError> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter().map(Into::into);
+
+        while let Some(arg) = args.next() {
+            let Some(text) = arg.to_str() else {
+                parsed.rest.push(arg);
+                continue;
+            };
+
+            if text == "--watch-config" {
+                parsed.watch = true;
+            } else if let Some(value) = option_value(text, "--config", &mut args)? {
+                parsed.config = Some(PathBuf::from(value));
+            } else if let Some(value) = option_value(text, "--set", &mut args)? {
+                let (path, value) = value
+                    .split_once('=')
+                    .ok_or_else(|| ConfigError::InvalidOverride(value.clone()))?;
+                let value =
+                    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.into()));
+                parsed.overrides.push((path.parse()?, value));
+            } else {
+                parsed.rest.push(arg);
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Returns the configuration file, if one was given.
+    #[must_use]
+    pub fn config_path(&self) -> Option<&std::path::Path> {
+        self.config.as_deref()
+    }
+
+    /// Returns `true` if `--watch-config` was given.
+    #[must_use]
+    pub const fn watch(&self) -> bool {
+        self.watch
+    }
+
+    /// Returns the arguments that were not recognized, in their original order.
+    #[must_use]
+    pub fn rest(&self) -> &[OsString] {
+        &self.rest
+    }
+
+    /// Reads the configuration file and applies the overrides.
+    fn read(&self) -> Result<Value, ConfigError> {
+        let document = match &self.config {
+            Some(path) => {
+                let text = fs::read_to_string(path).map_err(ConfigError::Io)?;
+                serde_json::from_str(&text).map_err(ConfigError::Json)?
+            }
+            None => Value::Object(serde_json::Map::new()),
+        };
+
+        let document = ReactiveJson::new(document);
+        for (path, value) in &self.overrides {
+            document
+                .set_path(path, value.clone())
+                .map_err(|_| ConfigError::InvalidOverride(path.to_string()))?;
+        }
+        Ok(document.get())
+    }
+}
+
+/// Reads the value of `--name value` or `--name=value`, if `arg` is that option.
+fn option_value(
+    arg: &str,
+    name: &str,
+    args: &mut impl Iterator<Item = OsString>,
+) -> Result<Option<String>, ConfigError> {
+    let Some(suffix) = arg.strip_prefix(name) else {
+        return Ok(None);
+    };
+    if let Some(value) = suffix.strip_prefix('=') {
+        return Ok(Some(value.into()));
+    }
+    if !suffix.is_empty() {
+        return Ok(None);
+    }
+    args.next()
+        .and_then(|value| value.into_string().ok())
+        .map(Some)
+        .ok_or_else(|| ConfigError::MissingValue(name.into()))
+}
+
+/// An error returned when configuration cannot be parsed or loaded.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// An option was given without its value.
+    MissingValue(String),
+    /// A `--set` override is not of the form `<path>=<value>`, or its path does
+    /// not fit the configuration document.
+    InvalidOverride(String),
+    /// An override path could not be parsed.
+    InvalidPath(PathError),
+    /// The configuration file could not be read.
+    Io(std::io::Error),
+    /// The configuration file is not valid JSON, or does not match the expected type.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingValue(name) => write!(f, "`{name}` requires a value"),
+            Self::InvalidOverride(value) => write!(f, "invalid override `{value}`"),
+            Self::InvalidPath(error) => write!(f, "invalid override: {error}"),
+            Self::Io(error) => write!(f, "failed to read the configuration: {error}"),
+            Self::Json(error) => write!(f, "invalid configuration: {error}"),
+        }
+    }
+}
+
+impl core::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::InvalidPath(error) => Some(error),
+            Self::Io(error) => Some(error),
+            Self::Json(error) => Some(error),
+            Self::MissingValue(_) | Self::InvalidOverride(_) => None,
+        }
+    }
+}
+
+impl From<PathError> for ConfigError {
+    fn from(error: PathError) -> Self {
+        Self::InvalidPath(error)
+    }
+}
+
+/// A typed configuration loaded from [`Args`], reloaded at runtime in watch mode.
+///
+/// Watchers are notified when a reload produces a different configuration. A
+/// reload that fails to read or deserialize the file is logged and keeps the
+/// previous configuration.
+#[derive(Clone)]
+pub struct ReactiveConfig<T: Clone + 'static> {
+    container: Container<T>,
+    reloader: Option<Rc<dyn Any>>,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for ReactiveConfig<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReactiveConfig")
+            .field("value", &self.container.get())
+            .field("watching", &self.reloader.is_some())
+            .finish()
+    }
+}
+
+impl<T> ReactiveConfig<T>
+where
+    T: DeserializeOwned + PartialEq + Clone + 'static,
+{
+    /// Loads the configuration, watching the file with the default executor if requested.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initial configuration cannot be loaded.
+    pub fn load(args: &Args) -> Result<Self, ConfigError> {
+        Self::with_executor(args, DEFAULT_POLL_INTERVAL, DefaultExecutor)
+    }
+
+    /// Loads the configuration, polling the file every `interval` on the given
+    /// executor if `--watch-config` was given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initial configuration cannot be loaded.
+    pub fn with_executor<E>(
+        args: &Args,
+        interval: Duration,
+        executor: E,
+    ) -> Result<Self, ConfigError>
+    where
+        E: LocalExecutor + 'static,
+    {
+        let container = Container::new(deserialize(args.read()?)?);
+        let task = match (&args.config, args.watch) {
+            (Some(path), true) => {
+                let args = args.clone();
+                let path = path.clone();
+                let container = container.clone();
+                let task = executor.spawn(async move {
+                    let mut modified = modified_time(&path);
+                    loop {
+                        Timer::after(interval).await;
+                        let current = modified_time(&path);
+                        if current == modified {
+                            continue;
+                        }
+                        modified = current;
+                        match args.read().and_then(deserialize::<T>) {
+                            Ok(config) if config != container.get() => container.set(config),
+                            Ok(_) => {}
+                            Err(error) => {
+                                log::warn!("keeping the previous configuration: {error}");
+                            }
+                        }
+                    }
+                });
+                Some(Rc::new(task) as Rc<dyn Any>)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            container,
+            reloader: task,
+        })
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(value: Value) -> Result<T, ConfigError> {
+    serde_json::from_value(value).map_err(ConfigError::Json)
+}
+
+fn modified_time(path: &std::path::Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl<T: Clone + 'static> Signal for ReactiveConfig<T> {
+    type Output = T;
+    type Guard = <Container<T> as Signal>::Guard;
+
+    /// Returns the current configuration.
+    fn get(&self) -> Self::Output {
+        self.container.get()
+    }
+
+    /// Watches for configuration reloads.
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.container.watch(watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.container.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.container.watcher_count()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::{
+        path::PathBuf,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    /// A file in the temporary directory, named uniquely to the test creating
+    /// it, and removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(contents: &str) -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let name = alloc::format!(
+                "nami-cli-test-{}-{}.json",
+                std::process::id(),
+                NEXT.fetch_add(1, Ordering::Relaxed)
+            );
+            let path = std::env::temp_dir().join(name);
+            fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_args_and_overrides() {
+        let file = TempFile::new(r#"{ "server": { "port": 80, "host": "localhost" } }"#);
+        let path = &file.0;
+
+        let args = Args::parse([
+            "serve",
+            "--config",
+            path.to_str().unwrap(),
+            "--set=server.port=8080",
+            "--set",
+            "server.host=example.com",
+            "--watch-config",
+        ])
+        .unwrap();
+        assert!(args.watch());
+        assert_eq!(args.config_path(), Some(path.as_path()));
+        assert_eq!(args.rest(), [OsString::from("serve")]);
+        assert_eq!(
+            args.read().unwrap(),
+            json!({ "server": { "port": 8080, "host": "example.com" } })
+        );
+
+        assert!(matches!(
+            Args::parse(["--config"]),
+            Err(ConfigError::MissingValue(_))
+        ));
+        assert!(matches!(
+            Args::parse(["--set", "port"]),
+            Err(ConfigError::InvalidOverride(_))
+        ));
+    }
+}
@@ -10,6 +10,19 @@
 //! - [`Collection`]: A trait defining the interface for observable collections
 //! - [`List<T>`]: A reactive list implementation using `Rc<RefCell<Vec<T>>>`
 //! - [`AnyCollection<T>`]: A type-erased wrapper for storing different collection types
+//! - [`ListChanges<T>`]: Metadata describing how a [`List`] changed, for incremental updates
+//! - [`ListSource`]: Lists and derived [`ListView`]s, which report [`ListChanges`]
+//! - [`ReactiveMap<K, V>`]: A reactive ordered map reporting [`MapChanges`] by key, with per-key [`Entry`] signals
+//!
+//! # Derived Views
+//!
+//! Views are read-only lists maintained incrementally from their sources:
+//! - [`concat`]: Both sources, one after the other
+//! - [`flat_map`]: The child lists of every parent, flattened in order
+//! - [`map_items`]: Every item of the source mapped, only mapping the items a change touches
+//! - [`map_keyed`]: Every item of a list signal mapped, reusing the results of unchanged items by key
+//! - [`sorted`] and [`sorted_by_key`]: The source in stable sorted order, by a possibly reactive key
+//! - [`zip_lists`]: Two sources combined element by element
 //!
 //! # Collection Types
 //!
@@ -64,15 +77,33 @@
 
 use core::{
     cell::RefCell,
+    fmt::Debug,
     ops::{Bound, RangeBounds},
 };
 
-use alloc::{boxed::Box, rc::Rc, vec::Vec};
+use alloc::{boxed::Box, rc::Rc, vec, vec::Vec};
 
-use crate::watcher::{
-    BoxWatcher, BoxWatcherGuard, WatcherGuard, WatcherManager, WatcherManagerGuard,
+use crate::{
+    diff::{Edit, edit_script},
+    watcher::{BoxWatcher, BoxWatcherGuard, WatcherGuard, WatcherManager, WatcherManagerGuard},
 };
 
+mod concat;
+mod flat_map;
+mod keyed;
+mod map;
+mod sorted;
+mod view;
+mod zip;
+pub use concat::concat;
+pub use flat_map::flat_map;
+pub use keyed::{Entry, MapChange, MapChanges, ReactiveMap};
+pub use map::{map_items, map_keyed};
+pub(crate) use sorted::filter_sorted_by_key;
+pub use sorted::{sorted, sorted_by_key};
+pub use view::ListView;
+pub use zip::zip_lists;
+
 /// A trait for collections that can be observed for changes.
 ///
 /// This trait provides a common interface for collections that support
@@ -131,17 +162,23 @@ impl<T: 'static> List<T> {
         }
     }
 
+    /// Returns a copy of the current contents.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        Clone::clone(&*self.vec.borrow())
+    }
+
     /// Adds an element to the end of the list.
     pub fn push(&self, value: T)
     where
         T: Clone,
     {
-        self.vec.borrow_mut().push(value);
-        let vec_clone = self.vec.clone();
-        self.watchers.notify(
-            move || Clone::clone(&*vec_clone.borrow()),
-            &crate::watcher::Metadata::new(),
-        );
+        let index = self.vec.borrow().len();
+        self.vec.borrow_mut().push(value.clone());
+        self.notify(vec![ListChange::Insert { index, value }]);
     }
 
     /// Removes and returns the last element of the list.
@@ -152,11 +189,8 @@ impl<T: 'static> List<T> {
     {
         let result = self.vec.borrow_mut().pop();
         if result.is_some() {
-            let vec_clone = self.vec.clone();
-            self.watchers.notify(
-                move || Clone::clone(&*vec_clone.borrow()),
-                &crate::watcher::Metadata::new(),
-            );
+            let index = self.vec.borrow().len();
+            self.notify(vec![ListChange::Remove { index }]);
         }
         result
     }
@@ -166,12 +200,8 @@ impl<T: 'static> List<T> {
     where
         T: Clone,
     {
-        self.vec.borrow_mut().insert(index, value);
-        let vec_clone = self.vec.clone();
-        self.watchers.notify(
-            move || Clone::clone(&*vec_clone.borrow()),
-            &crate::watcher::Metadata::new(),
-        );
+        self.vec.borrow_mut().insert(index, value.clone());
+        self.notify(vec![ListChange::Insert { index, value }]);
     }
 
     /// Removes and returns the element at the specified index.
@@ -181,29 +211,390 @@ impl<T: 'static> List<T> {
         T: Clone,
     {
         let result = self.vec.borrow_mut().remove(index);
-        let vec_clone = self.vec.clone();
-        self.watchers.notify(
-            move || Clone::clone(&*vec_clone.borrow()),
-            &crate::watcher::Metadata::new(),
-        );
+        self.notify(vec![ListChange::Remove { index }]);
         result
     }
 
+    /// Replaces the element at the specified index, returning the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&self, index: usize, value: T) -> T
+    where
+        T: Clone,
+    {
+        let previous = core::mem::replace(&mut self.vec.borrow_mut()[index], value.clone());
+        self.notify(vec![ListChange::Update { index, value }]);
+        previous
+    }
+
     /// Clears all elements from the list.
     pub fn clear(&self)
     where
         T: Clone,
     {
-        let was_empty = self.vec.borrow().is_empty();
+        let len = self.vec.borrow().len();
         self.vec.borrow_mut().clear();
-        if !was_empty {
-            let vec_clone = self.vec.clone();
-            self.watchers.notify(
-                move || Clone::clone(&*vec_clone.borrow()),
-                &crate::watcher::Metadata::new(),
+        if len > 0 {
+            self.notify(
+                (0..len)
+                    .rev()
+                    .map(|index| ListChange::Remove { index })
+                    .collect(),
             );
         }
     }
+
+    /// Replaces the contents of the list with `items`, notifying watchers with a
+    /// minimal set of changes.
+    ///
+    /// Elements present in both the old and the new contents are kept in place, so
+    /// state derived from them survives the sync. A removed element directly
+    /// followed by an inserted one is reported as an update. The common prefix
+    /// and suffix are matched directly, and the cost of the diff grows with the
+    /// size of the changed region times the number of differences. A region
+    /// with more than a thousand differences is replaced as a whole, element by
+    /// element.
+    pub fn replace_all(&self, items: Vec<T>)
+    where
+        T: Clone + PartialEq,
+    {
+        let changes = diff_lists(&self.vec.borrow(), &items, |item| item, true);
+        self.replace_with(items, changes);
+    }
+
+    /// Replaces the contents of the list with `items`, matching elements by `key`.
+    ///
+    /// Elements whose key is kept, in the same relative order, are reported as
+    /// updates if they changed, and left untouched otherwise. Reordered elements are
+    /// removed and reinserted. This suits syncing
+    /// with a freshly fetched list of records identified by an id.
+    pub fn replace_all_by_key<K: PartialEq>(&self, items: Vec<T>, key: impl Fn(&T) -> K)
+    where
+        T: Clone + PartialEq,
+    {
+        let changes = diff_lists(&self.vec.borrow(), &items, key, false);
+        self.replace_with(items, changes);
+    }
+
+    fn replace_with(&self, items: Vec<T>, changes: Vec<ListChange<T>>)
+    where
+        T: Clone,
+    {
+        if changes.is_empty() {
+            return;
+        }
+        *self.vec.borrow_mut() = items;
+        self.notify(changes);
+    }
+
+    /// Applies `changes` and notifies watchers once.
+    pub(crate) fn apply_changes(&self, changes: Vec<ListChange<T>>)
+    where
+        T: Clone,
+    {
+        if changes.is_empty() {
+            return;
+        }
+        let changes = ListChanges(changes.into());
+        changes.apply(&mut self.vec.borrow_mut());
+        self.notify_with(changes);
+    }
+
+    /// Notifies watchers with the current contents and the changes that led to them.
+    fn notify(&self, changes: Vec<ListChange<T>>)
+    where
+        T: Clone,
+    {
+        self.notify_with(ListChanges(changes.into()));
+    }
+
+    fn notify_with(&self, changes: ListChanges<T>)
+    where
+        T: Clone,
+    {
+        let vec = self.vec.clone();
+        let metadata = crate::watcher::Metadata::new().with(changes);
+        self.watchers
+            .notify(move || Clone::clone(&*vec.borrow()), &metadata);
+    }
+}
+
+/// A single structural change to a [`List`].
+///
+/// Indices refer to the list as it is after the preceding changes of the same
+/// notification have been applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ListChange<T> {
+    /// `value` was inserted at `index`.
+    Insert {
+        /// The position of the new element.
+        index: usize,
+        /// The new element.
+        value: T,
+    },
+    /// The element at `index` was removed.
+    Remove {
+        /// The position of the removed element.
+        index: usize,
+    },
+    /// The element at `index` was replaced by `value`.
+    Update {
+        /// The position of the replaced element.
+        index: usize,
+        /// The new element.
+        value: T,
+    },
+    /// The element at `from` was moved to `to`.
+    Move {
+        /// The previous position of the element.
+        from: usize,
+        /// The new position of the element, once it has been removed from `from`.
+        to: usize,
+    },
+}
+
+impl<T> ListChange<T> {
+    /// Returns the position this change applies to, or the previous position
+    /// of a moved element.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        match self {
+            Self::Insert { index, .. }
+            | Self::Remove { index }
+            | Self::Update { index, .. }
+            | Self::Move { from: index, .. } => *index,
+        }
+    }
+}
+
+impl<T: Clone> ListChange<T> {
+    /// Returns this change with its index moved by `offset`.
+    pub(crate) fn shifted(&self, offset: usize) -> Self {
+        match self {
+            Self::Insert { index, value } => Self::Insert {
+                index: index + offset,
+                value: value.clone(),
+            },
+            Self::Remove { index } => Self::Remove {
+                index: index + offset,
+            },
+            Self::Update { index, value } => Self::Update {
+                index: index + offset,
+                value: value.clone(),
+            },
+            Self::Move { from, to } => Self::Move {
+                from: from + offset,
+                to: to + offset,
+            },
+        }
+    }
+}
+
+/// A list-like collection that describes each of its changes as [`ListChanges`].
+///
+/// Derived views such as [`concat`] are built on top of this trait, so they accept
+/// both [`List`]s and other views.
+pub trait ListSource: Collection {
+    /// Returns a copy of the current contents.
+    fn to_vec(&self) -> Vec<Self::Item>;
+
+    /// Registers a watcher receiving the changes of every notification.
+    ///
+    /// Unlike [`Collection::watch`], the watcher is not called on registration.
+    fn watch_changes(
+        &self,
+        watcher: impl Fn(&ListChanges<Self::Item>) + 'static,
+    ) -> BoxWatcherGuard;
+}
+
+impl<T: Clone + 'static> ListSource for List<T> {
+    fn to_vec(&self) -> Vec<T> {
+        Self::to_vec(self)
+    }
+
+    fn watch_changes(&self, watcher: impl Fn(&ListChanges<T>) + 'static) -> BoxWatcherGuard {
+        Box::new(self.watchers.register_as_guard(move |ctx| {
+            if let Some(changes) = ctx.metadata.try_get::<ListChanges<T>>() {
+                watcher(&changes);
+            }
+        }))
+    }
+}
+
+/// Metadata attached to every notification of a [`List`], describing how its
+/// contents changed.
+///
+/// Derived views can use it to update incrementally instead of rebuilding:
+///
+/// ```rust
+/// use nami::collection::{Collection, List, ListChanges};
+///
+/// let list = List::from(vec![1, 2, 3]);
+/// let mirror = std::rc::Rc::new(std::cell::RefCell::new(list.to_vec()));
+/// let _guard = {
+///     let mirror = mirror.clone();
+///     list.watch(.., move |ctx| {
+///         if let Some(changes) = ctx.metadata.try_get::<ListChanges<i32>>() {
+///             changes.apply(&mut mirror.borrow_mut());
+///         }
+///     })
+/// };
+///
+/// list.replace_all(vec![0, 1, 3, 4]);
+/// assert_eq!(*mirror.borrow(), [0, 1, 3, 4]);
+/// ```
+#[derive(Debug)]
+pub struct ListChanges<T>(Rc<[ListChange<T>]>);
+
+impl<T> Clone for ListChanges<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> ListChanges<T> {
+    /// Returns the changes in the order they were applied.
+    #[must_use]
+    pub fn changes(&self) -> &[ListChange<T>] {
+        &self.0
+    }
+
+    /// Applies the changes to `items`, which must match the list before the change.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of bounds for `items`.
+    pub fn apply(&self, items: &mut Vec<T>)
+    where
+        T: Clone,
+    {
+        for change in self.changes() {
+            match change {
+                ListChange::Insert { index, value } => items.insert(*index, value.clone()),
+                ListChange::Remove { index } => {
+                    items.remove(*index);
+                }
+                ListChange::Update { index, value } => items[*index] = value.clone(),
+                ListChange::Move { from, to } => {
+                    let value = items.remove(*from);
+                    items.insert(*to, value);
+                }
+            }
+        }
+    }
+}
+
+/// Computes the changes turning `old` into `new`, matching elements by `key`.
+///
+/// Matched elements that are not equal become updates. If `pair_replacements` is
+/// set, removals directly followed by insertions at the same position become
+/// updates as well.
+pub(crate) fn diff_lists<'a, T, K>(
+    old: &'a [T],
+    new: &'a [T],
+    key: impl Fn(&'a T) -> K,
+    pair_replacements: bool,
+) -> Vec<ListChange<T>>
+where
+    T: Clone + PartialEq,
+    K: PartialEq,
+{
+    let old_keys: Vec<K> = old.iter().map(&key).collect();
+    let new_keys: Vec<K> = new.iter().map(&key).collect();
+
+    let mut changes = Vec::new();
+    let mut cursor = 0;
+    let (mut i, mut j) = (0, 0);
+    // Removals and insertions of the current replaced region, paired on flush.
+    let mut removed = 0;
+    let mut inserted: Vec<T> = Vec::new();
+
+    for edit in edit_script(&old_keys, &new_keys) {
+        match edit {
+            Edit::Keep => {
+                flush_replaced(
+                    &mut changes,
+                    &mut cursor,
+                    &mut removed,
+                    &mut inserted,
+                    pair_replacements,
+                );
+                if old[i] != new[j] {
+                    changes.push(ListChange::Update {
+                        index: cursor,
+                        value: new[j].clone(),
+                    });
+                }
+                cursor += 1;
+                i += 1;
+                j += 1;
+            }
+            Edit::Delete => {
+                removed += 1;
+                i += 1;
+            }
+            Edit::Insert => {
+                inserted.push(new[j].clone());
+                j += 1;
+            }
+        }
+    }
+    flush_replaced(
+        &mut changes,
+        &mut cursor,
+        &mut removed,
+        &mut inserted,
+        pair_replacements,
+    );
+
+    changes
+}
+
+/// Emits the changes of a replaced region of `removed` old and the `inserted` new
+/// elements starting at `cursor`, then resets the region.
+fn flush_replaced<T>(
+    changes: &mut Vec<ListChange<T>>,
+    cursor: &mut usize,
+    removed: &mut usize,
+    inserted: &mut Vec<T>,
+    pair_replacements: bool,
+) {
+    let paired = if pair_replacements {
+        (*removed).min(inserted.len())
+    } else {
+        0
+    };
+    let mut inserted = core::mem::take(inserted).into_iter();
+    for value in inserted.by_ref().take(paired) {
+        changes.push(ListChange::Update {
+            index: *cursor,
+            value,
+        });
+        *cursor += 1;
+    }
+    for _ in paired..*removed {
+        changes.push(ListChange::Remove { index: *cursor });
+    }
+    for value in inserted {
+        changes.push(ListChange::Insert {
+            index: *cursor,
+            value,
+        });
+        *cursor += 1;
+    }
+    *removed = 0;
+}
+
+impl<T: Debug + 'static> Debug for List<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("List")
+            .field("items", &*self.vec.borrow())
+            .field("watchers", &self.watchers.len())
+            .finish()
+    }
 }
 
 impl<T> Clone for List<T> {
@@ -830,4 +1221,381 @@ mod tests {
         list.push(2);
         assert_eq!(*notification_count.borrow(), 1);
     }
+
+    fn recorded_changes<T: Clone + 'static>(
+        list: &impl ListSource<Item = T>,
+    ) -> Rc<RefCell<Vec<ListChange<T>>>> {
+        let changes: Rc<RefCell<Vec<ListChange<T>>>> = Rc::default();
+        let recorder = changes.clone();
+        let guard = list.watch_changes(move |list_changes| {
+            recorder
+                .borrow_mut()
+                .extend_from_slice(list_changes.changes());
+        });
+        core::mem::forget(guard);
+        changes
+    }
+
+    #[test]
+    fn test_list_replace_all_minimal_changes() {
+        let list = List::from(vec![1, 2, 3, 4, 5]);
+        let changes = recorded_changes(&list);
+
+        list.replace_all(vec![1, 3, 4, 6, 5, 7]);
+        assert_eq!(
+            *changes.borrow(),
+            [
+                ListChange::Remove { index: 1 },
+                ListChange::Insert { index: 3, value: 6 },
+                ListChange::Insert { index: 5, value: 7 },
+            ]
+        );
+
+        // Replacing an element in place is reported as an update.
+        changes.borrow_mut().clear();
+        list.replace_all(vec![1, 30, 4, 6, 5, 7]);
+        assert_eq!(
+            *changes.borrow(),
+            [ListChange::Update {
+                index: 1,
+                value: 30
+            }]
+        );
+
+        // Identical contents do not notify.
+        changes.borrow_mut().clear();
+        list.replace_all(list.to_vec());
+        assert!(changes.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_list_replace_all_trims_common_ends() {
+        // A full table of the two lists would take 10^10 entries.
+        let list = List::from((0..100_000).collect::<Vec<i32>>());
+        let changes = recorded_changes(&list);
+
+        let mut items = list.to_vec();
+        items[50_000] = -1;
+        items.insert(50_010, -2);
+        list.replace_all(Clone::clone(&items));
+        assert_eq!(
+            *changes.borrow(),
+            [
+                ListChange::Update {
+                    index: 50_000,
+                    value: -1
+                },
+                ListChange::Insert {
+                    index: 50_010,
+                    value: -2
+                },
+            ]
+        );
+        assert_eq!(list.to_vec(), items);
+    }
+
+    #[test]
+    fn test_list_replace_all_of_changed_lists_is_linear() {
+        let list = List::from((0..20_000).collect::<Vec<i32>>());
+        let changes = recorded_changes(&list);
+
+        // Every element changes.
+        let items: Vec<i32> = (20_000..40_000).collect();
+        list.replace_all(Clone::clone(&items));
+        assert_eq!(changes.borrow().len(), 20_000);
+        assert!(
+            changes.borrow().iter().enumerate().all(
+                |(i, change)| matches!(change, ListChange::Update { index, .. } if *index == i)
+            )
+        );
+        assert_eq!(list.to_vec(), items);
+
+        // Every other element changes, which is too many differences to
+        // search for the shortest script.
+        let items: Vec<i32> = items
+            .iter()
+            .map(|&item| if item % 2 == 0 { -item } else { item })
+            .collect();
+        list.replace_all(Clone::clone(&items));
+        assert_eq!(list.to_vec(), items);
+    }
+
+    #[test]
+    fn test_list_replace_all_by_key() {
+        let list = List::from(vec![(1, "a"), (2, "b"), (3, "c")]);
+        let changes = recorded_changes(&list);
+        let mut mirror = list.to_vec();
+
+        let fetched = vec![(1, "A"), (3, "c"), (4, "d")];
+        list.replace_all_by_key(Clone::clone(&fetched), |(id, _)| *id);
+
+        assert_eq!(
+            *changes.borrow(),
+            [
+                ListChange::Update {
+                    index: 0,
+                    value: (1, "A")
+                },
+                ListChange::Remove { index: 1 },
+                ListChange::Insert {
+                    index: 2,
+                    value: (4, "d")
+                },
+            ]
+        );
+        ListChanges(Clone::clone(&*changes.borrow()).into()).apply(&mut mirror);
+        assert_eq!(mirror, fetched);
+        assert_eq!(list.to_vec(), fetched);
+    }
+
+    #[test]
+    fn test_concat_offsets_changes() {
+        let a = List::from(vec![1, 2]);
+        let b = List::from(vec![10]);
+        let c = List::from(vec![100]);
+        let ab = concat(&a, &b);
+        let abc = concat(&ab, &c);
+        let changes = recorded_changes(&abc);
+
+        c.push(200);
+        b.insert(0, 5);
+        let _ = a.remove(0);
+        b.set(1, 20);
+
+        assert_eq!(abc.to_vec(), [2, 5, 20, 100, 200]);
+        assert_eq!(ab.to_vec(), [2, 5, 20]);
+        assert_eq!(
+            *changes.borrow(),
+            [
+                ListChange::Insert {
+                    index: 4,
+                    value: 200
+                },
+                ListChange::Insert { index: 2, value: 5 },
+                ListChange::Remove { index: 0 },
+                ListChange::Update {
+                    index: 2,
+                    value: 20
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_items_only_maps_touched_items() {
+        let mapped = Rc::new(RefCell::new(Vec::new()));
+        let list = List::from(vec![1, 2, 3]);
+        let doubled = map_items(&list, {
+            let mapped = mapped.clone();
+            move |n: &i32| {
+                mapped.borrow_mut().push(*n);
+                n * 2
+            }
+        });
+        let changes = recorded_changes(&doubled);
+
+        list.insert(1, 5);
+        list.set(3, 4);
+        let _ = list.remove(0);
+        list.clear();
+
+        assert_eq!(*mapped.borrow(), [1, 2, 3, 5, 4]);
+        assert!(doubled.to_vec().is_empty());
+        assert_eq!(
+            changes.borrow()[..3],
+            [
+                ListChange::Insert {
+                    index: 1,
+                    value: 10
+                },
+                ListChange::Update { index: 3, value: 8 },
+                ListChange::Remove { index: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_keyed_only_maps_new_or_changed_keys() {
+        let mapped = Rc::new(RefCell::new(Vec::new()));
+        let source = crate::binding(vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+        let view = map_keyed(&source, |&(id, _)| id, {
+            let mapped = mapped.clone();
+            move |&(id, letter): &(i32, char)| {
+                mapped.borrow_mut().push(id);
+                letter.to_ascii_uppercase()
+            }
+        });
+        let changes = recorded_changes(&view);
+        let mut mirror = view.to_vec();
+
+        source.set(vec![(3, 'c'), (2, 'x'), (1, 'a'), (4, 'd')]);
+
+        assert_eq!(view.to_vec(), ['C', 'X', 'A', 'D']);
+        assert_eq!(*mapped.borrow(), [1, 2, 3, 2, 4]);
+        ListChanges(changes.take().into()).apply(&mut mirror);
+        assert_eq!(mirror, view.to_vec());
+    }
+
+    #[test]
+    fn test_flat_map_follows_parents_and_children() {
+        let first = List::from(vec![1, 2]);
+        let second = List::from(vec![10]);
+        let parents = List::from(vec![Clone::clone(&first), Clone::clone(&second)]);
+        let flat = flat_map(&parents, |child: &List<i32>| Clone::clone(child));
+        let changes = recorded_changes(&flat);
+
+        second.push(20);
+        first.insert(0, 0);
+        let _ = parents.remove(0);
+        first.push(3);
+        second.set(0, 5);
+        parents.insert(0, List::from(vec![7]));
+
+        assert_eq!(flat.to_vec(), [7, 5, 20]);
+        assert_eq!(
+            *changes.borrow(),
+            [
+                ListChange::Insert {
+                    index: 3,
+                    value: 20
+                },
+                ListChange::Insert { index: 0, value: 0 },
+                ListChange::Remove { index: 0 },
+                ListChange::Remove { index: 0 },
+                ListChange::Remove { index: 0 },
+                ListChange::Update { index: 0, value: 5 },
+                ListChange::Insert { index: 0, value: 7 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zip_lists_unequal_lengths() {
+        let left = List::from(vec![1, 2, 3]);
+        let right = List::from(vec![10, 20]);
+        let sums = zip_lists(&left, &right, |a, b| a + b);
+        let changes = recorded_changes(&sums);
+
+        left.set(2, 4);
+        right.set(1, 30);
+        right.push(40);
+        let _ = left.remove(0);
+
+        assert_eq!(sums.to_vec(), [12, 34]);
+        assert_eq!(
+            *changes.borrow(),
+            [
+                ListChange::Update {
+                    index: 1,
+                    value: 32
+                },
+                ListChange::Insert {
+                    index: 2,
+                    value: 44
+                },
+                ListChange::Update {
+                    index: 0,
+                    value: 12
+                },
+                ListChange::Update {
+                    index: 1,
+                    value: 34
+                },
+                ListChange::Remove { index: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sorted_by_key_emits_moves() {
+        let list = List::from(vec![("b", 2), ("a", 1), ("c", 1), ("d", 3)]);
+        let descending = crate::binding(false);
+        let view = sorted_by_key(
+            &list,
+            &descending,
+            |descending, &(_, rank): &(&str, i32)| {
+                if *descending { -rank } else { rank }
+            },
+        );
+        let changes = recorded_changes(&view);
+        let mirror = RefCell::new(view.to_vec());
+        let replay = || {
+            ListChanges(changes.take().into()).apply(&mut mirror.borrow_mut());
+            assert_eq!(*mirror.borrow(), view.to_vec());
+        };
+
+        // Equal ranks keep their source order.
+        assert_eq!(view.to_vec(), [("a", 1), ("c", 1), ("b", 2), ("d", 3)]);
+
+        list.set(1, ("a", 4));
+        assert_eq!(
+            *changes.borrow(),
+            [
+                ListChange::Move { from: 0, to: 3 },
+                ListChange::Update {
+                    index: 3,
+                    value: ("a", 4)
+                },
+            ]
+        );
+        replay();
+
+        list.insert(0, ("e", 2));
+        assert_eq!(
+            view.to_vec(),
+            [("c", 1), ("e", 2), ("b", 2), ("d", 3), ("a", 4)]
+        );
+        replay();
+
+        descending.set(true);
+        assert_eq!(
+            view.to_vec(),
+            [("a", 4), ("d", 3), ("e", 2), ("b", 2), ("c", 1)]
+        );
+        assert!(
+            changes
+                .borrow()
+                .iter()
+                .all(|change| matches!(change, ListChange::Move { .. }))
+        );
+        assert_eq!(changes.borrow().len(), 3);
+        replay();
+    }
+
+    #[test]
+    fn test_reactive_map_entries_only_see_their_key() {
+        use crate::Signal;
+
+        let map = ReactiveMap::from(alloc::collections::BTreeMap::from([("a", 1)]));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _entry = {
+            let seen = seen.clone();
+            map.entry("a")
+                .watch(move |ctx| seen.borrow_mut().push(ctx.value))
+        };
+        let batches = Rc::new(RefCell::new(Vec::new()));
+        let _changes = {
+            let batches = batches.clone();
+            map.watch_changes(move |changes| batches.borrow_mut().push(changes.changes().to_vec()))
+        };
+
+        map.insert("b", 2);
+        assert_eq!(map.insert("a", 3), Some(1));
+        assert_eq!(map.remove(&"c"), None);
+        map.clear();
+
+        assert_eq!(*seen.borrow(), [Some(3), None]);
+        assert!(map.is_empty());
+        assert_eq!(
+            *batches.borrow(),
+            [
+                vec![MapChange::Insert { key: "b", value: 2 }],
+                vec![MapChange::Update { key: "a", value: 3 }],
+                vec![
+                    MapChange::Remove { key: "a" },
+                    MapChange::Remove { key: "b" }
+                ],
+            ]
+        );
+    }
 }
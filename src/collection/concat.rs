@@ -0,0 +1,68 @@
+use core::cell::Cell;
+
+use alloc::{rc::Rc, vec::Vec};
+
+use super::{Collection, List, ListChange, ListSource, ListView};
+
+/// Creates a live view of `first` followed by `second`.
+///
+/// Changes of either source are forwarded with their indices offset to the
+/// position of that source within the view, so sectioned lists such as pinned
+/// items followed by regular items can be watched as one list.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::collection::{Collection, List, concat};
+///
+/// let pinned = List::from(vec!["welcome"]);
+/// let recent = List::from(vec!["hello", "again"]);
+/// let feed = concat(&pinned, &recent);
+///
+/// recent.insert(0, "news");
+/// pinned.push("rules");
+/// assert_eq!(feed.to_vec(), ["welcome", "rules", "news", "hello", "again"]);
+/// assert_eq!(feed.len(), 5);
+/// ```
+pub fn concat<A, B, T>(first: &A, second: &B) -> ListView<T>
+where
+    A: ListSource<Item = T>,
+    B: ListSource<Item = T>,
+    T: Clone + 'static,
+{
+    let mut items = first.to_vec();
+    let first_len = Rc::new(Cell::new(items.len()));
+    items.extend(second.to_vec());
+    let list = List::from(items);
+
+    let first_guard = {
+        let list = list.clone();
+        let first_len = first_len.clone();
+        first.watch_changes(move |changes| {
+            for change in changes.changes() {
+                match change {
+                    ListChange::Insert { .. } => first_len.set(first_len.get() + 1),
+                    ListChange::Remove { .. } => first_len.set(first_len.get() - 1),
+                    ListChange::Update { .. } | ListChange::Move { .. } => {}
+                }
+            }
+            list.apply_changes(changes.changes().to_vec());
+        })
+    };
+
+    let second_guard = {
+        let list = list.clone();
+        second.watch_changes(move |changes| {
+            let offset = first_len.get();
+            let changes: Vec<_> = changes
+                .changes()
+                .iter()
+                .map(|change| change.shifted(offset))
+                .collect();
+            list.apply_changes(changes);
+        })
+    };
+
+    debug_assert_eq!(list.len(), first.len() + second.len());
+    ListView::new(list, (first_guard, second_guard))
+}
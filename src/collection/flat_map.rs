@@ -0,0 +1,237 @@
+use core::cell::RefCell;
+
+use alloc::{
+    rc::{Rc, Weak},
+    vec::Vec,
+};
+
+use super::{List, ListChange, ListChanges, ListSource, ListView};
+use crate::watcher::BoxWatcherGuard;
+
+/// The child list of one parent, together with its subscription.
+struct Child<C> {
+    id: usize,
+    len: usize,
+    _guard: BoxWatcherGuard,
+    _list: C,
+}
+
+struct State<C> {
+    next_id: usize,
+    children: Vec<Child<C>>,
+}
+
+impl<C> State<C> {
+    /// Returns the position of the child with `id` and the view index it starts at.
+    fn locate(&self, id: usize) -> Option<(usize, usize)> {
+        let mut offset = 0;
+        for (position, child) in self.children.iter().enumerate() {
+            if child.id == id {
+                return Some((position, offset));
+            }
+            offset += child.len;
+        }
+        None
+    }
+
+    fn offset_of(&self, position: usize) -> usize {
+        self.children[..position]
+            .iter()
+            .map(|child| child.len)
+            .sum()
+    }
+}
+
+/// Creates a live view of the child lists of every parent, flattened in order.
+///
+/// `f` is called once for every parent when it is inserted or updated, and the
+/// view follows changes of both the parent list and every child list. This
+/// renders trees as flat lists, such as the visible rows of an expanded outline.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::collection::{Collection, List, flat_map};
+///
+/// let sections = List::from(vec![List::from(vec!["a1", "a2"]), List::from(vec!["b1"])]);
+/// let rows = flat_map(&sections, |section: &List<&str>| section.clone());
+/// assert_eq!(rows.to_vec(), ["a1", "a2", "b1"]);
+///
+/// sections.get(0).unwrap().push("a3");
+/// sections.push(List::from(vec!["c1"]));
+/// let _ = sections.remove(1);
+/// assert_eq!(rows.to_vec(), ["a1", "a2", "a3", "c1"]);
+/// ```
+pub fn flat_map<S, P, C, T, F>(parents: &S, f: F) -> ListView<T>
+where
+    S: ListSource<Item = P>,
+    P: 'static,
+    C: ListSource<Item = T>,
+    T: Clone + 'static,
+    F: Fn(&P) -> C + 'static,
+{
+    let list = List::new();
+    let state = Rc::new(RefCell::new(State {
+        next_id: 0,
+        children: Vec::new(),
+    }));
+
+    let mut initial = Vec::new();
+    for parent in parents.to_vec() {
+        let child = f(&parent);
+        initial.extend(child.to_vec());
+        let child = subscribe(&state, &list, child);
+        state.borrow_mut().children.push(child);
+    }
+    list.apply_changes(
+        initial
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| ListChange::Insert { index, value })
+            .collect(),
+    );
+
+    let guard = {
+        let list = list.clone();
+        let state = state.clone();
+        parents.watch_changes(move |changes: &ListChanges<P>| {
+            let mut flattened = Vec::new();
+            for change in changes.changes() {
+                match change {
+                    ListChange::Insert { index, value } => {
+                        insert_child(&state, &list, &f, *index, value, &mut flattened);
+                    }
+                    ListChange::Remove { index } => {
+                        remove_child(&state, *index, &mut flattened);
+                    }
+                    ListChange::Update { index, value } => {
+                        remove_child(&state, *index, &mut flattened);
+                        insert_child(&state, &list, &f, *index, value, &mut flattened);
+                    }
+                    ListChange::Move { from, to } => {
+                        move_child(&state, *from, *to, &mut flattened);
+                    }
+                }
+            }
+            list.apply_changes(flattened);
+        })
+    };
+
+    ListView::new(list, (guard, state))
+}
+
+fn insert_child<P, C, T>(
+    state: &Rc<RefCell<State<C>>>,
+    list: &List<T>,
+    f: &impl Fn(&P) -> C,
+    position: usize,
+    parent: &P,
+    flattened: &mut Vec<ListChange<T>>,
+) where
+    C: ListSource<Item = T>,
+    T: Clone + 'static,
+{
+    let child = f(parent);
+    let offset = state.borrow().offset_of(position);
+    flattened.extend(
+        child
+            .to_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| ListChange::Insert {
+                index: offset + index,
+                value,
+            }),
+    );
+    let child = subscribe(state, list, child);
+    state.borrow_mut().children.insert(position, child);
+}
+
+fn remove_child<C, T>(
+    state: &Rc<RefCell<State<C>>>,
+    position: usize,
+    flattened: &mut Vec<ListChange<T>>,
+) {
+    let mut state = state.borrow_mut();
+    let offset = state.offset_of(position);
+    let child = state.children.remove(position);
+    flattened.extend((0..child.len).map(|_| ListChange::Remove { index: offset }));
+}
+
+fn move_child<C, T>(
+    state: &Rc<RefCell<State<C>>>,
+    from: usize,
+    to: usize,
+    flattened: &mut Vec<ListChange<T>>,
+) {
+    let mut state = state.borrow_mut();
+    let start = state.offset_of(from);
+    let child = state.children.remove(from);
+    let len = child.len;
+    let target = state.offset_of(to);
+    state.children.insert(to, child);
+
+    // Elements are moved one at a time, keeping the block in order.
+    if target >= start {
+        flattened.extend((0..len).map(|_| ListChange::Move {
+            from: start,
+            to: target + len - 1,
+        }));
+    } else {
+        flattened.extend((0..len).map(|moved| ListChange::Move {
+            from: start + moved,
+            to: target + moved,
+        }));
+    }
+}
+
+/// Subscribes to `child`, forwarding its changes to `list` at the child's offset.
+fn subscribe<C, T>(state: &Rc<RefCell<State<C>>>, list: &List<T>, child: C) -> Child<C>
+where
+    C: ListSource<Item = T>,
+    T: Clone + 'static,
+{
+    let id = {
+        let mut state = state.borrow_mut();
+        state.next_id += 1;
+        state.next_id
+    };
+    let len = child.len();
+
+    let guard = {
+        let list = list.clone();
+        let state: Weak<RefCell<State<C>>> = Rc::downgrade(state);
+        child.watch_changes(move |changes: &ListChanges<T>| {
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            let shifted = {
+                let mut state = state.borrow_mut();
+                let Some((position, offset)) = state.locate(id) else {
+                    return;
+                };
+                let child = &mut state.children[position];
+                for change in changes.changes() {
+                    match change {
+                        ListChange::Insert { .. } => child.len += 1,
+                        ListChange::Remove { .. } => child.len -= 1,
+                        ListChange::Update { .. } | ListChange::Move { .. } => {}
+                    }
+                }
+                changes
+                    .changes()
+                    .iter()
+                    .map(|change| change.shifted(offset))
+                    .collect()
+            };
+            list.apply_changes(shifted);
+        })
+    };
+
+    Child {
+        id,
+        len,
+        _guard: guard,
+        _list: child,
+    }
+}
@@ -0,0 +1,324 @@
+use core::{cell::RefCell, fmt::Debug};
+
+use alloc::{collections::BTreeMap, rc::Rc, vec::Vec};
+
+use crate::{
+    NodeId, Signal,
+    watcher::{BoxWatcherGuard, Context, Metadata, WatcherManager, WatcherManagerGuard},
+};
+
+/// A reactive map whose notifications say which keys changed.
+///
+/// As a signal, it emits a copy of the whole map, with [`MapChanges`] attached
+/// as metadata. [`entry`](Self::entry) returns a signal of the value of a
+/// single key, which is only notified when that key is inserted, updated or
+/// removed, so a view of one entry is not re-rendered when others change.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::{Signal, collection::{MapChange, ReactiveMap}};
+///
+/// let prices = ReactiveMap::new();
+/// prices.insert("apple", 3);
+/// let apple = prices.entry("apple");
+/// let _guard = apple.watch(|ctx| println!("apples now cost {:?}", ctx.value));
+///
+/// let changes = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+/// let _changes = {
+///     let changes = changes.clone();
+///     prices.watch_changes(move |batch| changes.borrow_mut().extend_from_slice(batch.changes()))
+/// };
+///
+/// prices.insert("pear", 5); // `apple` is not notified.
+/// prices.insert("apple", 4);
+/// assert_eq!(apple.get(), Some(4));
+/// assert_eq!(changes.borrow()[1], MapChange::Update { key: "apple", value: 4 });
+/// ```
+pub struct ReactiveMap<K, V> {
+    map: Rc<RefCell<BTreeMap<K, V>>>,
+    watchers: WatcherManager<BTreeMap<K, V>>,
+    /// The watchers of single entries, by key.
+    entries: Rc<RefCell<BTreeMap<K, WatcherManager<Option<V>>>>>,
+    id: NodeId,
+}
+
+/// A single change to a [`ReactiveMap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MapChange<K, V> {
+    /// `key` was inserted with `value`.
+    Insert {
+        /// The new key.
+        key: K,
+        /// Its value.
+        value: V,
+    },
+    /// The value of `key` was replaced by `value`.
+    Update {
+        /// The updated key.
+        key: K,
+        /// Its new value.
+        value: V,
+    },
+    /// `key` was removed.
+    Remove {
+        /// The removed key.
+        key: K,
+    },
+}
+
+impl<K, V> MapChange<K, V> {
+    /// Returns the key this change applies to.
+    pub const fn key(&self) -> &K {
+        match self {
+            Self::Insert { key, .. } | Self::Update { key, .. } | Self::Remove { key } => key,
+        }
+    }
+}
+
+/// Metadata attached to every notification of a [`ReactiveMap`], describing
+/// how its entries changed.
+#[derive(Debug)]
+pub struct MapChanges<K, V>(Rc<[MapChange<K, V>]>);
+
+impl<K, V> Clone for MapChanges<K, V> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<K, V> MapChanges<K, V> {
+    /// Returns the changes in the order they were applied.
+    #[must_use]
+    pub fn changes(&self) -> &[MapChange<K, V>] {
+        &self.0
+    }
+}
+
+impl<K, V> Clone for ReactiveMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            watchers: self.watchers.clone(),
+            entries: self.entries.clone(),
+            id: self.id,
+        }
+    }
+}
+
+impl<K: Debug + 'static, V: Debug + 'static> Debug for ReactiveMap<K, V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReactiveMap")
+            .field("entries", &*self.map.borrow())
+            .field("watchers", &self.watchers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: Ord + Clone + 'static, V: Clone + 'static> Default for ReactiveMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: 'static, V: 'static> From<BTreeMap<K, V>> for ReactiveMap<K, V> {
+    fn from(map: BTreeMap<K, V>) -> Self {
+        Self {
+            map: Rc::new(RefCell::new(map)),
+            watchers: WatcherManager::new(),
+            entries: Rc::default(),
+            id: NodeId::next(),
+        }
+    }
+}
+
+impl<K: Ord + Clone + 'static, V: Clone + 'static> ReactiveMap<K, V> {
+    /// Creates an empty reactive map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from(BTreeMap::new())
+    }
+
+    /// Returns a copy of the value of `key`.
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.map.borrow().get(key).cloned()
+    }
+
+    /// Returns `true` if the map has a value for `key`.
+    #[must_use]
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.borrow().contains_key(key)
+    }
+
+    /// Returns the number of entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.map.borrow().len()
+    }
+
+    /// Returns `true` if the map has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.borrow().is_empty()
+    }
+
+    /// Returns the keys, in order.
+    #[must_use]
+    pub fn keys(&self) -> Vec<K> {
+        self.map.borrow().keys().cloned().collect()
+    }
+
+    /// Returns a copy of the entries.
+    #[must_use]
+    pub fn to_map(&self) -> BTreeMap<K, V> {
+        self.map.borrow().clone()
+    }
+
+    /// Sets the value of `key`, returning its previous value.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let previous = self.map.borrow_mut().insert(key.clone(), value.clone());
+        let change = if previous.is_some() {
+            MapChange::Update { key, value }
+        } else {
+            MapChange::Insert { key, value }
+        };
+        self.notify(alloc::vec![change]);
+        previous
+    }
+
+    /// Removes `key`, returning its value.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let removed = self.map.borrow_mut().remove(key)?;
+        self.notify(alloc::vec![MapChange::Remove { key: key.clone() }]);
+        Some(removed)
+    }
+
+    /// Removes every entry.
+    pub fn clear(&self) {
+        let removed = core::mem::take(&mut *self.map.borrow_mut());
+        if !removed.is_empty() {
+            self.notify(
+                removed
+                    .into_keys()
+                    .map(|key| MapChange::Remove { key })
+                    .collect(),
+            );
+        }
+    }
+
+    /// Returns a signal of the value of `key`, which is only notified when
+    /// `key` is inserted, updated or removed.
+    #[must_use]
+    pub fn entry(&self, key: K) -> Entry<K, V> {
+        Entry {
+            map: self.clone(),
+            key,
+        }
+    }
+
+    /// Registers a watcher receiving the changes of every notification.
+    ///
+    /// Unlike [`watch`](Signal::watch), the watcher is given the changes rather
+    /// than a copy of the whole map.
+    pub fn watch_changes(
+        &self,
+        watcher: impl Fn(&MapChanges<K, V>) + 'static,
+    ) -> WatcherManagerGuard<BTreeMap<K, V>> {
+        self.watchers.register_as_guard(move |ctx| {
+            if let Some(changes) = ctx.metadata.try_get::<MapChanges<K, V>>() {
+                watcher(&changes);
+            }
+        })
+    }
+
+    /// Notifies the watchers of the changed entries, then the watchers of the
+    /// whole map.
+    fn notify(&self, changes: Vec<MapChange<K, V>>) {
+        let metadata = Metadata::new().with(MapChanges(changes.into()));
+        let Some(changes) = metadata.try_get::<MapChanges<K, V>>() else {
+            return;
+        };
+        for change in changes.changes() {
+            let key = change.key();
+            let watchers = {
+                let mut entries = self.entries.borrow_mut();
+                match entries.get(key) {
+                    Some(watchers) if watchers.is_empty() => {
+                        entries.remove(key);
+                        None
+                    }
+                    watchers => watchers.cloned(),
+                }
+            };
+            if let Some(watchers) = watchers {
+                let value = self.get(key);
+                watchers.notify(move || value.clone(), &metadata);
+            }
+        }
+        let map = self.map.clone();
+        self.watchers
+            .notify(move || map.borrow().clone(), &metadata);
+    }
+}
+
+impl<K: Ord + Clone + 'static, V: Clone + 'static> Signal for ReactiveMap<K, V> {
+    type Output = BTreeMap<K, V>;
+    type Guard = WatcherManagerGuard<BTreeMap<K, V>>;
+
+    fn get(&self) -> Self::Output {
+        self.to_map()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.watchers.register_as_guard(watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        Some(self.id)
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        Some(self.watchers.len())
+    }
+}
+
+/// A signal of the value of a single key of a [`ReactiveMap`], created by
+/// [`ReactiveMap::entry`].
+///
+/// It is `None` while the map has no value for the key.
+#[derive(Clone)]
+pub struct Entry<K, V> {
+    map: ReactiveMap<K, V>,
+    key: K,
+}
+
+impl<K: Ord + Debug, V: Debug> Debug for Entry<K, V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Entry")
+            .field("key", &self.key)
+            .field("value", &self.map.map.borrow().get(&self.key))
+            .finish()
+    }
+}
+
+impl<K: Ord + Clone + 'static, V: Clone + 'static> Signal for Entry<K, V> {
+    type Output = Option<V>;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> Self::Output {
+        self.map.get(&self.key)
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let watchers = self
+            .map
+            .entries
+            .borrow_mut()
+            .entry(self.key.clone())
+            .or_default()
+            .clone();
+        alloc::boxed::Box::new(watchers.register_as_guard(watcher))
+    }
+}
@@ -0,0 +1,150 @@
+use alloc::{rc::Rc, vec::Vec};
+use core::cell::RefCell;
+
+use super::{List, ListChange, ListSource, ListView, diff_lists};
+use crate::Signal;
+
+/// Creates a live view of `f(item)` for every item of `source`.
+///
+/// Only the items a change touches are mapped again: inserting or updating an
+/// item maps that item, while removals and moves are forwarded as they are, so
+/// the mapped values of untouched items, such as row view models, are kept.
+///
+/// # Example
+///
+/// ```rust
+/// use core::cell::Cell;
+/// use std::rc::Rc;
+/// use nami::collection::{List, map_items};
+///
+/// let calls = Rc::new(Cell::new(0));
+/// let names = List::from(vec!["ada", "grace"]);
+/// let labels = map_items(&names, {
+///     let calls = calls.clone();
+///     move |name: &&str| {
+///         calls.set(calls.get() + 1);
+///         name.to_uppercase()
+///     }
+/// });
+///
+/// names.insert(1, "barbara");
+/// names.remove(0);
+/// assert_eq!(labels.to_vec(), ["BARBARA", "GRACE"]);
+/// assert_eq!(calls.get(), 3);
+/// ```
+pub fn map_items<S, T, U, F>(source: &S, f: F) -> ListView<U>
+where
+    S: ListSource<Item = T>,
+    T: Clone + 'static,
+    U: Clone + 'static,
+    F: Fn(&T) -> U + 'static,
+{
+    let list = List::from(source.to_vec().iter().map(&f).collect());
+
+    let guard = {
+        let list = list.clone();
+        source.watch_changes(move |changes| {
+            let changes: Vec<_> = changes
+                .changes()
+                .iter()
+                .map(|change| match change {
+                    ListChange::Insert { index, value } => ListChange::Insert {
+                        index: *index,
+                        value: f(value),
+                    },
+                    ListChange::Update { index, value } => ListChange::Update {
+                        index: *index,
+                        value: f(value),
+                    },
+                    ListChange::Remove { index } => ListChange::Remove { index: *index },
+                    ListChange::Move { from, to } => ListChange::Move {
+                        from: *from,
+                        to: *to,
+                    },
+                })
+                .collect();
+            list.apply_changes(changes);
+        })
+    };
+
+    ListView::new(list, guard)
+}
+
+/// Creates a live view of `f(item)` for every item of a signal of a `Vec`,
+/// matching items across updates by `key`.
+///
+/// Whenever the signal changes, its new items are diffed against the previous
+/// ones by key. `f` is only called for items with a new key or a changed value:
+/// the results of unchanged items, including reordered ones, are reused, and
+/// the view reports the difference as [`ListChanges`](super::ListChanges).
+///
+/// # Example
+///
+/// ```rust
+/// use core::cell::Cell;
+/// use std::rc::Rc;
+/// use nami::{binding, Binding, collection::map_keyed};
+///
+/// let calls = Rc::new(Cell::new(0));
+/// let users: Binding<Vec<(u32, &str)>> = binding(vec![(1, "ada"), (2, "grace")]);
+/// let rows = map_keyed(&users, |&(id, _)| id, {
+///     let calls = calls.clone();
+///     move |&(id, name): &(u32, &str)| {
+///         calls.set(calls.get() + 1);
+///         format!("#{id} {name}")
+///     }
+/// });
+///
+/// users.set(vec![(2, "grace"), (1, "ada"), (3, "edsger")]);
+/// assert_eq!(rows.to_vec(), ["#2 grace", "#1 ada", "#3 edsger"]);
+/// assert_eq!(calls.get(), 3);
+/// ```
+pub fn map_keyed<S, T, K, U>(
+    source: &S,
+    key: impl Fn(&T) -> K + 'static,
+    f: impl Fn(&T) -> U + 'static,
+) -> ListView<U>
+where
+    S: Signal<Output = Vec<T>>,
+    T: Clone + PartialEq + 'static,
+    K: PartialEq,
+    U: Clone + 'static,
+{
+    let items = source.get();
+    let list = List::from(items.iter().map(&f).collect());
+    let previous = Rc::new(RefCell::new(items));
+
+    let guard = {
+        let list = list.clone();
+        source.watch(move |ctx| {
+            let old_items = previous.replace(ctx.value);
+            let new_items = previous.borrow();
+            let old_values = list.to_vec();
+            // Reuses the result of an equal item that was removed or moved.
+            let map = |value: T| {
+                old_items
+                    .iter()
+                    .position(|old| *old == value)
+                    .map_or_else(|| f(&value), |index| old_values[index].clone())
+            };
+            let changes = diff_lists(&old_items, &new_items, &key, false)
+                .into_iter()
+                .map(|change| match change {
+                    ListChange::Insert { index, value } => ListChange::Insert {
+                        index,
+                        value: map(value),
+                    },
+                    ListChange::Update { index, value } => ListChange::Update {
+                        index,
+                        value: f(&value),
+                    },
+                    ListChange::Remove { index } => ListChange::Remove { index },
+                    ListChange::Move { from, to } => ListChange::Move { from, to },
+                })
+                .collect();
+            list.apply_changes(changes);
+        })
+    };
+
+    ListView::new(list, guard)
+}
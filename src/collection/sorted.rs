@@ -0,0 +1,335 @@
+use core::cell::RefCell;
+
+use alloc::{rc::Rc, vec, vec::Vec};
+
+use super::{List, ListChange, ListChanges, ListSource, ListView};
+use crate::{Signal, constant, watcher::Context};
+
+/// The source contents of a sorted view and the order they are shown in.
+///
+/// Elements without a key are filtered out of the view.
+struct State<T, K, V> {
+    items: Vec<T>,
+    keys: Vec<Option<K>>,
+    /// The source index of the element at each position of the view.
+    order: Vec<usize>,
+    criterion: V,
+}
+
+impl<T: Clone, K: Ord, V> State<T, K, V> {
+    /// Returns the view position for the source element at `index`, which must
+    /// have a key and not be part of `order`.
+    fn position(&self, index: usize) -> usize {
+        let key = (&self.keys[index], index);
+        self.order
+            .partition_point(|&other| (&self.keys[other], other) < key)
+    }
+
+    fn view_position(&self, index: usize) -> Option<usize> {
+        self.order.iter().position(|&other| other == index)
+    }
+
+    fn insert(&mut self, index: usize, value: T, key: Option<K>, changes: &mut Vec<ListChange<T>>) {
+        for other in &mut self.order {
+            if *other >= index {
+                *other += 1;
+            }
+        }
+        let shown = key.is_some();
+        self.items.insert(index, value.clone());
+        self.keys.insert(index, key);
+        if shown {
+            let position = self.position(index);
+            self.order.insert(position, index);
+            changes.push(ListChange::Insert {
+                index: position,
+                value,
+            });
+        }
+    }
+
+    fn remove(&mut self, index: usize, changes: &mut Vec<ListChange<T>>) -> T {
+        if let Some(position) = self.view_position(index) {
+            self.order.remove(position);
+            changes.push(ListChange::Remove { index: position });
+        }
+        for other in &mut self.order {
+            if *other > index {
+                *other -= 1;
+            }
+        }
+        self.keys.remove(index);
+        self.items.remove(index)
+    }
+
+    fn update(&mut self, index: usize, value: T, key: Option<K>, changes: &mut Vec<ListChange<T>>) {
+        let from = self.view_position(index);
+        if let Some(from) = from {
+            self.order.remove(from);
+        }
+        let shown = key.is_some();
+        self.items[index] = value.clone();
+        self.keys[index] = key;
+        if !shown {
+            if let Some(from) = from {
+                changes.push(ListChange::Remove { index: from });
+            }
+            return;
+        }
+
+        let to = self.position(index);
+        self.order.insert(to, index);
+        match from {
+            Some(from) => {
+                if from != to {
+                    changes.push(ListChange::Move { from, to });
+                }
+                changes.push(ListChange::Update { index: to, value });
+            }
+            None => changes.push(ListChange::Insert { index: to, value }),
+        }
+    }
+
+    /// Re-sorts the view after the keys changed, returning the changes that
+    /// turn the previous view into the new one.
+    fn resort(&mut self) -> Vec<ListChange<T>> {
+        let mut changes = Vec::new();
+        let mut position = 0;
+        while position < self.order.len() {
+            if self.keys[self.order[position]].is_some() {
+                position += 1;
+            } else {
+                self.order.remove(position);
+                changes.push(ListChange::Remove { index: position });
+            }
+        }
+
+        let mut target: Vec<usize> = (0..self.items.len())
+            .filter(|&index| self.keys[index].is_some())
+            .collect();
+        target.sort_by(|&a, &b| (&self.keys[a], a).cmp(&(&self.keys[b], b)));
+
+        let mut rank = vec![0; self.items.len()];
+        for (position, &index) in target.iter().enumerate() {
+            rank[index] = position;
+        }
+        let ranks: Vec<usize> = self.order.iter().map(|&index| rank[index]).collect();
+        let mut settled = vec![false; self.items.len()];
+        for position in longest_increasing(&ranks) {
+            settled[self.order[position]] = true;
+        }
+
+        // Elements outside the longest subsequence already in sorted order are
+        // moved or inserted, in their new order, directly after their new
+        // predecessor.
+        for (position, &index) in target.iter().enumerate() {
+            if settled[index] {
+                continue;
+            }
+            let from = self.view_position(index);
+            if let Some(from) = from {
+                self.order.remove(from);
+            }
+            let to = match position {
+                0 => 0,
+                _ => self
+                    .view_position(target[position - 1])
+                    .map_or(0, |p| p + 1),
+            };
+            self.order.insert(to, index);
+            match from {
+                Some(from) if from != to => changes.push(ListChange::Move { from, to }),
+                Some(_) => {}
+                None => changes.push(ListChange::Insert {
+                    index: to,
+                    value: self.items[index].clone(),
+                }),
+            }
+        }
+        debug_assert_eq!(self.order, target);
+        changes
+    }
+}
+
+/// Returns the positions of a longest strictly increasing subsequence of `values`.
+fn longest_increasing(values: &[usize]) -> Vec<usize> {
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; values.len()];
+    for (position, value) in values.iter().enumerate() {
+        let length = tails.partition_point(|&tail| values[tail] < *value);
+        previous[position] = length.checked_sub(1).map(|length| tails[length]);
+        if length == tails.len() {
+            tails.push(position);
+        } else {
+            tails[length] = position;
+        }
+    }
+
+    let mut positions = Vec::with_capacity(tails.len());
+    let mut current = tails.last().copied();
+    while let Some(position) = current {
+        positions.push(position);
+        current = previous[position];
+    }
+    positions.reverse();
+    positions
+}
+
+/// Creates a live view of `source` in ascending order.
+///
+/// This is [`sorted_by_key`] with the elements as their own keys.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::collection::{List, sorted};
+///
+/// let scores = List::from(vec![30, 10, 20]);
+/// let ranking = sorted(&scores);
+/// scores.push(15);
+/// assert_eq!(ranking.to_vec(), [10, 15, 20, 30]);
+/// ```
+pub fn sorted<S, T>(source: &S) -> ListView<T>
+where
+    S: ListSource<Item = T>,
+    T: Ord + Clone + 'static,
+{
+    sorted_by_key(source, &constant(()), |(), value: &T| value.clone())
+}
+
+/// Creates a live view of `source` sorted by `key`, which depends on the value
+/// of the `criterion` signal.
+///
+/// The sort is stable: elements with equal keys keep their order in the source.
+/// Changes of the source are placed at their sorted position, and an updated
+/// element whose key changed is reported as a [`ListChange::Move`] followed by
+/// its update. When the criterion changes, for example because the user picked
+/// a different sort column, the view is re-sorted with as few moves as possible
+/// instead of being rebuilt.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::{Binding, binding, collection::{List, sorted_by_key}};
+///
+/// #[derive(Clone, Copy)]
+/// enum Column {
+///     Name,
+///     Size,
+/// }
+///
+/// let files = List::from(vec![("b.txt", 30), ("c.txt", 10), ("a.txt", 20)]);
+/// let column: Binding<Column> = binding(Column::Name);
+/// let view = sorted_by_key(&files, &column, |column, &(name, size)| match column {
+///     Column::Name => (name, 0),
+///     Column::Size => ("", size),
+/// });
+/// assert_eq!(view.to_vec(), [("a.txt", 20), ("b.txt", 30), ("c.txt", 10)]);
+///
+/// column.set(Column::Size);
+/// assert_eq!(view.to_vec(), [("c.txt", 10), ("a.txt", 20), ("b.txt", 30)]);
+/// ```
+pub fn sorted_by_key<S, C, T, K, F>(source: &S, criterion: &C, key: F) -> ListView<T>
+where
+    S: ListSource<Item = T>,
+    C: Signal,
+    T: Clone + 'static,
+    K: Ord + 'static,
+    F: Fn(&C::Output, &T) -> K + 'static,
+{
+    filter_sorted_by_key(source, criterion, move |criterion, item| {
+        Some(key(criterion, item))
+    })
+}
+
+/// Creates a live view of the elements of `source` for which `key` returns a
+/// key, sorted by that key.
+///
+/// This behaves like [`sorted_by_key`], except that elements are inserted into
+/// and removed from the view as their key appears and disappears.
+pub fn filter_sorted_by_key<S, C, T, K, F>(source: &S, criterion: &C, key: F) -> ListView<T>
+where
+    S: ListSource<Item = T>,
+    C: Signal,
+    T: Clone + 'static,
+    K: Ord + 'static,
+    F: Fn(&C::Output, &T) -> Option<K> + 'static,
+{
+    let items = source.to_vec();
+    let criterion_value = criterion.get();
+    let keys: Vec<Option<K>> = items
+        .iter()
+        .map(|item| key(&criterion_value, item))
+        .collect();
+    let mut order: Vec<usize> = (0..items.len())
+        .filter(|&index| keys[index].is_some())
+        .collect();
+    order.sort_by(|&a, &b| (&keys[a], a).cmp(&(&keys[b], b)));
+
+    let list = List::from(
+        order
+            .iter()
+            .map(|&index| items[index].clone())
+            .collect::<Vec<_>>(),
+    );
+    let state = Rc::new(RefCell::new(State {
+        items,
+        keys,
+        order,
+        criterion: criterion_value,
+    }));
+    let key = Rc::new(key);
+
+    let source_guard = {
+        let list = list.clone();
+        let state = state.clone();
+        let key = key.clone();
+        source.watch_changes(move |changes: &ListChanges<T>| {
+            let mut sorted = Vec::new();
+            {
+                let mut state = state.borrow_mut();
+                for change in changes.changes() {
+                    match change {
+                        ListChange::Insert { index, value } => {
+                            let value_key = key(&state.criterion, value);
+                            state.insert(*index, value.clone(), value_key, &mut sorted);
+                        }
+                        ListChange::Remove { index } => {
+                            state.remove(*index, &mut sorted);
+                        }
+                        ListChange::Update { index, value } => {
+                            let value_key = key(&state.criterion, value);
+                            state.update(*index, value.clone(), value_key, &mut sorted);
+                        }
+                        ListChange::Move { from, to } => {
+                            let value = state.remove(*from, &mut sorted);
+                            let value_key = key(&state.criterion, &value);
+                            state.insert(*to, value, value_key, &mut sorted);
+                        }
+                    }
+                }
+            }
+            list.apply_changes(sorted);
+        })
+    };
+
+    let criterion_guard = {
+        let list = list.clone();
+        criterion.watch(move |context: Context<C::Output>| {
+            let changes = {
+                let mut state = state.borrow_mut();
+                let keys = state
+                    .items
+                    .iter()
+                    .map(|item| key(&context.value, item))
+                    .collect();
+                state.keys = keys;
+                state.criterion = context.value;
+                state.resort()
+            };
+            list.apply_changes(changes);
+        })
+    };
+
+    ListView::new(list, (source_guard, criterion_guard))
+}
@@ -0,0 +1,86 @@
+use core::{any::Any, fmt::Debug, ops::RangeBounds};
+
+use alloc::{rc::Rc, vec::Vec};
+
+use super::{Collection, List, ListChanges, ListSource};
+use crate::watcher::{BoxWatcherGuard, Context, WatcherManagerGuard};
+
+/// A read-only list maintained from one or more source lists.
+///
+/// Views keep their sources subscribed for as long as any clone of the view is
+/// alive, and report their own changes as [`ListChanges`], so they can be
+/// watched incrementally and used as sources of further views.
+pub struct ListView<T> {
+    list: List<T>,
+    sources: Rc<dyn Any>,
+}
+
+impl<T> Clone for ListView<T> {
+    fn clone(&self) -> Self {
+        Self {
+            list: self.list.clone(),
+            sources: self.sources.clone(),
+        }
+    }
+}
+
+impl<T: Debug + 'static> Debug for ListView<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ListView")
+            .field("items", &*self.list.vec.borrow())
+            .field("watchers", &self.list.watchers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static> ListView<T> {
+    /// Creates a view backed by `list`, keeping `sources` alive alongside it.
+    ///
+    /// `list` must only be modified by the subscriptions held in `sources`.
+    pub(crate) fn new(list: List<T>, sources: impl Any) -> Self {
+        Self {
+            list,
+            sources: Rc::new(sources),
+        }
+    }
+
+    /// Returns a copy of the current contents.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.list.to_vec()
+    }
+}
+
+impl<T: Clone + 'static> Collection for ListView<T> {
+    type Item = T;
+    type Guard = WatcherManagerGuard<Vec<T>>;
+
+    fn get(&self, index: usize) -> Option<Self::Item> {
+        Collection::get(&self.list, index)
+    }
+
+    fn len(&self) -> usize {
+        Collection::len(&self.list)
+    }
+
+    fn watch(
+        &self,
+        range: impl RangeBounds<usize>,
+        watcher: impl Fn(Context<Vec<Self::Item>>) + 'static,
+    ) -> Self::Guard {
+        Collection::watch(&self.list, range, watcher)
+    }
+}
+
+impl<T: Clone + 'static> ListSource for ListView<T> {
+    fn to_vec(&self) -> Vec<T> {
+        self.list.to_vec()
+    }
+
+    fn watch_changes(&self, watcher: impl Fn(&ListChanges<T>) + 'static) -> BoxWatcherGuard {
+        self.list.watch_changes(watcher)
+    }
+}
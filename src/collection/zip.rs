@@ -0,0 +1,145 @@
+use core::cell::RefCell;
+
+use alloc::{rc::Rc, vec::Vec};
+
+use super::{List, ListChange, ListChanges, ListSource, ListView};
+
+/// The current contents of both sources of a [`zip_lists`] view.
+struct Sides<A, B> {
+    left: Vec<A>,
+    right: Vec<B>,
+}
+
+impl<A, B> Sides<A, B> {
+    fn len(&self) -> usize {
+        self.left.len().min(self.right.len())
+    }
+
+    /// Returns the view changes after `changes` were applied to one side, when
+    /// the view had `old_len` elements before.
+    fn refresh<U, T>(
+        &self,
+        f: &impl Fn(&A, &B) -> T,
+        old_len: usize,
+        changes: &[ListChange<U>],
+    ) -> Vec<ListChange<T>> {
+        let new_len = self.len();
+        let value = |index: usize| f(&self.left[index], &self.right[index]);
+        let structural = changes
+            .iter()
+            .any(|change| !matches!(change, ListChange::Update { .. }));
+
+        if !structural {
+            let mut updated: Vec<usize> = changes
+                .iter()
+                .map(ListChange::index)
+                .filter(|index| *index < new_len)
+                .collect();
+            updated.sort_unstable();
+            updated.dedup();
+            return updated
+                .into_iter()
+                .map(|index| ListChange::Update {
+                    index,
+                    value: value(index),
+                })
+                .collect();
+        }
+
+        // An insertion, removal or move on one side pairs every later element with a
+        // different partner, so everything from the first change onwards is updated.
+        let first = changes
+            .iter()
+            .map(|change| match change {
+                ListChange::Move { from, to } => *from.min(to),
+                _ => change.index(),
+            })
+            .min()
+            .unwrap_or(0);
+        let mut result: Vec<_> = (first..old_len.min(new_len))
+            .map(|index| ListChange::Update {
+                index,
+                value: value(index),
+            })
+            .collect();
+        result.extend((old_len..new_len).map(|index| ListChange::Insert {
+            index,
+            value: value(index),
+        }));
+        result.extend((new_len..old_len).map(|_| ListChange::Remove { index: new_len }));
+        result
+    }
+}
+
+/// Creates a live view of `f(left[i], right[i])` for every index of both sources.
+///
+/// The view is as long as the shorter source; elements of the longer source
+/// without a partner are left out until the other source grows. Updating an
+/// element only recomputes its own pair, while inserting or removing elements
+/// recomputes the pairs from that position onwards, since they change partners.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::collection::{List, zip_lists};
+///
+/// let revenue = List::from(vec![120, 80, 95]);
+/// let costs = List::from(vec![100, 90]);
+/// let profit = zip_lists(&revenue, &costs, |revenue, cost| revenue - cost);
+/// assert_eq!(profit.to_vec(), [20, -10]);
+///
+/// costs.push(70);
+/// revenue.set(0, 150);
+/// assert_eq!(profit.to_vec(), [50, -10, 25]);
+/// ```
+pub fn zip_lists<L, R, A, B, T, F>(left: &L, right: &R, f: F) -> ListView<T>
+where
+    L: ListSource<Item = A>,
+    R: ListSource<Item = B>,
+    A: Clone + 'static,
+    B: Clone + 'static,
+    T: Clone + 'static,
+    F: Fn(&A, &B) -> T + 'static,
+{
+    let sides = Sides {
+        left: left.to_vec(),
+        right: right.to_vec(),
+    };
+    let list = List::from(
+        (0..sides.len())
+            .map(|index| f(&sides.left[index], &sides.right[index]))
+            .collect::<Vec<_>>(),
+    );
+    let sides = Rc::new(RefCell::new(sides));
+    let f = Rc::new(f);
+
+    let left_guard = {
+        let list = list.clone();
+        let sides = sides.clone();
+        let f = f.clone();
+        left.watch_changes(move |changes: &ListChanges<A>| {
+            let changes = {
+                let mut sides = sides.borrow_mut();
+                let old_len = sides.len();
+                changes.apply(&mut sides.left);
+                sides.refresh(&*f, old_len, changes.changes())
+            };
+            list.apply_changes(changes);
+        })
+    };
+
+    let right_guard = {
+        let list = list.clone();
+        right.watch_changes(move |changes: &ListChanges<B>| {
+            let changes = {
+                let mut sides = sides.borrow_mut();
+                let old_len = sides.len();
+                changes.apply(&mut sides.right);
+                sides.refresh(&*f, old_len, changes.changes())
+            };
+            list.apply_changes(changes);
+        })
+    };
+
+    ListView::new(list, (left_guard, right_guard))
+}
@@ -0,0 +1,314 @@
+//! # Constraints
+//!
+//! A [`Map`](crate::map::Map) keeps a value derived from others, in one
+//! direction. Some relationships are multi-directional instead, such as a form
+//! with a net price, a tax and a total price, where editing any of the three
+//! fields updates the others. A [`Solver`] keeps such relationships between
+//! bindings.
+//!
+//! A relationship is declared with [`Solver::relate`] as a function computing
+//! any of its variables from the others, or with helpers such as
+//! [`Solver::sum`]. When one of the bindings is edited, the solver updates
+//! another variable of each relationship involving it, then the relationships
+//! involving the updated variables, and so on. Each variable is updated at
+//! most once per edit, and the edited one is never overwritten.
+//!
+//! Among the variables of a relationship, the solver updates the one with the
+//! lowest priority, given to [`Solver::variable`]. Ties are broken by updating
+//! the variable edited least recently, so the values the user entered last
+//! are kept. A relationship whose variables are all fixed by the current edit
+//! is left unsatisfied until a later edit.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, constraint::Solver};
+//!
+//! let total: Binding<i32> = binding(0);
+//! let net: Binding<i32> = binding(0);
+//! let tax: Binding<i32> = binding(0);
+//!
+//! let solver = Solver::new();
+//! let total_var = solver.variable(total.clone(), 0);
+//! let net_var = solver.variable(net.clone(), 1);
+//! let tax_var = solver.variable(tax.clone(), 1);
+//! solver.sum(total_var, &[net_var, tax_var]);
+//!
+//! // The total has the lowest priority, so it follows its parts.
+//! net.set(100);
+//! tax.set(20);
+//! assert_eq!(total.get(), 120);
+//!
+//! // Editing the total updates the part edited least recently.
+//! total.set(150);
+//! assert_eq!((net.get(), tax.get()), (130, 20));
+//! ```
+
+use alloc::{
+    collections::{BTreeSet, VecDeque},
+    rc::{Rc, Weak},
+    vec::Vec,
+};
+use core::{
+    cell::{Cell, RefCell},
+    fmt,
+    ops::{Add, Sub},
+};
+
+use crate::{Binding, Signal, watcher::BoxWatcherGuard};
+
+/// A variable of a [`Solver`], created by [`Solver::variable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Var(usize);
+
+/// Keeps multi-directional relationships between bindings.
+///
+/// All clones share the same variables and relationships. See the
+/// [module documentation](self) for how it behaves.
+pub struct Solver<T: Clone + PartialEq + 'static> {
+    inner: Rc<Inner<T>>,
+}
+
+impl<T: Clone + PartialEq + 'static> Clone for Solver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Default for Solver<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> fmt::Debug for Solver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Solver")
+            .field("variables", &self.inner.variables.borrow().len())
+            .field("relationships", &self.inner.relationships.borrow().len())
+            .finish()
+    }
+}
+
+struct Inner<T: Clone + PartialEq + 'static> {
+    variables: RefCell<Vec<Variable<T>>>,
+    relationships: RefCell<Vec<Relationship<T>>>,
+    /// Ignores the notifications of the solver's own writes.
+    solving: Cell<bool>,
+    /// Counts edits, to order variables by their last edit.
+    clock: Cell<u64>,
+}
+
+struct Variable<T: 'static> {
+    binding: Binding<T>,
+    priority: i32,
+    edited: u64,
+    _guard: BoxWatcherGuard,
+}
+
+type Solve<T> = Rc<dyn Fn(usize, &[T]) -> T>;
+
+struct Relationship<T> {
+    variables: Vec<usize>,
+    solve: Solve<T>,
+}
+
+impl<T: Clone + PartialEq + 'static> Solver<T> {
+    /// Creates a solver without variables.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                variables: RefCell::default(),
+                relationships: RefCell::default(),
+                solving: Cell::new(false),
+                clock: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Adds `binding` as a variable with `priority`.
+    ///
+    /// Variables with a lower priority are updated first to satisfy a
+    /// relationship.
+    #[must_use]
+    pub fn variable(&self, binding: Binding<T>, priority: i32) -> Var {
+        let index = self.inner.variables.borrow().len();
+        let guard = {
+            let inner = Rc::downgrade(&self.inner);
+            binding.watch(move |_| {
+                if let Some(inner) = Weak::upgrade(&inner) {
+                    inner.edit(index);
+                }
+            })
+        };
+        self.inner.variables.borrow_mut().push(Variable {
+            binding,
+            priority,
+            edited: 0,
+            _guard: guard,
+        });
+        Var(index)
+    }
+
+    /// Changes the priority of `var`.
+    pub fn set_priority(&self, var: Var, priority: i32) {
+        self.inner.variables.borrow_mut()[var.0].priority = priority;
+    }
+
+    /// Relates `variables` with `solve`, which computes the value of the
+    /// variable at the given index of `variables` from the values of all of
+    /// them, in the same order.
+    ///
+    /// The relationship is satisfied right away, updating its variable with
+    /// the lowest priority.
+    pub fn relate(&self, variables: &[Var], solve: impl Fn(usize, &[T]) -> T + 'static) {
+        let index = {
+            let mut relationships = self.inner.relationships.borrow_mut();
+            relationships.push(Relationship {
+                variables: variables.iter().map(|var| var.0).collect(),
+                solve: Rc::new(solve),
+            });
+            relationships.len() - 1
+        };
+        self.inner.solve(BTreeSet::new(), VecDeque::from([index]));
+    }
+
+    /// Keeps `total` equal to the sum of `parts`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parts` is empty.
+    pub fn sum(&self, total: Var, parts: &[Var])
+    where
+        T: Add<Output = T> + Sub<Output = T>,
+    {
+        assert!(!parts.is_empty(), "a sum needs at least one part");
+        let mut variables = Vec::with_capacity(parts.len() + 1);
+        variables.push(total);
+        variables.extend_from_slice(parts);
+        self.relate(&variables, |target, values: &[T]| {
+            if target == 0 {
+                let mut parts = values[1..].iter().cloned();
+                let first = parts.next().unwrap_or_else(|| values[0].clone());
+                parts.fold(first, |sum, part| sum + part)
+            } else {
+                values[1..]
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| index + 1 != target)
+                    .fold(values[0].clone(), |rest, (_, part)| rest - part.clone())
+            }
+        });
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Inner<T> {
+    fn edit(&self, variable: usize) {
+        if self.solving.get() {
+            return;
+        }
+        let clock = self.clock.get() + 1;
+        self.clock.set(clock);
+        self.variables.borrow_mut()[variable].edited = clock;
+
+        let queue = self.involving(variable, None);
+        self.solve(BTreeSet::from([variable]), queue);
+    }
+
+    /// Returns the relationships involving `variable`, except `skip`.
+    fn involving(&self, variable: usize, skip: Option<usize>) -> VecDeque<usize> {
+        self.relationships
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(index, relationship)| {
+                Some(*index) != skip && relationship.variables.contains(&variable)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Satisfies the relationships in `queue`, without updating the variables
+    /// in `fixed`.
+    fn solve(&self, mut fixed: BTreeSet<usize>, mut queue: VecDeque<usize>) {
+        self.solving.set(true);
+        let mut satisfied = BTreeSet::new();
+        while let Some(index) = queue.pop_front() {
+            if !satisfied.insert(index) {
+                continue;
+            }
+            let Some((target, binding, value)) = self.update(index, &fixed) else {
+                continue;
+            };
+            fixed.insert(target);
+            if binding.get() != value {
+                binding.set(value);
+                queue.extend(self.involving(target, Some(index)));
+            }
+        }
+        self.solving.set(false);
+    }
+
+    /// Computes the update of the relationship at `index`, if any of its
+    /// variables is not fixed.
+    fn update(&self, index: usize, fixed: &BTreeSet<usize>) -> Option<(usize, Binding<T>, T)> {
+        let relationships = self.relationships.borrow();
+        let variables = self.variables.borrow();
+        let relationship = &relationships[index];
+        let position = relationship
+            .variables
+            .iter()
+            .enumerate()
+            .filter(|(_, variable)| !fixed.contains(variable))
+            .min_by_key(|(_, variable)| {
+                let variable = &variables[**variable];
+                (variable.priority, variable.edited)
+            })
+            .map(|(position, _)| position)?;
+        let values: Vec<T> = relationship
+            .variables
+            .iter()
+            .map(|variable| variables[*variable].binding.get())
+            .collect();
+        let target = relationship.variables[position];
+        Some((
+            target,
+            variables[target].binding.clone(),
+            (relationship.solve)(position, &values),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding;
+
+    #[test]
+    fn test_solver_propagates_through_relationships() {
+        // celsius * 9 / 5 + 32 = fahrenheit, kelvin = celsius + 273
+        let celsius: Binding<i32> = binding(0);
+        let fahrenheit: Binding<i32> = binding(0);
+        let kelvin: Binding<i32> = binding(0);
+        let solver = Solver::new();
+        let c = solver.variable(celsius.clone(), 1);
+        let f = solver.variable(fahrenheit.clone(), 0);
+        let k = solver.variable(kelvin.clone(), 0);
+        solver.relate(&[c, f], |target, values: &[i32]| match target {
+            0 => (values[1] - 32) * 5 / 9,
+            _ => values[0] * 9 / 5 + 32,
+        });
+        let offset = solver.variable(binding(273), 10);
+        solver.sum(k, &[c, offset]);
+        assert_eq!((fahrenheit.get(), kelvin.get()), (32, 273));
+
+        fahrenheit.set(212);
+        assert_eq!((celsius.get(), kelvin.get()), (100, 373));
+
+        kelvin.set(283);
+        assert_eq!((celsius.get(), fahrenheit.get()), (10, 50));
+    }
+}
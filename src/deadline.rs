@@ -0,0 +1,342 @@
+//! # Flush deadlines
+//!
+//! This module provides an opt-in soft real-time mode for apps that must stay
+//! within a frame or control loop budget. While it is enabled, every flush, that
+//! is everything run by a top-level write, is given a [budget](Deadline::new),
+//! and the cost of each step of the flush is recorded: each watcher and each
+//! transformation, identified by the type name of its closure like in the
+//! [causality tracer](crate::trace), and each write notifying its watchers.
+//!
+//! A step's cost excludes the steps it caused, and adds up over all of its runs
+//! in the flush. When a flush takes longer than its budget, an [`Overrun`] is
+//! reported with the most expensive steps, so the nodes that blew the budget
+//! can be found in the field.
+//!
+//! The mode is soft: an overrunning flush still runs to completion. Watchers
+//! doing optional work, such as prefetching, can skip it when [`remaining`]
+//! reports that the flush is out of time.
+//!
+//! Like the [`watchdog`](crate::watchdog), deadlines are configured per thread.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use std::{cell::RefCell, rc::Rc};
+//! use nami::{binding, Binding, Signal, deadline::{self, Deadline}};
+//!
+//! let overruns = Rc::new(RefCell::new(Vec::new()));
+//! deadline::enable_with_hook(Deadline::new(Duration::from_millis(1)), {
+//!     let overruns = overruns.clone();
+//!     move |overrun| overruns.borrow_mut().push(overrun.clone())
+//! });
+//!
+//! let frame: Binding<i32> = binding(0);
+//! let _slow = frame.watch(|_| std::thread::sleep(Duration::from_millis(5)));
+//! let _fast = frame.watch(|_| {});
+//! frame.set(1);
+//!
+//! let overrun = &overruns.borrow()[0];
+//! assert!(overrun.elapsed >= Duration::from_millis(5));
+//! println!("{overrun}");
+//!
+//! deadline::disable();
+//! ```
+
+use alloc::{collections::BTreeMap, rc::Rc, vec::Vec};
+use core::{
+    cell::RefCell,
+    cmp::Reverse,
+    fmt::{self, Debug, Display},
+    time::Duration,
+};
+use std::time::Instant;
+
+use crate::trace::Step;
+
+/// The budget of each flush.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline {
+    budget: Duration,
+    reported: usize,
+}
+
+impl Deadline {
+    /// Gives each flush `budget` to complete, reporting the five most
+    /// expensive steps of overrunning flushes.
+    #[must_use]
+    pub const fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            reported: 5,
+        }
+    }
+
+    /// Reports the `count` most expensive steps of overrunning flushes.
+    #[must_use]
+    pub const fn reported(mut self, count: usize) -> Self {
+        self.reported = count;
+        self
+    }
+}
+
+/// The cost of a step over all of its runs in a flush.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Cost {
+    /// The step.
+    pub step: Step,
+    /// The time spent in the step itself, excluding the steps it caused.
+    pub total: Duration,
+    /// How many times the step ran.
+    pub runs: usize,
+}
+
+/// A report of a flush that took longer than its budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Overrun {
+    /// The budget of the flush.
+    pub budget: Duration,
+    /// How long the flush took.
+    pub elapsed: Duration,
+    /// The most expensive steps of the flush, most expensive first.
+    pub costs: Vec<Cost>,
+}
+
+/// Formats the overrun followed by its most expensive steps, one per line.
+impl Display for Overrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "flush took {:?}, over its budget of {:?}",
+            self.elapsed, self.budget
+        )?;
+        for cost in &self.costs {
+            write!(
+                f,
+                "\n  {:?} in {} runs: {}",
+                cost.total, cost.runs, cost.step
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Type alias for an overrun hook.
+type Hook = Rc<dyn Fn(&Overrun)>;
+
+struct Config {
+    deadline: Deadline,
+    hook: Option<Hook>,
+}
+
+impl Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("deadline", &self.deadline)
+            .field("hook", &self.hook.as_ref().map(|_| "<hook>"))
+            .finish()
+    }
+}
+
+/// The running flush.
+struct Flush {
+    started: Instant,
+    costs: BTreeMap<Step, Cost>,
+    /// The time spent in the steps caused by each running step, outermost
+    /// first.
+    stack: Vec<Duration>,
+}
+
+std::thread_local! {
+    static CONFIG: RefCell<Option<Config>> = const { RefCell::new(None) };
+    static FLUSH: RefCell<Option<Flush>> = const { RefCell::new(None) };
+}
+
+/// Enables `deadline` on the current thread, logging overruns.
+///
+/// Every overrun is reported through `log::warn!`.
+pub fn enable(deadline: Deadline) {
+    install(Config {
+        deadline,
+        hook: None,
+    });
+}
+
+/// Enables `deadline` on the current thread with a custom overrun hook.
+///
+/// The hook is invoked instead of logging for every overrunning flush.
+pub fn enable_with_hook(deadline: Deadline, hook: impl Fn(&Overrun) + 'static) {
+    install(Config {
+        deadline,
+        hook: Some(Rc::new(hook)),
+    });
+}
+
+/// Disables deadlines on the current thread.
+pub fn disable() {
+    CONFIG.with(|config| config.borrow_mut().take());
+}
+
+/// Returns `true` if deadlines are enabled on the current thread.
+#[must_use]
+pub fn is_enabled() -> bool {
+    CONFIG.with(|config| config.borrow().is_some())
+}
+
+/// Returns the time left before the budget of the running flush is exhausted,
+/// or `None` outside of a flush or while deadlines are disabled.
+///
+/// An overrunning flush has [`Duration::ZERO`] left.
+#[must_use]
+pub fn remaining() -> Option<Duration> {
+    let budget = CONFIG.with(|config| config.borrow().as_ref().map(|c| c.deadline.budget))?;
+    let started = FLUSH.with(|flush| flush.borrow().as_ref().map(|flush| flush.started))?;
+    Some(budget.saturating_sub(started.elapsed()))
+}
+
+fn install(config: Config) {
+    CONFIG.with(|slot| *slot.borrow_mut() = Some(config));
+}
+
+/// Runs `f` as `step`, recording its cost in the running flush.
+///
+/// A step outside of a flush starts one, except for transformations, which are
+/// plain reads outside of a flush.
+pub(crate) fn measure<R>(step: Step, f: impl FnOnce() -> R) -> R {
+    if !is_enabled() {
+        return f();
+    }
+    let entered = FLUSH.with(|flush| {
+        let mut flush = flush.borrow_mut();
+        if flush.is_none() && matches!(step, Step::Compute { .. }) {
+            return false;
+        }
+        flush
+            .get_or_insert_with(|| Flush {
+                started: Instant::now(),
+                costs: BTreeMap::new(),
+                stack: Vec::new(),
+            })
+            .stack
+            .push(Duration::ZERO);
+        true
+    });
+    if !entered {
+        return f();
+    }
+    let _exit = Exit {
+        step,
+        started: Instant::now(),
+    };
+    f()
+}
+
+/// Records the cost of a step when dropped, ending the flush at the top level.
+struct Exit {
+    step: Step,
+    started: Instant,
+}
+
+impl Drop for Exit {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        let finished = FLUSH.with(|slot| {
+            let mut slot = slot.borrow_mut();
+            let flush = slot.as_mut()?;
+            let caused = flush.stack.pop().unwrap_or_default();
+            let cost = flush.costs.entry(self.step).or_insert(Cost {
+                step: self.step,
+                total: Duration::ZERO,
+                runs: 0,
+            });
+            cost.total += elapsed.saturating_sub(caused);
+            cost.runs += 1;
+            if let Some(parent) = flush.stack.last_mut() {
+                *parent += elapsed;
+                return None;
+            }
+            slot.take()
+        });
+        if let Some(flush) = finished {
+            check(flush);
+        }
+    }
+}
+
+/// Reports `flush` if it overran its budget.
+fn check(flush: Flush) {
+    let elapsed = flush.started.elapsed();
+    // Clone the hook out so it may reconfigure deadlines without a borrow conflict.
+    let config = CONFIG.with(|config| {
+        config
+            .borrow()
+            .as_ref()
+            .map(|config| (config.deadline, config.hook.clone()))
+    });
+    let Some((deadline, hook)) = config else {
+        return;
+    };
+    if elapsed <= deadline.budget {
+        return;
+    }
+    let mut costs: Vec<Cost> = flush.costs.into_values().collect();
+    costs.sort_by_key(|cost| Reverse(cost.total));
+    costs.truncate(deadline.reported);
+    let overrun = Overrun {
+        budget: deadline.budget,
+        elapsed,
+        costs,
+    };
+    if let Some(hook) = hook {
+        hook(&overrun);
+    } else {
+        log::warn!("{overrun}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, Signal, SignalExt, binding};
+    use core::cell::Cell;
+
+    #[test]
+    fn test_overrun_reports_the_most_expensive_steps() {
+        let overruns = Rc::new(RefCell::new(Vec::new()));
+        enable_with_hook(Deadline::new(Duration::from_millis(2)).reported(2), {
+            let overruns = overruns.clone();
+            move |overrun: &Overrun| overruns.borrow_mut().push(overrun.clone())
+        });
+
+        let source: Binding<i32> = binding(0);
+        let slow = source.clone().map(|n| {
+            std::thread::sleep(Duration::from_millis(3));
+            n
+        });
+        let left = Rc::new(Cell::new(None));
+        let _watchers = (
+            slow.watch(|_| {}),
+            source.watch({
+                let left = left.clone();
+                move |_| left.set(remaining())
+            }),
+        );
+
+        // Reads outside of a flush are not measured.
+        assert_eq!(slow.get(), 0);
+        assert!(remaining().is_none());
+
+        source.set(1);
+        disable();
+        assert!(left.get().is_some());
+        let overruns = overruns.borrow();
+        assert_eq!(overruns.len(), 1);
+        let costs = &overruns[0].costs;
+        assert_eq!(costs.len(), 2);
+        assert!(matches!(costs[0].step, Step::Compute { .. }));
+        assert!(costs[0].total >= Duration::from_millis(3));
+        assert!(costs[1].total <= costs[0].total);
+    }
+}
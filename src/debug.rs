@@ -22,9 +22,32 @@
 //! // Use default configuration (same as changes())
 //! let debug_default = Debug::with_config(value, Config::default());
 //! ```
+//!
+//! For chatty sources, [`log_changes`] and [`ChangeLogger`] log value changes with
+//! built-in sampling and rate limits:
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use nami::{binding, Binding, debug::{ChangeLogger, log_changes}};
+//!
+//! let position: Binding<f64> = binding(0.0);
+//!
+//! // Logs at most once per second.
+//! let _guard = log_changes(&position, log::Level::Info, "position");
+//!
+//! // Logs every 10th change, and only if it moved by more than 0.5 since the last log.
+//! let _guard = ChangeLogger::new(log::Level::Debug, "position")
+//!     .every_nth(10)
+//!     .min_delta(0.5)
+//!     .watch(&position);
+//! ```
 
 use alloc::{boxed::Box, rc::Rc};
-use core::any::type_name;
+#[cfg(feature = "std")]
+use core::time::Duration;
+use core::{any::type_name, cell::RefCell, num::NonZeroU64};
+#[cfg(feature = "std")]
+use std::time::Instant;
 
 use crate::{
     Signal,
@@ -225,3 +248,310 @@ where
         Box::new(guard)
     }
 }
+
+/// A predicate deciding whether a change from the last logged value is significant.
+type Significance<T> = Rc<dyn Fn(&T, &T) -> bool>;
+
+/// A builder for watchers that log value changes with sampling and rate limits.
+///
+/// Plain watchers flood the logs when attached to chatty sources. A change logger
+/// skips changes according to its limits, and reports how many changes were
+/// skipped since the last logged one.
+pub struct ChangeLogger<T> {
+    level: log::Level,
+    name: &'static str,
+    every_nth: NonZeroU64,
+    #[cfg(feature = "std")]
+    interval: Option<Duration>,
+    significance: Option<Significance<T>>,
+}
+
+impl<T> core::fmt::Debug for ChangeLogger<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut debug = f.debug_struct("ChangeLogger");
+        debug
+            .field("level", &self.level)
+            .field("name", &self.name)
+            .field("every_nth", &self.every_nth);
+        #[cfg(feature = "std")]
+        debug.field("interval", &self.interval);
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl<T> Clone for ChangeLogger<T> {
+    fn clone(&self) -> Self {
+        Self {
+            level: self.level,
+            name: self.name,
+            every_nth: self.every_nth,
+            #[cfg(feature = "std")]
+            interval: self.interval,
+            significance: self.significance.clone(),
+        }
+    }
+}
+
+struct ChangeLoggerState<T> {
+    last_logged: Option<T>,
+    #[cfg(feature = "std")]
+    last_time: Option<Instant>,
+    changes: u64,
+    skipped: u64,
+}
+
+impl<T: core::fmt::Debug + Clone + 'static> ChangeLogger<T> {
+    /// Creates a change logger that logs every change at `level` under `name`.
+    #[must_use]
+    pub const fn new(level: log::Level, name: &'static str) -> Self {
+        Self {
+            level,
+            name,
+            every_nth: NonZeroU64::MIN,
+            #[cfg(feature = "std")]
+            interval: None,
+            significance: None,
+        }
+    }
+
+    /// Only considers every `n`th change for logging.
+    ///
+    /// A value of `0` is treated as `1`.
+    #[must_use]
+    pub fn every_nth(mut self, n: u64) -> Self {
+        self.every_nth = NonZeroU64::new(n).unwrap_or(NonZeroU64::MIN);
+        self
+    }
+
+    /// Logs at most once per `interval`.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub const fn at_most_every(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Only logs a change if `significant(last_logged, new)` returns `true`.
+    ///
+    /// The first change is always logged.
+    #[must_use]
+    pub fn when(mut self, significant: impl Fn(&T, &T) -> bool + 'static) -> Self {
+        self.significance = Some(Rc::new(significant));
+        self
+    }
+
+    /// Only logs a change if it differs from the last logged value by more than `delta`.
+    #[must_use]
+    pub fn min_delta(self, delta: f64) -> Self
+    where
+        T: Copy + Into<f64>,
+    {
+        self.when(move |last, new| {
+            let last: f64 = (*last).into();
+            let new: f64 = (*new).into();
+            (new - last).abs() > delta
+        })
+    }
+
+    /// Starts logging the changes of `signal`.
+    ///
+    /// Returns the signal's watcher guard; dropping it stops logging.
+    pub fn watch<S>(self, signal: &S) -> S::Guard
+    where
+        S: Signal<Output = T>,
+    {
+        let state = RefCell::new(ChangeLoggerState {
+            last_logged: None,
+            #[cfg(feature = "std")]
+            last_time: None,
+            changes: 0,
+            skipped: 0,
+        });
+
+        signal.watch(move |context: Context<T>| {
+            let mut state = state.borrow_mut();
+            if self.should_log(&mut state, &context.value) {
+                let Self { level, name, .. } = self;
+                let value = &context.value;
+                match state.skipped {
+                    0 => log::log!(level, "`{name}` changed to {value:?}"),
+                    skipped => log::log!(
+                        level,
+                        "`{name}` changed to {value:?} ({skipped} changes skipped)"
+                    ),
+                }
+                state.skipped = 0;
+                state.last_logged = Some(context.value);
+            } else {
+                state.skipped += 1;
+            }
+        })
+    }
+
+    /// Decides whether `value` passes the sampling and rate limits.
+    fn should_log(&self, state: &mut ChangeLoggerState<T>, value: &T) -> bool {
+        state.changes += 1;
+        if !state.changes.is_multiple_of(self.every_nth.get()) {
+            return false;
+        }
+
+        if let (Some(significant), Some(last)) = (&self.significance, &state.last_logged)
+            && !significant(last, value)
+        {
+            return false;
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(interval) = self.interval {
+            let now = Instant::now();
+            if state
+                .last_time
+                .is_some_and(|last| now.duration_since(last) < interval)
+            {
+                return false;
+            }
+            state.last_time = Some(now);
+        }
+
+        true
+    }
+}
+
+/// Logs the changes of `signal` at `level` under `name`, rate limited.
+///
+/// With the `std` feature, at most one change per second is logged. Use
+/// [`ChangeLogger`] to configure other limits.
+///
+/// Returns the signal's watcher guard; dropping it stops logging.
+pub fn log_changes<S>(signal: &S, level: log::Level, name: &'static str) -> S::Guard
+where
+    S: Signal,
+    S::Output: core::fmt::Debug + Clone,
+{
+    let logger = ChangeLogger::new(level, name);
+    #[cfg(feature = "std")]
+    let logger = logger.at_most_every(Duration::from_secs(1));
+    logger.watch(signal)
+}
+
+/// Formats a value of any type, falling back to its type name.
+///
+/// Primitive types and strings are formatted with their `Debug` implementation,
+/// since values stored in reactive nodes are not required to implement it.
+pub(crate) struct Value<'a, T>(pub &'a T);
+
+macro_rules! debug_as {
+    ($value:expr, $f:expr, $($ty:ty),* $(,)?) => {
+        $(
+            if let Some(value) = ($value as &dyn core::any::Any).downcast_ref::<$ty>() {
+                return core::fmt::Debug::fmt(value, $f);
+            }
+        )*
+    };
+}
+
+impl<T: 'static> core::fmt::Debug for Value<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        debug_as!(
+            self.0,
+            f,
+            bool,
+            char,
+            i8,
+            i16,
+            i32,
+            i64,
+            i128,
+            isize,
+            u8,
+            u16,
+            u32,
+            u64,
+            u128,
+            usize,
+            f32,
+            f64,
+            (),
+            &'static str,
+            alloc::string::String,
+        );
+        write!(f, "<{}>", type_name::<T>())
+    }
+}
+
+/// Formats a reactive node as `name { node, type, value, watchers }`.
+///
+/// The node id and watcher count are left out if the node does not report them,
+/// and the value is left out if `value` is `None`.
+pub(crate) fn fmt_node<S: Signal>(
+    f: &mut core::fmt::Formatter<'_>,
+    name: &str,
+    signal: &S,
+    value: Option<&dyn core::fmt::Debug>,
+) -> core::fmt::Result {
+    let mut node = f.debug_struct(name);
+    if let Some(id) = signal.node_id() {
+        node.field("node", &format_args!("{id}"));
+    }
+    node.field("type", &type_name::<S::Output>());
+    if let Some(value) = value {
+        node.field("value", value);
+    }
+    if let Some(count) = signal.watcher_count() {
+        node.field("watchers", &count);
+    }
+    node.finish()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::binding;
+    use alloc::{string::ToString, vec::Vec};
+
+    struct Recorder;
+
+    std::thread_local! {
+        static RECORDS: RefCell<Vec<alloc::string::String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    impl log::Log for Recorder {
+        fn enabled(&self, _metadata: &log::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            RECORDS.with(|records| records.borrow_mut().push(record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static RECORDER: Recorder = Recorder;
+
+    #[test]
+    fn test_change_logger_limits() {
+        let _ = log::set_logger(&RECORDER);
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let value = binding::<f64>(0.0);
+        let _guard = ChangeLogger::new(log::Level::Info, "value")
+            .every_nth(2)
+            .min_delta(1.0)
+            .watch(&value);
+
+        for v in [0.5, 1.0, 1.2, 1.5, 2.0, 3.0] {
+            value.set(v);
+        }
+
+        let records = RECORDS.with(|records| records.borrow().clone());
+        // Odd changes are sampled out, the 4th is not significant.
+        assert_eq!(
+            records,
+            [
+                "`value` changed to 1.0 (1 changes skipped)",
+                "`value` changed to 3.0 (3 changes skipped)"
+            ]
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! # Global mutation hook for devtools
+//!
+//! [`set_global_change_hook`] installs a function that is called for every
+//! mutation of a [`Container`](crate::Container), and therefore of every
+//! binding created with [`binding`](crate::binding). Devtools overlays and
+//! automatic persistence layers can observe the whole application this way,
+//! without wrapping each binding.
+//!
+//! The hook receives the [`NodeId`] of the mutated node, which matches
+//! [`Signal::node_id`](crate::Signal::node_id) of every handle to it, and the
+//! new value. Values of primitive types and strings are formatted as usual;
+//! since bindings do not require their values to implement [`Debug`], other
+//! values are formatted as their type name.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::fmt::Debug;
+//! use nami::{binding, Binding, NodeId, devtools::set_global_change_hook};
+//!
+//! fn report(node: NodeId, value: &dyn Debug) {
+//!     println!("{node} = {value:?}");
+//! }
+//!
+//! set_global_change_hook(Some(report));
+//!
+//! let count: Binding<i32> = binding(0);
+//! count.set(1); // prints "#… = 1"
+//! set_global_change_hook(None);
+//! ```
+//!
+//! With the `devtools-server` feature, an [`Inspector`] exposes named
+//! bindings to external tools over a local TCP socket.
+
+use core::fmt::Debug;
+use std::sync::RwLock;
+
+use crate::NodeId;
+
+#[cfg(feature = "devtools-server")]
+mod server;
+#[cfg(feature = "devtools-server")]
+pub use server::{Inspector, Server};
+
+/// A function observing every binding mutation.
+pub type ChangeHook = fn(NodeId, &dyn Debug);
+
+static HOOK: RwLock<Option<ChangeHook>> = RwLock::new(None);
+
+/// Installs `hook` to observe every binding mutation, replacing the previous
+/// hook, or removes the hook if `hook` is `None`.
+///
+/// The hook is global to the process. It is called on the thread performing
+/// the mutation, after the new value is stored and before watchers are notified.
+pub fn set_global_change_hook(hook: Option<ChangeHook>) {
+    *HOOK
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = hook;
+}
+
+/// Returns the currently installed hook.
+#[must_use]
+pub fn global_change_hook() -> Option<ChangeHook> {
+    *HOOK
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Reports a mutation of `node` to the global hook, if one is installed.
+///
+/// `value` is only called if there is a hook.
+pub(crate) fn report<'a, T: 'static>(node: NodeId, value: impl FnOnce() -> &'a T) {
+    if let Some(hook) = global_change_hook() {
+        hook(node, &crate::debug::Value(value()));
+    }
+}
+
+/// Installs a hook for the duration of a test, removing it when dropped.
+///
+/// The hook is global to the process, so tests depending on it take turns,
+/// including those depending on no hook being installed.
+#[cfg(test)]
+pub(crate) struct TestHook(#[allow(dead_code)] std::sync::MutexGuard<'static, ()>);
+
+#[cfg(test)]
+impl TestHook {
+    /// Waits for the other tests depending on the hook, then installs `hook`.
+    pub(crate) fn install(hook: Option<ChangeHook>) -> Self {
+        static TURN: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        let turn = TURN
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        set_global_change_hook(hook);
+        Self(turn)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestHook {
+    fn drop(&mut self) {
+        set_global_change_hook(None);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::{Binding, Signal, binding};
+    use alloc::{format, string::String, vec::Vec};
+    use std::sync::Mutex;
+
+    static REPORTS: Mutex<Vec<(NodeId, String)>> = Mutex::new(Vec::new());
+
+    fn record(node: NodeId, value: &dyn Debug) {
+        REPORTS.lock().unwrap().push((node, format!("{value:?}")));
+    }
+
+    #[test]
+    fn test_global_change_hook() {
+        let hook = TestHook::install(Some(record));
+        let count: Binding<i32> = binding(0);
+        let items: Binding<Vec<u8>> = binding(Vec::new());
+        count.set(3);
+        items.push(1);
+        drop(hook);
+        count.set(4);
+
+        let reports: Vec<_> = REPORTS
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(node, _)| Some(*node) == count.node_id() || Some(*node) == items.node_id())
+            .cloned()
+            .collect();
+        let (count_id, items_id) = (count.node_id().unwrap(), items.node_id().unwrap());
+        assert_eq!(
+            reports,
+            [
+                (count_id, String::from("3")),
+                (items_id, String::from("<alloc::vec::Vec<u8>>")),
+            ]
+        );
+    }
+}
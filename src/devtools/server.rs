@@ -0,0 +1,482 @@
+use alloc::{
+    boxed::Box,
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    any::{Any, type_name},
+    cell::{Cell, RefCell},
+    fmt,
+};
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+};
+
+use async_io::Async;
+use executor_core::LocalExecutor;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::{Binding, NodeId, Signal, watcher::BoxWatcherGuard};
+
+type Setter = Rc<dyn Fn(Value) -> Result<(), String>>;
+
+/// The longest command a client may send, in bytes.
+const MAX_COMMAND_LEN: usize = 64 * 1024;
+
+/// A node registered with an [`Inspector`].
+struct Node {
+    name: String,
+    id: Option<NodeId>,
+    type_name: &'static str,
+    get: Box<dyn Fn() -> Value>,
+    set: Option<Setter>,
+    changes: Rc<Cell<u64>>,
+    _guard: BoxWatcherGuard,
+}
+
+#[derive(Default)]
+struct Registry {
+    nodes: Vec<Node>,
+    edges: Vec<(String, String)>,
+}
+
+/// A registry of named signals that external tools can inspect and mutate.
+///
+/// Signals are registered by name, since the graph itself does not track names
+/// or dependencies. Edges between registered names can be declared to render
+/// the graph. [`Inspector::serve`] exposes the registry over TCP.
+///
+/// ```rust
+/// use nami::{binding, Binding, SignalExt, devtools::Inspector};
+///
+/// let count: Binding<i32> = binding(1);
+/// let doubled = count.clone().map(|count| count * 2);
+///
+/// let inspector = Inspector::new();
+/// inspector.register("count", &count);
+/// inspector.register_signal("doubled", &doubled);
+/// inspector.add_edge("count", "doubled");
+///
+/// let reply = inspector.handle(r#"{ "command": "set", "name": "count", "value": 5 }"#);
+/// assert_eq!(reply, serde_json::json!({ "ok": true }));
+/// assert_eq!(inspector.snapshot()["nodes"][1]["value"], 10);
+/// ```
+#[derive(Clone, Default)]
+pub struct Inspector {
+    registry: Rc<RefCell<Registry>>,
+}
+
+impl fmt::Debug for Inspector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let registry = self.registry.borrow();
+        f.debug_struct("Inspector")
+            .field(
+                "nodes",
+                &registry
+                    .nodes
+                    .iter()
+                    .map(|node| node.name.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .field("edges", &registry.edges)
+            .finish()
+    }
+}
+
+impl Inspector {
+    /// Creates an empty inspector.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a binding under `name`, allowing tools to set its value.
+    pub fn register<T>(&self, name: impl Into<String>, binding: &Binding<T>)
+    where
+        T: Serialize + DeserializeOwned + 'static,
+    {
+        let target = binding.clone();
+        let set: Setter = Rc::new(move |value| {
+            let value: T = serde_json::from_value(value).map_err(|error| error.to_string())?;
+            target.set(value);
+            Ok(())
+        });
+        self.insert(name.into(), binding, Some(set));
+    }
+
+    /// Registers a read-only signal under `name`.
+    pub fn register_signal<S>(&self, name: impl Into<String>, signal: &S)
+    where
+        S: Signal,
+        S::Output: Serialize,
+    {
+        self.insert(name.into(), signal, None);
+    }
+
+    /// Declares that the node named `to` is derived from the node named `from`.
+    pub fn add_edge(&self, from: impl Into<String>, to: impl Into<String>) {
+        self.registry
+            .borrow_mut()
+            .edges
+            .push((from.into(), to.into()));
+    }
+
+    fn insert<S>(&self, name: String, signal: &S, set: Option<Setter>)
+    where
+        S: Signal,
+        S::Output: Serialize,
+    {
+        let changes = Rc::new(Cell::new(0));
+        let guard = {
+            let changes = changes.clone();
+            Box::new(signal.watch(move |_| changes.set(changes.get() + 1)))
+        };
+        let source = signal.clone();
+        let mut registry = self.registry.borrow_mut();
+        registry.nodes.retain(|node| node.name != name);
+        registry.nodes.push(Node {
+            name,
+            id: signal.node_id(),
+            type_name: type_name::<S::Output>(),
+            get: Box::new(move || serde_json::to_value(source.get()).unwrap_or(Value::Null)),
+            set,
+            changes,
+            _guard: guard,
+        });
+    }
+
+    /// Returns the current state of every registered node and the declared edges.
+    ///
+    /// Each node reports its `name`, `id` (if it has one), `type`, current
+    /// `value`, whether it is `writable`, and how many `changes` it has notified
+    /// since it was registered.
+    #[must_use]
+    pub fn snapshot(&self) -> Value {
+        let registry = self.registry.borrow();
+        let nodes: Vec<Value> = registry
+            .nodes
+            .iter()
+            .map(|node| {
+                json!({
+                    "name": node.name,
+                    "id": node.id.map(NodeId::get),
+                    "type": node.type_name,
+                    "value": (node.get)(),
+                    "writable": node.set.is_some(),
+                    "changes": node.changes.get(),
+                })
+            })
+            .collect();
+        json!({ "nodes": nodes, "edges": registry.edges })
+    }
+
+    /// Handles one command of the devtools protocol, returning the reply.
+    ///
+    /// Commands are JSON objects with a `command` field:
+    ///
+    /// - `{ "command": "snapshot" }` replies with [`snapshot`](Self::snapshot).
+    /// - `{ "command": "set", "name": <name>, "value": <value> }` sets a
+    ///   registered binding and replies with `{ "ok": true }`.
+    ///
+    /// Invalid commands reply with `{ "error": <message> }`.
+    #[must_use]
+    pub fn handle(&self, command: &str) -> Value {
+        match self.try_handle(command) {
+            Ok(reply) => reply,
+            Err(error) => json!({ "error": error }),
+        }
+    }
+
+    fn try_handle(&self, command: &str) -> Result<Value, String> {
+        let command: Value = serde_json::from_str(command).map_err(|error| error.to_string())?;
+        match command["command"].as_str() {
+            Some("snapshot") => Ok(self.snapshot()),
+            Some("set") => {
+                let name = command["name"].as_str().ok_or("`set` requires a `name`")?;
+                let value = command
+                    .get("value")
+                    .cloned()
+                    .ok_or("`set` requires a `value`")?;
+                // The watchers of the binding may register nodes, so the
+                // registry must not be borrowed while it is set.
+                let set = {
+                    let registry = self.registry.borrow();
+                    let node = registry
+                        .nodes
+                        .iter()
+                        .find(|node| node.name == name)
+                        .ok_or_else(|| format!("no node named `{name}`"))?;
+                    node.set
+                        .clone()
+                        .ok_or_else(|| format!("`{name}` is read-only"))?
+                };
+                set(value)?;
+                Ok(json!({ "ok": true }))
+            }
+            Some(other) => Err(format!("unknown command `{other}`")),
+            None => Err("missing `command`".into()),
+        }
+    }
+
+    /// Serves the devtools protocol on `address`, running on `executor`.
+    ///
+    /// The protocol is newline-delimited JSON over TCP: every line sent by the
+    /// client is a command as accepted by [`handle`](Self::handle), and is
+    /// answered by one line with the reply. Clients are served one at a time,
+    /// and a client sending a line longer than 64 KiB is disconnected.
+    ///
+    /// Clients can set any registered binding without authenticating, so only
+    /// loopback addresses, such as `127.0.0.1:0`, are accepted. Expose the
+    /// server to other machines through a tunnel you secure yourself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`PermissionDenied`](io::ErrorKind::PermissionDenied)
+    /// if `address` resolves to an address other than a loopback one, or any
+    /// error binding it.
+    pub fn serve<E>(&self, address: impl ToSocketAddrs, executor: E) -> io::Result<Server>
+    where
+        E: LocalExecutor + 'static,
+    {
+        let addresses: Vec<SocketAddr> = address.to_socket_addrs()?.collect();
+        if let Some(remote) = addresses.iter().find(|address| !address.ip().is_loopback()) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("devtools only serve on loopback addresses, not on {remote}"),
+            ));
+        }
+        let listener = Async::new(TcpListener::bind(&*addresses)?)?;
+        let address = listener.get_ref().local_addr()?;
+        let inspector = self.clone();
+        let task = executor.spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        if let Err(error) = inspector.session(&stream).await {
+                            log::debug!("devtools client disconnected: {error}");
+                        }
+                    }
+                    Err(error) => log::warn!("devtools server failed to accept: {error}"),
+                }
+            }
+        });
+        Ok(Server {
+            address,
+            _task: Rc::new(task),
+        })
+    }
+
+    /// Answers the commands of one client until it disconnects.
+    #[allow(clippy::future_not_send)] // The registry is thread-local.
+    async fn session(&self, stream: &Async<TcpStream>) -> io::Result<()> {
+        let mut buffer = Vec::new();
+        let mut chunk = [0; 1024];
+        loop {
+            let read = stream
+                .read_with(|mut stream| stream.read(&mut chunk))
+                .await?;
+            if read == 0 {
+                return Ok(());
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+            while let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let command = String::from_utf8_lossy(&line);
+                if command.trim().is_empty() {
+                    continue;
+                }
+                let mut reply = self.handle(&command).to_string();
+                reply.push('\n');
+                write_all(stream, reply.as_bytes()).await?;
+            }
+            if buffer.len() > MAX_COMMAND_LEN {
+                let mut reply =
+                    json!({ "error": format!("commands are limited to {MAX_COMMAND_LEN} bytes") })
+                        .to_string();
+                reply.push('\n');
+                write_all(stream, reply.as_bytes()).await?;
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "devtools command too long",
+                ));
+            }
+        }
+    }
+}
+
+async fn write_all(stream: &Async<TcpStream>, mut bytes: &[u8]) -> io::Result<()> {
+    while !bytes.is_empty() {
+        let written = stream.write_with(|mut stream| stream.write(bytes)).await?;
+        if written == 0 {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+        bytes = &bytes[written..];
+    }
+    Ok(())
+}
+
+/// A running devtools server. The server stops when this handle is dropped.
+pub struct Server {
+    address: SocketAddr,
+    _task: Rc<dyn Any>,
+}
+
+impl fmt::Debug for Server {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Server")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Server {
+    /// Returns the address the server listens on.
+    #[must_use]
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.address
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SignalExt, binding, test_executor::Manual};
+    use alloc::vec;
+    use std::{
+        io::{BufRead, BufReader},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn test_inspector_commands() {
+        let count: Binding<i32> = binding(1);
+        let label = count.clone().map(|count| alloc::format!("{count} items"));
+        let inspector = Inspector::new();
+        inspector.register("count", &count);
+        inspector.register_signal("label", &label);
+        inspector.add_edge("count", "label");
+
+        assert_eq!(
+            inspector.handle(r#"{"command": "set", "name": "count", "value": 2}"#),
+            json!({ "ok": true })
+        );
+        assert_eq!(count.get(), 2);
+        assert_eq!(
+            inspector.handle(r#"{"command": "set", "name": "label", "value": ""}"#),
+            json!({ "error": "`label` is read-only" })
+        );
+        assert!(
+            inspector.handle(r#"{"command": "set", "name": "count", "value": "two"}"#)["error"]
+                .is_string()
+        );
+        assert_eq!(
+            inspector.handle(r#"{"command": "reset"}"#),
+            json!({ "error": "unknown command `reset`" })
+        );
+
+        let snapshot = inspector.handle(r#"{"command": "snapshot"}"#);
+        assert_eq!(snapshot["nodes"][0]["value"], 2);
+        assert_eq!(snapshot["nodes"][0]["changes"], 1);
+        assert_eq!(snapshot["nodes"][0]["writable"], true);
+        assert_eq!(snapshot["nodes"][1]["value"], "2 items");
+        assert_eq!(snapshot["nodes"][1]["type"], "alloc::string::String");
+        assert_eq!(snapshot["edges"], json!([["count", "label"]]));
+    }
+
+    #[test]
+    fn test_watchers_may_register_while_set() {
+        let count: Binding<i32> = binding(1);
+        let inspector = Inspector::new();
+        inspector.register("count", &count);
+        let _guard = {
+            let inspector = inspector.clone();
+            count.watch(move |context| {
+                inspector.register_signal(
+                    alloc::format!("seen {}", context.value),
+                    &binding::<i32>(context.value),
+                );
+            })
+        };
+
+        assert_eq!(
+            inspector.handle(r#"{"command": "set", "name": "count", "value": 2}"#),
+            json!({ "ok": true })
+        );
+        assert_eq!(inspector.snapshot()["nodes"][1]["name"], "seen 2");
+    }
+
+    /// Runs `executor` until `client` is done, returning what it returned.
+    fn serve_client<T: Send + 'static>(
+        executor: &Manual,
+        client: impl FnOnce() -> io::Result<T> + Send + 'static,
+    ) -> T {
+        let client = thread::spawn(client);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !client.is_finished() {
+            assert!(Instant::now() < deadline, "the devtools client timed out");
+            executor.run();
+            thread::sleep(Duration::from_millis(1));
+        }
+        client
+            .join()
+            .unwrap_or_else(|_| panic!("the devtools client panicked"))
+            .unwrap_or_else(|error| panic!("the devtools client failed: {error}"))
+    }
+
+    #[test]
+    fn test_serve_answers_over_tcp() {
+        let count: Binding<i32> = binding(1);
+        let inspector = Inspector::new();
+        inspector.register("count", &count);
+        let executor = Manual::default();
+        let server = inspector
+            .serve("127.0.0.1:0", executor.clone())
+            .unwrap_or_else(|error| panic!("failed to serve: {error}"));
+        let address = server.local_addr();
+
+        let replies = serve_client(&executor, move || {
+            let mut stream = TcpStream::connect(address)?;
+            stream.write_all(
+                b"{\"command\": \"set\", \"name\": \"count\", \"value\": 3}\n\n{\"command\": \"snapshot\"}\n",
+            )?;
+            let mut lines = BufReader::new(stream).lines();
+            let mut replies = vec![];
+            for _ in 0..2 {
+                replies.push(lines.next().unwrap_or_else(|| Ok(String::new()))?);
+            }
+            Ok(replies)
+        });
+        assert_eq!(replies[0], r#"{"ok":true}"#);
+        let snapshot: Value = serde_json::from_str(&replies[1])
+            .unwrap_or_else(|error| panic!("invalid snapshot: {error}"));
+        assert_eq!(snapshot["nodes"][0]["value"], 3);
+        assert_eq!(count.get(), 3);
+
+        // A client sending an endless line is told why, then disconnected.
+        let replies = serve_client(&executor, move || {
+            let mut stream = TcpStream::connect(address)?;
+            stream.write_all(&vec![b' '; MAX_COMMAND_LEN + 1])?;
+            BufReader::new(stream)
+                .lines()
+                .collect::<io::Result<Vec<_>>>()
+        });
+        assert_eq!(
+            replies,
+            ["{\"error\":\"commands are limited to 65536 bytes\"}"]
+        );
+    }
+
+    #[test]
+    fn test_serve_rejects_remote_addresses() {
+        let error = Inspector::new()
+            .serve("0.0.0.0:0", Manual::default())
+            .err()
+            .map(|error| error.kind());
+        assert_eq!(error, Some(io::ErrorKind::PermissionDenied));
+    }
+}
@@ -0,0 +1,499 @@
+//! # Text diffs between successive values
+//!
+//! This module turns a text signal into a signal of diffs, so change-highlighting
+//! views can render what changed without diffing the values themselves.
+//!
+//! [`Diffed`] compares each new value of its source with the previous one and
+//! emits the result as a list of [`Change`]s, at line or word [`Granularity`].
+//! [`diff_text`] exposes the same algorithm for one-off comparisons.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt, diff::{Change, Granularity}};
+//!
+//! let text: Binding<String> = binding("hello world");
+//! let diffed = text.clone().diffed(Granularity::Word);
+//!
+//! text.set("hello there".to_string());
+//! assert_eq!(
+//!     diffed.get(),
+//!     vec![
+//!         Change::Equal("hello ".into()),
+//!         Change::Delete("world".into()),
+//!         Change::Insert("there".into()),
+//!     ]
+//! );
+//! ```
+
+use alloc::{
+    rc::Rc,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{any::Any, cell::RefCell};
+
+use crate::{Signal, watcher::Context};
+
+/// The unit that text is split into before diffing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Granularity {
+    /// Compare whole lines, including their line terminators.
+    #[default]
+    Line,
+    /// Compare words and the whitespace between them.
+    Word,
+}
+
+/// A single segment of a text diff.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// Text present in both the old and the new value.
+    Equal(String),
+    /// Text only present in the new value.
+    Insert(String),
+    /// Text only present in the old value.
+    Delete(String),
+}
+
+impl Change {
+    /// Returns the text of this segment.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Equal(text) | Self::Insert(text) | Self::Delete(text) => text,
+        }
+    }
+
+    /// Returns `true` if this segment is unchanged text.
+    #[must_use]
+    pub const fn is_equal(&self) -> bool {
+        matches!(self, Self::Equal(_))
+    }
+}
+
+/// Splits `text` into tokens that concatenate back to `text`.
+fn tokenize(text: &str, granularity: Granularity) -> Vec<&str> {
+    match granularity {
+        Granularity::Line => text.split_inclusive('\n').collect(),
+        Granularity::Word => {
+            let mut tokens = Vec::new();
+            let mut start = 0;
+            let mut last_whitespace = None;
+            for (index, ch) in text.char_indices() {
+                let whitespace = ch.is_whitespace();
+                if last_whitespace.is_some_and(|last| last != whitespace) {
+                    tokens.push(&text[start..index]);
+                    start = index;
+                }
+                last_whitespace = Some(whitespace);
+            }
+            if start < text.len() {
+                tokens.push(&text[start..]);
+            }
+            tokens
+        }
+    }
+}
+
+/// Appends `change`, merging it into the last segment if it has the same kind.
+fn push(changes: &mut Vec<Change>, change: Change) {
+    match (changes.last_mut(), change) {
+        (Some(Change::Equal(last)), Change::Equal(text))
+        | (Some(Change::Insert(last)), Change::Insert(text))
+        | (Some(Change::Delete(last)), Change::Delete(text)) => last.push_str(&text),
+        (_, change) => changes.push(change),
+    }
+}
+
+/// A step of an [`edit_script`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Edit {
+    /// The next element of both sequences is kept.
+    Keep,
+    /// The next element of the old sequence is deleted.
+    Delete,
+    /// The next element of the new sequence is inserted.
+    Insert,
+}
+
+/// The largest number of differences searched for in a changed region before
+/// reporting it as entirely replaced, which bounds the cost of a diff.
+const MAX_EDIT_COST: usize = 1024;
+
+/// Computes a shortest edit script turning `old` into `new`.
+///
+/// Within a replaced region, deletions come before insertions. This is Myers'
+/// algorithm in linear space: its cost grows with the size of the changed
+/// region times the number of differences. A region with more than
+/// [`MAX_EDIT_COST`] differences is reported as entirely replaced, so the
+/// script is only shortest up to that many differences.
+pub(crate) fn edit_script<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let mut edits = Vec::with_capacity(old.len().max(new.len()));
+    push_edits(old, new, &mut edits);
+    for region in edits.split_mut(|edit| *edit == Edit::Keep) {
+        region.sort_unstable_by_key(|edit| *edit == Edit::Insert);
+    }
+    edits
+}
+
+/// Returns the length of the common prefix of `old` and `new`.
+fn common_prefix<T: PartialEq>(old: &[T], new: &[T]) -> usize {
+    old.iter().zip(new).take_while(|(a, b)| a == b).count()
+}
+
+/// Returns the length of the common suffix of `old` and `new`.
+fn common_suffix<T: PartialEq>(old: &[T], new: &[T]) -> usize {
+    old.iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// Appends the edits turning `old` into `new`, splitting the changed region at
+/// the middle of a shortest path until no element of one side is kept.
+fn push_edits<T: PartialEq>(old: &[T], new: &[T], edits: &mut Vec<Edit>) {
+    let prefix = common_prefix(old, new);
+    let suffix = common_suffix(&old[prefix..], &new[prefix..]);
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+
+    edits.extend(core::iter::repeat_n(Edit::Keep, prefix));
+    if let Some((x, y)) = middle_snake(old, new) {
+        push_edits(&old[..x], &new[..y], edits);
+        push_edits(&old[x..], &new[y..], edits);
+    } else {
+        edits.extend(core::iter::repeat_n(Edit::Delete, old.len()));
+        edits.extend(core::iter::repeat_n(Edit::Insert, new.len()));
+    }
+    edits.extend(core::iter::repeat_n(Edit::Keep, suffix));
+}
+
+/// Finds where a shortest path through the edit graph of `old` and `new` can
+/// be split in two, given that their first and last elements differ.
+///
+/// The path is searched from both corners at once, keeping the furthest point
+/// reached on each diagonal `x - y`, stored at `x - y + offset`, until the two
+/// searches overlap. Returns `None` if either side is empty, if no element is
+/// kept, or if the sides differ in more than [`MAX_EDIT_COST`] places.
+fn middle_snake<T: PartialEq>(old: &[T], new: &[T]) -> Option<(usize, usize)> {
+    let (n, m) = (old.len(), new.len());
+    if n == 0 || m == 0 {
+        return None;
+    }
+    let max_cost = (n + m).div_ceil(2).min(MAX_EDIT_COST);
+    let offset = max_cost;
+    let mut forward = vec![None; 2 * max_cost + 1];
+    let mut backward = vec![None; 2 * max_cost + 1];
+    forward[offset + 1] = Some(0);
+    backward[offset + 1] = Some(0);
+    // The searches can only meet on the forward step if `n - m` is odd.
+    let odd = (n + m) % 2 == 1;
+    // Diagonals left at an edge of the graph, which are no longer extended.
+    let (mut forward_skip, mut forward_stop) = (0, 0);
+    let (mut backward_skip, mut backward_stop) = (0, 0);
+
+    // The furthest point reached on diagonal `index`, after one more edit.
+    let step = |furthest: &[Option<usize>], d: usize, index: usize| {
+        if index == offset - d || (index != offset + d && furthest[index - 1] < furthest[index + 1])
+        {
+            furthest[index + 1]
+        } else {
+            furthest[index - 1].map(|x| x + 1)
+        }
+    };
+    // The diagonal of the other search ending at the same point as `index`.
+    let mirror = |index: usize| (2 * offset + n).checked_sub(m + index);
+
+    for d in 0..max_cost {
+        let diagonals = offset - d + forward_skip..(offset + d + 1).saturating_sub(forward_stop);
+        for index in diagonals.step_by(2) {
+            let Some(mut x) = step(&forward, d, index) else {
+                continue;
+            };
+            let mut y = x + offset - index;
+            if x < n && y < m {
+                let run = common_prefix(&old[x..], &new[y..]);
+                x += run;
+                y += run;
+            }
+            forward[index] = Some(x);
+            if x > n {
+                forward_stop += 2;
+            } else if y > m {
+                forward_skip += 2;
+            } else if odd
+                && let Some(mirror) = mirror(index)
+                && let Some(Some(back)) = backward.as_slice().get(mirror).copied()
+                && x + back >= n
+            {
+                return Some((x, y));
+            }
+        }
+
+        let diagonals = offset - d + backward_skip..(offset + d + 1).saturating_sub(backward_stop);
+        for index in diagonals.step_by(2) {
+            let Some(mut x) = step(&backward, d, index) else {
+                continue;
+            };
+            let mut y = x + offset - index;
+            if x < n && y < m {
+                let run = common_suffix(&old[..n - x], &new[..m - y]);
+                x += run;
+                y += run;
+            }
+            backward[index] = Some(x);
+            if x > n {
+                backward_stop += 2;
+            } else if y > m {
+                backward_skip += 2;
+            } else if !odd
+                && let Some(mirror) = mirror(index)
+                && let Some(Some(front)) = forward.as_slice().get(mirror).copied()
+                && front + x >= n
+            {
+                return Some((front, front + offset - mirror));
+            }
+        }
+    }
+    None
+}
+
+/// Computes the diff between `old` and `new` at the given granularity.
+///
+/// Adjacent segments of the same kind are merged, and within a replaced region
+/// deletions come before insertions. Concatenating the `Equal` and `Delete`
+/// segments yields `old`; concatenating the `Equal` and `Insert` segments yields `new`.
+///
+/// The diff is a shortest edit script over the tokens, computed in time
+/// growing with the size of the changed region times the number of
+/// differences. Regions with more than a thousand differences are reported as
+/// entirely replaced.
+#[must_use]
+pub fn diff_text(old: &str, new: &str, granularity: Granularity) -> Vec<Change> {
+    let old = tokenize(old, granularity);
+    let new = tokenize(new, granularity);
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for edit in edit_script(&old, &new) {
+        let change = match edit {
+            Edit::Keep => Change::Equal(old[i].to_string()),
+            Edit::Delete => Change::Delete(old[i].to_string()),
+            Edit::Insert => Change::Insert(new[j].to_string()),
+        };
+        if edit != Edit::Insert {
+            i += 1;
+        }
+        if edit != Edit::Delete {
+            j += 1;
+        }
+        push(&mut changes, change);
+    }
+    changes
+}
+
+/// A signal emitting the diff between successive values of a text signal.
+///
+/// `get()` returns the diff of the most recent change of the source, which is
+/// entirely [`Change::Equal`] before the first change. Each watcher receives the
+/// diff between the value it saw last and the new value.
+#[derive(Debug, Clone)]
+pub struct Diffed<S> {
+    source: S,
+    granularity: Granularity,
+    /// The previous and the current value of the source.
+    values: Rc<RefCell<(String, String)>>,
+    _guard: Rc<dyn Any>,
+}
+
+impl<S> Diffed<S>
+where
+    S: Signal,
+    S::Output: AsRef<str>,
+{
+    /// Creates a diffing wrapper around `source`.
+    pub fn new(source: S, granularity: Granularity) -> Self {
+        let initial = source.get().as_ref().to_string();
+        let values = Rc::new(RefCell::new((initial.clone(), initial)));
+        let guard = {
+            let values = values.clone();
+            source.watch(move |context: Context<S::Output>| {
+                let mut values = values.borrow_mut();
+                let (previous, current) = &mut *values;
+                *previous = core::mem::replace(current, context.value.as_ref().to_string());
+            })
+        };
+
+        Self {
+            source,
+            granularity,
+            values,
+            _guard: Rc::new(guard),
+        }
+    }
+}
+
+impl<S> Signal for Diffed<S>
+where
+    S: Signal,
+    S::Output: AsRef<str>,
+{
+    type Output = Vec<Change>;
+    type Guard = S::Guard;
+
+    fn get(&self) -> Self::Output {
+        let values = self.values.borrow();
+        diff_text(&values.0, &values.1, self.granularity)
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let granularity = self.granularity;
+        let last = RefCell::new(self.source.get().as_ref().to_string());
+        self.source.watch(move |context: Context<S::Output>| {
+            let Context { value, metadata } = context;
+            let new = value.as_ref().to_string();
+            let old = last.replace(new.clone());
+            watcher(Context::new(diff_text(&old, &new, granularity), metadata));
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+
+    fn join<'a>(changes: impl Iterator<Item = &'a Change>) -> String {
+        changes.map(Change::text).collect()
+    }
+
+    /// Returns the length of a longest common subsequence of `old` and `new`.
+    fn lcs(old: &[u8], new: &[u8]) -> usize {
+        let mut lengths = vec![vec![0; new.len() + 1]; old.len() + 1];
+        for (i, a) in old.iter().enumerate() {
+            for (j, b) in new.iter().enumerate() {
+                lengths[i + 1][j + 1] = if a == b {
+                    lengths[i][j] + 1
+                } else {
+                    lengths[i][j + 1].max(lengths[i + 1][j])
+                };
+            }
+        }
+        lengths[old.len()][new.len()]
+    }
+
+    /// Applies `edits` to `old`, checking that they take `new` from it.
+    fn apply(old: &[u8], new: &[u8], edits: &[Edit]) -> Vec<u8> {
+        let (mut i, mut j) = (0, 0);
+        let mut result = Vec::new();
+        for edit in edits {
+            match edit {
+                Edit::Keep => {
+                    assert_eq!(old[i], new[j]);
+                    result.push(old[i]);
+                    i += 1;
+                    j += 1;
+                }
+                Edit::Delete => i += 1,
+                Edit::Insert => {
+                    result.push(new[j]);
+                    j += 1;
+                }
+            }
+        }
+        assert_eq!((i, j), (old.len(), new.len()));
+        result
+    }
+
+    #[test]
+    fn test_edit_script_is_shortest() {
+        // A xorshift generator, so failures can be reproduced.
+        let mut state = 0x2545_f491_usize;
+        let mut next = |bound: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+
+        for _ in 0..2_000 {
+            let old: Vec<u8> = (0..next(16)).map(|_| b"abc"[next(3)]).collect();
+            let new: Vec<u8> = (0..next(16)).map(|_| b"abc"[next(3)]).collect();
+            let edits = edit_script(&old, &new);
+
+            assert_eq!(apply(&old, &new, &edits), new);
+            let changed = edits.iter().filter(|edit| **edit != Edit::Keep).count();
+            assert_eq!(changed, old.len() + new.len() - 2 * lcs(&old, &new));
+            for region in edits.split(|edit| *edit == Edit::Keep) {
+                assert!(region.is_sorted_by_key(|edit| *edit == Edit::Insert));
+            }
+        }
+    }
+
+    #[test]
+    fn test_edit_script_replaces_regions_with_many_differences() {
+        let old: Vec<u32> = (0..20_000).collect();
+        let new: Vec<u32> = (20_000..40_000).collect();
+        let edits = edit_script(&old, &new);
+        assert_eq!(edits.len(), 40_000);
+        assert!(edits[..20_000].iter().all(|edit| *edit == Edit::Delete));
+        assert!(edits[20_000..].iter().all(|edit| *edit == Edit::Insert));
+
+        // Above the limit, kept elements may be reported as replaced, but the
+        // script still turns one side into the other.
+        let new: Vec<u32> = old
+            .iter()
+            .map(|&x| if x % 2 == 0 { x } else { x + 1 })
+            .collect();
+        let edits = edit_script(&old, &new);
+        assert_eq!(
+            edits.iter().filter(|edit| **edit != Edit::Insert).count(),
+            old.len()
+        );
+        assert_eq!(
+            edits.iter().filter(|edit| **edit != Edit::Delete).count(),
+            new.len()
+        );
+    }
+
+    #[test]
+    fn test_diff_text_reconstructs_both_sides() {
+        let old = "one\ntwo\nthree\nfour\n";
+        let new = "zero\none\nthree\n4\nfour\n";
+        let changes = diff_text(old, new, Granularity::Line);
+
+        assert_eq!(
+            changes,
+            [
+                Change::Insert("zero\n".into()),
+                Change::Equal("one\n".into()),
+                Change::Delete("two\n".into()),
+                Change::Equal("three\n".into()),
+                Change::Insert("4\n".into()),
+                Change::Equal("four\n".into()),
+            ]
+        );
+        assert_eq!(
+            join(changes.iter().filter(|c| !matches!(c, Change::Insert(_)))),
+            old
+        );
+        assert_eq!(
+            join(changes.iter().filter(|c| !matches!(c, Change::Delete(_)))),
+            new
+        );
+
+        let words: Vec<_> = diff_text("the quick  fox", "the slow fox", Granularity::Word);
+        assert_eq!(
+            words,
+            [
+                Change::Equal("the ".into()),
+                Change::Delete("quick  ".into()),
+                Change::Insert("slow ".into()),
+                Change::Equal("fox".into()),
+            ]
+        );
+    }
+}
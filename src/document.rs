@@ -0,0 +1,166 @@
+//! # Documents
+//!
+//! A [`Document`] holds the model of the open document of a multi-document app,
+//! such as a struct of the bindings of an open file, and manages its lifecycle:
+//!
+//! - Each model has its own [`Scope`], owning the async effects and watcher
+//!   guards set up for it, such as autosaving.
+//! - [`open`](Document::open) replaces the model. The scope of the previous
+//!   model is dropped first, cancelling its effects and watchers, so they can
+//!   never act on the new model.
+//! - The document is a signal of the current model, built on a
+//!   [`BindingSlot`]. Opening a model notifies its watchers once, after the new
+//!   model is installed, and any writes made while building it with
+//!   [`open_with`](Document::open_with) are [batched](crate::batch) along with it.
+//!
+//! Views showing a part of the model follow the current model with
+//! [`switch_map`](crate::SignalExt::switch_map).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt, document::Document};
+//!
+//! #[derive(Clone)]
+//! struct Model {
+//!     title: Binding<String>,
+//! }
+//!
+//! let document = Document::new(Model { title: binding("Untitled") });
+//! let title = document.clone().switch_map(|model| model.title);
+//!
+//! document.open_with(|scope| {
+//!     let title: Binding<String> = binding("notes.txt");
+//!     scope.keep(title.watch(|context| println!("renamed to {}", context.value)));
+//!     Model { title }
+//! });
+//! assert_eq!(title.get(), "notes.txt");
+//! ```
+
+use alloc::rc::Rc;
+use core::{cell::RefCell, fmt};
+
+use crate::{
+    Binding, NodeId, Signal, batch::batch, scope::Scope, slot::BindingSlot, watcher::Context,
+};
+
+/// The open model of a multi-document app, with the scope of its effects.
+///
+/// All clones share the same model. See the [module documentation](self) for
+/// how it behaves.
+#[derive(Clone)]
+pub struct Document<T: Clone + 'static> {
+    slot: BindingSlot<T>,
+    scope: Rc<RefCell<Rc<Scope>>>,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for Document<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Document")
+            .field("model", &self.slot.get())
+            .field("scope", &self.scope.borrow())
+            .finish()
+    }
+}
+
+impl<T: Clone + 'static> Document<T> {
+    /// Creates a document with `model` open, with an empty scope.
+    #[must_use]
+    pub fn new(model: T) -> Self {
+        Self {
+            slot: BindingSlot::new(Binding::container(model)),
+            scope: Rc::new(RefCell::new(Rc::new(Scope::new()))),
+        }
+    }
+
+    /// Opens `model`, dropping the scope of the previous model.
+    pub fn open(&self, model: T) {
+        self.open_with(|_| model);
+    }
+
+    /// Opens the model returned by `build`, which is given the new, empty
+    /// scope of the model to set its effects up in.
+    ///
+    /// The scope of the previous model is dropped before `build` runs.
+    /// Watchers of the document, and of bindings written by `build`, are
+    /// notified once `build` returns.
+    pub fn open_with(&self, build: impl FnOnce(&Scope) -> T) {
+        let scope = Rc::new(Scope::new());
+        let previous = self.scope.replace(scope.clone());
+        drop(previous);
+        batch(|| {
+            let model = build(&scope);
+            self.slot.assign(Binding::container(model));
+        });
+    }
+
+    /// Returns the scope of the current model, for effects set up after it
+    /// was opened.
+    #[must_use]
+    pub fn scope(&self) -> Rc<Scope> {
+        self.scope.borrow().clone()
+    }
+}
+
+impl<T: Clone + 'static> Signal for Document<T> {
+    type Output = T;
+    type Guard = <BindingSlot<T> as Signal>::Guard;
+
+    fn get(&self) -> Self::Output {
+        self.slot.get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.slot.watch(watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.slot.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.slot.watcher_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding;
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_open_tears_down_the_previous_model_and_notifies_once() {
+        let saved = Rc::new(Cell::new(0));
+        let open_count: Binding<i32> = binding(0);
+        let document = Document::new(binding::<i32>(1));
+        document.scope().keep({
+            let saved = saved.clone();
+            document.get().watch(move |_| saved.set(saved.get() + 1))
+        });
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guards = {
+            let (documents, counts) = (seen.clone(), seen.clone());
+            (
+                document.watch(move |context| documents.borrow_mut().push(context.value.get())),
+                open_count.watch(move |context| counts.borrow_mut().push(context.value)),
+            )
+        };
+
+        let first = document.get();
+        first.set(2);
+        document.open_with(|_| {
+            open_count.set(open_count.get() + 1);
+            open_count.set(open_count.get() + 1);
+            binding(10)
+        });
+        first.set(3);
+
+        assert_eq!(saved.get(), 1);
+        // The count and the document notify once each, in write order.
+        assert_eq!(*seen.borrow(), [2, 10]);
+        assert_eq!(first.watcher_count(), Some(0));
+    }
+}
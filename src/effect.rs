@@ -0,0 +1,223 @@
+//! # Synchronous effects
+//!
+//! Effects bridge reactive values to the outside world, such as rendering,
+//! logging or I/O. They run once immediately, then again on every change, until
+//! the value they return is dropped:
+//!
+//! - [`effect`] runs a closure with each value of one signal.
+//! - [`autorun`] runs a closure reading any number of signals through a
+//!   [`Tracker`], and re-runs it when any of them changes. The signals are
+//!   tracked again on each run, so a closure reading different signals
+//!   depending on a condition only re-runs for the ones it last read.
+//!
+//! For async side effects, see [`scope`](crate::scope) and
+//! [`resource`](crate::resource).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::{cell::RefCell, rc::Rc};
+//! use nami::{binding, Binding, effect::autorun};
+//!
+//! let show_name: Binding<bool> = binding(true);
+//! let name: Binding<String> = binding("Ada");
+//! let id: Binding<i32> = binding(7);
+//! let log = Rc::new(RefCell::new(Vec::new()));
+//!
+//! let run = {
+//!     let (show_name, name, id, log) = (show_name.clone(), name.clone(), id.clone(), log.clone());
+//!     autorun(move |tracker| {
+//!         let label = if tracker.get(&show_name) {
+//!             tracker.get(&name)
+//!         } else {
+//!             format!("#{}", tracker.get(&id))
+//!         };
+//!         log.borrow_mut().push(label);
+//!     })
+//! };
+//!
+//! name.set("Grace");
+//! // `id` is not read while the name is shown.
+//! id.set(8);
+//! show_name.set(false);
+//! assert_eq!(*log.borrow(), ["Ada", "Grace", "#8"]);
+//!
+//! drop(run);
+//! id.set(9);
+//! assert_eq!(log.borrow().len(), 3);
+//! ```
+
+use alloc::{
+    boxed::Box,
+    rc::{Rc, Weak},
+    vec::Vec,
+};
+use core::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fmt,
+};
+
+use crate::{NodeId, Signal, watcher::Context};
+
+/// Runs `f` with the current value of `source`, then with each new value.
+///
+/// Returns a guard that stops the effect when dropped.
+pub fn effect<S, F>(source: S, f: F) -> S::Guard
+where
+    S: Signal,
+    F: Fn(S::Output) + 'static,
+{
+    f(source.get());
+    source.watch(move |context: Context<S::Output>| f(context.value))
+}
+
+/// Runs `f` now, then again each time a signal it read through its
+/// [`Tracker`] in its last run changes.
+///
+/// Returns a handle that stops the effect when dropped.
+///
+/// A change made by `f` itself to a signal it reads does not re-enter it, but
+/// runs it again once the current run ends.
+pub fn autorun<F>(f: F) -> Autorun
+where
+    F: Fn(&Tracker) + 'static,
+{
+    let run = Rc::new_cyclic(|run: &Weak<Run>| Run {
+        f: Box::new(f),
+        tracker: Tracker {
+            run: run.clone(),
+            guards: RefCell::new(Vec::new()),
+            read: RefCell::new(Vec::new()),
+        },
+        running: Cell::new(false),
+        dirty: Cell::new(false),
+    });
+    run.execute();
+    Autorun { run }
+}
+
+/// A running [`autorun`] effect, stopped when dropped.
+#[must_use = "the effect stops when the handle is dropped"]
+pub struct Autorun {
+    run: Rc<Run>,
+}
+
+impl fmt::Debug for Autorun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Autorun")
+            .field("dependencies", &self.run.tracker.guards.borrow().len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads signals for an [`autorun`] effect, re-running it when they change.
+pub struct Tracker {
+    run: Weak<Run>,
+    guards: RefCell<Vec<Box<dyn Any>>>,
+    /// The identities of the signals read in the current run.
+    read: RefCell<Vec<NodeId>>,
+}
+
+impl fmt::Debug for Tracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracker")
+            .field("dependencies", &self.guards.borrow().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Tracker {
+    /// Returns the current value of `signal`, re-running the effect when it
+    /// changes.
+    pub fn get<S: Signal>(&self, signal: &S) -> S::Output {
+        let value = signal.get();
+        if let Some(id) = signal.node_id() {
+            let mut read = self.read.borrow_mut();
+            if read.contains(&id) {
+                return value;
+            }
+            read.push(id);
+        }
+        let run = self.run.clone();
+        let guard = signal.watch(move |_: Context<S::Output>| {
+            if let Some(run) = run.upgrade() {
+                run.execute();
+            }
+        });
+        self.guards.borrow_mut().push(Box::new(guard));
+        value
+    }
+}
+
+struct Run {
+    f: Box<dyn Fn(&Tracker)>,
+    tracker: Tracker,
+    running: Cell<bool>,
+    /// Whether a dependency changed during the current run.
+    dirty: Cell<bool>,
+}
+
+impl Run {
+    fn execute(&self) {
+        if self.running.replace(true) {
+            self.dirty.set(true);
+            return;
+        }
+        loop {
+            self.dirty.set(false);
+            let previous = core::mem::take(&mut *self.tracker.guards.borrow_mut());
+            self.tracker.read.borrow_mut().clear();
+            (self.f)(&self.tracker);
+            // Dropped after the run, so signals read again are never left
+            // without a watcher in between.
+            drop(previous);
+            if !self.dirty.get() {
+                break;
+            }
+        }
+        self.running.set(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+
+    #[test]
+    fn test_effect_runs_immediately_and_on_changes() {
+        let count: Binding<i32> = binding(1);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let guard = {
+            let seen = seen.clone();
+            effect(count.clone(), move |value| seen.borrow_mut().push(value))
+        };
+
+        count.set(2);
+        drop(guard);
+        count.set(3);
+        assert_eq!(*seen.borrow(), [1, 2]);
+    }
+
+    #[test]
+    fn test_autorun_reruns_after_writing_its_dependency() {
+        let count: Binding<i32> = binding(0);
+        let runs = Rc::new(Cell::new(0));
+        let _run = {
+            let (count, runs) = (count.clone(), runs.clone());
+            autorun(move |tracker| {
+                runs.set(runs.get() + 1);
+                let value = tracker.get(&count);
+                // Clamps the count without re-entering the effect.
+                if value > 10 {
+                    count.set(10);
+                }
+            })
+        };
+
+        count.set(15);
+        assert_eq!(count.get(), 10);
+        assert_eq!(runs.get(), 3);
+    }
+}
@@ -1,14 +1,45 @@
 use executor_core::DefaultExecutor;
 
 use crate::{
-    Computed, Signal, cache::Cached, debounce::Debounce, map::Map, signal::WithMetadata, zip::Zip,
+    Computed, Signal,
+    cache::Cached,
+    debounce::Debounce,
+    diff::{Diffed, Granularity},
+    flatten::Flatten,
+    future::{NextChange, WaitUntil},
+    intern::Interned,
+    map::Map,
+    memo::Memo,
+    operator::{Operator, OperatorSignal},
+    scan::Scan,
+    signal::WithMetadata,
+    stream::SignalStream,
+    throttle::Throttle,
+    variant::Variants,
+    watcher::{Context, Transition, WhileGuard},
+    zip::{Elements, Part, Zip},
+};
+use alloc::string::{String, ToString};
+use core::{
+    cell::{Cell, RefCell},
+    fmt::Display,
+    ops::ControlFlow,
+    time::Duration,
 };
-use core::time::Duration;
 
 /// Extension trait providing convenient methods for all Signal types.
 ///
 /// This trait adds utility methods to any type implementing Signal,
 /// allowing for easy chaining of operations like mapping, zipping, and caching.
+///
+/// ```rust
+/// use nami::{binding, Binding, Signal, SignalExt};
+///
+/// let a: Binding<i32> = binding(2);
+/// let b: Binding<i32> = binding(3);
+/// let label = a.zip(b).map(|(a, b)| a + b).bind_display();
+/// assert_eq!(label.get(), "5");
+/// ```
 pub trait SignalExt: Signal + Sized {
     /// Transforms the output of this signal using the provided function.
     fn map<F, Output>(self, f: F) -> Map<Self, F, Output>
@@ -24,6 +55,163 @@ pub trait SignalExt: Signal + Sized {
         Zip::new(self, b)
     }
 
+    /// Splits this signal of a pair into a signal for each side.
+    ///
+    /// Each side only notifies its watchers when its own element changes.
+    fn unzip<A, B>(self) -> (Part<Self, A>, Part<Self, B>)
+    where
+        Self: Signal<Output = (A, B)>,
+        A: PartialEq + Clone + 'static,
+        B: PartialEq + Clone + 'static,
+    {
+        crate::zip::unzip(self)
+    }
+
+    /// Projects the first element of this signal's tuple output.
+    ///
+    /// The result only notifies its watchers when the first element changes.
+    ///
+    /// ```rust
+    /// use nami::{binding, Binding, Signal, SignalExt};
+    ///
+    /// let pair: Binding<(i32, &'static str)> = binding((1, "a"));
+    /// let (first, second) = (pair.clone().first(), pair.clone().second());
+    /// let _guard = first.watch(|_| panic!("the first element did not change"));
+    /// pair.set((1, "b"));
+    /// assert_eq!(second.get(), "b");
+    /// ```
+    fn first(self) -> Part<Self, <Self::Output as Elements>::First>
+    where
+        Self::Output: Elements,
+        <Self::Output as Elements>::First: PartialEq + Clone + 'static,
+    {
+        Part::new(self, Elements::first)
+    }
+
+    /// Projects the second element of this signal's tuple output.
+    ///
+    /// The result only notifies its watchers when the second element changes.
+    fn second(self) -> Part<Self, <Self::Output as Elements>::Second>
+    where
+        Self::Output: Elements,
+        <Self::Output as Elements>::Second: PartialEq + Clone + 'static,
+    {
+        Part::new(self, Elements::second)
+    }
+
+    /// Projects the kind of this signal's enum output, naming its variant.
+    ///
+    /// The result only notifies its watchers when the variant changes. See
+    /// [`variant`](crate::variant) for deriving [`Variants`].
+    fn kind(self) -> Part<Self, <Self::Output as Variants>::Kind>
+    where
+        Self::Output: Variants,
+    {
+        Part::new(self, |value| value.kind())
+    }
+
+    /// Skips notifications whose value equals the previous one.
+    ///
+    /// Each watcher compares against the last value it was notified with, so
+    /// an expensive transform mapped over the result only re-runs when the
+    /// value actually changes.
+    ///
+    /// ```rust
+    /// use core::cell::Cell;
+    /// use std::rc::Rc;
+    /// use nami::{binding, Binding, Signal, SignalExt};
+    ///
+    /// let runs = Rc::new(Cell::new(0));
+    /// let text: Binding<String> = binding("hello".to_string());
+    /// let shouted = text.clone().dedup().map({
+    ///     let runs = runs.clone();
+    ///     move |text: String| {
+    ///         runs.set(runs.get() + 1);
+    ///         text.to_uppercase()
+    ///     }
+    /// });
+    /// let _guard = shouted.watch(|_| {});
+    ///
+    /// text.set("hello".to_string());
+    /// text.set("hi".to_string());
+    /// assert_eq!(runs.get(), 1);
+    /// assert_eq!(shouted.get(), "HI");
+    /// ```
+    fn dedup(self) -> Part<Self, Self::Output>
+    where
+        Self::Output: PartialEq + Clone,
+    {
+        Part::new(self, core::convert::identity)
+    }
+
+    /// Renders this signal's output as text with its [`Display`] implementation.
+    ///
+    /// Watchers are only notified when the rendered text changes, so a value
+    /// feeding a label does not cause a re-render when it changes to a value
+    /// displayed the same way.
+    ///
+    /// ```rust
+    /// use nami::{binding, Binding, Signal, SignalExt};
+    ///
+    /// let count: Binding<i32> = binding(3);
+    /// let label = count.clone().bind_display();
+    /// let _guard = label.watch(|ctx| panic!("the label did not change: {}", ctx.value));
+    /// count.set(3);
+    /// assert_eq!(label.get(), "3");
+    /// ```
+    fn bind_display(self) -> Part<Self, String>
+    where
+        Self::Output: Display,
+    {
+        Part::new(self, |value| value.to_string())
+    }
+
+    /// Interns this signal's text as shared [`Rc<str>`](alloc::rc::Rc)s.
+    ///
+    /// The previous allocation is reused when the new text is equal to it, and
+    /// watchers are only notified when the text changes.
+    fn interned(self) -> Interned<Self>
+    where
+        Self::Output: AsRef<str>,
+    {
+        Interned::new(self)
+    }
+
+    /// Follows the values of the current inner signal of this signal of signals.
+    ///
+    /// See [`flatten`](crate::flatten) for how it behaves.
+    fn flatten(self) -> Flatten<<Self::Output as Signal>::Output>
+    where
+        Self::Output: Signal,
+        <Self::Output as Signal>::Output: Clone,
+    {
+        crate::flatten::flatten(self)
+    }
+
+    /// Maps each value of this signal to a signal, following the values of the
+    /// current one.
+    ///
+    /// See [`flatten`](crate::flatten) for how it behaves.
+    fn switch_map<F, I>(self, f: F) -> Flatten<I::Output>
+    where
+        F: Fn(Self::Output) -> I + 'static,
+        I: Signal,
+        I::Output: Clone,
+    {
+        crate::flatten::switch_map(self, f)
+    }
+
+    /// Folds each new value of this signal into an accumulator starting as `init`.
+    ///
+    /// See [`scan`](crate::scan) for how it behaves.
+    fn scan<A, F>(self, init: A, f: F) -> Scan<A>
+    where
+        A: Clone + 'static,
+        F: Fn(&mut A, Self::Output) + 'static,
+    {
+        crate::scan::scan(self, init, f)
+    }
+
     /// Wraps this signal with caching to avoid redundant computations.
     fn cached(self) -> Cached<Self>
     where
@@ -32,6 +220,26 @@ pub trait SignalExt: Signal + Sized {
         Cached::new(self)
     }
 
+    /// Applies a custom [`Operator`] to this signal.
+    ///
+    /// See [`operator`](crate::operator) for how it behaves.
+    fn operator<O>(self, op: O) -> OperatorSignal<O::Output>
+    where
+        O: Operator<Self::Output>,
+    {
+        crate::operator::operator(self, op)
+    }
+
+    /// Caches this signal's last value, only notifying watchers when it changes.
+    ///
+    /// See [`memo`](crate::memo::memo) for how it differs from [`dedup`](Self::dedup).
+    fn memo(self) -> Memo<Self::Output>
+    where
+        Self::Output: PartialEq + Clone,
+    {
+        crate::memo::memo(self)
+    }
+
     /// Converts this signal into a type-erased `Computed` container.
     fn computed(self) -> Computed<Self::Output>
     where
@@ -40,6 +248,14 @@ pub trait SignalExt: Signal + Sized {
         Computed::new(self)
     }
 
+    /// Converts this signal into a `Stream` of its values, for consumption in
+    /// async code with `while let Some(value) = stream.next().await`.
+    ///
+    /// See [`SignalStream`] for how updates are delivered.
+    fn to_stream(self) -> SignalStream<Self> {
+        SignalStream::new(self)
+    }
+
     /// Attaches metadata to this signal's watcher notifications.
     fn with<T>(self, metadata: T) -> WithMetadata<Self, T> {
         WithMetadata::new(metadata, self)
@@ -55,6 +271,265 @@ pub trait SignalExt: Signal + Sized {
     {
         Debounce::new(self, duration)
     }
+
+    /// Creates a throttled version of this signal.
+    ///
+    /// The throttled signal emits at most one value per `duration`.
+    fn throttle(self, duration: Duration) -> Throttle<Self, DefaultExecutor>
+    where
+        Self::Output: Clone,
+    {
+        Throttle::new(self, duration)
+    }
+
+    /// Emits the diff between successive values of this text signal.
+    fn diffed(self, granularity: Granularity) -> Diffed<Self>
+    where
+        Self::Output: AsRef<str>,
+    {
+        Diffed::new(self, granularity)
+    }
+
+    /// Returns a future resolving to the first value of this signal that
+    /// satisfies `predicate`, checking the current value first.
+    ///
+    /// ```rust
+    /// use core::{pin::pin, task::{Context, Poll, Waker}};
+    /// use nami::{binding, Binding, SignalExt};
+    ///
+    /// let progress: Binding<u8> = binding(0);
+    /// let mut done = pin!(progress.clone().wait_until(|progress| *progress == 100));
+    ///
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// assert!(done.as_mut().poll(&mut cx).is_pending());
+    /// progress.set(100);
+    /// assert_eq!(done.as_mut().poll(&mut cx), Poll::Ready(100));
+    /// ```
+    fn wait_until<P>(self, predicate: P) -> WaitUntil<Self, P>
+    where
+        P: Fn(&Self::Output) -> bool,
+    {
+        crate::future::wait_until(self, predicate)
+    }
+
+    /// Returns a future resolving to the value of the next notification of this
+    /// signal.
+    fn next_change(self) -> NextChange<Self> {
+        crate::future::next_change(self)
+    }
+
+    /// Watches this signal until `watcher` returns [`ControlFlow::Break`].
+    ///
+    /// This lets a watcher remove itself from inside its callback without access
+    /// to its guard. Dropping the returned guard removes the watcher as well.
+    ///
+    /// ```rust
+    /// use core::ops::ControlFlow;
+    /// use nami::{binding, Binding, SignalExt};
+    ///
+    /// let progress: Binding<u8> = binding(0);
+    /// let guard = progress.watch_while(|ctx| {
+    ///     println!("{}%", ctx.value);
+    ///     if ctx.value == 100 {
+    ///         ControlFlow::Break(())
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// });
+    /// progress.set(100);
+    /// assert!(guard.is_stopped());
+    /// ```
+    fn watch_while(
+        &self,
+        watcher: impl Fn(Context<Self::Output>) -> ControlFlow<()> + 'static,
+    ) -> WhileGuard<Self::Guard> {
+        WhileGuard::new(|watcher| self.watch(watcher), watcher)
+    }
+
+    /// Calls `callback` on the next change of this signal, then stops watching.
+    ///
+    /// Dropping the returned guard before the change cancels the callback. To
+    /// await a value instead, use [`next_change`](Self::next_change).
+    ///
+    /// ```rust
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use nami::{binding, Binding, SignalExt};
+    ///
+    /// let status: Binding<&str> = binding("connecting");
+    /// let seen = Rc::new(RefCell::new(Vec::new()));
+    /// let guard = {
+    ///     let seen = seen.clone();
+    ///     status.watch_once(move |ctx| seen.borrow_mut().push(ctx.value))
+    /// };
+    ///
+    /// status.set("online");
+    /// status.set("offline");
+    /// assert_eq!(*seen.borrow(), ["online"]);
+    /// assert!(guard.is_stopped());
+    /// ```
+    fn watch_once(
+        &self,
+        callback: impl FnOnce(Context<Self::Output>) + 'static,
+    ) -> WhileGuard<Self::Guard> {
+        self.watch_first(|_| true, callback)
+    }
+
+    /// Calls `callback` on the first change of this signal to a value
+    /// satisfying `predicate`, then stops watching.
+    ///
+    /// The current value is not checked. To await a value, checking the
+    /// current one first, use [`wait_until`](Self::wait_until).
+    fn watch_first(
+        &self,
+        predicate: impl Fn(&Self::Output) -> bool + 'static,
+        callback: impl FnOnce(Context<Self::Output>) + 'static,
+    ) -> WhileGuard<Self::Guard> {
+        let callback = Cell::new(Some(callback));
+        self.watch_while(move |context| {
+            if !predicate(&context.value) {
+                return ControlFlow::Continue(());
+            }
+            if let Some(callback) = callback.take() {
+                callback(context);
+            }
+            ControlFlow::Break(())
+        })
+    }
+
+    /// Watches this signal, passing each watcher call the previous value along
+    /// with the new one.
+    ///
+    /// The previous value is the one this watcher saw last, starting with the
+    /// value when it was registered. It is kept rather than recomputed, so it
+    /// is correct for derived signals like maps and zips too.
+    ///
+    /// ```rust
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use nami::{binding, Binding, SignalExt};
+    ///
+    /// let count: Binding<i32> = binding(1);
+    /// let steps = Rc::new(RefCell::new(Vec::new()));
+    /// let _guard = {
+    ///     let steps = steps.clone();
+    ///     count
+    ///         .clone()
+    ///         .map(|n| n * 10)
+    ///         .watch_transitions(move |change| steps.borrow_mut().push((change.old, change.new)))
+    /// };
+    ///
+    /// count.set(2);
+    /// count.set(5);
+    /// assert_eq!(*steps.borrow(), [(10, 20), (20, 50)]);
+    /// ```
+    fn watch_transitions(&self, watcher: impl Fn(Transition<Self::Output>) + 'static) -> Self::Guard
+    where
+        Self::Output: Clone,
+    {
+        let last = RefCell::new(self.get());
+        self.watch(move |context: Context<Self::Output>| {
+            let Context { value, metadata } = context;
+            let old = last.replace(value.clone());
+            watcher(Transition {
+                old,
+                new: value,
+                metadata,
+            });
+        })
+    }
 }
 
 impl<C: Signal + Sized> SignalExt for C {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding, test_executor::Manual};
+    use alloc::{rc::Rc, vec::Vec};
+    use core::{
+        future::poll_fn,
+        pin::{Pin, pin},
+        task::{Context as TaskContext, Waker},
+    };
+    use executor_core::LocalExecutor;
+    use futures_core::Stream;
+
+    #[test]
+    fn test_dedup_suppresses_repeated_values() {
+        let count: Binding<i32> = binding(0);
+        let deduped = count.clone().dedup();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            deduped.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        count.set(0);
+        count.set(1);
+        count.set(1);
+        count.set(2);
+        count.set(2);
+        count.set(1);
+        assert_eq!(*seen.borrow(), [1, 2, 1]);
+        assert_eq!(deduped.get(), 1);
+    }
+
+    #[test]
+    fn test_bind_display_notifies_when_the_text_changes() {
+        #[derive(Clone)]
+        struct Celsius(f64);
+
+        impl Display for Celsius {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{:.1}°C", self.0)
+            }
+        }
+
+        let temperature: Binding<Celsius> = binding(Celsius(20.0));
+        let label = temperature.clone().bind_display();
+        assert_eq!(label.get(), "20.0°C");
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            label.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        temperature.set(Celsius(21.04));
+        temperature.set(Celsius(21.01));
+        temperature.set(Celsius(-3.5));
+        assert_eq!(*seen.borrow(), ["21.0°C", "-3.5°C"]);
+        assert_eq!(label.get(), "-3.5°C");
+    }
+
+    #[test]
+    fn test_to_stream_yields_each_change_and_ends_with_its_source() {
+        let executor = Manual::default();
+        let count: Binding<i32> = binding(0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let task = executor.spawn({
+            let mut stream = count.clone().to_stream();
+            let seen = seen.clone();
+            async move {
+                while let Some(value) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+                    seen.borrow_mut().push(value);
+                }
+            }
+        });
+
+        executor.run();
+        assert_eq!(*seen.borrow(), [0]);
+        count.set(1);
+        executor.run();
+        count.set(2);
+        executor.run();
+        executor.run();
+        assert_eq!(*seen.borrow(), [0, 1, 2]);
+
+        let mut task = pin!(task);
+        let mut cx = TaskContext::from_waker(Waker::noop());
+        assert!(task.as_mut().poll(&mut cx).is_pending());
+        drop(count);
+        executor.run();
+        assert!(task.as_mut().poll(&mut cx).is_ready());
+        assert_eq!(*seen.borrow(), [0, 1, 2]);
+    }
+}
@@ -0,0 +1,113 @@
+//! # Filtered signals
+//!
+//! [`filter`] derives a signal that only follows the values of its source
+//! passing a predicate, such as only even numbers, and [`filter_map`] one that
+//! follows the values a function maps to `Some`, transforming them on the way.
+//! A value that is rejected does not notify watchers: the filtered signal keeps
+//! the last value that passed.
+//!
+//! A filtered signal reads as an `Option`, which is `None` until a value
+//! passes, including when the initial value of the source is rejected. Once a
+//! value passed, it never goes back to `None`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, filter::filter};
+//!
+//! let count: Binding<i32> = binding(1);
+//! let even = filter(count.clone(), |n: &i32| n % 2 == 0);
+//! assert_eq!(even.get(), None);
+//!
+//! count.set(2);
+//! count.set(3);
+//! assert_eq!(even.get(), Some(2));
+//! ```
+
+use core::fmt;
+
+use crate::{
+    Container, CustomBinding, Signal,
+    held::{Held, held_signal},
+    watcher::Context,
+};
+
+/// A signal of the last value of a source that passed a filter.
+///
+/// Created by [`filter`] and [`filter_map`]. It reads as `None` until a value
+/// passes.
+#[derive(Clone)]
+pub struct Filtered<T: Clone + 'static> {
+    held: Held<Option<T>>,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for Filtered<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.held.debug(f, "Filtered", "value")
+    }
+}
+
+held_signal!(Filtered<T> => Option<T>);
+
+/// Returns a signal following the values of `source` for which `predicate`
+/// returns `true`.
+///
+/// See the [module documentation](self) for how it behaves.
+pub fn filter<S, P>(source: S, predicate: P) -> Filtered<S::Output>
+where
+    S: Signal,
+    S::Output: Clone,
+    P: Fn(&S::Output) -> bool + 'static,
+{
+    filter_map(source, move |value| predicate(&value).then_some(value))
+}
+
+/// Returns a signal following the values `f` returns for the values of
+/// `source`, skipping those for which it returns `None`.
+///
+/// See the [module documentation](self) for how it behaves.
+pub fn filter_map<S, T, F>(source: S, f: F) -> Filtered<T>
+where
+    S: Signal,
+    T: Clone + 'static,
+    F: Fn(S::Output) -> Option<T> + 'static,
+{
+    let container = Container::new(f(source.get()));
+    let guard = {
+        let container = container.clone();
+        source.watch(move |context: Context<S::Output>| {
+            if let Some(value) = f(context.value) {
+                container.set(Some(value));
+            }
+        })
+    };
+    Filtered {
+        held: Held::new(container, guard),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+    use alloc::{rc::Rc, vec::Vec};
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_filter_map_suppresses_rejected_values() {
+        let input: Binding<&'static str> = binding("42");
+        let number = filter_map(input.clone(), |text: &str| text.parse::<i32>().ok());
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            number.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        assert_eq!(number.get(), Some(42));
+        input.set("4x");
+        input.set("7");
+        input.set("");
+        assert_eq!(number.get(), Some(7));
+        assert_eq!(*seen.borrow(), [Some(7)]);
+    }
+}
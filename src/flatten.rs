@@ -0,0 +1,139 @@
+//! # Nested signals
+//!
+//! A signal can produce another signal, such as a map selecting one of two
+//! bindings depending on a third. [`flatten`] turns such a signal of signals
+//! into a signal of the inner values, following whichever inner signal is the
+//! current one, and [`switch_map`] maps each value to a signal and flattens
+//! the result in one step.
+//!
+//! When the outer signal changes, the watcher of the previous inner signal is
+//! dropped before the new one is watched, so a signal that is no longer
+//! selected cannot notify. Switching notifies with the value of the new inner
+//! signal.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt};
+//!
+//! let celsius: Binding<i32> = binding(20);
+//! let fahrenheit: Binding<i32> = binding(68);
+//! let use_celsius: Binding<bool> = binding(true);
+//!
+//! let shown = use_celsius.clone().switch_map({
+//!     let (celsius, fahrenheit) = (celsius.clone(), fahrenheit.clone());
+//!     move |use_celsius| if use_celsius { celsius.clone() } else { fahrenheit.clone() }
+//! });
+//! assert_eq!(shown.get(), 20);
+//!
+//! use_celsius.set(false);
+//! celsius.set(25);
+//! assert_eq!(shown.get(), 68);
+//! ```
+
+use alloc::rc::Rc;
+use core::{cell::RefCell, fmt};
+
+use crate::{
+    Container, CustomBinding, Signal, SignalExt,
+    held::{Held, held_signal},
+    watcher::Context,
+};
+
+/// A signal of the values of the current inner signal of a signal of signals.
+///
+/// Created by [`flatten`] and [`switch_map`].
+#[derive(Clone)]
+pub struct Flatten<T: Clone + 'static> {
+    held: Held<T>,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for Flatten<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.held.debug(f, "Flatten", "value")
+    }
+}
+
+held_signal!(Flatten<T> => T);
+
+/// Returns a signal following the values of the current inner signal of
+/// `source`.
+///
+/// See the [module documentation](self) for how it behaves.
+pub fn flatten<S>(source: S) -> Flatten<<S::Output as Signal>::Output>
+where
+    S: Signal,
+    S::Output: Signal,
+    <S::Output as Signal>::Output: Clone,
+{
+    let inner = source.get();
+    let container = Container::new(inner.get());
+    let current = Rc::new(RefCell::new(Some(follow(inner, &container))));
+    let guard = {
+        let (container, current) = (container.clone(), current.clone());
+        source.watch(move |context: Context<S::Output>| {
+            // The previous inner signal stops notifying before switching.
+            current.borrow_mut().take();
+            let inner = context.value;
+            container.set(inner.get());
+            *current.borrow_mut() = Some(follow(inner, &container));
+        })
+    };
+    Flatten {
+        held: Held::new(container, (guard, current)),
+    }
+}
+
+/// Returns a signal following the values of the signal `f` returns for the
+/// current value of `source`.
+///
+/// This is `flatten(source.map(f))`. See the [module documentation](self) for
+/// how it behaves.
+pub fn switch_map<S, F, I>(source: S, f: F) -> Flatten<I::Output>
+where
+    S: Signal,
+    F: Fn(S::Output) -> I + 'static,
+    I: Signal,
+    I::Output: Clone,
+{
+    flatten(source.map(f))
+}
+
+/// Keeps the inner signal alive while forwarding its values into `container`.
+fn follow<I>(inner: I, container: &Container<I::Output>) -> (I, I::Guard)
+where
+    I: Signal,
+    I::Output: Clone,
+{
+    let container = container.clone();
+    let guard = inner.watch(move |context: Context<I::Output>| container.set(context.value));
+    (inner, guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_flatten_drops_the_previous_inner_watcher() {
+        let first: Binding<i32> = binding(1);
+        let second: Binding<i32> = binding(2);
+        let selected: Binding<Binding<i32>> = binding(first.clone());
+        let value = selected.clone().flatten();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            value.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        first.set(10);
+        selected.set(second.clone());
+        assert_eq!(first.watcher_count(), Some(0));
+        first.set(11);
+        second.set(20);
+        assert_eq!(value.get(), 20);
+        assert_eq!(*seen.borrow(), [10, 2, 20]);
+    }
+}
@@ -0,0 +1,531 @@
+//! # Locale-aware formatting
+//!
+//! This module formats numbers and dates as reactive strings that follow both
+//! the value and the locale, so a label updates when either changes:
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, format::{Locale, NumberOptions, format_number}};
+//!
+//! let price: Binding<f64> = binding(1234.5);
+//! let locale: Binding<Locale> = binding(Locale::EN_US);
+//! let label = format_number(price.clone(), locale.clone(), NumberOptions::fixed(2));
+//! assert_eq!(label.get(), "1,234.50");
+//!
+//! locale.set(Locale::DE_DE);
+//! assert_eq!(label.get(), "1.234,50");
+//! ```
+//!
+//! The formatting backend is built in and covers a small set of locales with
+//! their separators, date orders and month names. A [`Locale`] is a plain value,
+//! so a constant locale can be passed wherever a locale signal is expected.
+
+use alloc::{format, string::String};
+use core::fmt::{self, Display};
+
+use crate::{Signal, SignalExt, watcher::Context};
+
+/// The order of the fields of a short date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateOrder {
+    MonthDayYear,
+    DayMonthYear,
+    YearMonthDay,
+}
+
+/// The formatting conventions of a locale.
+#[derive(Debug, PartialEq, Eq)]
+struct Conventions {
+    tag: &'static str,
+    decimal: char,
+    group: Option<char>,
+    order: DateOrder,
+    date_separator: char,
+    months: [&'static str; 12],
+    long_date: LongDate,
+}
+
+/// How a long date combines its day, month name and year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LongDate {
+    /// `January 31, 2024`
+    MonthDayYear,
+    /// `31 January 2024`
+    DayMonthYear,
+    /// `31. Januar 2024`
+    OrdinalDayMonthYear,
+    /// `2024年1月31日`
+    YearMonthDay,
+}
+
+const ENGLISH_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// A locale supported by the built-in formatting backend.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Locale(&'static Conventions);
+
+impl Locale {
+    /// English as used in the United States.
+    pub const EN_US: Self = Self(&Conventions {
+        tag: "en-US",
+        decimal: '.',
+        group: Some(','),
+        order: DateOrder::MonthDayYear,
+        date_separator: '/',
+        months: ENGLISH_MONTHS,
+        long_date: LongDate::MonthDayYear,
+    });
+
+    /// English as used in the United Kingdom.
+    pub const EN_GB: Self = Self(&Conventions {
+        tag: "en-GB",
+        decimal: '.',
+        group: Some(','),
+        order: DateOrder::DayMonthYear,
+        date_separator: '/',
+        months: ENGLISH_MONTHS,
+        long_date: LongDate::DayMonthYear,
+    });
+
+    /// German as used in Germany.
+    pub const DE_DE: Self = Self(&Conventions {
+        tag: "de-DE",
+        decimal: ',',
+        group: Some('.'),
+        order: DateOrder::DayMonthYear,
+        date_separator: '.',
+        months: [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        long_date: LongDate::OrdinalDayMonthYear,
+    });
+
+    /// French as used in France.
+    pub const FR_FR: Self = Self(&Conventions {
+        tag: "fr-FR",
+        decimal: ',',
+        group: Some('\u{202f}'),
+        order: DateOrder::DayMonthYear,
+        date_separator: '/',
+        months: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        long_date: LongDate::DayMonthYear,
+    });
+
+    /// Japanese as used in Japan.
+    pub const JA_JP: Self = Self(&Conventions {
+        tag: "ja-JP",
+        decimal: '.',
+        group: Some(','),
+        order: DateOrder::YearMonthDay,
+        date_separator: '/',
+        months: [
+            "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+        ],
+        long_date: LongDate::YearMonthDay,
+    });
+
+    const ALL: [Self; 5] = [
+        Self::EN_US,
+        Self::EN_GB,
+        Self::DE_DE,
+        Self::FR_FR,
+        Self::JA_JP,
+    ];
+
+    /// Looks up a locale by its language tag, such as `"de-DE"` or `"fr"`.
+    ///
+    /// Tags are matched case-insensitively, with `-` or `_` as the separator. A
+    /// bare language selects its most common region. Returns `None` if the
+    /// locale is not supported.
+    #[must_use]
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let matches = |locale: &Self| {
+            let known = locale.0.tag;
+            tag.len() == known.len()
+                && tag
+                    .chars()
+                    .zip(known.chars())
+                    .all(|(a, b)| a.eq_ignore_ascii_case(&b) || (a == '_' && b == '-'))
+        };
+        Self::ALL.into_iter().find(matches).or_else(|| {
+            Self::ALL.into_iter().find(|locale| {
+                locale
+                    .0
+                    .tag
+                    .split('-')
+                    .next()
+                    .is_some_and(|language| language.eq_ignore_ascii_case(tag))
+            })
+        })
+    }
+
+    /// Returns the language tag of this locale.
+    #[must_use]
+    pub const fn tag(self) -> &'static str {
+        self.0.tag
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::EN_US
+    }
+}
+
+impl fmt::Debug for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Locale").field(&self.0.tag).finish()
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.tag)
+    }
+}
+
+/// A locale is a constant signal of itself.
+impl Signal for Locale {
+    type Output = Self;
+    type Guard = ();
+
+    fn get(&self) -> Self::Output {
+        *self
+    }
+
+    fn watch(&self, _watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {}
+}
+
+/// Options for [`format_number`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberOptions {
+    min_fraction_digits: usize,
+    max_fraction_digits: usize,
+    grouping: bool,
+}
+
+impl Default for NumberOptions {
+    /// Up to three fraction digits, with digit grouping.
+    fn default() -> Self {
+        Self {
+            min_fraction_digits: 0,
+            max_fraction_digits: 3,
+            grouping: true,
+        }
+    }
+}
+
+impl NumberOptions {
+    /// Options showing exactly `digits` fraction digits, with digit grouping.
+    #[must_use]
+    pub const fn fixed(digits: usize) -> Self {
+        Self {
+            min_fraction_digits: digits,
+            max_fraction_digits: digits,
+            grouping: true,
+        }
+    }
+
+    /// Sets the range of fraction digits shown, rounding to the maximum.
+    ///
+    /// Trailing zeros are removed down to the minimum.
+    #[must_use]
+    pub const fn fraction_digits(mut self, min: usize, max: usize) -> Self {
+        self.min_fraction_digits = if min < max { min } else { max };
+        self.max_fraction_digits = max;
+        self
+    }
+
+    /// Sets whether the integer digits are grouped by thousands.
+    #[must_use]
+    pub const fn grouping(mut self, grouping: bool) -> Self {
+        self.grouping = grouping;
+        self
+    }
+}
+
+/// Formats `value` following `locale`.
+fn number(value: f64, locale: Locale, options: NumberOptions) -> String {
+    if value.is_nan() {
+        return "NaN".into();
+    }
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+    if value.is_infinite() {
+        return format!("{sign}∞");
+    }
+
+    let digits = format!("{:.*}", options.max_fraction_digits, value.abs());
+    let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+    let mut fraction = fraction.trim_end_matches('0');
+    if fraction.len() < options.min_fraction_digits {
+        fraction = &digits[integer.len() + 1..][..options.min_fraction_digits];
+    }
+    let zero = integer.bytes().all(|digit| digit == b'0') && fraction.is_empty();
+
+    let mut result = String::with_capacity(digits.len() + integer.len() / 3 + 1);
+    if !zero {
+        result.push_str(sign);
+    }
+    for (index, digit) in integer.chars().enumerate() {
+        let remaining = integer.len() - index;
+        if let Some(group) = locale.0.group
+            && options.grouping
+            && index > 0
+            && remaining % 3 == 0
+        {
+            result.push(group);
+        }
+        result.push(digit);
+    }
+    if !fraction.is_empty() {
+        result.push(locale.0.decimal);
+        result.push_str(fraction);
+    }
+    result
+}
+
+/// Creates a reactive string of `value` formatted as a number following `locale`.
+///
+/// The string is recomputed when either the value or the locale changes. Pass a
+/// [`Locale`] directly for a fixed locale.
+///
+/// ```rust
+/// use nami::{Signal, format::{Locale, NumberOptions, format_number}};
+///
+/// let options = NumberOptions::default().fraction_digits(1, 2);
+/// assert_eq!(format_number(-9876543.216, Locale::FR_FR, options).get(), "-9\u{202f}876\u{202f}543,22");
+/// assert_eq!(format_number(3.0, Locale::EN_US, options).get(), "3.0");
+/// ```
+pub fn format_number<V, L>(
+    value: V,
+    locale: L,
+    options: NumberOptions,
+) -> impl Signal<Output = String>
+where
+    V: Signal,
+    V::Output: Into<f64>,
+    L: Signal<Output = Locale>,
+{
+    value
+        .zip(locale)
+        .map(move |(value, locale)| number(value.into(), locale, options))
+}
+
+/// A calendar date in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Creates a date, or returns `None` if `month` or `day` is out of range.
+    #[must_use]
+    pub const fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+        if day == 0 || day > days {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    /// Returns the year.
+    #[must_use]
+    pub const fn year(self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month, from 1 to 12.
+    #[must_use]
+    pub const fn month(self) -> u8 {
+        self.month
+    }
+
+    /// Returns the day of the month, starting at 1.
+    #[must_use]
+    pub const fn day(self) -> u8 {
+        self.day
+    }
+}
+
+/// A date is a constant signal of itself.
+impl Signal for Date {
+    type Output = Self;
+    type Guard = ();
+
+    fn get(&self) -> Self::Output {
+        *self
+    }
+
+    fn watch(&self, _watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {}
+}
+
+/// How a date is written by [`format_date`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum DateStyle {
+    /// Numeric fields, such as `1/31/2024` or `31.01.2024`.
+    #[default]
+    Short,
+    /// The month written out, such as `January 31, 2024` or `31. Januar 2024`.
+    Long,
+}
+
+/// Formats `date` following `locale`.
+fn date(date: Date, locale: Locale, style: DateStyle) -> String {
+    let Date { year, month, day } = date;
+    let conventions = locale.0;
+    match style {
+        DateStyle::Short => {
+            let separator = conventions.date_separator;
+            match conventions.order {
+                DateOrder::MonthDayYear => format!("{month}{separator}{day}{separator}{year}"),
+                DateOrder::DayMonthYear => {
+                    format!("{day:02}{separator}{month:02}{separator}{year}")
+                }
+                DateOrder::YearMonthDay => {
+                    format!("{year}{separator}{month:02}{separator}{day:02}")
+                }
+            }
+        }
+        DateStyle::Long => {
+            let name = conventions.months[usize::from(month - 1)];
+            match conventions.long_date {
+                LongDate::MonthDayYear => format!("{name} {day}, {year}"),
+                LongDate::DayMonthYear => format!("{day} {name} {year}"),
+                LongDate::OrdinalDayMonthYear => format!("{day}. {name} {year}"),
+                LongDate::YearMonthDay => format!("{year}年{name}{day}日"),
+            }
+        }
+    }
+}
+
+/// Creates a reactive string of `date` formatted following `locale`.
+///
+/// The string is recomputed when either the date or the locale changes. Pass a
+/// [`Locale`] or [`Date`] directly for a fixed one.
+///
+/// ```rust
+/// use nami::{binding, Binding, Signal, format::{Date, DateStyle, Locale, format_date}};
+///
+/// let due: Binding<Date> = binding(Date::new(2024, 1, 31).unwrap());
+/// let locale: Binding<Locale> = binding(Locale::EN_US);
+/// let label = format_date(due.clone(), locale.clone(), DateStyle::Long);
+/// assert_eq!(label.get(), "January 31, 2024");
+///
+/// locale.set(Locale::from_tag("de").unwrap());
+/// assert_eq!(label.get(), "31. Januar 2024");
+/// ```
+pub fn format_date<D, L>(date: D, locale: L, style: DateStyle) -> impl Signal<Output = String>
+where
+    D: Signal<Output = Date>,
+    L: Signal<Output = Locale>,
+{
+    date.zip(locale)
+        .map(move |(value, locale)| self::date(value, locale, style))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+    use alloc::{rc::Rc, vec::Vec};
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_number_formatting() {
+        let options = NumberOptions::default();
+        assert_eq!(number(0.0, Locale::EN_US, options), "0");
+        assert_eq!(number(-0.0001, Locale::EN_US, options), "0");
+        assert_eq!(number(999.9996, Locale::EN_US, options), "1,000");
+        assert_eq!(number(123_456.789, Locale::DE_DE, options), "123.456,789");
+        assert_eq!(
+            number(1_234_567.0, Locale::EN_GB, options.grouping(false)),
+            "1234567"
+        );
+        assert_eq!(
+            number(-2.5, Locale::JA_JP, NumberOptions::fixed(2)),
+            "-2.50"
+        );
+        assert_eq!(number(f64::NEG_INFINITY, Locale::EN_US, options), "-∞");
+    }
+
+    #[test]
+    fn test_date_formatting() {
+        let date_of = |year, month, day| Date::new(year, month, day).unwrap();
+        assert_eq!(Date::new(2023, 2, 29), None);
+        assert!(Date::new(2024, 2, 29).is_some());
+        assert_eq!(Date::new(2024, 13, 1), None);
+
+        let day = date_of(2024, 3, 5);
+        let short = |locale| date(day, locale, DateStyle::Short);
+        assert_eq!(short(Locale::EN_US), "3/5/2024");
+        assert_eq!(short(Locale::EN_GB), "05/03/2024");
+        assert_eq!(short(Locale::DE_DE), "05.03.2024");
+        assert_eq!(short(Locale::JA_JP), "2024/03/05");
+        assert_eq!(date(day, Locale::FR_FR, DateStyle::Long), "5 mars 2024");
+        assert_eq!(date(day, Locale::JA_JP, DateStyle::Long), "2024年3月5日");
+    }
+
+    #[test]
+    fn test_format_follows_locale() {
+        let amount: Binding<i32> = binding(1500);
+        let locale: Binding<Locale> = binding(Locale::EN_US);
+        let label = format_number(amount.clone(), locale.clone(), NumberOptions::default());
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            label.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        amount.set(2500);
+        locale.set(Locale::from_tag("DE_de").unwrap());
+        assert_eq!(*seen.borrow(), ["2,500", "2.500"]);
+        assert_eq!(Locale::from_tag("pt-BR"), None);
+    }
+}
@@ -5,6 +5,18 @@
 //! becomes `Some(value)` and notifies watchers.
 //!
 //! This is handy for wiring async computations into a reactive graph.
+//!
+//! In the other direction, [`wait_until`] returns a future resolving once a
+//! signal reaches a state, and [`next_change`] one resolving on its next
+//! notification, so async workflows can pause on the graph.
+
+use alloc::rc::Rc;
+use core::{
+    cell::RefCell,
+    fmt,
+    pin::Pin,
+    task::{Poll, Waker},
+};
 
 use executor_core::{DefaultExecutor, LocalExecutor};
 
@@ -69,3 +81,177 @@ where
         self.container.watch(watcher)
     }
 }
+
+/// Returns a future resolving to the first value of `signal` that satisfies
+/// `predicate`.
+///
+/// The current value is checked first, so the future resolves immediately if it
+/// already satisfies `predicate`. To wait for several conditions at once, pass
+/// a tuple of signals.
+///
+/// ```rust
+/// use core::{pin::pin, task::{Context, Poll, Waker}};
+/// use nami::{binding, Binding, future::wait_until};
+///
+/// let loaded: Binding<bool> = binding(false);
+/// let progress: Binding<u8> = binding(0);
+/// let mut ready = pin!(wait_until((loaded.clone(), progress.clone()), |(loaded, progress)| {
+///     *loaded && *progress == 100
+/// }));
+///
+/// let mut cx = Context::from_waker(Waker::noop());
+/// loaded.set(true);
+/// assert!(ready.as_mut().poll(&mut cx).is_pending());
+/// progress.set(100);
+/// assert_eq!(ready.as_mut().poll(&mut cx), Poll::Ready((true, 100)));
+/// ```
+pub fn wait_until<S, P>(signal: S, predicate: P) -> WaitUntil<S, P>
+where
+    S: Signal,
+    P: Fn(&S::Output) -> bool,
+{
+    WaitUntil {
+        signal,
+        predicate,
+        waker: Rc::default(),
+        guard: None,
+    }
+}
+
+/// A future resolving to the first value of a signal that satisfies a
+/// predicate, returned by [`wait_until`].
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitUntil<S: Signal, P> {
+    signal: S,
+    predicate: P,
+    waker: Rc<RefCell<Option<Waker>>>,
+    guard: Option<S::Guard>,
+}
+
+impl<S: Signal + fmt::Debug, P> fmt::Debug for WaitUntil<S, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitUntil")
+            .field("signal", &self.signal)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Signal, P> Unpin for WaitUntil<S, P> {}
+
+impl<S, P> Future for WaitUntil<S, P>
+where
+    S: Signal,
+    P: Fn(&S::Output) -> bool,
+{
+    type Output = S::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<S::Output> {
+        let this = self.get_mut();
+        let value = this.signal.get();
+        if (this.predicate)(&value) {
+            this.guard = None;
+            return Poll::Ready(value);
+        }
+        this.waker.replace(Some(cx.waker().clone()));
+        if this.guard.is_none() {
+            let waker = this.waker.clone();
+            this.guard = Some(this.signal.watch(move |_| {
+                if let Some(waker) = waker.borrow_mut().take() {
+                    waker.wake();
+                }
+            }));
+        }
+        Poll::Pending
+    }
+}
+
+/// Returns a future resolving to the value of the next notification of
+/// `signal`.
+///
+/// Only notifications after the future is first polled are observed.
+///
+/// ```rust
+/// use core::{pin::pin, task::{Context, Poll, Waker}};
+/// use nami::{binding, Binding, future::next_change};
+///
+/// let count: Binding<i32> = binding(0);
+/// let mut change = pin!(next_change(count.clone()));
+///
+/// let mut cx = Context::from_waker(Waker::noop());
+/// assert!(change.as_mut().poll(&mut cx).is_pending());
+/// count.set(1);
+/// count.set(2);
+/// assert_eq!(change.as_mut().poll(&mut cx), Poll::Ready(1));
+/// ```
+pub fn next_change<S: Signal>(signal: S) -> NextChange<S> {
+    NextChange {
+        signal,
+        state: Rc::default(),
+        guard: None,
+    }
+}
+
+/// The value received by a [`NextChange`], and the waker to wake with it.
+struct Change<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+}
+
+impl<T> Default for Change<T> {
+    fn default() -> Self {
+        Self {
+            value: None,
+            waker: None,
+        }
+    }
+}
+
+/// A future resolving to the value of the next notification of a signal,
+/// returned by [`next_change`].
+#[must_use = "futures do nothing unless polled"]
+pub struct NextChange<S: Signal> {
+    signal: S,
+    state: Rc<RefCell<Change<S::Output>>>,
+    guard: Option<S::Guard>,
+}
+
+impl<S: Signal + fmt::Debug> fmt::Debug for NextChange<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NextChange")
+            .field("signal", &self.signal)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Signal> Unpin for NextChange<S> {}
+
+impl<S: Signal> Future for NextChange<S> {
+    type Output = S::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<S::Output> {
+        let this = self.get_mut();
+        let mut state = this.state.borrow_mut();
+        if let Some(value) = state.value.take() {
+            drop(state);
+            this.guard = None;
+            return Poll::Ready(value);
+        }
+        state.waker = Some(cx.waker().clone());
+        drop(state);
+        if this.guard.is_none() {
+            let state = this.state.clone();
+            this.guard = Some(this.signal.watch(move |context| {
+                let mut state = state.borrow_mut();
+                if state.value.is_none() {
+                    state.value = Some(context.value);
+                    let waker = state.waker.take();
+                    drop(state);
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+            }));
+        }
+        Poll::Pending
+    }
+}
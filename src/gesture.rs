@@ -0,0 +1,386 @@
+//! # Gestures
+//!
+//! [`gestures`] recognizes drags, taps and long presses from a signal of
+//! pointer events, so interaction logic can live in the reactive graph instead
+//! of in glue code for each UI framework. The platform layer only forwards its
+//! pointer events, in order, by setting a binding of [`PointerEvent`]s: each
+//! notification is one event.
+//!
+//! A press becomes a drag once the pointer moves further than the
+//! [slop](GestureConfig::slop) from where it went down. A press released
+//! before becoming a drag, within the [tap timeout](GestureConfig::tap_timeout),
+//! is a tap. A press held without dragging for the
+//! [long press delay](GestureConfig::long_press) is a long press, and is not a
+//! tap when released. Since no event arrives while the pointer is held still,
+//! long presses are detected on the ticks of a clock signal, like the one
+//! driving [`animation`](crate::animation).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use nami::{binding, Binding, Signal, gesture::{GestureConfig, Point, PointerEvent, gestures}};
+//!
+//! let start = PointerEvent::down(Point::new(10.0, 10.0), Duration::ZERO);
+//! let events: Binding<PointerEvent> = binding(start);
+//! let clock: Binding<Duration> = binding(Duration::ZERO);
+//! let gesture = gestures(events.clone(), clock, GestureConfig::default());
+//!
+//! events.set(start);
+//! events.set(PointerEvent::moved(Point::new(40.0, 30.0), Duration::from_millis(50)));
+//! assert!(gesture.dragging().get());
+//! assert_eq!(gesture.drag_delta().get(), Point::new(30.0, 20.0));
+//!
+//! events.set(PointerEvent::up(Point::new(40.0, 30.0), Duration::from_millis(90)));
+//! assert!(!gesture.dragging().get());
+//! assert_eq!(gesture.tap().get(), None);
+//! ```
+
+use alloc::rc::Rc;
+use core::{any::Any, cell::RefCell, fmt, time::Duration};
+
+use crate::{Computed, Container, CustomBinding, Signal, SignalExt, watcher::Context};
+
+/// A position on screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Point {
+    /// The horizontal coordinate.
+    pub x: f64,
+    /// The vertical coordinate.
+    pub y: f64,
+}
+
+impl Point {
+    /// Creates a point at `x` and `y`.
+    #[must_use]
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+/// The kind of a [`PointerEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerKind {
+    /// The pointer was pressed.
+    Down,
+    /// The pointer moved.
+    Move,
+    /// The pointer was released.
+    Up,
+    /// The platform took the pointer over, such as for scrolling.
+    Cancel,
+}
+
+/// A pointer event forwarded by the platform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerEvent {
+    /// What happened to the pointer.
+    pub kind: PointerKind,
+    /// Where the pointer is.
+    pub position: Point,
+    /// When the event happened, on the same clock as the clock signal.
+    pub time: Duration,
+}
+
+impl PointerEvent {
+    /// Creates an event of `kind` at `position` and `time`.
+    #[must_use]
+    pub const fn new(kind: PointerKind, position: Point, time: Duration) -> Self {
+        Self {
+            kind,
+            position,
+            time,
+        }
+    }
+
+    /// Creates a [`PointerKind::Down`] event.
+    #[must_use]
+    pub const fn down(position: Point, time: Duration) -> Self {
+        Self::new(PointerKind::Down, position, time)
+    }
+
+    /// Creates a [`PointerKind::Move`] event.
+    #[must_use]
+    pub const fn moved(position: Point, time: Duration) -> Self {
+        Self::new(PointerKind::Move, position, time)
+    }
+
+    /// Creates a [`PointerKind::Up`] event.
+    #[must_use]
+    pub const fn up(position: Point, time: Duration) -> Self {
+        Self::new(PointerKind::Up, position, time)
+    }
+}
+
+/// The thresholds used to recognize gestures.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureConfig {
+    /// The distance the pointer must move for a press to become a drag.
+    pub slop: f64,
+    /// The longest press that is a tap.
+    pub tap_timeout: Duration,
+    /// How long a press must be held without dragging to be a long press.
+    pub long_press: Duration,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            slop: 8.0,
+            tap_timeout: Duration::from_millis(300),
+            long_press: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Reactive gesture state recognized from pointer events.
+///
+/// Created by [`gestures`]. All clones share the same state.
+#[derive(Clone)]
+pub struct Gestures {
+    pressed: Container<bool>,
+    dragging: Container<bool>,
+    drag_delta: Container<Point>,
+    tap: Container<Option<Point>>,
+    long_press: Container<Option<Point>>,
+    _guard: Rc<dyn Any>,
+}
+
+impl fmt::Debug for Gestures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Gestures")
+            .field("pressed", &self.pressed.get())
+            .field("dragging", &self.dragging.get())
+            .field("drag_delta", &self.drag_delta.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Gestures {
+    /// Returns a signal of whether the pointer is pressed.
+    #[must_use]
+    pub fn pressed(&self) -> Computed<bool> {
+        self.pressed.clone().computed()
+    }
+
+    /// Returns a signal of whether the pointer is dragging.
+    #[must_use]
+    pub fn dragging(&self) -> Computed<bool> {
+        self.dragging.clone().computed()
+    }
+
+    /// Returns a signal of the offset of the pointer from where the current
+    /// or last drag started.
+    #[must_use]
+    pub fn drag_delta(&self) -> Computed<Point> {
+        self.drag_delta.clone().computed()
+    }
+
+    /// Returns a signal notifying with the position of each tap.
+    ///
+    /// It reads as `None` until the first tap.
+    #[must_use]
+    pub fn tap(&self) -> Computed<Option<Point>> {
+        self.tap.clone().computed()
+    }
+
+    /// Returns a signal notifying with the position of each long press.
+    ///
+    /// It reads as `None` until the first long press.
+    #[must_use]
+    pub fn long_press(&self) -> Computed<Option<Point>> {
+        self.long_press.clone().computed()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Phase {
+    Idle,
+    /// Pressed at `start`, neither dragging nor long pressed yet.
+    Pressed {
+        start: Point,
+        time: Duration,
+    },
+    LongPressed {
+        start: Point,
+    },
+    Dragging {
+        start: Point,
+    },
+}
+
+/// Returns the gesture state recognized from `events`, with long presses
+/// detected on the ticks of `clock`.
+///
+/// See the [module documentation](self) for how gestures are recognized.
+pub fn gestures<E, C>(events: E, clock: C, config: GestureConfig) -> Gestures
+where
+    E: Signal<Output = PointerEvent>,
+    C: Signal<Output = Duration>,
+{
+    let state = Recognizer {
+        config,
+        phase: Rc::new(RefCell::new(Phase::Idle)),
+        pressed: Container::new(false),
+        dragging: Container::new(false),
+        drag_delta: Container::new(Point::default()),
+        tap: Container::new(None),
+        long_press: Container::new(None),
+    };
+    let events_guard = {
+        let state = state.clone();
+        events.watch(move |context: Context<PointerEvent>| state.event(context.value))
+    };
+    let clock_guard = {
+        let state = state.clone();
+        clock.watch(move |context: Context<Duration>| state.tick(context.value))
+    };
+
+    Gestures {
+        pressed: state.pressed,
+        dragging: state.dragging,
+        drag_delta: state.drag_delta,
+        tap: state.tap,
+        long_press: state.long_press,
+        _guard: Rc::new((events_guard, clock_guard)),
+    }
+}
+
+#[derive(Clone)]
+struct Recognizer {
+    config: GestureConfig,
+    phase: Rc<RefCell<Phase>>,
+    pressed: Container<bool>,
+    dragging: Container<bool>,
+    drag_delta: Container<Point>,
+    tap: Container<Option<Point>>,
+    long_press: Container<Option<Point>>,
+}
+
+impl Recognizer {
+    fn event(&self, event: PointerEvent) {
+        let phase = *self.phase.borrow();
+        match (event.kind, phase) {
+            (PointerKind::Down, _) => {
+                self.enter(Phase::Pressed {
+                    start: event.position,
+                    time: event.time,
+                });
+                set_if_changed(&self.pressed, true);
+            }
+            (PointerKind::Move, Phase::Pressed { start, time }) => {
+                if self.beyond_slop(start, event.position) {
+                    self.start_drag(start, event.position);
+                } else {
+                    self.check_long_press(start, time, event.time);
+                }
+            }
+            (PointerKind::Move, Phase::LongPressed { start }) => {
+                if self.beyond_slop(start, event.position) {
+                    self.start_drag(start, event.position);
+                }
+            }
+            (PointerKind::Move, Phase::Dragging { start }) => {
+                self.drag_delta.set(offset(start, event.position));
+            }
+            (PointerKind::Up, Phase::Pressed { start, time }) => {
+                self.release();
+                if self.check_long_press(start, time, event.time) {
+                    return;
+                }
+                if event.time.saturating_sub(time) <= self.config.tap_timeout {
+                    self.tap.set(Some(event.position));
+                }
+            }
+            (PointerKind::Up | PointerKind::Cancel, _) => self.release(),
+            (PointerKind::Move, Phase::Idle) => {}
+        }
+    }
+
+    fn tick(&self, now: Duration) {
+        let phase = *self.phase.borrow();
+        if let Phase::Pressed { start, time } = phase {
+            self.check_long_press(start, time, now);
+        }
+    }
+
+    fn enter(&self, phase: Phase) {
+        *self.phase.borrow_mut() = phase;
+    }
+
+    fn beyond_slop(&self, start: Point, position: Point) -> bool {
+        let Point { x, y } = offset(start, position);
+        x.mul_add(x, y * y) > self.config.slop * self.config.slop
+    }
+
+    fn start_drag(&self, start: Point, position: Point) {
+        self.enter(Phase::Dragging { start });
+        self.drag_delta.set(offset(start, position));
+        set_if_changed(&self.dragging, true);
+    }
+
+    /// Recognizes a long press if the press started at `time` has been held
+    /// long enough at `now`, returning whether it did.
+    fn check_long_press(&self, start: Point, time: Duration, now: Duration) -> bool {
+        if now.saturating_sub(time) < self.config.long_press {
+            return false;
+        }
+        self.enter(Phase::LongPressed { start });
+        self.long_press.set(Some(start));
+        true
+    }
+
+    fn release(&self) {
+        self.enter(Phase::Idle);
+        set_if_changed(&self.pressed, false);
+        set_if_changed(&self.dragging, false);
+    }
+}
+
+fn offset(from: Point, to: Point) -> Point {
+    Point::new(to.x - from.x, to.y - from.y)
+}
+
+fn set_if_changed(container: &Container<bool>, value: bool) {
+    if container.get() != value {
+        container.set(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+
+    #[test]
+    fn test_gestures_recognize_taps_and_long_presses() {
+        let at = Point::new(5.0, 5.0);
+        let events: Binding<PointerEvent> = binding(PointerEvent::up(at, Duration::ZERO));
+        let clock: Binding<Duration> = binding(Duration::ZERO);
+        let gesture = gestures(events.clone(), clock.clone(), GestureConfig::default());
+        let ms = Duration::from_millis;
+
+        events.set(PointerEvent::down(at, ms(0)));
+        events.set(PointerEvent::moved(Point::new(7.0, 6.0), ms(20)));
+        assert!(gesture.pressed().get());
+        events.set(PointerEvent::up(Point::new(7.0, 6.0), ms(100)));
+        assert_eq!(gesture.tap().get(), Some(Point::new(7.0, 6.0)));
+        assert!(!gesture.dragging().get());
+
+        events.set(PointerEvent::down(at, ms(1000)));
+        clock.set(ms(1400));
+        assert_eq!(gesture.long_press().get(), None);
+        clock.set(ms(1500));
+        assert_eq!(gesture.long_press().get(), Some(at));
+
+        // Releasing a long press is not a tap.
+        let taps = Rc::new(RefCell::new(0));
+        let _guard = {
+            let taps = taps.clone();
+            gesture.tap().watch(move |_| *taps.borrow_mut() += 1)
+        };
+        events.set(PointerEvent::up(at, ms(1600)));
+        assert_eq!(*taps.borrow(), 0);
+        assert!(!gesture.pressed().get());
+    }
+}
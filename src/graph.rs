@@ -0,0 +1,108 @@
+//! # Declarative graphs
+//!
+//! The [`graph!`](crate::graph!) macro describes a reactive graph as a list of
+//! definitions, one per node, instead of the plumbing of clones, zips and maps:
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use nami::{binding, graph, Binding, Signal, graph::Graph};
+//!
+//! let pricing = graph! {
+//!     price: Binding<i32> = binding(10);
+//!     quantity: Binding<i32> = binding(3);
+//!     subtotal = price * quantity;
+//!     total = subtotal + subtotal / 10;
+//! };
+//!
+//! pricing.quantity.set(4);
+//! assert_eq!(pricing.total.get(), 44);
+//! assert_eq!(pricing.edges(), [
+//!     ("price", "subtotal"),
+//!     ("quantity", "subtotal"),
+//!     ("subtotal", "total"),
+//! ]);
+//! # }
+//! ```
+//!
+//! A definition mentioning earlier nodes computes its value from their values,
+//! like `subtotal` above, and notifies when any of them changes. A definition
+//! mentioning no earlier node is used as it is, so it must be a signal, such as
+//! a binding or a signal built outside of the graph. A definition can name its
+//! type, as in `price: Binding<i32> = binding(10)`, where inference needs it.
+//!
+//! The macro returns a struct with a field for every node. It implements
+//! [`Graph`], naming the nodes and the edges between them for debug tooling,
+//! such as rendering the graph with [`Graph::to_dot`].
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::{NodeId, Signal};
+
+/// A named node of a [`Graph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Node {
+    /// The name of the node's definition.
+    pub name: &'static str,
+    /// The identity of the node's signal, if it has one.
+    pub id: Option<NodeId>,
+    /// The type name of the node's output.
+    pub output: &'static str,
+}
+
+impl Node {
+    /// Describes `signal` as the node named `name`.
+    pub fn new<S: Signal>(name: &'static str, signal: &S) -> Self {
+        Self {
+            name,
+            id: signal.node_id(),
+            output: core::any::type_name::<S::Output>(),
+        }
+    }
+}
+
+/// A graph of named signals, usually created by [`graph!`](crate::graph!).
+pub trait Graph {
+    /// Returns the nodes of the graph, in definition order.
+    fn nodes(&self) -> Vec<Node>;
+
+    /// Returns the edges of the graph as pairs of node names, from each node
+    /// to the nodes computed from it.
+    fn edges(&self) -> Vec<(&'static str, &'static str)>;
+
+    /// Renders the graph in the Graphviz DOT language.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "derive")]
+    /// # {
+    /// use nami::{binding, graph, Binding, graph::Graph};
+    ///
+    /// let counter = graph! {
+    ///     count: Binding<i32> = binding(1);
+    ///     doubled = count * 2;
+    /// };
+    /// assert_eq!(counter.to_dot(), "digraph {
+    ///     count [label=\"count: i32\"];
+    ///     doubled [label=\"doubled: i32\"];
+    ///     count -> doubled;
+    /// }");
+    /// # }
+    /// ```
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        for node in self.nodes() {
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"{}: {}\"];",
+                node.name, node.name, node.output
+            );
+        }
+        for (from, to) in self.edges() {
+            let _ = writeln!(dot, "    {from} -> {to};");
+        }
+        dot.push('}');
+        dot
+    }
+}
+
@@ -0,0 +1,241 @@
+//! # Graph health telemetry
+//!
+//! This module samples the load on the reactive graph of the current thread,
+//! so apps can warn developers in the field when the reactive engine is
+//! overloaded, such as when a flush takes longer than a frame.
+//!
+//! A [`GraphHealth`] snapshot holds:
+//!
+//! - The number of live reactive nodes, counted as the nodes holding a list of
+//!   watchers, such as bindings and cached maps.
+//! - The number of registered watchers.
+//! - How long the last flush took. A flush is everything run by a write made
+//!   outside of any watcher: the watchers it notified, and the writes they made
+//!   in turn.
+//! - The queue depth of the last flush: the largest number of watchers waiting
+//!   to be notified at once during it.
+//!
+//! [`sample`] takes a snapshot directly, and [`sampler`] samples one on each
+//! tick of a clock signal, like the one driving
+//! [`animation`](crate::animation), as a signal itself. Like the
+//! [`watchdog`](crate::watchdog), the counters are kept per thread.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use nami::{binding, Binding, Signal, SignalExt, health};
+//!
+//! let clock: Binding<Duration> = binding(Duration::ZERO);
+//! let health = health::sampler(clock.clone());
+//! let overloaded = health
+//!     .clone()
+//!     .map(|health| health.last_flush.is_some_and(|flush| flush > Duration::from_millis(16)));
+//!
+//! let count: Binding<i32> = binding(0);
+//! let _watchers: Vec<_> = (0..3).map(|_| count.watch(|_| {})).collect();
+//! count.set(1);
+//!
+//! clock.set(Duration::from_millis(16));
+//! assert_eq!(health.get().queue_depth, 3);
+//! assert!(!overloaded.get());
+//! ```
+
+use core::{cell::Cell, fmt, time::Duration};
+use std::time::Instant;
+
+use crate::{
+    Container, CustomBinding, Signal,
+    held::{Held, held_signal},
+    watcher::Context,
+};
+
+/// A snapshot of the load on the reactive graph of the current thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct GraphHealth {
+    /// The number of live reactive nodes holding a list of watchers.
+    pub nodes: usize,
+    /// The number of registered watchers.
+    pub watchers: usize,
+    /// How long the last completed flush took, or `None` before the first one.
+    pub last_flush: Option<Duration>,
+    /// The largest number of watchers waiting to be notified at once during
+    /// the last completed flush.
+    pub queue_depth: usize,
+}
+
+std::thread_local! {
+    static NODES: Cell<usize> = const { Cell::new(0) };
+    static WATCHERS: Cell<usize> = const { Cell::new(0) };
+    /// The depth of the running notification, zero outside of a flush.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// When the running flush started.
+    static STARTED: Cell<Option<Instant>> = const { Cell::new(None) };
+    /// The number of watchers waiting to be notified in the running flush.
+    static PENDING: Cell<usize> = const { Cell::new(0) };
+    /// The largest value of `PENDING` in the running flush.
+    static PEAK: Cell<usize> = const { Cell::new(0) };
+    static LAST_FLUSH: Cell<Option<Duration>> = const { Cell::new(None) };
+    static QUEUE_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Returns a snapshot of the load on the reactive graph of the current thread.
+#[must_use]
+pub fn sample() -> GraphHealth {
+    GraphHealth {
+        nodes: NODES.with(Cell::get),
+        watchers: WATCHERS.with(Cell::get),
+        last_flush: LAST_FLUSH.with(Cell::get),
+        queue_depth: QUEUE_DEPTH.with(Cell::get),
+    }
+}
+
+/// A signal of the health of the reactive graph, sampled on each clock tick.
+///
+/// Created by [`sampler`]. All clones share the same samples.
+#[derive(Clone)]
+pub struct HealthSampler {
+    held: Held<GraphHealth>,
+}
+
+impl fmt::Debug for HealthSampler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.held.debug(f, "HealthSampler", "health")
+    }
+}
+
+held_signal!(HealthSampler => GraphHealth);
+
+/// Returns a signal of the health of the reactive graph of the current thread,
+/// sampled now and on each tick of `clock`.
+///
+/// It only notifies when a sample differs from the previous one. A sample taken
+/// on a tick describes the last flush completed before the tick.
+pub fn sampler<C: Signal>(clock: C) -> HealthSampler {
+    let container = Container::new(sample());
+    let guard = {
+        let container = container.clone();
+        clock.watch(move |_: Context<C::Output>| {
+            let health = sample();
+            if container.get() != health {
+                container.set(health);
+            }
+        })
+    };
+    HealthSampler {
+        held: Held::new(container, guard),
+    }
+}
+
+/// Adds `delta` to `counter`, ignoring a thread being torn down.
+fn add(counter: &'static std::thread::LocalKey<Cell<usize>>, delta: usize) {
+    let _ = counter.try_with(|counter| counter.set(counter.get() + delta));
+}
+
+/// Subtracts `delta` from `counter`, ignoring a thread being torn down.
+fn sub(counter: &'static std::thread::LocalKey<Cell<usize>>, delta: usize) {
+    let _ = counter.try_with(|counter| counter.set(counter.get().saturating_sub(delta)));
+}
+
+/// Counts a new node.
+pub(crate) fn node_created() {
+    add(&NODES, 1);
+}
+
+/// Counts a dropped node, along with its `watchers`.
+pub(crate) fn node_dropped(watchers: usize) {
+    sub(&NODES, 1);
+    sub(&WATCHERS, watchers);
+}
+
+/// Counts a registered watcher.
+pub(crate) fn watcher_registered() {
+    add(&WATCHERS, 1);
+}
+
+/// Counts `count` removed watchers.
+pub(crate) fn watchers_removed(count: usize) {
+    sub(&WATCHERS, count);
+}
+
+/// A running notification of `pending` watchers, ending the flush at the top
+/// level when dropped.
+pub(crate) struct Notification {
+    pending: Cell<usize>,
+}
+
+impl Notification {
+    /// Enters a notification of `pending` watchers.
+    pub(crate) fn enter(pending: usize) -> Self {
+        if DEPTH.with(|depth| depth.replace(depth.get() + 1)) == 0 {
+            STARTED.with(|started| started.set(Some(Instant::now())));
+            PEAK.with(|peak| peak.set(0));
+        }
+        let waiting = PENDING.with(|waiting| {
+            waiting.set(waiting.get() + pending);
+            waiting.get()
+        });
+        PEAK.with(|peak| peak.set(peak.get().max(waiting)));
+        Self {
+            pending: Cell::new(pending),
+        }
+    }
+
+    /// Counts one watcher as no longer waiting.
+    pub(crate) fn next(&self) {
+        self.pending.set(self.pending.get().saturating_sub(1));
+        sub(&PENDING, 1);
+    }
+}
+
+impl Drop for Notification {
+    fn drop(&mut self) {
+        sub(&PENDING, self.pending.get());
+        let Ok(depth) = DEPTH.try_with(|depth| {
+            depth.set(depth.get().saturating_sub(1));
+            depth.get()
+        }) else {
+            return;
+        };
+        if depth > 0 {
+            return;
+        }
+        if let Some(started) = STARTED.with(Cell::take) {
+            LAST_FLUSH.with(|last| last.set(Some(started.elapsed())));
+        }
+        QUEUE_DEPTH.with(|queue| queue.set(PEAK.with(Cell::get)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+
+    #[test]
+    fn test_sample_counts_nodes_watchers_and_queue_depth() {
+        let before = sample();
+        let a: Binding<i32> = binding(0);
+        let b: Binding<i32> = binding(0);
+        let forward = {
+            let b = b.clone();
+            a.watch(move |context| b.set(context.value))
+        };
+        let _b_watchers = (b.watch(|_| {}), b.watch(|_| {}));
+        let during = sample();
+        assert_eq!(during.nodes, before.nodes + 2);
+        assert_eq!(during.watchers, before.watchers + 3);
+
+        a.set(1);
+        let after = sample();
+        // Both watchers of `b` wait while `forward` runs.
+        assert_eq!(after.queue_depth, 2);
+        assert!(after.last_flush.is_some());
+
+        drop(forward);
+        drop(a);
+        assert_eq!(sample().nodes, before.nodes + 1);
+        assert_eq!(sample().watchers, before.watchers + 2);
+    }
+}
@@ -0,0 +1,123 @@
+//! # Held values
+//!
+//! Many signals hold a value in a [`Container`] that watchers of other signals
+//! keep up to date, such as [`Scan`](crate::scan::Scan) or
+//! [`Filtered`](crate::filter::Filtered). [`Held`] bundles the container with
+//! the guards of those watchers, which stay registered for as long as a clone
+//! of it is alive, and [`held_signal!`] implements [`Signal`] for a public type
+//! wrapping one in a `held` field.
+
+use alloc::rc::Rc;
+use core::{any::Any, fmt};
+
+use crate::{
+    Container, NodeId, Signal,
+    watcher::{Context, Metadata},
+};
+
+/// A container kept up to date by watchers of other signals, along with their
+/// guards.
+#[derive(Clone)]
+pub struct Held<T: Clone + 'static> {
+    container: Container<T>,
+    _guard: Rc<dyn Any>,
+}
+
+impl<T: Clone + 'static> Held<T> {
+    /// Holds `container`, keeping `guard` alive for as long as a clone of the
+    /// result is.
+    pub fn new(container: Container<T>, guard: impl Any) -> Self {
+        Self {
+            container,
+            _guard: Rc::new(guard),
+        }
+    }
+
+    /// Formats the signal named `name` holding this value, showing the value
+    /// as `field`, or as `<borrowed>` while it is being modified.
+    pub fn debug(&self, f: &mut fmt::Formatter<'_>, name: &str, field: &str) -> fmt::Result
+    where
+        T: fmt::Debug,
+    {
+        let mut debug = f.debug_struct(name);
+        if self
+            .container
+            .try_peek(|value| {
+                debug.field(field, value);
+            })
+            .is_none()
+        {
+            debug.field(field, &format_args!("<borrowed>"));
+        }
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + 'static> Signal for Held<T> {
+    type Output = T;
+    type Guard = <Container<T> as Signal>::Guard;
+
+    fn get(&self) -> Self::Output {
+        self.container.get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.container.watch(watcher)
+    }
+
+    fn watch_filtered(
+        &self,
+        filter: impl Fn(&Metadata) -> bool + 'static,
+        watcher: impl Fn(Context<Self::Output>) + 'static,
+    ) -> Self::Guard {
+        self.container.watch_filtered(filter, watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.container.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.container.watcher_count()
+    }
+}
+
+/// Implements [`Signal`] with output `$output` for `$name`, forwarding to the
+/// [`Held`] value in its `held` field.
+macro_rules! held_signal {
+    ($name:ident $(<$($param:ident),*>)? => $output:ty) => {
+        impl $(<$($param: Clone + 'static),*>)? $crate::Signal for $name $(<$($param),*>)? {
+            type Output = $output;
+            type Guard = <$crate::held::Held<$output> as $crate::Signal>::Guard;
+
+            fn get(&self) -> Self::Output {
+                self.held.get()
+            }
+
+            fn watch(
+                &self,
+                watcher: impl Fn($crate::watcher::Context<Self::Output>) + 'static,
+            ) -> Self::Guard {
+                self.held.watch(watcher)
+            }
+
+            fn watch_filtered(
+                &self,
+                filter: impl Fn(&$crate::watcher::Metadata) -> bool + 'static,
+                watcher: impl Fn($crate::watcher::Context<Self::Output>) + 'static,
+            ) -> Self::Guard {
+                self.held.watch_filtered(filter, watcher)
+            }
+
+            fn node_id(&self) -> Option<$crate::NodeId> {
+                self.held.node_id()
+            }
+
+            fn watcher_count(&self) -> Option<usize> {
+                self.held.watcher_count()
+            }
+        }
+    };
+}
+
+pub(crate) use held_signal;
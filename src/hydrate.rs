@@ -0,0 +1,184 @@
+//! # Progressive hydration
+//!
+//! Loading a large persisted model builds a binding for every part of it, even
+//! though most of them are never shown. A [`Lazy`] field defers this: when the
+//! model is deserialized, the field keeps its raw JSON, and its binding is only
+//! built when it is first accessed with [`binding`](Lazy::binding).
+//!
+//! A model is saved back without hydrating anything: a field that was never
+//! accessed serializes its raw JSON unchanged, and a hydrated one serializes
+//! the current value of its binding. `Lazy` fields can be nested, so opening a
+//! part of a document only hydrates the fields on the way to it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::hydrate::Lazy;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Chapter {
+//!     title: String,
+//!     text: String,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Book {
+//!     chapters: Vec<Lazy<Chapter>>,
+//! }
+//!
+//! let saved = r#"{"chapters": [
+//!     {"title": "One", "text": "..."},
+//!     {"title": "Two", "text": "..."}
+//! ]}"#;
+//! let book: Book = serde_json::from_str(saved).unwrap();
+//!
+//! let first = book.chapters[0].binding().unwrap();
+//! first.set(Chapter { title: "Prologue".into(), ..first.get() });
+//! assert!(!book.chapters[1].is_hydrated());
+//!
+//! let resaved = serde_json::to_value(&book).unwrap();
+//! assert_eq!(resaved["chapters"][0]["title"], "Prologue");
+//! ```
+
+use alloc::rc::Rc;
+use core::{cell::RefCell, fmt};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{Binding, binding};
+
+/// A part of a model whose binding is built from its serialized form on first
+/// access.
+///
+/// All clones share the same binding once it is built. See the
+/// [module documentation](self) for how it behaves.
+pub struct Lazy<T: 'static> {
+    state: Rc<RefCell<State<T>>>,
+}
+
+enum State<T: 'static> {
+    /// Not accessed yet, holding the serialized value.
+    Raw(Value),
+    /// Accessed, holding the binding built from the serialized value.
+    Hydrated(Binding<T>),
+}
+
+impl<T: 'static> Clone for Lazy<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: 'static> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy")
+            .field("hydrated", &self.is_hydrated())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static> Lazy<T> {
+    /// Creates a part holding `value`, whose binding is built on first access.
+    #[must_use]
+    pub fn from_value(value: Value) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(State::Raw(value))),
+        }
+    }
+
+    /// Creates a part that is already hydrated with `binding`.
+    #[must_use]
+    pub fn hydrated(binding: Binding<T>) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(State::Hydrated(binding))),
+        }
+    }
+
+    /// Returns `true` once the binding of this part has been built.
+    #[must_use]
+    pub fn is_hydrated(&self) -> bool {
+        matches!(*self.state.borrow(), State::Hydrated(_))
+    }
+}
+
+impl<T: Clone + DeserializeOwned + 'static> Lazy<T> {
+    /// Returns the binding of this part, deserializing it on first access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the serialized value does not match `T`. The part
+    /// keeps its serialized value, so it can still be saved unchanged.
+    pub fn binding(&self) -> Result<Binding<T>, serde_json::Error> {
+        let mut state = self.state.borrow_mut();
+        let value = match &*state {
+            State::Hydrated(binding) => return Ok(binding.clone()),
+            State::Raw(raw) => T::deserialize(raw)?,
+        };
+        let hydrated: Binding<T> = binding(value);
+        *state = State::Hydrated(hydrated.clone());
+        Ok(hydrated)
+    }
+}
+
+impl<T: Clone + 'static> From<T> for Lazy<T> {
+    fn from(value: T) -> Self {
+        Self::hydrated(binding(value))
+    }
+}
+
+/// Serializes the raw value of a part that was never accessed, and the current
+/// value of its binding otherwise.
+impl<T: Clone + Serialize + 'static> Serialize for Lazy<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &*self.state.borrow() {
+            State::Raw(raw) => raw.serialize(serializer),
+            State::Hydrated(binding) => binding.get().serialize(serializer),
+        }
+    }
+}
+
+/// Keeps the serialized value without deserializing `T`.
+impl<'de, T: 'static> Deserialize<'de> for Lazy<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(Self::from_value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec::Vec};
+    use serde_json::json;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Section {
+        heading: String,
+        notes: Vec<Lazy<String>>,
+    }
+
+    #[test]
+    fn test_lazy_parts_hydrate_on_access() {
+        let saved = json!([
+            { "heading": "a", "notes": ["x", "y"] },
+            { "heading": "b", "notes": [1] },
+        ]);
+        let sections: Vec<Lazy<Section>> = serde_json::from_value(saved.clone()).unwrap();
+
+        let first = sections[0].binding().unwrap();
+        let note = first.get().notes[1].clone();
+        note.binding().unwrap().set("z");
+        assert!(sections[0].is_hydrated() && !first.get().notes[0].is_hydrated());
+
+        // An invalid note fails to hydrate, and is saved as loaded.
+        let second = sections[1].binding().unwrap();
+        assert!(second.get().notes[0].binding().is_err());
+        let mut expected = saved;
+        expected[0]["notes"][1] = json!("z");
+        assert_eq!(serde_json::to_value(&sections).unwrap(), expected);
+    }
+}
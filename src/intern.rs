@@ -0,0 +1,112 @@
+//! # Interned text
+//!
+//! Text-heavy graphs often recompute strings that come out the same as before.
+//! [`Interned`] turns such a signal into a signal of shared [`Rc<str>`]s that
+//! reuses the previous allocation whenever the new text is equal to it, and only
+//! notifies its watchers when the text actually changes.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::rc::Rc;
+//! use nami::{binding, Binding, Signal, SignalExt};
+//!
+//! let count: Binding<i32> = binding(3);
+//! let label = count.clone().map(|n: i32| format!("{n} items")).interned();
+//!
+//! let first = label.get();
+//! count.set(3);
+//! assert!(Rc::ptr_eq(&first, &label.get()));
+//! ```
+
+use alloc::rc::Rc;
+use core::cell::RefCell;
+
+use crate::{Signal, watcher::Context};
+
+/// A signal of text that reuses the previous allocation for equal values.
+///
+/// Created by [`SignalExt::interned`](crate::SignalExt::interned). All clones
+/// share the last interned text.
+#[derive(Debug, Clone)]
+pub struct Interned<S> {
+    source: S,
+    last: Rc<RefCell<Option<Rc<str>>>>,
+}
+
+impl<S> Interned<S>
+where
+    S: Signal,
+    S::Output: AsRef<str>,
+{
+    /// Creates a signal interning the text of `source`.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            last: Rc::default(),
+        }
+    }
+}
+
+/// Returns the text in `last` if it is equal to `text`, or stores and returns a
+/// new allocation of `text`.
+fn intern(last: &RefCell<Option<Rc<str>>>, text: &str) -> Rc<str> {
+    let mut last = last.borrow_mut();
+    match &*last {
+        Some(interned) if **interned == *text => interned.clone(),
+        _ => last.insert(Rc::from(text)).clone(),
+    }
+}
+
+impl<S> Signal for Interned<S>
+where
+    S: Signal,
+    S::Output: AsRef<str>,
+{
+    type Output = Rc<str>;
+    type Guard = S::Guard;
+
+    fn get(&self) -> Self::Output {
+        intern(&self.last, self.source.get().as_ref())
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let interned = self.last.clone();
+        let notified = RefCell::new(self.get());
+        self.source.watch(move |context: Context<S::Output>| {
+            let Context { value, metadata } = context;
+            let value = intern(&interned, value.as_ref());
+            if !Rc::ptr_eq(&notified.borrow(), &value) {
+                notified.replace(value.clone());
+                watcher(Context::new(value, metadata));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, SignalExt, binding};
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn test_interned_suppresses_equal_text() {
+        let text: Binding<String> = binding("a");
+        let interned = text.clone().interned();
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            interned.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        text.set("a");
+        text.set("b");
+        text.set("b");
+
+        let seen = seen.borrow();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(&*seen[0], "b");
+        assert!(Rc::ptr_eq(&seen[0], &interned.clone().get()));
+    }
+}
@@ -0,0 +1,223 @@
+//! # Per-item signals over list signals
+//!
+//! This module splits a binding of a `Vec` into one signal per index, so
+//! consumers can subscribe to a single element of a list.
+//!
+//! An [`Item`] is stable by index: it always observes position `i` of the source,
+//! and yields `None` while the list is shorter than that. Its watchers are only
+//! notified when the element at that position changes, so editing element 3 does
+//! not wake the watchers of element 0. The list is borrowed rather than cloned
+//! to read or compare an element, so only the element itself is cloned.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, items::iter_items};
+//!
+//! let names: Binding<Vec<&'static str>> = binding(vec!["ada", "grace"]);
+//! let items = iter_items(names.clone());
+//!
+//! let second = items.item(1);
+//! let third = items.item(2);
+//! assert_eq!(second.get(), Some("grace"));
+//! assert_eq!(third.get(), None);
+//!
+//! let _guard = second.watch(|_| panic!("the second name did not change"));
+//! names.push("edsger");
+//! assert_eq!(third.get(), Some("edsger"));
+//! ```
+
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::{Binding, Signal, watcher::Context, zip::Part};
+
+/// Per-index access to a binding of a `Vec`.
+#[derive(Debug, Clone)]
+pub struct Items<T: 'static> {
+    source: Binding<Vec<T>>,
+}
+
+impl<T> Items<T>
+where
+    T: PartialEq + Clone + 'static,
+{
+    /// Creates per-index access to `source`.
+    #[must_use]
+    pub const fn new(source: Binding<Vec<T>>) -> Self {
+        Self { source }
+    }
+
+    /// Returns a signal of the element at `index`.
+    #[must_use]
+    pub fn item(&self, index: usize) -> Item<T> {
+        Item {
+            source: self.source.clone(),
+            index,
+        }
+    }
+
+    /// Returns a signal of the length of the list.
+    ///
+    /// It only notifies its watchers when the length changes.
+    #[must_use]
+    pub fn len(&self) -> Part<Binding<Vec<T>>, usize> {
+        Part::new(self.source.clone(), |items| items.len())
+    }
+
+    /// Returns a signal of whether the list is empty.
+    ///
+    /// It only notifies its watchers when the list becomes empty or stops
+    /// being so.
+    #[must_use]
+    pub fn is_empty(&self) -> Part<Binding<Vec<T>>, bool> {
+        Part::new(self.source.clone(), |items| items.is_empty())
+    }
+
+    /// Returns a signal for each index of the list at its current length.
+    ///
+    /// The signals stay bound to their index if the list later shrinks or grows.
+    pub fn iter(&self) -> impl Iterator<Item = Item<T>> + '_ {
+        (0..self.source.peek(Vec::len)).map(|index| self.item(index))
+    }
+}
+
+/// A signal of the element at a fixed index of a list binding.
+///
+/// The value is `None` while the index is out of bounds.
+#[derive(Debug, Clone)]
+pub struct Item<T: 'static> {
+    source: Binding<Vec<T>>,
+    index: usize,
+}
+
+impl<T: 'static> Item<T> {
+    /// Returns the index this signal observes.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T> Signal for Item<T>
+where
+    T: PartialEq + Clone + 'static,
+{
+    type Output = Option<T>;
+    type Guard = <Binding<Vec<T>> as Signal>::Guard;
+
+    fn get(&self) -> Self::Output {
+        self.source
+            .peek(|items| items.as_slice().get(self.index).cloned())
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let (source, index) = (self.source.clone(), self.index);
+        let last = RefCell::new(self.get());
+        // The element is compared and read from a borrow of the list in the
+        // filter, which runs before the list is cloned for the watcher, and the
+        // notification is always rejected so the list is never cloned.
+        self.source.watch_filtered(
+            move |metadata| {
+                let changed = source.peek(|items| {
+                    let value = items.as_slice().get(index);
+                    (last.borrow().as_ref() != value).then(|| value.cloned())
+                });
+                if let Some(value) = changed {
+                    last.replace(value.clone());
+                    watcher(Context::new(value, metadata.clone()));
+                }
+                false
+            },
+            |_| {},
+        )
+    }
+}
+
+/// Splits a binding of a `Vec` into per-index signals.
+///
+/// This is a convenience function equivalent to `Items::new(source)`.
+#[must_use]
+pub const fn iter_items<T>(source: Binding<Vec<T>>) -> Items<T>
+where
+    T: PartialEq + Clone + 'static,
+{
+    Items::new(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding;
+    use alloc::{rc::Rc, vec};
+    use core::cell::Cell;
+
+    /// An element counting how often it is cloned.
+    #[derive(Debug)]
+    struct Counted(i32, Rc<Cell<usize>>);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            self.1.set(self.1.get() + 1);
+            Self(self.0, self.1.clone())
+        }
+    }
+
+    impl PartialEq for Counted {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    #[test]
+    fn test_items_clone_only_their_element() {
+        // A devtools hook would clone the list to report it.
+        #[cfg(feature = "devtools")]
+        let _hook = crate::devtools::TestHook::install(None);
+        let clones = Rc::new(Cell::new(0));
+        let list: Binding<Vec<Counted>> = binding(
+            (0..4)
+                .map(|n| Counted(n, clones.clone()))
+                .collect::<Vec<_>>(),
+        );
+        let items = iter_items(list.clone());
+        let (first, second) = (items.item(0), items.item(1));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guards = [&first, &second].map(|item| {
+            let seen = seen.clone();
+            let index = item.index();
+            item.watch(move |context| {
+                seen.borrow_mut()
+                    .push((index, context.value.map(|counted| counted.0)));
+            })
+        });
+
+        clones.set(0);
+        list.update(|list| list[1].0 = 10);
+        assert_eq!(*seen.borrow(), [(1, Some(10))]);
+        // The changed element is cloned for the watcher and to compare with
+        // later changes, while the list of four is never cloned.
+        assert_eq!(clones.get(), 2);
+        assert_eq!(second.get().map(|counted| counted.0), Some(10));
+        assert_eq!(clones.get(), 3);
+        assert_eq!(items.iter().count(), 4);
+        assert_eq!(clones.get(), 3);
+    }
+
+    #[test]
+    fn test_items_len_and_is_empty_are_signals() {
+        let list: Binding<Vec<i32>> = binding(vec![1]);
+        let items = iter_items(list.clone());
+        let (len, is_empty) = (items.len(), items.is_empty());
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            is_empty.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        list.set(vec![2]);
+        list.set(Vec::new());
+        assert_eq!((len.get(), is_empty.get()), (0, true));
+        assert_eq!(*seen.borrow(), [true]);
+    }
+}
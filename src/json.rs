@@ -0,0 +1,663 @@
+//! # Reactive JSON documents
+//!
+//! This module provides [`ReactiveJson`], a reactive wrapper around a
+//! [`serde_json::Value`] for dynamic documents without a static schema.
+//!
+//! Every mutation computes which [`Path`]s of the document changed and attaches them
+//! to the notification as [`Changes`] metadata. Path subscriptions use this to only
+//! notify when the part of the document they observe is affected:
+//!
+//! - [`ReactiveJson::path`] returns a [`JsonPath`] signal for the value at a path.
+//! - [`ReactiveJson::watch_path`] watches a path directly.
+//! - [`ReactiveJson::apply_patch`] and [`ReactiveJson::merge_patch`] apply several
+//!   edits as one change.
+//!
+//! Paths use dotted keys and bracketed array indices, such as `a.b[2].c`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{Signal, json::ReactiveJson};
+//! use serde_json::json;
+//!
+//! let doc = ReactiveJson::new(json!({ "user": { "name": "Ada", "tags": ["a", "b"] } }));
+//!
+//! let name = doc.path("user.name").unwrap();
+//! let _guard = doc
+//!     .watch_path("user.tags[1]", |ctx| println!("second tag is now {:?}", ctx.value))
+//!     .unwrap();
+//!
+//! doc.set_path(&"user.tags[1]".parse().unwrap(), json!("c")).unwrap();
+//! assert_eq!(name.get(), Some(json!("Ada")));
+//! assert_eq!(doc.get_path(&"user.tags".parse().unwrap()), Some(json!(["a", "c"])));
+//! ```
+
+use alloc::{rc::Rc, string::String, vec::Vec};
+use core::{cell::RefCell, fmt, str::FromStr};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    CustomBinding, NodeId, Signal,
+    watcher::{BoxWatcherGuard, Context, Metadata, WatcherManager},
+};
+
+/// A single step of a [`Path`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Segment {
+    /// A key of an object.
+    Key(String),
+    /// An index into an array.
+    Index(usize),
+}
+
+/// A location within a JSON document.
+///
+/// The empty path refers to the whole document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Path(Vec<Segment>);
+
+impl Path {
+    /// Returns the path of the whole document.
+    #[must_use]
+    pub const fn root() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Parses a path such as `a.b[2].c`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` contains an empty key or a malformed index.
+    pub fn parse(path: &str) -> Result<Self, PathError> {
+        let mut segments = Vec::new();
+        let bytes = path.as_bytes();
+        let mut position = 0;
+
+        while position < bytes.len() {
+            if bytes[position] == b'[' {
+                let start = position + 1;
+                let end = path[start..]
+                    .find(']')
+                    .map(|offset| start + offset)
+                    .ok_or(PathError { position })?;
+                let index = path[start..end]
+                    .parse()
+                    .map_err(|_| PathError { position: start })?;
+                segments.push(Segment::Index(index));
+                position = end + 1;
+            } else {
+                if bytes[position] == b'.' {
+                    if segments.is_empty() {
+                        return Err(PathError { position });
+                    }
+                    position += 1;
+                }
+                let start = position;
+                let end = path[start..]
+                    .find(['.', '['])
+                    .map_or(path.len(), |offset| start + offset);
+                if start == end || path[start..end].contains(']') {
+                    return Err(PathError { position: start });
+                }
+                segments.push(Segment::Key(path[start..end].into()));
+                position = end;
+            }
+        }
+
+        Ok(Self(segments))
+    }
+
+    /// Appends an object key to this path.
+    #[must_use]
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.0.push(Segment::Key(key.into()));
+        self
+    }
+
+    /// Appends an array index to this path.
+    #[must_use]
+    pub fn index(mut self, index: usize) -> Self {
+        self.0.push(Segment::Index(index));
+        self
+    }
+
+    /// Returns the segments of this path.
+    #[must_use]
+    pub fn segments(&self) -> &[Segment] {
+        &self.0
+    }
+
+    /// Returns `true` if this path refers to the whole document.
+    #[must_use]
+    pub const fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if `prefix` is an ancestor of or equal to this path.
+    #[must_use]
+    pub fn starts_with(&self, prefix: &Self) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+
+    /// Returns `true` if a change at one of the paths affects the value at the other.
+    #[must_use]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.starts_with(other) || other.starts_with(self)
+    }
+
+    pub(crate) fn lookup<'a>(&self, mut value: &'a Value) -> Option<&'a Value> {
+        for segment in &self.0 {
+            value = match segment {
+                Segment::Key(key) => value.as_object()?.get(key)?,
+                Segment::Index(index) => value.as_array()?.as_slice().get(*index)?,
+            };
+        }
+        Some(value)
+    }
+
+    fn lookup_mut<'a>(&self, mut value: &'a mut Value) -> Option<&'a mut Value> {
+        for segment in &self.0 {
+            value = match segment {
+                Segment::Key(key) => value.as_object_mut()?.get_mut(key)?,
+                Segment::Index(index) => value.as_array_mut()?.get_mut(*index)?,
+            };
+        }
+        Some(value)
+    }
+
+    fn split_last(&self) -> Option<(Self, &Segment)> {
+        let (last, parent) = self.0.split_last()?;
+        Some((Self(parent.to_vec()), last))
+    }
+}
+
+impl FromStr for Path {
+    type Err = PathError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::parse(path)
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if position == 0 => f.write_str(key)?,
+                Segment::Key(key) => write!(f, ".{key}")?,
+                Segment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error returned when a path cannot be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathError {
+    position: usize,
+}
+
+impl PathError {
+    /// Returns the byte offset at which parsing failed.
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON path at byte {}", self.position)
+    }
+}
+
+impl core::error::Error for PathError {}
+
+/// An error returned when an edit cannot be applied to a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JsonError {
+    /// The parent of the edited path does not exist.
+    NotFound(Path),
+    /// The edited path does not match the shape of the document, such as a key
+    /// into an array or an index past the end of an array.
+    Mismatch(Path),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "no value at `{path}`"),
+            Self::Mismatch(path) => write!(f, "`{path}` does not match the document"),
+        }
+    }
+}
+
+impl core::error::Error for JsonError {}
+
+/// A single edit of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchOp {
+    /// Sets the value at a path, inserting it if the parent exists.
+    Set {
+        /// The path to set.
+        path: Path,
+        /// The new value.
+        value: Value,
+    },
+    /// Removes the value at a path.
+    Remove {
+        /// The path to remove.
+        path: Path,
+    },
+}
+
+/// Metadata attached to every notification of a [`ReactiveJson`], listing the
+/// paths that changed.
+///
+/// Paths are the deepest locations that differ: editing `a.b` in place reports
+/// `a.b`, while replacing a scalar with an object reports the replaced path.
+#[derive(Debug, Clone)]
+pub struct Changes(Rc<[Path]>);
+
+impl Changes {
+    /// Returns the changed paths.
+    #[must_use]
+    pub fn paths(&self) -> &[Path] {
+        &self.0
+    }
+
+    /// Returns `true` if the value at `path` was affected by this change.
+    #[must_use]
+    pub fn affects(&self, path: &Path) -> bool {
+        self.0.iter().any(|changed| changed.overlaps(path))
+    }
+}
+
+/// A reactive JSON document with path-level change tracking.
+#[derive(Debug, Clone)]
+pub struct ReactiveJson {
+    value: Rc<RefCell<Value>>,
+    watchers: WatcherManager<Value>,
+    id: NodeId,
+}
+
+impl ReactiveJson {
+    /// Creates a reactive document holding `value`.
+    #[must_use]
+    pub fn new(value: Value) -> Self {
+        Self {
+            value: Rc::new(RefCell::new(value)),
+            watchers: WatcherManager::new(),
+            id: NodeId::next(),
+        }
+    }
+
+    /// Returns a copy of the value at `path`, or `None` if it does not exist.
+    #[must_use]
+    pub fn get_path(&self, path: &Path) -> Option<Value> {
+        path.lookup(&self.value.borrow()).cloned()
+    }
+
+    /// Returns a signal for the value at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` cannot be parsed.
+    pub fn path(&self, path: &str) -> Result<JsonPath, PathError> {
+        Ok(JsonPath {
+            document: self.clone(),
+            path: path.parse()?,
+        })
+    }
+
+    /// Watches the value at `path`, notifying only when a change affects it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` cannot be parsed.
+    pub fn watch_path(
+        &self,
+        path: &str,
+        watcher: impl Fn(Context<Option<Value>>) + 'static,
+    ) -> Result<BoxWatcherGuard, PathError> {
+        Ok(self.path(path)?.watch(watcher))
+    }
+
+    /// Sets the value at `path`.
+    ///
+    /// Object keys are inserted if missing, and an index equal to the length of an
+    /// array appends to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent of `path` does not exist or is not a matching
+    /// container. The document is left unchanged in that case.
+    pub fn set_path(&self, path: &Path, value: Value) -> Result<(), JsonError> {
+        self.edit(|document| set_in(document, path, value))
+    }
+
+    /// Removes and returns the value at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no value at `path`, or if `path` is the root.
+    pub fn remove_path(&self, path: &Path) -> Result<Value, JsonError> {
+        self.edit(|document| remove_in(document, path))
+    }
+
+    /// Applies all operations of `patch` in order, notifying watchers once.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered. The patch is applied atomically, so the
+    /// document is left unchanged if any operation fails.
+    pub fn apply_patch(&self, patch: &[PatchOp]) -> Result<(), JsonError> {
+        self.edit(|document| {
+            for op in patch {
+                match op {
+                    PatchOp::Set { path, value } => set_in(document, path, value.clone())?,
+                    PatchOp::Remove { path } => {
+                        remove_in(document, path)?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Applies a JSON merge patch, as described in RFC 7386.
+    ///
+    /// Objects in `patch` are merged recursively, `null` members remove keys, and any
+    /// other value replaces the target.
+    pub fn merge_patch(&self, patch: &Value) {
+        let mut document = self.value.borrow().clone();
+        merge(&mut document, patch);
+        self.replace(document);
+    }
+
+    /// Runs `f` on a copy of the document, committing and notifying on success.
+    fn edit<R>(&self, f: impl FnOnce(&mut Value) -> Result<R, JsonError>) -> Result<R, JsonError> {
+        let mut document = self.value.borrow().clone();
+        let result = f(&mut document)?;
+        self.replace(document);
+        Ok(result)
+    }
+
+    /// Replaces the document, notifying watchers with the changed paths.
+    fn replace(&self, document: Value) {
+        let mut changes = Vec::new();
+        changed_paths(
+            &self.value.borrow(),
+            &document,
+            &mut Path::root(),
+            &mut changes,
+        );
+        if changes.is_empty() {
+            return;
+        }
+
+        self.value.replace(document.clone());
+        let metadata = Metadata::new().with(Changes(changes.into()));
+        self.watchers.notify(move || document.clone(), &metadata);
+    }
+}
+
+impl Default for ReactiveJson {
+    fn default() -> Self {
+        Self::new(Value::Null)
+    }
+}
+
+impl From<Value> for ReactiveJson {
+    fn from(value: Value) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Signal for ReactiveJson {
+    type Output = Value;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> Self::Output {
+        self.value.borrow().clone()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        alloc::boxed::Box::new(self.watchers.register_as_guard(watcher))
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        Some(self.id)
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        Some(self.watchers.len())
+    }
+}
+
+impl CustomBinding for ReactiveJson {
+    /// Replaces the whole document, notifying watchers if it changed.
+    fn set(&self, value: Self::Output) {
+        self.replace(value);
+    }
+}
+
+/// A signal for the value at a path of a [`ReactiveJson`] document.
+///
+/// The value is `None` while the path does not exist. Watchers are only notified
+/// when a change affects the path.
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    document: ReactiveJson,
+    path: Path,
+}
+
+impl JsonPath {
+    /// Returns the observed path.
+    #[must_use]
+    pub const fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Signal for JsonPath {
+    type Output = Option<Value>;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> Self::Output {
+        self.document.get_path(&self.path)
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let path = self.path.clone();
+        self.document.watch(move |context: Context<Value>| {
+            let Context { value, metadata } = context;
+            if metadata
+                .try_get::<Changes>()
+                .is_none_or(|changes| changes.affects(&path))
+            {
+                watcher(Context::new(path.lookup(&value).cloned(), metadata));
+            }
+        })
+    }
+}
+
+/// Collects the deepest paths at which `old` and `new` differ.
+pub(crate) fn changed_paths(old: &Value, new: &Value, path: &mut Path, changes: &mut Vec<Path>) {
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for key in old
+                .keys()
+                .chain(new.keys().filter(|key| !old.contains_key(*key)))
+            {
+                path.0.push(Segment::Key(key.clone()));
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => changed_paths(old, new, path, changes),
+                    _ => changes.push(path.clone()),
+                }
+                path.0.pop();
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for index in 0..old.len().max(new.len()) {
+                path.0.push(Segment::Index(index));
+                match (old.as_slice().get(index), new.as_slice().get(index)) {
+                    (Some(old), Some(new)) => changed_paths(old, new, path, changes),
+                    _ => changes.push(path.clone()),
+                }
+                path.0.pop();
+            }
+        }
+        _ => changes.push(path.clone()),
+    }
+}
+
+pub(crate) fn set_in(document: &mut Value, path: &Path, value: Value) -> Result<(), JsonError> {
+    let Some((parent_path, last)) = path.split_last() else {
+        *document = value;
+        return Ok(());
+    };
+    let parent = parent_path
+        .lookup_mut(document)
+        .ok_or(JsonError::NotFound(parent_path))?;
+
+    match (parent, last) {
+        (Value::Object(object), Segment::Key(key)) => {
+            object.insert(key.clone(), value);
+        }
+        (Value::Array(array), Segment::Index(index)) if *index < array.len() => {
+            array[*index] = value;
+        }
+        (Value::Array(array), Segment::Index(index)) if *index == array.len() => {
+            array.push(value);
+        }
+        _ => return Err(JsonError::Mismatch(path.clone())),
+    }
+    Ok(())
+}
+
+pub(crate) fn remove_in(document: &mut Value, path: &Path) -> Result<Value, JsonError> {
+    let not_found = || JsonError::NotFound(path.clone());
+    let (parent_path, last) = path.split_last().ok_or_else(not_found)?;
+    let parent = parent_path.lookup_mut(document).ok_or_else(not_found)?;
+
+    match (parent, last) {
+        (Value::Object(object), Segment::Key(key)) => object.remove(key).ok_or_else(not_found),
+        (Value::Array(array), Segment::Index(index)) if *index < array.len() => {
+            Ok(array.remove(*index))
+        }
+        _ => Err(not_found()),
+    }
+}
+
+fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec};
+    use serde_json::json;
+
+    #[test]
+    fn test_path_parse() {
+        let path = Path::parse("a.b[2].c").unwrap();
+        assert_eq!(path, Path::root().key("a").key("b").index(2).key("c"));
+        assert_eq!(path.to_string(), "a.b[2].c");
+        assert_eq!(Path::parse("[0][1]").unwrap().to_string(), "[0][1]");
+        assert!(Path::parse("").unwrap().is_root());
+
+        assert_eq!(Path::parse("a..b").unwrap_err().position(), 2);
+        assert!(Path::parse(".a").is_err());
+        assert!(Path::parse("a[x]").is_err());
+        assert!(Path::parse("a[1").is_err());
+    }
+
+    #[test]
+    fn test_path_subscriptions() {
+        let doc = ReactiveJson::new(json!({ "a": { "b": [1, 2, 3] }, "c": true }));
+        let seen: Rc<RefCell<Vec<Option<Value>>>> = Rc::default();
+        let paths: Rc<RefCell<Vec<Vec<Path>>>> = Rc::default();
+
+        let _path_guard = {
+            let seen = seen.clone();
+            doc.watch_path("a.b[1]", move |ctx| seen.borrow_mut().push(ctx.value))
+                .unwrap()
+        };
+        let _doc_guard = {
+            let paths = paths.clone();
+            doc.watch(move |ctx| {
+                let changes = ctx.metadata.try_get::<Changes>().unwrap();
+                paths.borrow_mut().push(changes.paths().to_vec());
+            })
+        };
+
+        // Unrelated and no-op edits do not reach the path subscription.
+        doc.set_path(&"c".parse().unwrap(), json!(false)).unwrap();
+        doc.set_path(&"c".parse().unwrap(), json!(false)).unwrap();
+        assert!(seen.borrow().is_empty());
+
+        doc.apply_patch(&[
+            PatchOp::Set {
+                path: "a.b[1]".parse().unwrap(),
+                value: json!(20),
+            },
+            PatchOp::Set {
+                path: "a.b[3]".parse().unwrap(),
+                value: json!(4),
+            },
+        ])
+        .unwrap();
+        assert_eq!(*seen.borrow(), vec![Some(json!(20))]);
+
+        // Failing patches leave the document untouched.
+        let error = doc.apply_patch(&[
+            PatchOp::Remove {
+                path: "c".parse().unwrap(),
+            },
+            PatchOp::Set {
+                path: "x.y".parse().unwrap(),
+                value: json!(1),
+            },
+        ]);
+        assert_eq!(error, Err(JsonError::NotFound("x".parse().unwrap())));
+        assert_eq!(doc.get_path(&"c".parse().unwrap()), Some(json!(false)));
+
+        doc.merge_patch(&json!({ "a": null }));
+        assert_eq!(*seen.borrow(), vec![Some(json!(20)), None]);
+        assert_eq!(doc.get(), json!({ "c": false }));
+
+        assert_eq!(
+            *paths.borrow(),
+            vec![
+                vec!["c".parse().unwrap()],
+                vec!["a.b[1]".parse().unwrap(), "a.b[3]".parse().unwrap()],
+                vec!["a".parse().unwrap()],
+            ]
+        );
+    }
+}
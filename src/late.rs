@@ -0,0 +1,296 @@
+//! # Deferred initialization
+//!
+//! This module provides [`LateBinding`], a binding that starts without a value.
+//! Data that is not loaded yet is often modeled as an `Option` with a fake
+//! default, which every downstream computation then has to work around. A late
+//! binding instead reports a typed [`Uninitialized`] error until its first value
+//! arrives, and notifies its watchers once it is initialized.
+//!
+//! [`OnceBinding`] is its write-once counterpart for one-shot results, such as
+//! "initial configuration loaded": like a promise, it is set at most once, and
+//! its value can be watched or awaited with [`OnceBinding::wait`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{Signal, late::{LateBinding, Uninitialized}};
+//!
+//! let profile: LateBinding<String> = LateBinding::new();
+//! assert_eq!(profile.get(), Err(Uninitialized));
+//!
+//! let _guard = profile.watch(|context| assert_eq!(context.value.as_deref(), Ok("Ada")));
+//! profile.initialize("Ada");
+//! assert_eq!(profile.get().as_deref(), Ok("Ada"));
+//! ```
+
+use core::{fmt, future::Future, pin::Pin, task::Poll};
+
+use crate::{
+    Container, CustomBinding, NodeId, Signal,
+    watcher::{BoxWatcherGuard, Context},
+};
+
+/// An error returned when reading a [`LateBinding`] before it is initialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Uninitialized;
+
+impl fmt::Display for Uninitialized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("binding read before it was initialized")
+    }
+}
+
+impl core::error::Error for Uninitialized {}
+
+/// A binding without an initial value.
+///
+/// Reading it returns [`Uninitialized`] until [`initialize`](Self::initialize)
+/// is called. Watchers are first notified with the initial value, and with every
+/// later value after that. All clones share the same value.
+#[derive(Clone)]
+pub struct LateBinding<T: Clone + 'static> {
+    container: Container<Option<T>>,
+}
+
+impl<T: Clone + 'static> Default for LateBinding<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for LateBinding<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LateBinding")
+            .field("value", &self.container.get())
+            .finish()
+    }
+}
+
+impl<T: Clone + 'static> LateBinding<T> {
+    /// Creates a binding that is not initialized yet.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            container: Container::new(None),
+        }
+    }
+
+    /// Sets the value, initializing the binding on the first call, and notifies
+    /// watchers.
+    pub fn initialize(&self, value: impl Into<T>) {
+        self.container.set(Some(value.into()));
+    }
+
+    /// Returns `true` once the binding has been initialized.
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.container.get().is_some()
+    }
+}
+
+impl<T: Clone + 'static> Signal for LateBinding<T> {
+    type Output = Result<T, Uninitialized>;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> Self::Output {
+        self.container.get().ok_or(Uninitialized)
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.container.watch(move |context: Context<Option<T>>| {
+            let Context { value, metadata } = context;
+            watcher(Context::new(value.ok_or(Uninitialized), metadata));
+        })
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.container.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.container.watcher_count()
+    }
+}
+
+/// An error returned when setting a [`OnceBinding`] that already has a value.
+///
+/// It hands back the rejected value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadySet<T>(pub T);
+
+impl<T> fmt::Display for AlreadySet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("binding was already set")
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for AlreadySet<T> {}
+
+/// A binding that can be set only once.
+///
+/// It reads as `None` until [`set`](Self::set) is first called, and as that
+/// value from then on: later writes are rejected with [`AlreadySet`] and do not
+/// notify watchers. All clones share the same value.
+///
+/// ```rust
+/// use nami::{Signal, late::{AlreadySet, OnceBinding}};
+///
+/// let loaded: OnceBinding<&str> = OnceBinding::new();
+/// assert_eq!(loaded.get(), None);
+/// assert_eq!(loaded.set("config.json"), Ok(()));
+/// assert_eq!(loaded.set("other.json"), Err(AlreadySet("other.json")));
+/// assert_eq!(loaded.get(), Some("config.json"));
+/// ```
+#[derive(Clone)]
+pub struct OnceBinding<T: Clone + 'static> {
+    container: Container<Option<T>>,
+}
+
+impl<T: Clone + 'static> Default for OnceBinding<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for OnceBinding<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnceBinding")
+            .field("value", &self.container.get())
+            .finish()
+    }
+}
+
+impl<T: Clone + 'static> OnceBinding<T> {
+    /// Creates a binding that is not set yet.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            container: Container::new(None),
+        }
+    }
+
+    /// Sets the value and notifies watchers, unless it is already set.
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back as [`AlreadySet`] if the binding was set before.
+    pub fn set(&self, value: impl Into<T>) -> Result<(), AlreadySet<T>> {
+        let value = value.into();
+        if self.is_set() {
+            return Err(AlreadySet(value));
+        }
+        self.container.set(Some(value));
+        Ok(())
+    }
+
+    /// Returns `true` once the binding has been set.
+    #[must_use]
+    pub fn is_set(&self) -> bool {
+        self.container.get().is_some()
+    }
+
+    /// Returns a future resolving to the value once the binding is set.
+    pub fn wait(&self) -> Wait<T> {
+        Wait {
+            binding: self.clone(),
+        }
+    }
+}
+
+impl<T: Clone + 'static> Signal for OnceBinding<T> {
+    type Output = Option<T>;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> Self::Output {
+        self.container.get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.container.watch(watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.container.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.container.watcher_count()
+    }
+}
+
+/// A future resolving to the value of a [`OnceBinding`], returned by
+/// [`OnceBinding::wait`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Wait<T: Clone + 'static> {
+    binding: OnceBinding<T>,
+}
+
+impl<T: Clone + 'static> fmt::Debug for Wait<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Wait")
+            .field("set", &self.binding.is_set())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + 'static> Future for Wait<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<T> {
+        if let Some(value) = self.binding.get() {
+            return Poll::Ready(value);
+        }
+        self.binding.container.add_waker(cx.waker());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SignalExt;
+    use alloc::{rc::Rc, vec::Vec};
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_late_binding_initialization() {
+        let count: LateBinding<i32> = LateBinding::default();
+        let doubled = count
+            .clone()
+            .map(|count: Result<i32, Uninitialized>| count.map(|n| n * 2));
+        assert!(!count.is_initialized());
+        assert_eq!(doubled.get(), Err(Uninitialized));
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            doubled.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        count.initialize(2);
+        count.initialize(5);
+
+        assert!(count.is_initialized());
+        assert_eq!(*seen.borrow(), [Ok(4), Ok(10)]);
+    }
+
+    #[test]
+    fn test_once_binding_rejects_later_writes() {
+        use core::task::{Context, Poll, Waker};
+
+        let loaded: OnceBinding<i32> = OnceBinding::default();
+        let mut wait = loaded.wait();
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(Pin::new(&mut wait).poll(&mut cx), Poll::Pending);
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            loaded.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        assert_eq!(loaded.set(1), Ok(()));
+        assert_eq!(loaded.set(2), Err(AlreadySet(2)));
+
+        assert_eq!(*seen.borrow(), [Some(1)]);
+        assert_eq!(Pin::new(&mut wait).poll(&mut cx), Poll::Ready(1));
+    }
+}
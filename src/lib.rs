@@ -12,6 +12,8 @@
 #![deny(clippy::unimplemented)]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 pub mod binding;
 #[doc(inline)]
 pub use binding::{Binding, Container, CustomBinding, binding};
@@ -20,33 +22,111 @@ pub mod constant;
 pub use constant::constant;
 pub mod signal;
 #[doc(inline)]
-pub use signal::{Computed, Signal};
+pub use signal::{Computed, NodeId, Signal};
+pub mod a11y;
+#[cfg(feature = "std")]
+pub mod actor;
+pub mod animation;
+pub mod asset;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "bytes")]
+pub mod bytes;
 pub mod cache;
+pub mod cell;
+#[cfg(feature = "cli")]
+pub mod cli;
 pub mod collection;
+pub mod constraint;
 pub mod debounce;
 pub mod debug;
+#[cfg(feature = "std")]
+pub mod deadline;
+#[cfg(feature = "devtools")]
+pub mod devtools;
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod document;
+pub mod effect;
+#[cfg(feature = "format")]
+pub mod format;
 mod ext;
+pub mod filter;
+pub mod flatten;
 pub mod future;
+pub mod gesture;
+pub mod graph;
+#[cfg(feature = "std")]
+pub mod health;
+mod held;
+#[cfg(feature = "persist")]
+pub mod hydrate;
+pub mod intern;
+pub mod items;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod late;
+pub mod like;
+#[cfg(feature = "std")]
+pub mod limits;
 pub mod map;
+pub mod memo;
+pub mod operator;
+#[cfg(feature = "merge")]
+pub mod merge;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "persist")]
+pub mod persist;
+#[cfg(feature = "pipeline")]
+pub mod pipeline;
+#[cfg(feature = "process")]
+pub mod process;
 /// Projection utilities for decomposing bindings into component parts.
 pub mod project;
+pub mod pump;
+pub mod random;
+pub mod rate_limit;
+pub mod resource;
+pub mod scan;
+pub mod scope;
+pub mod search;
+pub mod shard;
+pub mod sheet;
+pub mod slot;
 pub mod stream;
+#[cfg(feature = "std")]
+pub mod sync;
+pub mod task;
+#[cfg(test)]
+mod test_executor;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 /// Throttling utilities for limiting signal update rates.
 pub mod throttle;
+#[cfg(feature = "std")]
+pub mod trace;
+pub mod units;
 #[doc(inline)]
 pub use project::Project;
 pub mod utils;
+pub mod variant;
+#[cfg(feature = "std")]
+pub mod watchdog;
 pub mod watcher;
+pub mod what_if;
 pub mod zip;
 #[doc(inline)]
 pub use ext::SignalExt;
 
 #[cfg(feature = "derive")]
 #[doc(inline)]
-pub use nami_derive::{Project, s};
+pub use nami_derive::{Project, Variants, graph, s};
 
 #[doc(hidden)]
 pub use alloc::format as __format;
+#[doc(hidden)]
+pub use alloc::vec::Vec as __Vec;
 
 /// Macro to implement the Signal trait for constant types.
 ///
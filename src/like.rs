@@ -0,0 +1,270 @@
+//! # Object-safe state traits
+//!
+//! Application layers, such as view models, are easier to test when they do not
+//! depend on concrete reactive types. [`BindingLike`] and [`ListLike`] are
+//! object-safe interfaces to a single value and to a list, so a model can hold
+//! an `Rc<dyn BindingLike<T>>` or an `Rc<dyn ListLike<T>>`, backed by a
+//! [`Binding`] or a [`List`] in the application and by a simple fake in tests.
+//!
+//! `BindingLike` is implemented by `Binding` and every [`CustomBinding`], and
+//! `ListLike` by `List`. The trait objects plug back into the rest of the crate:
+//! `Rc<dyn BindingLike<T>>` is a [`Signal`] and `Rc<dyn ListLike<T>>` is a
+//! [`Collection`].
+//!
+//! These traits share method names with [`Signal`] and [`Collection`], so they
+//! are best imported only where the trait objects are used.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::cell::Cell;
+//! use std::rc::Rc;
+//! use nami::{binding, like::BindingLike, watcher::{BoxWatcher, BoxWatcherGuard}};
+//!
+//! struct Counter {
+//!     count: Rc<dyn BindingLike<i32>>,
+//! }
+//!
+//! impl Counter {
+//!     fn increment(&self) {
+//!         self.count.set(self.count.get() + 1);
+//!     }
+//! }
+//!
+//! // A fake that never notifies.
+//! struct Fake(Cell<i32>);
+//!
+//! impl BindingLike<i32> for Fake {
+//!     fn get(&self) -> i32 {
+//!         self.0.get()
+//!     }
+//!
+//!     fn set(&self, value: i32) {
+//!         self.0.set(value);
+//!     }
+//!
+//!     fn watch(&self, _watcher: BoxWatcher<i32>) -> BoxWatcherGuard {
+//!         Box::new(())
+//!     }
+//! }
+//!
+//! let counter = Counter { count: Rc::new(Fake(Cell::new(0))) };
+//! counter.increment();
+//! assert_eq!(counter.count.get(), 1);
+//!
+//! let counter = Counter { count: Rc::new(binding(0)) };
+//! counter.increment();
+//! assert_eq!(counter.count.get(), 1);
+//! ```
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+use core::ops::{Bound, RangeBounds};
+
+use crate::{
+    Binding, CustomBinding, Signal,
+    collection::{Collection, List},
+    watcher::{BoxWatcher, BoxWatcherGuard, Context},
+};
+
+/// An object-safe interface to a value of type `T` that can be read, written
+/// and watched.
+pub trait BindingLike<T> {
+    /// Returns the current value.
+    fn get(&self) -> T;
+
+    /// Sets a new value, notifying watchers.
+    fn set(&self, value: T);
+
+    /// Registers a watcher to be notified when the value changes.
+    ///
+    /// Returns a guard that unregisters the watcher when dropped.
+    fn watch(&self, watcher: BoxWatcher<T>) -> BoxWatcherGuard;
+}
+
+impl<T: 'static> BindingLike<T> for Binding<T> {
+    fn get(&self) -> T {
+        Self::get(self)
+    }
+
+    fn set(&self, value: T) {
+        Self::set(self, value);
+    }
+
+    fn watch(&self, watcher: BoxWatcher<T>) -> BoxWatcherGuard {
+        <Self as Signal>::watch(self, watcher)
+    }
+}
+
+impl<B: CustomBinding> BindingLike<B::Output> for B {
+    fn get(&self) -> B::Output {
+        <Self as Signal>::get(self)
+    }
+
+    fn set(&self, value: B::Output) {
+        <Self as CustomBinding>::set(self, value);
+    }
+
+    fn watch(&self, watcher: BoxWatcher<B::Output>) -> BoxWatcherGuard {
+        Box::new(<Self as Signal>::watch(self, watcher))
+    }
+}
+
+impl<T: 'static> Signal for Rc<dyn BindingLike<T>> {
+    type Output = T;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> T {
+        (**self).get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<T>) + 'static) -> Self::Guard {
+        (**self).watch(Box::new(watcher))
+    }
+}
+
+/// An object-safe interface to a list of `T` that can be read, modified and
+/// watched.
+pub trait ListLike<T> {
+    /// Gets the item at `index`, or `None` if it is out of bounds.
+    fn get(&self, index: usize) -> Option<T>;
+
+    /// Returns the number of items.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the list contains no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a copy of the current contents.
+    fn to_vec(&self) -> Vec<T>;
+
+    /// Adds an item to the end of the list.
+    fn push(&self, value: T);
+
+    /// Inserts an item at `index`.
+    fn insert(&self, index: usize, value: T);
+
+    /// Removes and returns the item at `index`.
+    fn remove(&self, index: usize) -> T;
+
+    /// Replaces the item at `index`, returning the previous one.
+    fn set(&self, index: usize, value: T) -> T;
+
+    /// Removes all items.
+    fn clear(&self);
+
+    /// Registers a watcher for changes in `range` of the list.
+    ///
+    /// Returns a guard that unregisters the watcher when dropped.
+    fn watch(
+        &self,
+        range: (Bound<usize>, Bound<usize>),
+        watcher: BoxWatcher<Vec<T>>,
+    ) -> BoxWatcherGuard;
+}
+
+impl<T: Clone + 'static> ListLike<T> for List<T> {
+    fn get(&self, index: usize) -> Option<T> {
+        <Self as Collection>::get(self, index)
+    }
+
+    fn len(&self) -> usize {
+        <Self as Collection>::len(self)
+    }
+
+    fn to_vec(&self) -> Vec<T> {
+        Self::to_vec(self)
+    }
+
+    fn push(&self, value: T) {
+        Self::push(self, value);
+    }
+
+    fn insert(&self, index: usize, value: T) {
+        Self::insert(self, index, value);
+    }
+
+    fn remove(&self, index: usize) -> T {
+        Self::remove(self, index)
+    }
+
+    fn set(&self, index: usize, value: T) -> T {
+        Self::set(self, index, value)
+    }
+
+    fn clear(&self) {
+        Self::clear(self);
+    }
+
+    fn watch(
+        &self,
+        range: (Bound<usize>, Bound<usize>),
+        watcher: BoxWatcher<Vec<T>>,
+    ) -> BoxWatcherGuard {
+        Box::new(<Self as Collection>::watch(self, range, watcher))
+    }
+}
+
+impl<T: 'static> Collection for Rc<dyn ListLike<T>> {
+    type Item = T;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self, index: usize) -> Option<T> {
+        (**self).get(index)
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn watch(
+        &self,
+        range: impl RangeBounds<usize>,
+        watcher: impl Fn(Context<Vec<T>>) + 'static,
+    ) -> Self::Guard {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        (**self).watch(range, Box::new(watcher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SignalExt, binding, collection::AnyCollection};
+    use alloc::vec;
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_binding_like_objects_are_signals() {
+        let count = binding(1);
+        let shared: Rc<dyn BindingLike<i32>> = Rc::new(count.clone());
+        let doubled = shared.clone().map(|count| count * 2);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            Signal::watch(&doubled, move |context| seen.borrow_mut().push(context.value))
+        };
+
+        shared.set(2);
+        count.set(3);
+        assert_eq!(*seen.borrow(), [4, 6]);
+        assert_eq!(Signal::get(&doubled), 6);
+    }
+
+    #[test]
+    fn test_list_like_objects_are_collections() {
+        let list: Rc<dyn ListLike<i32>> = Rc::new(List::from(vec![1, 2]));
+        let any = AnyCollection::new(list.clone());
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            any.watch(1.., move |context| seen.borrow_mut().push(context.value))
+        };
+
+        list.push(3);
+        assert_eq!(list.set(0, 0), 1);
+        assert_eq!(list.to_vec(), [0, 2, 3]);
+        assert_eq!(*seen.borrow(), [vec![2], vec![2, 3], vec![2, 3]]);
+    }
+}
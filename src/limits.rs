@@ -0,0 +1,291 @@
+//! # Propagation limits
+//!
+//! This module provides opt-in safeguards against runaway feedback loops, such
+//! as two watchers that keep setting each other's binding. Without them, such a
+//! loop recurses until the stack overflows or hangs the UI.
+//!
+//! While limits are enabled, every write that notifies watchers is counted.
+//! Writes performed by those watchers are nested one level deeper, and all
+//! writes caused by a top-level write form a flush. A write that would exceed
+//! the [maximum depth](Limits::max_depth) or the [maximum number of writes per
+//! flush](Limits::max_writes) still changes its binding, but does not notify its
+//! watchers, cutting the propagation off. Each cut is reported.
+//!
+//! Like the [`watchdog`](crate::watchdog), limits are configured per thread.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, limits::{self, Limits}};
+//!
+//! limits::enable_with_hook(Limits::new().max_depth(8), |violation| {
+//!     eprintln!("{violation}");
+//! });
+//!
+//! let ping: Binding<i32> = binding(0);
+//! let pong: Binding<i32> = binding(0);
+//! let _ping = {
+//!     let pong = pong.clone();
+//!     ping.watch(move |context| pong.set(context.value + 1))
+//! };
+//! let _pong = {
+//!     let ping = ping.clone();
+//!     pong.watch(move |context| ping.set(context.value + 1))
+//! };
+//!
+//! // Instead of recursing forever, the loop is cut off eight writes deep.
+//! ping.set(1);
+//! assert_eq!(ping.get(), 9);
+//!
+//! limits::disable();
+//! ```
+
+use alloc::rc::Rc;
+use core::{
+    cell::{Cell, RefCell},
+    fmt::{self, Debug, Display},
+};
+
+use crate::NodeId;
+
+/// The limits applied to propagation.
+///
+/// Both limits are unbounded by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    max_depth: usize,
+    max_writes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Limits {
+    /// Creates unbounded limits.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_writes: usize::MAX,
+        }
+    }
+
+    /// Limits how deeply writes may cascade: a top-level write has depth 1,
+    /// a write performed by one of its watchers depth 2, and so on.
+    #[must_use]
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Limits how many writes may notify watchers within one flush, including
+    /// the top-level write.
+    #[must_use]
+    pub const fn max_writes(mut self, max_writes: usize) -> Self {
+        self.max_writes = max_writes;
+        self
+    }
+}
+
+/// The limit a write exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Limit {
+    /// The maximum depth, see [`Limits::max_depth`].
+    Depth(usize),
+    /// The maximum number of writes per flush, see [`Limits::max_writes`].
+    Writes(usize),
+}
+
+/// A report of a write whose propagation was cut off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Violation {
+    /// The node that was written to.
+    pub node: NodeId,
+    /// The limit that was exceeded.
+    pub limit: Limit,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.limit {
+            Limit::Depth(max) => write!(
+                f,
+                "write to {} exceeded the maximum propagation depth of {max}",
+                self.node
+            ),
+            Limit::Writes(max) => write!(
+                f,
+                "write to {} exceeded the maximum of {max} writes per flush",
+                self.node
+            ),
+        }
+    }
+}
+
+/// Type alias for a violation hook.
+type Hook = Rc<dyn Fn(&Violation)>;
+
+struct Config {
+    limits: Limits,
+    hook: Option<Hook>,
+}
+
+impl Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("limits", &self.limits)
+            .field("hook", &self.hook.as_ref().map(|_| "<hook>"))
+            .finish()
+    }
+}
+
+std::thread_local! {
+    static CONFIG: RefCell<Option<Config>> = const { RefCell::new(None) };
+    /// The depth of the running write, zero outside of a flush.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// The number of writes in the running flush.
+    static WRITES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Enables `limits` on the current thread, logging violations.
+///
+/// Every cut-off write is reported through `log::error!`.
+pub fn enable(limits: Limits) {
+    install(Config { limits, hook: None });
+}
+
+/// Enables `limits` on the current thread with a custom violation hook.
+///
+/// The hook is invoked instead of logging for every cut-off write.
+pub fn enable_with_hook(limits: Limits, hook: impl Fn(&Violation) + 'static) {
+    install(Config {
+        limits,
+        hook: Some(Rc::new(hook)),
+    });
+}
+
+/// Disables the limits on the current thread.
+pub fn disable() {
+    CONFIG.with(|config| config.borrow_mut().take());
+}
+
+/// Returns `true` if limits are enabled on the current thread.
+#[must_use]
+pub fn is_enabled() -> bool {
+    CONFIG.with(|config| config.borrow().is_some())
+}
+
+fn install(config: Config) {
+    CONFIG.with(|slot| *slot.borrow_mut() = Some(config));
+}
+
+/// Leaves a counted write, ending the flush at the top level.
+struct Exit;
+
+impl Drop for Exit {
+    fn drop(&mut self) {
+        let depth = DEPTH.with(|depth| {
+            depth.set(depth.get().saturating_sub(1));
+            depth.get()
+        });
+        if depth == 0 {
+            WRITES.with(|writes| writes.set(0));
+        }
+    }
+}
+
+/// Runs `notify`, which notifies the watchers of `node` after a write, unless
+/// it exceeds the configured limits.
+pub(crate) fn propagate(node: NodeId, notify: impl FnOnce()) {
+    let limits = CONFIG.with(|config| config.borrow().as_ref().map(|config| config.limits));
+    let Some(limits) = limits else {
+        return notify();
+    };
+
+    let (depth, writes) = (DEPTH.with(Cell::get) + 1, WRITES.with(Cell::get) + 1);
+    let limit = if depth > limits.max_depth {
+        Some(Limit::Depth(limits.max_depth))
+    } else if writes > limits.max_writes {
+        Some(Limit::Writes(limits.max_writes))
+    } else {
+        None
+    };
+    if let Some(limit) = limit {
+        report(&Violation { node, limit });
+        return;
+    }
+
+    DEPTH.with(|cell| cell.set(depth));
+    WRITES.with(|cell| cell.set(writes));
+    let _exit = Exit;
+    notify();
+}
+
+fn report(violation: &Violation) {
+    // Clone the hook out so it may reconfigure the limits without a borrow conflict.
+    let hook = CONFIG.with(|config| config.borrow().as_ref().and_then(|c| c.hook.clone()));
+    if let Some(hook) = hook {
+        hook(violation);
+    } else {
+        log::error!("{violation}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, Signal, binding};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_limits_cut_off_runaway_writes() {
+        let violations: Rc<RefCell<Vec<Violation>>> = Rc::default();
+        {
+            let violations = violations.clone();
+            enable_with_hook(Limits::new().max_writes(3), move |violation| {
+                violations.borrow_mut().push(*violation);
+            });
+        }
+
+        // Each write fans out to two writes, which would never end.
+        let counter: Binding<i32> = binding(0);
+        let _guards: Vec<_> = (0..2)
+            .map(|_| {
+                let counter = counter.clone();
+                counter
+                    .clone()
+                    .watch(move |context| counter.set(context.value + 1))
+            })
+            .collect();
+
+        counter.set(1);
+        assert_eq!(violations.borrow().len(), 4);
+        assert!(
+            violations
+                .borrow()
+                .iter()
+                .all(|v| v.limit == Limit::Writes(3) && Some(v.node) == counter.node_id())
+        );
+
+        // Each flush starts with a fresh budget.
+        violations.borrow_mut().clear();
+        enable_with_hook(Limits::new().max_depth(1), {
+            let violations = violations.clone();
+            move |violation| violations.borrow_mut().push(*violation)
+        });
+        counter.set(0);
+        assert_eq!(violations.borrow().len(), 2);
+        assert!(
+            violations
+                .borrow()
+                .iter()
+                .all(|v| v.limit == Limit::Depth(1))
+        );
+        disable();
+    }
+}
@@ -3,8 +3,24 @@
 //! This module provides transformation and memoization capabilities for reactive values.
 //!
 //! The `Map` type enables you to transform values from one type to another while preserving
-//! the reactive nature of the computation. It automatically caches the result of the transformation
-//! for better performance, invalidating the cache only when the source value changes.
+//! the reactive nature of the computation. By default, every read runs the
+//! transformation again.
+//!
+//! A map of a clonable output can cache its result instead, with
+//! [`with_cache`](Map::with_cache). The cache is shared by all clones of the map,
+//! and marked dirty when the source notifies its watchers, so writing an unrelated
+//! binding keeps it. Only sources with a [node id](Signal::node_id), such as
+//! bindings, promise to notify on every change of their value, so the result is
+//! only cached for them; a map of any other source, such as a zip, a debounced
+//! signal or a custom signal, still runs the transformation on every read.
+//!
+//! Since writes that do not notify right away, such as the silent writes of
+//! [`what_if`](crate::what_if) or writes inside a [`batch`](crate::batch), cannot
+//! mark the cache dirty, they invalidate every cache, as does any write while
+//! watchers are being notified, before the source may have notified the map. A
+//! read therefore never observes a stale result. A result computed while
+//! notifying the map's watchers is cached too, so reading the map from a watcher
+//! does not run the transformation again.
 //!
 //! ## Usage Example
 //!
@@ -13,31 +29,85 @@
 //! use nami::map::map;
 //!
 //! let number: Binding<i32> = binding(5);
-//! let doubled = map(number, |n: i32| n * 2);
+//! let doubled = map(number.clone(), |n: i32| n * 2).with_cache();
 //!
 //! assert_eq!(doubled.get(), 10);
 //!
-//! // The transformation is automatically cached
+//! // The transformation is cached
 //! doubled.get(); // Uses cached value, doesn't recompute
+//!
+//! number.set(6); // Marks the cached value dirty
+//! assert_eq!(doubled.get(), 12);
 //! ```
 
-use core::marker::PhantomData;
+use core::{
+    any::{Any, type_name},
+    cell::RefCell,
+    fmt::Debug,
+    marker::PhantomData,
+};
 
-use alloc::rc::Rc;
+use alloc::{boxed::Box, rc::Rc};
 
-use crate::{Signal, watcher::Context};
+use crate::{
+    Signal,
+    watcher::{Context, Metadata},
+};
 
 /// A reactive computation that transforms values from a source computation.
 ///
 /// `Map<C, F, Output>` applies a transformation function `F` to the results
-/// of a source computation `C`, producing a value of type `Output`. The result
-/// is automatically cached and only recomputed when the source value changes.
+/// of a source computation `C`, producing a value of type `Output`. With
+/// [`with_cache`](Self::with_cache), the result is only recomputed when the
+/// source value changes.
 pub struct Map<C, F, Output> {
     source: C,
     f: Rc<F>,
+    cache: Rc<RefCell<MapCache<Output>>>,
     _marker: PhantomData<Output>,
 }
 
+/// The last result of a [`Map`], shared by its clones.
+struct MapCache<Output> {
+    /// The last result, or `None` if it is dirty.
+    value: Option<Output>,
+    /// The generation the result was computed in.
+    generation: usize,
+    /// The silent generation the result was computed in.
+    silent: usize,
+    /// Keeps the source watcher marking the result dirty.
+    guard: Option<Box<dyn Any>>,
+    /// Clones the result, or `None` if the map does not cache it.
+    clone: Option<fn(&Output) -> Output>,
+}
+
+impl<Output> MapCache<Output> {
+    /// Creates an empty cache, caching results cloned with `clone`.
+    const fn new(clone: Option<fn(&Output) -> Output>) -> Self {
+        Self {
+            value: None,
+            generation: 0,
+            silent: 0,
+            guard: None,
+            clone,
+        }
+    }
+
+    /// Returns the last result, unless it is dirty or a binding was written
+    /// since it was computed without the source marking it dirty yet.
+    fn cached(&self) -> Option<Output> {
+        let clone = self.clone?;
+        self.value
+            .as_ref()
+            .filter(|_| {
+                self.silent == crate::signal::silent_generation()
+                    && (!crate::signal::is_notifying()
+                        || self.generation == crate::signal::generation())
+            })
+            .map(clone)
+    }
+}
+
 impl<C: Signal + 'static, F: 'static, Output> Map<C, F, Output> {
     /// Creates a new `Map` that transforms values from `source` using function `f`.
     ///
@@ -53,9 +123,26 @@ impl<C: Signal + 'static, F: 'static, Output> Map<C, F, Output> {
         Self {
             source,
             f: Rc::new(f),
+            cache: Rc::new(RefCell::new(MapCache::new(None))),
             _marker: PhantomData,
         }
     }
+
+    /// Caches the result, so it is only recomputed once the source changed.
+    ///
+    /// The result is only cached if the source has a
+    /// [node id](Signal::node_id). See the [module documentation](self) for
+    /// how it behaves.
+    #[must_use]
+    pub fn with_cache(self) -> Self
+    where
+        Output: Clone,
+    {
+        Self {
+            cache: Rc::new(RefCell::new(MapCache::new(Some(Output::clone)))),
+            ..self
+        }
+    }
 }
 
 /// Helper function to create a new `Map` transformation.
@@ -89,16 +176,113 @@ where
     Map::new(source, f)
 }
 
+/// Formats the output type, the transformation's type name and the source.
+impl<C: Debug, F, Output> Debug for Map<C, F, Output> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Map")
+            .field("type", &type_name::<Output>())
+            .field("f", &type_name::<F>())
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<C: Clone, F, Output> Clone for Map<C, F, Output> {
     fn clone(&self) -> Self {
         Self {
             source: self.source.clone(),
             f: self.f.clone(),
+            cache: self.cache.clone(),
             _marker: PhantomData,
         }
     }
 }
 
+impl<C, F, Output> Map<C, F, Output>
+where
+    C: Signal,
+    F: 'static + Fn(C::Output) -> Output,
+    Output: 'static,
+{
+    /// Applies the transformation, measuring and tracing it when enabled.
+    fn apply(&self, value: C::Output) -> Output {
+        #[cfg(feature = "std")]
+        {
+            let name = type_name::<F>();
+            crate::trace::compute(name, || crate::watchdog::measure(name, || (self.f)(value)))
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            (self.f)(value)
+        }
+    }
+
+    /// Returns how to clone the result if it is cached, which requires the
+    /// source to notify on every change of its value.
+    fn cloner(&self) -> Option<fn(&Output) -> Output> {
+        self.cache
+            .borrow()
+            .clone
+            .filter(|_| self.source.node_id().is_some())
+    }
+
+    /// Watches the source to mark the cached result dirty, unless it already
+    /// does.
+    fn invalidate_on_change(&self) {
+        if self.cache.borrow().guard.is_some() {
+            return;
+        }
+        let cache = Rc::downgrade(&self.cache);
+        let guard = self.source.watch(move |_| {
+            if let Some(cache) = cache.upgrade() {
+                cache.borrow_mut().value = None;
+            }
+        });
+        self.cache.borrow_mut().guard = Some(Box::new(guard));
+    }
+
+    /// Caches `value`, computed in `generation` and `silent`.
+    fn store(&self, value: Output, (generation, silent): (usize, usize)) {
+        let previous = {
+            let mut cache = self.cache.borrow_mut();
+            cache.generation = generation;
+            cache.silent = silent;
+            cache.value.replace(value)
+        };
+        drop(previous);
+    }
+
+    /// Wraps a watcher of the map into a watcher of its source, which maps
+    /// the value of each change, caching it if the map caches results.
+    fn forward(
+        &self,
+        watcher: impl Fn(Context<Output>) + 'static,
+    ) -> impl Fn(Context<C::Output>) + 'static {
+        let clone = self.cloner();
+        if clone.is_some() {
+            // Registered first, so the cache is marked dirty before any
+            // watcher of the map stores a new result.
+            self.invalidate_on_change();
+        }
+        let this = self.clone();
+
+        #[cfg(feature = "std")]
+        let watcher = crate::trace::traced(watcher);
+        move |context| {
+            let Context { value, metadata } = context;
+            let generation = (
+                crate::signal::generation(),
+                crate::signal::silent_generation(),
+            );
+            let value = this.apply(value);
+            if let Some(clone) = clone {
+                this.store(clone(&value), generation);
+            }
+            watcher(Context::new(value, metadata));
+        }
+    }
+}
+
 impl<C, F, Output> Signal for Map<C, F, Output>
 where
     C: Signal,
@@ -110,16 +294,185 @@ where
 
     /// Computes the transformed value, using the cache when available.
     fn get(&self) -> Output {
-        (self.f)(self.source.get())
+        let cached = self.cache.borrow().cached();
+        if let Some(value) = cached {
+            return value;
+        }
+        let Some(clone) = self.cloner() else {
+            return self.apply(self.source.get());
+        };
+        self.invalidate_on_change();
+        let generation = (
+            crate::signal::generation(),
+            crate::signal::silent_generation(),
+        );
+        let value = self.apply(self.source.get());
+        self.store(clone(&value), generation);
+        value
     }
 
     /// Registers a watcher to be notified when the transformed value changes.
     fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
-        let this = self.clone();
+        self.source.watch(self.forward(watcher))
+    }
 
-        self.source.watch(move |context| {
-            let Context { value, metadata } = context;
-            watcher(Context::new((this.f)(value), metadata));
+    /// Forwards the filter to the source, so rejected changes are not mapped.
+    fn watch_filtered(
+        &self,
+        filter: impl Fn(&Metadata) -> bool + 'static,
+        watcher: impl Fn(Context<Self::Output>) + 'static,
+    ) -> Self::Guard {
+        self.source.watch_filtered(filter, self.forward(watcher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, SignalExt, binding, what_if::sandbox};
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    fn counted(source: &Binding<i32>, runs: &Rc<Cell<usize>>) -> impl Signal<Output = i32> + use<> {
+        let runs = runs.clone();
+        map(source.clone(), move |value: i32| {
+            runs.set(runs.get() + 1);
+            value * 2
         })
+        .with_cache()
+    }
+
+    #[test]
+    fn test_map_reruns_only_when_dirty() {
+        let source = binding(1);
+        let runs = Rc::new(Cell::new(0));
+        let doubled = counted(&source, &runs);
+
+        // Clones share the cache.
+        let copy = doubled.clone();
+        assert_eq!((doubled.get(), copy.get()), (2, 2));
+        assert_eq!(runs.get(), 1);
+
+        source.set(2);
+        source.set(3);
+        assert_eq!(doubled.get(), 6);
+        assert_eq!(runs.get(), 2);
+
+        // Writing an unrelated binding keeps the cache.
+        let unrelated: Binding<i32> = binding(0);
+        unrelated.set(5);
+        assert_eq!(doubled.get(), 6);
+        assert_eq!(runs.get(), 2);
+
+        // Silent writes invalidate the cache too.
+        assert_eq!(
+            sandbox(|| {
+                source.set(10);
+                copy.get()
+            }),
+            20
+        );
+        assert_eq!(doubled.get(), 6);
+        assert_eq!(runs.get(), 4);
+    }
+
+    #[test]
+    fn test_map_of_dedup_skips_equal_writes() {
+        let source: Binding<i32> = binding(1);
+        let runs = Rc::new(Cell::new(0));
+        let doubled = source
+            .clone()
+            .dedup()
+            .map({
+                let runs = runs.clone();
+                move |value: i32| {
+                    runs.set(runs.get() + 1);
+                    value * 2
+                }
+            });
+        let _guard = doubled.watch(|_| {});
+
+        source.set(1);
+        assert_eq!(runs.get(), 0);
+        source.set(2);
+        assert_eq!(runs.get(), 1);
+    }
+
+    #[test]
+    fn test_map_caches_results_computed_for_watchers() {
+        let source = binding(1);
+        let runs = Rc::new(Cell::new(0));
+        let doubled = counted(&source, &runs);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        // Registered before the map, so it reads the map before its watchers
+        // run.
+        let _early = {
+            let (doubled, seen) = (doubled.clone(), seen.clone());
+            source.watch(move |_| seen.borrow_mut().push(doubled.get()))
+        };
+        let _guard = {
+            let (doubled, seen) = (doubled.clone(), seen.clone());
+            doubled.clone().watch(move |context| {
+                seen.borrow_mut().push(context.value);
+                seen.borrow_mut().push(doubled.get());
+            })
+        };
+
+        source.set(2);
+        assert_eq!(*seen.borrow(), [4, 4, 4]);
+        assert_eq!(doubled.get(), 4);
+        // Once for the early read, once for the watchers of the map.
+        assert_eq!(runs.get(), 2);
+    }
+
+    /// A signal reading a cell, which never notifies.
+    #[derive(Clone)]
+    struct Polled(Rc<Cell<i32>>);
+
+    impl Signal for Polled {
+        type Output = i32;
+        type Guard = ();
+
+        fn get(&self) -> i32 {
+            self.0.get()
+        }
+
+        fn watch(&self, _: impl Fn(Context<i32>) + 'static) {}
+    }
+
+    #[test]
+    fn test_map_of_silent_source_is_not_cached() {
+        let cell = Rc::new(Cell::new(1));
+        let doubled = Polled(cell.clone()).map(|value| value * 2).with_cache();
+
+        assert_eq!(doubled.get(), 2);
+        cell.set(7);
+        assert_eq!(doubled.get(), 14);
+    }
+
+    #[test]
+    fn test_map_output_need_not_be_clone() {
+        struct Label(i32);
+
+        let source: Binding<i32> = binding(3);
+        let label = source.clone().map(Label);
+        source.set(4);
+        assert_eq!(label.get().0, 4);
+    }
+
+    #[test]
+    fn test_map_of_zip_left_follows_both_sides() {
+        let a: Binding<i32> = binding(1);
+        let b: Binding<i32> = binding(10);
+        let sum = crate::zip::zip_left(a.clone(), b.clone())
+            .map(|(a, b)| a + b)
+            .with_cache();
+
+        assert_eq!(sum.get(), 11);
+        b.set(20);
+        assert_eq!(sum.get(), 21);
+        a.set(2);
+        assert_eq!(sum.get(), 22);
     }
 }
@@ -0,0 +1,662 @@
+//! # Memoization
+//!
+//! This module provides memoization for computations, single or parameterized.
+//!
+//! [`Memo`] keeps the last value of a signal and only notifies its watchers
+//! when a new value differs from it. Reads are served from the cache, and an
+//! upstream change that recomputes an equal value stops there, so an expensive
+//! graph downstream of it is not re-run.
+//!
+//! [`KeyedMemo`] creates a signal for each key on demand and reuses it on later
+//! lookups, so a view parameterized by a selected ID does not rebuild its derived
+//! state every time it is revisited. Every per-key signal is [`Cached`], which keeps
+//! its value in sync with its sources on its own. The number of retained keys is
+//! bounded, and the least recently used key is evicted once the capacity is reached.
+//!
+//! [`Family`] follows the same pattern without a capacity: it only keeps weak
+//! references to the signals it creates, and disposes of a key as soon as the last
+//! handle for it is dropped. This suits per-item derived state in lists, which should
+//! go away together with the item.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt, memo::memo_keyed};
+//!
+//! let prices: Binding<Vec<u32>> = binding(vec![10, 20, 30]);
+//!
+//! let price_of = {
+//!     let prices = prices.clone();
+//!     memo_keyed(move |index: usize| {
+//!         prices.clone().map(move |prices: Vec<u32>| prices[index])
+//!     })
+//! };
+//!
+//! assert_eq!(price_of.get(1).get(), 20);
+//!
+//! // Each per-key signal stays reactive.
+//! prices.set(vec![10, 25, 30]);
+//! assert_eq!(price_of.get(1).get(), 25);
+//! ```
+
+use core::{cell::RefCell, fmt::Debug, num::NonZeroUsize};
+
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    NodeId, Signal,
+    cache::Cached,
+    watcher::{BoxWatcherGuard, Context, Metadata, WatcherManager},
+};
+
+/// The default number of keys retained by [`memo_keyed`].
+pub const DEFAULT_CAPACITY: NonZeroUsize = match NonZeroUsize::new(64) {
+    Some(capacity) => capacity,
+    None => unreachable!(),
+};
+
+/// A memo cache that creates and reuses a signal per key.
+///
+/// Signals are created by a factory function the first time a key is requested.
+/// When more than `capacity` keys are retained, the least recently used one is
+/// evicted. Handles returned before an eviction remain valid; they are simply no
+/// longer shared with later lookups.
+pub struct KeyedMemo<K, S, F>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    factory: Rc<F>,
+    inner: Rc<RefCell<KeyedMemoInner<K, S>>>,
+}
+
+struct KeyedMemoInner<K, S>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    capacity: NonZeroUsize,
+    tick: u64,
+    entries: BTreeMap<K, Entry<S>>,
+    /// Keys ordered by their last access, oldest first.
+    recency: BTreeMap<u64, K>,
+}
+
+struct Entry<S>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    signal: Cached<S>,
+    tick: u64,
+}
+
+impl<K, S, F> Clone for KeyedMemo<K, S, F>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            factory: self.factory.clone(),
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, S, F> Debug for KeyedMemo<K, S, F>
+where
+    K: Debug,
+    S: Signal,
+    S::Output: Clone,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let inner = self.inner.borrow();
+        f.debug_struct("KeyedMemo")
+            .field("capacity", &inner.capacity)
+            .field("keys", &inner.entries.keys())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, S, F> KeyedMemo<K, S, F>
+where
+    K: Ord + Clone + 'static,
+    S: Signal,
+    S::Output: Clone,
+    F: Fn(K) -> S + 'static,
+{
+    /// Creates a new keyed memo retaining at most `capacity` keys.
+    pub fn with_capacity(capacity: NonZeroUsize, factory: F) -> Self {
+        Self {
+            factory: Rc::new(factory),
+            inner: Rc::new(RefCell::new(KeyedMemoInner {
+                capacity,
+                tick: 0,
+                entries: BTreeMap::new(),
+                recency: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// Returns the memoized signal for `key`, creating it if necessary.
+    ///
+    /// Looking up a key marks it as the most recently used one.
+    pub fn get(&self, key: K) -> Cached<S> {
+        if let Some(signal) = self.inner.borrow_mut().touch(&key) {
+            return signal;
+        }
+
+        // The factory runs without holding the borrow, so it may use this memo too.
+        let signal = Cached::new((self.factory)(key.clone()));
+        self.inner.borrow_mut().insert(key, signal.clone());
+        signal
+    }
+
+    /// Returns `true` if a signal for `key` is currently retained.
+    #[must_use]
+    pub fn contains(&self, key: &K) -> bool {
+        self.inner.borrow().entries.contains_key(key)
+    }
+
+    /// Returns the number of retained keys.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.borrow().entries.len()
+    }
+
+    /// Returns `true` if no keys are retained.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().entries.is_empty()
+    }
+
+    /// Removes the signal for `key`, so the next lookup creates a fresh one.
+    pub fn invalidate(&self, key: &K) {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(entry) = inner.entries.remove(key) {
+            inner.recency.remove(&entry.tick);
+        }
+    }
+
+    /// Removes all retained signals.
+    pub fn clear(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.entries.clear();
+        inner.recency.clear();
+    }
+}
+
+impl<K, S> KeyedMemoInner<K, S>
+where
+    K: Ord + Clone,
+    S: Signal,
+    S::Output: Clone,
+{
+    const fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Marks `key` as recently used and returns its signal, if retained.
+    fn touch(&mut self, key: &K) -> Option<Cached<S>> {
+        let tick = self.next_tick();
+        let entry = self.entries.get_mut(key)?;
+        let previous = core::mem::replace(&mut entry.tick, tick);
+        let signal = entry.signal.clone();
+        self.recency.remove(&previous);
+        self.recency.insert(tick, key.clone());
+        Some(signal)
+    }
+
+    /// Inserts a new entry, evicting the least recently used keys if over capacity.
+    fn insert(&mut self, key: K, signal: Cached<S>) {
+        let tick = self.next_tick();
+        if let Some(previous) = self.entries.insert(key.clone(), Entry { signal, tick }) {
+            self.recency.remove(&previous.tick);
+        }
+        self.recency.insert(tick, key);
+
+        while self.entries.len() > self.capacity.get() {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+/// Creates a keyed memo cache with the [default capacity](DEFAULT_CAPACITY).
+///
+/// This is a convenience function equivalent to
+/// `KeyedMemo::with_capacity(DEFAULT_CAPACITY, factory)`.
+pub fn memo_keyed<K, S, F>(factory: F) -> KeyedMemo<K, S, F>
+where
+    K: Ord + Clone + 'static,
+    S: Signal,
+    S::Output: Clone,
+    F: Fn(K) -> S + 'static,
+{
+    KeyedMemo::with_capacity(DEFAULT_CAPACITY, factory)
+}
+
+/// A factory that creates a signal per key and disposes of it when unused.
+///
+/// Unlike [`KeyedMemo`], a family never evicts a key while a [`FamilyMember`]
+/// for it is alive, and never retains a key once all of its members are dropped.
+pub struct Family<K: Ord, S, F>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    factory: Rc<F>,
+    members: Rc<RefCell<Members<K, S>>>,
+}
+
+impl<K: Ord, S, F> Clone for Family<K, S, F>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            factory: self.factory.clone(),
+            members: self.members.clone(),
+        }
+    }
+}
+
+impl<K, S, F> Debug for Family<K, S, F>
+where
+    K: Ord + Debug,
+    S: Signal,
+    S::Output: Clone,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Family")
+            .field("keys", &self.members.borrow().keys())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, S, F> Family<K, S, F>
+where
+    K: Ord + Clone + 'static,
+    S: Signal,
+    S::Output: Clone,
+    F: Fn(K) -> S + 'static,
+{
+    /// Creates a new family that builds its members with `factory`.
+    pub fn new(factory: F) -> Self {
+        Self {
+            factory: Rc::new(factory),
+            members: Rc::default(),
+        }
+    }
+
+    /// Returns the member for `key`, creating it if no live member exists.
+    pub fn get(&self, key: K) -> FamilyMember<K, S> {
+        if let Some(inner) = self.members.borrow().get(&key).and_then(Weak::upgrade) {
+            return FamilyMember { inner };
+        }
+
+        // The factory runs without holding the borrow, so it may use this family too.
+        let signal = Cached::new((self.factory)(key.clone()));
+        let inner = Rc::new(MemberInner {
+            key: key.clone(),
+            signal,
+            members: Rc::downgrade(&self.members),
+        });
+        self.members.borrow_mut().insert(key, Rc::downgrade(&inner));
+        FamilyMember { inner }
+    }
+
+    /// Returns `true` if a live member exists for `key`.
+    #[must_use]
+    pub fn contains(&self, key: &K) -> bool {
+        self.members.borrow().contains_key(key)
+    }
+
+    /// Returns the number of live members.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.members.borrow().len()
+    }
+
+    /// Returns `true` if the family has no live members.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.members.borrow().is_empty()
+    }
+}
+
+/// Creates a family of signals built on demand by `factory`.
+///
+/// This is a convenience function equivalent to `Family::new(factory)`.
+///
+/// # Examples
+///
+/// ```rust
+/// use nami::{binding, Binding, Signal, SignalExt, memo::family};
+///
+/// let scale: Binding<i32> = binding(2);
+/// let scaled = {
+///     let scale = scale.clone();
+///     family(move |item: i32| scale.clone().map(move |scale: i32| item * scale))
+/// };
+///
+/// let member = scaled.get(21);
+/// assert_eq!(member.get(), 42);
+/// assert_eq!(scaled.len(), 1);
+///
+/// // Dropping the last handle disposes of the member.
+/// drop(member);
+/// assert!(scaled.is_empty());
+/// ```
+pub fn family<K, S, F>(factory: F) -> Family<K, S, F>
+where
+    K: Ord + Clone + 'static,
+    S: Signal,
+    S::Output: Clone,
+    F: Fn(K) -> S + 'static,
+{
+    Family::new(factory)
+}
+
+/// Weak references to the live members of a family, by key.
+type Members<K, S> = BTreeMap<K, Weak<MemberInner<K, S>>>;
+
+/// A handle to a signal created by a [`Family`].
+///
+/// Members for the same key share one signal. The member is removed from its
+/// family when the last handle is dropped.
+pub struct FamilyMember<K: Ord, S>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    inner: Rc<MemberInner<K, S>>,
+}
+
+struct MemberInner<K: Ord, S>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    key: K,
+    signal: Cached<S>,
+    members: Weak<RefCell<Members<K, S>>>,
+}
+
+impl<K: Ord, S> Drop for MemberInner<K, S>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    fn drop(&mut self) {
+        let Some(members) = self.members.upgrade() else {
+            return;
+        };
+        let mut members = members.borrow_mut();
+        // Only remove the entry if it still refers to this (now dead) member.
+        if members
+            .get(&self.key)
+            .is_some_and(|member| member.strong_count() == 0)
+        {
+            members.remove(&self.key);
+        }
+    }
+}
+
+impl<K: Ord, S> FamilyMember<K, S>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    /// Returns the key this member was created for.
+    #[must_use]
+    pub fn key(&self) -> &K {
+        &self.inner.key
+    }
+}
+
+impl<K: Ord, S> Clone for FamilyMember<K, S>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K: Ord + Debug, S> Debug for FamilyMember<K, S>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FamilyMember")
+            .field("key", &self.inner.key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: Ord + 'static, S> Signal for FamilyMember<K, S>
+where
+    S: Signal,
+    S::Output: Clone,
+{
+    type Output = S::Output;
+    type Guard = S::Guard;
+
+    fn get(&self) -> Self::Output {
+        self.inner.signal.get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.inner.signal.watch(watcher)
+    }
+}
+
+/// A signal caching the last value of its source, which only notifies its
+/// watchers when the value changes.
+///
+/// Created by [`memo`]. Unlike [`dedup`](crate::SignalExt::dedup), which still
+/// reads its source on every [`get`](Signal::get), reads return the cached
+/// value. The source is watched for as long as a clone of the memo is alive.
+#[derive(Clone)]
+pub struct Memo<T> {
+    value: Rc<RefCell<T>>,
+    watchers: WatcherManager<T>,
+    id: NodeId,
+    _guard: Rc<dyn core::any::Any>,
+}
+
+impl<T: Debug + 'static> Debug for Memo<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Memo")
+            .field("value", &*self.value.borrow())
+            .field("watchers", &self.watchers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Memoizes `source`, caching its last value and only notifying watchers when
+/// a new value is not equal to it.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::{binding, Binding, Signal, SignalExt, memo::memo};
+///
+/// let items: Binding<Vec<u32>> = binding(vec![3, 1, 2]);
+/// let max = memo(items.clone().map(|items| items.into_iter().max()));
+/// let _guard = max.watch(|ctx| println!("new maximum: {:?}", ctx.value));
+///
+/// items.set(vec![3, 2]); // Still 3: the watcher is not notified.
+/// assert_eq!(max.get(), Some(3));
+/// ```
+pub fn memo<S>(source: S) -> Memo<S::Output>
+where
+    S: Signal,
+    S::Output: PartialEq + Clone,
+{
+    let value = Rc::new(RefCell::new(source.get()));
+    let watchers = WatcherManager::new();
+    let guard = {
+        let (value, watchers) = (value.clone(), watchers.clone());
+        source.watch(move |context: Context<S::Output>| {
+            let Context {
+                value: new,
+                metadata,
+            } = context;
+            if *value.borrow() == new {
+                return;
+            }
+            let previous = value.replace(new);
+            drop(previous);
+            watchers.notify(|| value.borrow().clone(), &metadata);
+        })
+    };
+
+    Memo {
+        value,
+        watchers,
+        id: NodeId::next(),
+        _guard: Rc::new(guard),
+    }
+}
+
+impl<T: Clone + 'static> Signal for Memo<T> {
+    type Output = T;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> Self::Output {
+        self.value.borrow().clone()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        Box::new(self.watchers.register_as_guard(watcher))
+    }
+
+    fn watch_filtered(
+        &self,
+        filter: impl Fn(&Metadata) -> bool + 'static,
+        watcher: impl Fn(Context<Self::Output>) + 'static,
+    ) -> Self::Guard {
+        Box::new(self.watchers.register_filtered(filter, watcher))
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        Some(self.id)
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        Some(self.watchers.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SignalExt, binding};
+    use core::cell::Cell;
+
+    #[test]
+    fn test_memo_caches_and_skips_equal_values() {
+        let (computed, notified) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let count = binding(1);
+        let parity = memo(count.clone().map({
+            let computed = computed.clone();
+            move |n: i32| {
+                computed.set(computed.get() + 1);
+                n % 2
+            }
+        }));
+        let _guard = {
+            let notified = notified.clone();
+            parity.watch(move |_| notified.set(notified.get() + 1))
+        };
+
+        count.set(3);
+        assert_eq!((parity.get(), parity.get()), (1, 1));
+        assert_eq!((computed.get(), notified.get()), (2, 0));
+
+        count.set(4);
+        assert_eq!(parity.get(), 0);
+        assert_eq!((computed.get(), notified.get()), (3, 1));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn test_keyed_memo_reuses_and_evicts() {
+        let created = Rc::new(Cell::new(0));
+        let source = binding(1);
+
+        let memo = {
+            let created = created.clone();
+            let source = source.clone();
+            KeyedMemo::with_capacity(NonZeroUsize::new(2).unwrap(), move |key: i32| {
+                created.set(created.get() + 1);
+                source.clone().map(move |value: i32| value * key)
+            })
+        };
+
+        assert_eq!(memo.get(1).get(), 1);
+        assert_eq!(memo.get(2).get(), 2);
+        assert_eq!(memo.get(1).get(), 1);
+        assert_eq!(created.get(), 2);
+
+        // Key 2 is the least recently used and is evicted.
+        assert_eq!(memo.get(3).get(), 3);
+        assert_eq!(memo.len(), 2);
+        assert!(memo.contains(&1));
+        assert!(!memo.contains(&2));
+        assert!(memo.contains(&3));
+
+        // Retained signals follow their sources.
+        source.set(10);
+        assert_eq!(memo.get(1).get(), 10);
+        assert_eq!(memo.get(3).get(), 30);
+        assert_eq!(created.get(), 3);
+
+        memo.invalidate(&1);
+        assert_eq!(memo.get(1).get(), 10);
+        assert_eq!(created.get(), 4);
+    }
+
+    #[test]
+    fn test_family_disposes_dropped_members() {
+        let created = Rc::new(Cell::new(0));
+        let family = {
+            let created = created.clone();
+            family(move |key: i32| {
+                created.set(created.get() + 1);
+                binding::<i32>(key)
+            })
+        };
+
+        let first = family.get(1);
+        let second = family.get(1);
+        assert_eq!(created.get(), 1);
+        assert_eq!(first.get(), 1);
+        assert_eq!(*second.key(), 1);
+
+        drop(first);
+        assert!(family.contains(&1));
+        drop(second);
+        assert!(!family.contains(&1));
+        assert!(family.is_empty());
+
+        let _third = family.get(1);
+        assert_eq!(created.get(), 2);
+        assert_eq!(family.len(), 1);
+    }
+}
@@ -0,0 +1,327 @@
+//! # Model merges
+//!
+//! This module diffs two versions of a model, such as the local version being
+//! edited and the version received from a sync server, for conflict resolution
+//! UIs. Models are compared through their JSON serialization, so any model
+//! deriving `Serialize` and `Deserialize` can be merged.
+//!
+//! - [`diff`] lists the [`FieldChange`]s between two versions, one per field
+//!   that differs, identified by its [`Path`] in the serialized model.
+//! - [`Merge`] follows a binding of the local version and exposes its pending
+//!   changes against the other version as a signal. Each change can be
+//!   [accepted](Merge::accept), copying the other version's field into the
+//!   binding, or [rejected](Merge::reject), keeping the local field. Either
+//!   way, the change leaves the diff.
+//!
+//! Arrays are compared index by index, so an element inserted near the start
+//! of a list shows up as a change of every following element.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, merge::Merge};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Note {
+//!     title: String,
+//!     body: String,
+//! }
+//!
+//! let local: Binding<Note> = binding(Note { title: "Draft".into(), body: "Hello".into() });
+//! let remote = Note { title: "Plan".into(), body: "Hi".into() };
+//! let merge = Merge::new(local.clone(), &remote).unwrap();
+//!
+//! let paths: Vec<String> = merge.get().iter().map(|change| change.path.to_string()).collect();
+//! assert_eq!(paths, ["body", "title"]);
+//!
+//! merge.accept(&"title".parse().unwrap()).unwrap();
+//! merge.reject(&"body".parse().unwrap()).unwrap();
+//! assert!(merge.get().is_empty());
+//! assert_eq!((local.get().title, local.get().body), ("Plan".into(), "Hello".into()));
+//! ```
+
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    Binding, Container, CustomBinding, NodeId, Signal, SignalExt,
+    json::{JsonError, Path, changed_paths, remove_in, set_in},
+    watcher::{BoxWatcherGuard, Context},
+};
+
+/// A field that differs between two versions of a model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FieldChange {
+    /// The path of the field in the serialized model.
+    pub path: Path,
+    /// The field in the local version, or `None` if it only exists in the
+    /// other version.
+    pub ours: Option<Value>,
+    /// The field in the other version, or `None` if it only exists in the
+    /// local version.
+    pub theirs: Option<Value>,
+}
+
+/// An error returned when two versions of a model cannot be merged.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MergeError {
+    /// A change could not be applied to the serialized model.
+    Json(JsonError),
+    /// The model could not be converted to or from JSON.
+    Serde(serde_json::Error),
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(error) => write!(f, "failed to apply change: {error}"),
+            Self::Serde(error) => write!(f, "failed to convert model: {error}"),
+        }
+    }
+}
+
+impl core::error::Error for MergeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Json(error) => Some(error),
+            Self::Serde(error) => Some(error),
+        }
+    }
+}
+
+impl From<JsonError> for MergeError {
+    fn from(error: JsonError) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl From<serde_json::Error> for MergeError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Serde(error)
+    }
+}
+
+/// Lists the fields that differ between `ours` and `theirs`, ordered by path.
+///
+/// # Errors
+///
+/// Returns an error if either version fails to serialize.
+pub fn diff<T: Serialize>(ours: &T, theirs: &T) -> Result<Vec<FieldChange>, MergeError> {
+    Ok(diff_values(
+        &serde_json::to_value(ours)?,
+        &serde_json::to_value(theirs)?,
+    ))
+}
+
+/// Lists the fields that differ between two serialized versions of a model.
+#[must_use]
+pub fn diff_values(ours: &Value, theirs: &Value) -> Vec<FieldChange> {
+    let mut paths = Vec::new();
+    changed_paths(ours, theirs, &mut Path::root(), &mut paths);
+    paths
+        .into_iter()
+        .map(|path| FieldChange {
+            ours: path.lookup(ours).cloned(),
+            theirs: path.lookup(theirs).cloned(),
+            path,
+        })
+        .collect()
+}
+
+/// Copies the field at `path` of `from` into `into`, removing it from `into`
+/// if `from` does not have it.
+fn copy_field(from: &Value, into: &mut Value, path: &Path) -> Result<(), JsonError> {
+    match path.lookup(from) {
+        Some(value) => set_in(into, path, value.clone()),
+        None => remove_in(into, path).map(drop),
+    }
+}
+
+/// A merge of a binding of the local version of a model with another version.
+///
+/// As a signal, it emits the pending changes, which update as the binding is
+/// edited. All clones share the same other version. See the
+/// [module documentation](self) for how it behaves.
+#[derive(Debug, Clone)]
+pub struct Merge<T: 'static> {
+    ours: Binding<T>,
+    theirs: Container<Value>,
+}
+
+impl<T> Merge<T>
+where
+    T: Clone + Serialize + DeserializeOwned + 'static,
+{
+    /// Creates a merge of `ours` with `theirs`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `theirs` fails to serialize.
+    pub fn new(ours: Binding<T>, theirs: &T) -> Result<Self, MergeError> {
+        Ok(Self {
+            ours,
+            theirs: Container::new(serde_json::to_value(theirs)?),
+        })
+    }
+
+    /// Replaces the other version, such as when a newer one is received.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `theirs` fails to serialize.
+    pub fn set_theirs(&self, theirs: &T) -> Result<(), MergeError> {
+        self.theirs.set(serde_json::to_value(theirs)?);
+        Ok(())
+    }
+
+    /// Returns the other version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the other version fails to deserialize.
+    pub fn theirs(&self) -> Result<T, MergeError> {
+        Ok(serde_json::from_value(self.theirs.get())?)
+    }
+
+    /// Accepts the change at `path`, setting the field of the binding to the
+    /// one of the other version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent of the field does not exist in the local
+    /// version, or if the updated model fails to convert.
+    pub fn accept(&self, path: &Path) -> Result<(), MergeError> {
+        let mut ours = serde_json::to_value(self.ours.get())?;
+        copy_field(&self.theirs.get(), &mut ours, path)?;
+        self.ours.set(serde_json::from_value::<T>(ours)?);
+        Ok(())
+    }
+
+    /// Rejects the change at `path`, keeping the field of the binding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent of the field does not exist in the other
+    /// version, or if the local version fails to serialize.
+    pub fn reject(&self, path: &Path) -> Result<(), MergeError> {
+        let mut theirs = self.theirs.get();
+        copy_field(&serde_json::to_value(self.ours.get())?, &mut theirs, path)?;
+        self.theirs.set(theirs);
+        Ok(())
+    }
+
+    /// Accepts every change, setting the binding to the other version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the other version fails to deserialize.
+    pub fn accept_all(&self) -> Result<(), MergeError> {
+        self.ours.set(self.theirs()?);
+        Ok(())
+    }
+
+    /// Rejects every change, keeping the binding as it is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the local version fails to serialize.
+    pub fn reject_all(&self) -> Result<(), MergeError> {
+        self.theirs.set(serde_json::to_value(self.ours.get())?);
+        Ok(())
+    }
+}
+
+/// Serializes `model`, treating a model that fails to serialize as `null`.
+fn to_value<T: Serialize>(model: &T) -> Value {
+    serde_json::to_value(model).unwrap_or_default()
+}
+
+impl<T> Signal for Merge<T>
+where
+    T: Clone + Serialize + DeserializeOwned + 'static,
+{
+    type Output = Vec<FieldChange>;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> Self::Output {
+        diff_values(&to_value(&self.ours.get()), &self.theirs.get())
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let changes = self
+            .ours
+            .clone()
+            .zip(self.theirs.clone())
+            .map(|(ours, theirs)| diff_values(&to_value(&ours), &theirs));
+        Box::new(changes.watch(watcher))
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.theirs.node_id()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::binding;
+    use alloc::{rc::Rc, string::String, vec};
+    use core::cell::RefCell;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Task {
+        title: String,
+        tags: Vec<String>,
+        done: bool,
+    }
+
+    #[test]
+    fn test_merge_tracks_pending_changes() {
+        let ours: Binding<Task> = binding(Task {
+            title: "Ship".into(),
+            tags: vec!["work".into()],
+            done: false,
+        });
+        let theirs = Task {
+            title: "Ship it".into(),
+            tags: vec!["work".into(), "urgent".into()],
+            done: false,
+        };
+        let merge = Merge::new(ours.clone(), &theirs).unwrap();
+        let counts = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let counts = counts.clone();
+            merge.watch(move |context| counts.borrow_mut().push(context.value.len()))
+        };
+
+        assert_eq!(
+            merge.get()[0],
+            FieldChange {
+                path: "tags[1]".parse().unwrap(),
+                ours: None,
+                theirs: Some(json!("urgent")),
+            }
+        );
+
+        merge.accept(&"tags[1]".parse().unwrap()).unwrap();
+        ours.set(Task {
+            done: true,
+            ..ours.get()
+        });
+        merge.reject(&"title".parse().unwrap()).unwrap();
+        merge.reject_all().unwrap();
+
+        assert_eq!(*counts.borrow(), [1, 2, 1, 0]);
+        assert_eq!(ours.get().tags, ["work", "urgent"]);
+        assert_eq!(merge.theirs().unwrap(), ours.get());
+    }
+}
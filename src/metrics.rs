@@ -0,0 +1,133 @@
+//! # Metrics export
+//!
+//! This module bridges signals to the [`metrics`](::metrics) facade, so
+//! operational dashboards can reflect internal reactive state without manual
+//! bridging code.
+//!
+//! An exported signal sets its gauge to the current value immediately, and again
+//! every time it changes, for as long as the returned guard is alive.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, metrics::{export_gauge, export_to_gauge}};
+//!
+//! let queue_depth: Binding<u32> = binding(0u32);
+//! let _guard = export_gauge("queue_depth", &queue_depth);
+//!
+//! // Labeled gauges can be registered with the `metrics` macros directly.
+//! let gauge = metrics::gauge!("queue_depth_by_worker", "worker" => "1");
+//! let _labeled = export_to_gauge(gauge, &queue_depth);
+//!
+//! queue_depth.set(3u32); // Both gauges are now set to 3.0
+//! ```
+
+use metrics::{Gauge, IntoF64, SharedString};
+
+use crate::{Signal, watcher::Context};
+
+/// Exports `signal` as the gauge named `name`.
+///
+/// The gauge is registered with the globally installed recorder. Returns the
+/// signal's watcher guard; dropping it stops updating the gauge.
+pub fn export_gauge<S>(name: impl Into<SharedString>, signal: &S) -> S::Guard
+where
+    S: Signal,
+    S::Output: IntoF64,
+{
+    export_to_gauge(metrics::gauge!(name.into()), signal)
+}
+
+/// Exports `signal` to an already registered gauge.
+///
+/// Use this for gauges with labels or other custom registration. Returns the
+/// signal's watcher guard; dropping it stops updating the gauge.
+pub fn export_to_gauge<S>(gauge: Gauge, signal: &S) -> S::Guard
+where
+    S: Signal,
+    S::Output: IntoF64,
+{
+    gauge.set(signal.get());
+    signal.watch(move |context: Context<S::Output>| {
+        gauge.set(context.value);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+    use alloc::{collections::BTreeMap, string::String, sync::Arc};
+    use core::sync::atomic::{AtomicU64, Ordering};
+    use metrics::{Counter, Histogram, Key, KeyName, Metadata, Recorder, Unit};
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    fn value(gauge: &AtomicU64) -> f64 {
+        f64::from_bits(gauge.load(Ordering::Relaxed))
+    }
+
+    /// Records the gauges registered with it by name.
+    #[derive(Default)]
+    struct Gauges(Mutex<BTreeMap<String, Arc<AtomicU64>>>);
+
+    impl Gauges {
+        fn lock(&self) -> MutexGuard<'_, BTreeMap<String, Arc<AtomicU64>>> {
+            self.0.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        fn names(&self) -> alloc::vec::Vec<String> {
+            self.lock().keys().cloned().collect()
+        }
+
+        fn gauge(&self, name: &str) -> Option<Arc<AtomicU64>> {
+            self.lock().get(name).cloned()
+        }
+    }
+
+    impl Recorder for Gauges {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::noop()
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+            let gauge = Arc::new(AtomicU64::new(0));
+            self.lock().insert(key.name().into(), gauge.clone());
+            Gauge::from_arc(gauge)
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn test_export_to_gauge_follows_the_signal() {
+        let depth: Binding<u32> = binding(2u32);
+        let gauge = Arc::new(AtomicU64::new(0));
+        let guard = export_to_gauge(Gauge::from_arc(gauge.clone()), &depth);
+        assert!((value(&gauge) - 2.0).abs() < f64::EPSILON);
+
+        depth.set(5u32);
+        assert!((value(&gauge) - 5.0).abs() < f64::EPSILON);
+
+        drop(guard);
+        depth.set(7u32);
+        assert!((value(&gauge) - 5.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_export_gauge_registers_with_the_recorder() {
+        let recorder = Gauges::default();
+        let depth: Binding<u32> = binding(3u32);
+        let _guard = metrics::with_local_recorder(&recorder, || export_gauge("depth", &depth));
+        depth.set(4u32);
+
+        assert_eq!(recorder.names(), ["depth"]);
+        let gauge = recorder.gauge("depth");
+        assert!(gauge.is_some_and(|gauge| (value(&gauge) - 4.0).abs() < f64::EPSILON));
+    }
+}
@@ -0,0 +1,212 @@
+//! # Custom operators
+//!
+//! The [`Operator`] trait lets other crates define their own combinators, such
+//! as domain-specific smoothing, which behave like the built-in ones without
+//! depending on how [`Map`](crate::map::Map) or [`Zip`](crate::zip::Zip) work:
+//!
+//! - The input of an operator is any signal. An operator of several inputs
+//!   takes them [zipped](crate::SignalExt::zip) into one.
+//! - [`init`](Operator::init) computes the first output from the value of the
+//!   input when the operator is applied.
+//! - [`on_source_change`](Operator::on_source_change) is called for every value
+//!   the input notifies, and emits outputs through an [`Emitter`]. It may emit
+//!   nothing, such as for a value below a threshold, or several times.
+//!
+//! The output is held by a binding, so emitting follows the same rules as a
+//! write to one: watchers are notified once the running
+//! [`batch`](crate::batch::batch) ends, writes count towards the
+//! [limits](crate::limits) and appear in [traces](crate::trace). Every call to
+//! `on_source_change` runs in a batch of its own, so its emissions reach
+//! watchers as one notification with the final value, after the hook returns.
+//!
+//! An emitter can be cloned and kept, to emit later from a timer or a task
+//! spawned on an executor, as time-based operators like
+//! [debouncing](crate::debounce) do.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt, operator::{Emitter, Operator}, watcher::Context};
+//!
+//! /// Exponential smoothing of noisy readings.
+//! struct Smooth {
+//!     alpha: f64,
+//!     value: f64,
+//! }
+//!
+//! impl Operator<f64> for Smooth {
+//!     type Output = f64;
+//!
+//!     fn init(&mut self, input: f64) -> f64 {
+//!         self.value = input;
+//!         input
+//!     }
+//!
+//!     fn on_source_change(&mut self, input: Context<f64>, output: &Emitter<f64>) {
+//!         self.value += self.alpha * (input.value - self.value);
+//!         output.emit(self.value);
+//!     }
+//! }
+//!
+//! let reading: Binding<f64> = binding(10.0);
+//! let smoothed = reading.clone().operator(Smooth { alpha: 0.5, value: 0.0 });
+//!
+//! reading.set(20.0);
+//! assert_eq!(smoothed.get(), 15.0);
+//! ```
+
+use core::{cell::RefCell, fmt};
+
+use crate::{
+    Binding, Container, Signal,
+    held::{Held, held_signal},
+    watcher::Context,
+};
+
+/// A combinator computing its output from the values of an input of type `I`.
+///
+/// Apply one with [`operator`] or [`SignalExt::operator`](crate::SignalExt::operator).
+/// See the [module documentation](self) for how it behaves.
+pub trait Operator<I>: 'static {
+    /// The type of the values the operator emits.
+    type Output: Clone + 'static;
+
+    /// Returns the first output, computed from the value of the input when the
+    /// operator is applied.
+    fn init(&mut self, input: I) -> Self::Output;
+
+    /// Handles a value notified by the input, emitting outputs through
+    /// `output`.
+    fn on_source_change(&mut self, input: Context<I>, output: &Emitter<Self::Output>);
+}
+
+/// A handle emitting the outputs of an [`Operator`].
+///
+/// Clones emit to the same signal, and can be kept to emit later.
+#[derive(Clone)]
+pub struct Emitter<T: Clone + 'static> {
+    binding: Binding<T>,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for Emitter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Emitter");
+        if self
+            .binding
+            .try_peek(|value| {
+                debug.field("value", value);
+            })
+            .is_none()
+        {
+            debug.field("value", &format_args!("<borrowed>"));
+        }
+        debug.finish()
+    }
+}
+
+impl<T: Clone + 'static> Emitter<T> {
+    /// Emits `value`, notifying the watchers of the operator.
+    pub fn emit(&self, value: T) {
+        self.binding.set(value);
+    }
+
+    /// Modifies the last output in place, then notifies the watchers of the
+    /// operator.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        self.binding.update(f);
+    }
+
+    /// Returns the last output.
+    #[must_use]
+    pub fn get(&self) -> T {
+        self.binding.get()
+    }
+}
+
+/// A signal of the outputs of an [`Operator`] applied to an input.
+///
+/// Created by [`operator`]. The input is watched for as long as a clone of the
+/// signal is alive.
+#[derive(Clone)]
+pub struct OperatorSignal<T: Clone + 'static> {
+    held: Held<T>,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for OperatorSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.held.debug(f, "OperatorSignal", "value")
+    }
+}
+
+held_signal!(OperatorSignal<T> => T);
+
+/// Applies `op` to `source`, returning a signal of its outputs.
+///
+/// See the [module documentation](self) for how it behaves.
+pub fn operator<S, O>(source: S, mut op: O) -> OperatorSignal<O::Output>
+where
+    S: Signal,
+    O: Operator<S::Output>,
+{
+    let container = Container::new(op.init(source.get()));
+    let guard = {
+        let op = RefCell::new(op);
+        let emitter = Emitter {
+            binding: Binding::custom(container.clone()),
+        };
+        source.watch(move |context: Context<S::Output>| {
+            let run = || op.borrow_mut().on_source_change(context, &emitter);
+            #[cfg(feature = "std")]
+            crate::batch::batch(run);
+            #[cfg(not(feature = "std"))]
+            run();
+        })
+    };
+    OperatorSignal {
+        held: Held::new(container, guard),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SignalExt, binding};
+    use alloc::{rc::Rc, vec::Vec};
+
+    /// Emits ten times even values in two steps, and nothing for odd ones.
+    struct EvenTwice;
+
+    impl Operator<i32> for EvenTwice {
+        type Output = i32;
+
+        fn init(&mut self, input: i32) -> i32 {
+            input
+        }
+
+        fn on_source_change(&mut self, input: Context<i32>, output: &Emitter<i32>) {
+            if input.value % 2 == 0 {
+                output.emit(input.value);
+                output.update(|value| *value *= 10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_operator_emissions_are_batched_per_change() {
+        let count: Binding<i32> = binding(1);
+        let even = count.clone().operator(EvenTwice);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            even.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        assert_eq!(even.get(), 1);
+        count.set(2);
+        count.set(3);
+        count.set(4);
+        #[cfg(feature = "std")]
+        assert_eq!(*seen.borrow(), [20, 40]);
+        assert_eq!(even.get(), 40);
+    }
+}
@@ -0,0 +1,331 @@
+//! # Versioned persistence with schema migrations
+//!
+//! This module saves reactive state as versioned JSON [`Snapshot`]s and upgrades
+//! older snapshots on load, so persisted state survives changes to the model types.
+//!
+//! A [`Schema`] records the current version of the state and a registry of
+//! migrations, each rewriting the JSON of one version into a later one. Loading a
+//! snapshot runs every migration between its version and the current one before
+//! deserializing.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, persist::{Schema, Snapshot}};
+//! use serde::Deserialize;
+//! use serde_json::json;
+//!
+//! #[derive(Clone, Deserialize, serde::Serialize)]
+//! struct Settings {
+//!     theme: String,
+//!     font_size: u32,
+//! }
+//!
+//! let mut schema = Schema::new(2);
+//! // Version 1 stored the theme under `dark: bool`.
+//! schema.register_migration(1, 2, |mut state| {
+//!     let dark = state["dark"].take().as_bool().unwrap_or(false);
+//!     json!({ "theme": if dark { "dark" } else { "light" }, "font_size": state["font_size"] })
+//! });
+//!
+//! let saved = Snapshot::new(1, json!({ "dark": true, "font_size": 14 }));
+//! let settings: Binding<Settings> = binding(Settings { theme: "light".into(), font_size: 12 });
+//! schema.restore(&settings, saved).unwrap();
+//! assert_eq!(settings.get().theme, "dark");
+//!
+//! let snapshot = schema.save(&settings).unwrap();
+//! assert_eq!(snapshot.version(), 2);
+//! ```
+
+use alloc::{boxed::Box, collections::BTreeMap};
+use core::fmt;
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::{Binding, Signal};
+
+/// A version number of persisted state.
+pub type Version = u32;
+
+/// Serialized state tagged with the schema version it was saved with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    version: Version,
+    state: Value,
+}
+
+impl Snapshot {
+    /// Creates a snapshot of `state` at `version`.
+    #[must_use]
+    pub const fn new(version: Version, state: Value) -> Self {
+        Self { version, state }
+    }
+
+    /// Wraps state that was saved before versioning was introduced, as version `0`.
+    #[must_use]
+    pub const fn unversioned(state: Value) -> Self {
+        Self::new(0, state)
+    }
+
+    /// Returns the schema version of this snapshot.
+    #[must_use]
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the serialized state.
+    #[must_use]
+    pub const fn state(&self) -> &Value {
+        &self.state
+    }
+
+    /// Encodes this snapshot as `{ "version": .., "state": .. }`.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({ "version": self.version, "state": self.state })
+    }
+
+    /// Decodes a snapshot produced by [`Snapshot::to_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PersistError::Malformed`] if `value` does not have a numeric
+    /// `version` and a `state`.
+    pub fn from_json(mut value: Value) -> Result<Self, PersistError> {
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .and_then(|version| Version::try_from(version).ok())
+            .ok_or(PersistError::Malformed)?;
+        let state = value
+            .get_mut("state")
+            .map(Value::take)
+            .ok_or(PersistError::Malformed)?;
+        Ok(Self::new(version, state))
+    }
+}
+
+/// An error returned when state cannot be saved or loaded.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PersistError {
+    /// The snapshot envelope is missing its version or state.
+    Malformed,
+    /// The snapshot was saved by a newer schema than the current one.
+    Newer {
+        /// The version of the snapshot.
+        version: Version,
+        /// The current schema version.
+        current: Version,
+    },
+    /// No migration is registered from this version.
+    MissingMigration {
+        /// The version that could not be upgraded.
+        from: Version,
+    },
+    /// The state could not be converted to or from JSON.
+    Serde(serde_json::Error),
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => f.write_str("snapshot is missing its version or state"),
+            Self::Newer { version, current } => write!(
+                f,
+                "snapshot version {version} is newer than the current version {current}"
+            ),
+            Self::MissingMigration { from } => write!(f, "no migration from version {from}"),
+            Self::Serde(error) => write!(f, "failed to convert state: {error}"),
+        }
+    }
+}
+
+impl core::error::Error for PersistError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Serde(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for PersistError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Serde(error)
+    }
+}
+
+/// Type alias for a migration rewriting the JSON of one version into another.
+type Migration = Box<dyn Fn(Value) -> Value>;
+
+/// The current version of persisted state and the migrations leading up to it.
+pub struct Schema {
+    version: Version,
+    migrations: BTreeMap<Version, (Version, Migration)>,
+}
+
+impl fmt::Debug for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Schema")
+            .field("version", &self.version)
+            .field(
+                "migrations",
+                &self
+                    .migrations
+                    .iter()
+                    .map(|(from, (to, _))| (from, to))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+            .finish()
+    }
+}
+
+impl Schema {
+    /// Creates a schema whose current version is `version`, with no migrations.
+    #[must_use]
+    pub const fn new(version: Version) -> Self {
+        Self {
+            version,
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the current version.
+    #[must_use]
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Registers a migration upgrading state saved at `from` to version `to`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `to` is not greater than `from`, or if a migration from `from` is
+    /// already registered.
+    pub fn register_migration(
+        &mut self,
+        from: Version,
+        to: Version,
+        migration: impl Fn(Value) -> Value + 'static,
+    ) -> &mut Self {
+        assert!(to > from, "migrations must upgrade to a later version");
+        let previous = self.migrations.insert(from, (to, Box::new(migration)));
+        assert!(
+            previous.is_none(),
+            "a migration from version {from} is already registered"
+        );
+        self
+    }
+
+    /// Upgrades `snapshot` to the current version, returning the migrated state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot is newer than the current version, or if no
+    /// chain of migrations leads from its version to the current one.
+    pub fn migrate(&self, snapshot: Snapshot) -> Result<Value, PersistError> {
+        let Snapshot {
+            mut version,
+            mut state,
+        } = snapshot;
+
+        while version < self.version {
+            let (to, migration) = self
+                .migrations
+                .get(&version)
+                .ok_or(PersistError::MissingMigration { from: version })?;
+            state = migration(state);
+            version = *to;
+        }
+
+        if version > self.version {
+            return Err(PersistError::Newer {
+                version,
+                current: self.version,
+            });
+        }
+        Ok(state)
+    }
+
+    /// Serializes the current value of `signal` as a snapshot at the current version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be serialized.
+    pub fn save<S>(&self, signal: &S) -> Result<Snapshot, PersistError>
+    where
+        S: Signal,
+        S::Output: Serialize,
+    {
+        Ok(Snapshot::new(
+            self.version,
+            serde_json::to_value(signal.get())?,
+        ))
+    }
+
+    /// Migrates and deserializes `snapshot`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if migration fails or the migrated state does not match `T`.
+    pub fn load<T: DeserializeOwned>(&self, snapshot: Snapshot) -> Result<T, PersistError> {
+        Ok(serde_json::from_value(self.migrate(snapshot)?)?)
+    }
+
+    /// Migrates and deserializes `snapshot` into `binding`, notifying its watchers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if loading fails, in which case `binding` is left unchanged.
+    pub fn restore<T>(&self, binding: &Binding<T>, snapshot: Snapshot) -> Result<(), PersistError>
+    where
+        T: DeserializeOwned,
+    {
+        binding.set(self.load::<T>(snapshot)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migration_chain() {
+        let mut schema = Schema::new(3);
+        schema
+            .register_migration(0, 2, |state| json!({ "count": state }))
+            .register_migration(2, 3, |mut state| {
+                state["label"] = json!("untitled");
+                state
+            });
+
+        let state = schema.migrate(Snapshot::unversioned(json!(5)));
+        assert_eq!(state.ok(), Some(json!({ "count": 5, "label": "untitled" })));
+
+        // Older snapshots can start anywhere along the chain.
+        let state = schema.migrate(Snapshot::new(2, json!({ "count": 1 })));
+        assert_eq!(state.ok(), Some(json!({ "count": 1, "label": "untitled" })));
+
+        assert!(matches!(
+            schema.migrate(Snapshot::new(1, json!(null))),
+            Err(PersistError::MissingMigration { from: 1 })
+        ));
+        assert!(matches!(
+            schema.migrate(Snapshot::new(4, json!(null))),
+            Err(PersistError::Newer {
+                version: 4,
+                current: 3
+            })
+        ));
+
+        let snapshot = Snapshot::new(2, json!([1, 2]));
+        assert_eq!(Snapshot::from_json(snapshot.to_json()).ok(), Some(snapshot));
+        assert!(matches!(
+            Snapshot::from_json(json!({ "state": 1 })),
+            Err(PersistError::Malformed)
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+//! # Pipelines as data
+//!
+//! A [`PipelineSpec`] describes a derived signal as data rather than code: the
+//! name of a source, followed by stages mapping, filtering or debouncing its
+//! values. Specs are (de)serializable with serde, so the end users of an app can
+//! define their own derived metrics, such as in a JSON settings file, without
+//! recompiling it.
+//!
+//! Code cannot be deserialized, so stages refer to functions by name. A
+//! [`Pipelines`] registry holds the sources and the named functions an app
+//! offers, and [`instantiate`](Pipelines::instantiate) builds the signal a spec
+//! describes from them. Every stage passes the named [`Params`] of the spec on
+//! to its function, which lets one function serve many specs, such as a scale
+//! by a configurable factor.
+//!
+//! Values flow through a pipeline as JSON [`Value`]s, so stages of any types
+//! can be chained. A filter stage keeps the last value that passed, and is
+//! `null` until one does.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt, pipeline::{PipelineSpec, Pipelines}};
+//! use serde_json::{Value, json};
+//!
+//! let celsius: Binding<f64> = binding(20.0);
+//! let mut pipelines = Pipelines::new();
+//! pipelines
+//!     .register_source("celsius", celsius.clone().map(Value::from))
+//!     .register_map("linear", |value, params| {
+//!         let number = |value: Option<&Value>| value.and_then(Value::as_f64).unwrap_or(0.0);
+//!         json!(number(Some(&value)) * number(params.get("scale")) + number(params.get("offset")))
+//!     });
+//!
+//! let spec: PipelineSpec = serde_json::from_value(json!({
+//!     "source": "celsius",
+//!     "stages": [{ "stage": "map", "function": "linear", "params": { "scale": 1.8, "offset": 32 } }],
+//! }))
+//! .unwrap();
+//! let fahrenheit = pipelines.instantiate(&spec).unwrap();
+//!
+//! celsius.set(100.0);
+//! assert_eq!(fahrenheit.get(), json!(212.0));
+//! ```
+
+use alloc::{collections::BTreeMap, rc::Rc, string::String, vec::Vec};
+use core::{fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Computed, Signal, SignalExt, filter::filter};
+
+/// The named parameters of a stage, passed on to its function.
+pub type Params = serde_json::Map<String, Value>;
+
+/// A registered map function.
+type MapFn = Rc<dyn Fn(Value, &Params) -> Value>;
+
+/// A registered filter predicate.
+type FilterFn = Rc<dyn Fn(&Value, &Params) -> bool>;
+
+/// A description of a derived signal: a source, and the stages applied to its
+/// values in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineSpec {
+    /// The name of the registered source.
+    pub source: String,
+    /// The stages applied to the values of the source.
+    #[serde(default)]
+    pub stages: Vec<StageSpec>,
+}
+
+/// A single stage of a [`PipelineSpec`].
+///
+/// Serialized with its kind in a `stage` field, such as
+/// `{ "stage": "debounce", "millis": 300 }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum StageSpec {
+    /// Maps every value with a registered map function.
+    Map {
+        /// The name of the function.
+        function: String,
+        /// The parameters passed to the function.
+        #[serde(default)]
+        params: Params,
+    },
+    /// Only follows the values a registered predicate accepts.
+    Filter {
+        /// The name of the predicate.
+        predicate: String,
+        /// The parameters passed to the predicate.
+        #[serde(default)]
+        params: Params,
+    },
+    /// Only emits a value once no other has followed it for `millis`
+    /// milliseconds.
+    Debounce {
+        /// The quiet period, in milliseconds.
+        millis: u64,
+    },
+}
+
+/// An error returned when a spec refers to a name that is not registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PipelineError {
+    /// No source is registered under this name.
+    UnknownSource(String),
+    /// No map function is registered under this name.
+    UnknownMap(String),
+    /// No filter predicate is registered under this name.
+    UnknownFilter(String),
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSource(name) => write!(f, "unknown pipeline source `{name}`"),
+            Self::UnknownMap(name) => write!(f, "unknown pipeline map function `{name}`"),
+            Self::UnknownFilter(name) => write!(f, "unknown pipeline filter `{name}`"),
+        }
+    }
+}
+
+impl core::error::Error for PipelineError {}
+
+/// A registry of the sources and named functions pipelines are built from.
+///
+/// See the [module documentation](self) for how it behaves.
+#[derive(Clone, Default)]
+pub struct Pipelines {
+    sources: BTreeMap<String, Computed<Value>>,
+    maps: BTreeMap<String, MapFn>,
+    filters: BTreeMap<String, FilterFn>,
+}
+
+impl fmt::Debug for Pipelines {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipelines")
+            .field("sources", &self.sources.keys().collect::<Vec<_>>())
+            .field("maps", &self.maps.keys().collect::<Vec<_>>())
+            .field("filters", &self.filters.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Pipelines {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `source` under `name`, replacing any source of that name.
+    pub fn register_source(
+        &mut self,
+        name: impl Into<String>,
+        source: impl Signal<Output = Value>,
+    ) -> &mut Self {
+        self.sources.insert(name.into(), source.computed());
+        self
+    }
+
+    /// Registers a map function under `name`, called with each value and the
+    /// parameters of its stage.
+    pub fn register_map(
+        &mut self,
+        name: impl Into<String>,
+        f: impl Fn(Value, &Params) -> Value + 'static,
+    ) -> &mut Self {
+        self.maps.insert(name.into(), Rc::new(f));
+        self
+    }
+
+    /// Registers a filter predicate under `name`, called with each value and
+    /// the parameters of its stage.
+    pub fn register_filter(
+        &mut self,
+        name: impl Into<String>,
+        predicate: impl Fn(&Value, &Params) -> bool + 'static,
+    ) -> &mut Self {
+        self.filters.insert(name.into(), Rc::new(predicate));
+        self
+    }
+
+    /// Builds the signal described by `spec`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the spec refers to a source or function that is not
+    /// registered.
+    pub fn instantiate(&self, spec: &PipelineSpec) -> Result<Computed<Value>, PipelineError> {
+        let mut signal = self
+            .sources
+            .get(&spec.source)
+            .cloned()
+            .ok_or_else(|| PipelineError::UnknownSource(spec.source.clone()))?;
+        for stage in &spec.stages {
+            signal = match stage {
+                StageSpec::Map { function, params } => {
+                    let f = self
+                        .maps
+                        .get(function)
+                        .cloned()
+                        .ok_or_else(|| PipelineError::UnknownMap(function.clone()))?;
+                    let params = params.clone();
+                    signal.map(move |value| f(value, &params)).computed()
+                }
+                StageSpec::Filter { predicate, params } => {
+                    let f = self
+                        .filters
+                        .get(predicate)
+                        .cloned()
+                        .ok_or_else(|| PipelineError::UnknownFilter(predicate.clone()))?;
+                    let params = params.clone();
+                    filter(signal, move |value: &Value| f(value, &params))
+                        .map(Option::unwrap_or_default)
+                        .computed()
+                }
+                StageSpec::Debounce { millis } => {
+                    signal.debounce(Duration::from_millis(*millis)).computed()
+                }
+            };
+        }
+        Ok(signal)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+    use serde_json::json;
+
+    #[test]
+    fn test_specs_are_built_from_registered_names() {
+        let load: Binding<i64> = binding(5i64);
+        let mut pipelines = Pipelines::new();
+        pipelines
+            .register_source("load", load.clone().map(Value::from))
+            .register_map("scale", |value, params| {
+                json!(value.as_i64().unwrap_or(0) * params["by"].as_i64().unwrap_or(1))
+            })
+            .register_filter("above", |value, params| {
+                value.as_i64() > params["min"].as_i64()
+            });
+
+        let spec: PipelineSpec = serde_json::from_value(json!({
+            "source": "load",
+            "stages": [
+                { "stage": "map", "function": "scale", "params": { "by": 10 } },
+                { "stage": "filter", "predicate": "above", "params": { "min": 60 } },
+            ],
+        }))
+        .unwrap();
+        assert_eq!(
+            serde_json::from_value::<PipelineSpec>(serde_json::to_value(&spec).unwrap()).unwrap(),
+            spec
+        );
+
+        let high_load = pipelines.instantiate(&spec).unwrap();
+        assert_eq!(high_load.get(), Value::Null);
+        load.set(7);
+        load.set(3);
+        assert_eq!(high_load.get(), json!(70));
+
+        let unknown = PipelineSpec {
+            source: "load".into(),
+            stages: alloc::vec![StageSpec::Map {
+                function: "square".into(),
+                params: Params::new(),
+            }],
+        };
+        assert_eq!(
+            pipelines.instantiate(&unknown).unwrap_err(),
+            PipelineError::UnknownMap("square".into())
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! # Process-level event sources
+//!
+//! This module exposes process-level events as signals, so CLI tools and daemons
+//! can wire shutdown and reload handling into the same graph as their state.
+//!
+//! - [`OsSignal`]: counts deliveries of operating system signals such as `SIGHUP`
+//!   or `SIGINT` (unix only). Use [`reload_requests`] and [`ctrl_c`] for the
+//!   common cases.
+//! - [`EnvVar`]: reflects the value of an environment variable, polled at a fixed
+//!   interval.
+//!
+//! All sources update on a local executor task, which stops once every clone of
+//! the source has been dropped.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use core::time::Duration;
+//! use nami::{Signal, process};
+//!
+//! let reload = process::reload_requests().expect("failed to register SIGHUP");
+//! let _guard = reload.watch(|ctx| {
+//!     println!("reload requested ({} so far)", ctx.value);
+//! });
+//!
+//! let log_level = process::env_var("LOG_LEVEL", Duration::from_secs(1));
+//! let _guard = log_level.watch(|ctx| {
+//!     println!("LOG_LEVEL is now {:?}", ctx.value);
+//! });
+//! ```
+
+use alloc::{rc::Rc, string::String};
+use async_io::Timer;
+use core::{any::Any, fmt::Debug, time::Duration};
+use executor_core::{DefaultExecutor, LocalExecutor};
+
+use crate::{Container, CustomBinding, Signal, watcher::Context};
+
+#[cfg(unix)]
+pub use os::{OsSignal, ctrl_c, reload_requests};
+
+#[cfg(unix)]
+mod os {
+    use super::{Any, Container, Context, CustomBinding, Debug, Rc, Signal};
+    use alloc::boxed::Box;
+    use core::ffi::c_int;
+    use executor_core::{DefaultExecutor, LocalExecutor};
+    use signal_hook::{
+        consts::{SIGHUP, SIGINT},
+        iterator::{Handle, Signals},
+    };
+    use std::io;
+
+    /// A signal counting how many times the given operating system signals were received.
+    ///
+    /// The value starts at `0` and increments on every delivery, notifying watchers
+    /// each time. Registering a handler replaces the default behavior of the signal,
+    /// so `SIGINT` no longer terminates the process once it is observed.
+    #[derive(Clone)]
+    pub struct OsSignal {
+        container: Container<u64>,
+        _listener: Rc<Listener>,
+    }
+
+    impl Debug for OsSignal {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("OsSignal")
+                .field("count", &self.container.get())
+                .finish_non_exhaustive()
+        }
+    }
+
+    /// Keeps the listener thread and forwarding task alive, closing both on drop.
+    struct Listener {
+        handle: Handle,
+        _task: Box<dyn Any>,
+    }
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            self.handle.close();
+        }
+    }
+
+    impl OsSignal {
+        /// Listens for `signals` using the default executor.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the signal handlers cannot be registered.
+        pub fn new(signals: &[c_int]) -> io::Result<Self> {
+            Self::with_executor(signals, DefaultExecutor)
+        }
+
+        /// Listens for `signals`, forwarding deliveries on the given executor.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the signal handlers cannot be registered.
+        pub fn with_executor<E>(signals: &[c_int], executor: E) -> io::Result<Self>
+        where
+            E: LocalExecutor + 'static,
+        {
+            let mut signals = Signals::new(signals)?;
+            let handle = signals.handle();
+            let (sender, receiver) = async_channel::unbounded::<c_int>();
+
+            std::thread::spawn(move || {
+                for signal in &mut signals {
+                    if sender.send_blocking(signal).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            let container = Container::new(0);
+            let task = {
+                let container = container.clone();
+                executor.spawn(async move {
+                    while receiver.recv().await.is_ok() {
+                        container.set(container.get() + 1);
+                    }
+                })
+            };
+
+            Ok(Self {
+                container,
+                _listener: Rc::new(Listener {
+                    handle,
+                    _task: Box::new(task),
+                }),
+            })
+        }
+    }
+
+    impl Signal for OsSignal {
+        type Output = u64;
+        type Guard = <Container<u64> as Signal>::Guard;
+
+        /// Returns how many times the signals were received so far.
+        fn get(&self) -> Self::Output {
+            self.container.get()
+        }
+
+        /// Watches for deliveries of the signals.
+        fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+            self.container.watch(watcher)
+        }
+    }
+
+    /// Listens for `SIGHUP`, the conventional request to reload configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signal handler cannot be registered.
+    pub fn reload_requests() -> io::Result<OsSignal> {
+        OsSignal::new(&[SIGHUP])
+    }
+
+    /// Listens for `SIGINT`, sent when the user presses ctrl-c.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signal handler cannot be registered.
+    pub fn ctrl_c() -> io::Result<OsSignal> {
+        OsSignal::new(&[SIGINT])
+    }
+}
+
+/// A signal reflecting the value of an environment variable.
+///
+/// The variable is read when the signal is created and then polled at a fixed
+/// interval. Watchers are only notified when the value actually changes. The value
+/// is `None` while the variable is unset or not valid unicode.
+#[derive(Clone)]
+pub struct EnvVar {
+    container: Container<Option<String>>,
+    _task: Rc<dyn Any>,
+}
+
+impl Debug for EnvVar {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EnvVar")
+            .field("value", &self.container.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl EnvVar {
+    /// Polls the variable `name` every `interval` using the default executor.
+    pub fn new(name: impl Into<String>, interval: Duration) -> Self {
+        Self::with_executor(name, interval, DefaultExecutor)
+    }
+
+    /// Polls the variable `name` every `interval` on the given executor.
+    pub fn with_executor<E>(name: impl Into<String>, interval: Duration, executor: E) -> Self
+    where
+        E: LocalExecutor + 'static,
+    {
+        let name = name.into();
+        let container = Container::new(std::env::var(&name).ok());
+        let task = {
+            let container = container.clone();
+            executor.spawn(async move {
+                loop {
+                    Timer::after(interval).await;
+                    let value = std::env::var(&name).ok();
+                    if value != container.get() {
+                        container.set(value);
+                    }
+                }
+            })
+        };
+
+        Self {
+            container,
+            _task: Rc::new(task),
+        }
+    }
+}
+
+impl Signal for EnvVar {
+    type Output = Option<String>;
+    type Guard = <Container<Option<String>> as Signal>::Guard;
+
+    /// Returns the most recently observed value of the variable.
+    fn get(&self) -> Self::Output {
+        self.container.get()
+    }
+
+    /// Watches for changes of the variable.
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.container.watch(watcher)
+    }
+}
+
+/// Polls the environment variable `name` every `interval`.
+///
+/// This is a convenience function equivalent to `EnvVar::new(name, interval)`.
+pub fn env_var(name: impl Into<String>, interval: Duration) -> EnvVar {
+    EnvVar::new(name, interval)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_executor::Manual;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    /// Runs `executor` until `done` returns `true`, giving up after a second.
+    fn run_until(executor: &Manual, done: impl Fn() -> bool) {
+        for _ in 0..100 {
+            executor.run();
+            if done() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("timed out");
+    }
+
+    #[test]
+    fn test_env_var_follows_changes() {
+        const NAME: &str = "NAMI_TEST_ENV_VAR_FOLLOWS_CHANGES";
+        // SAFETY: no other code reads or writes this variable, and the
+        // standard library serializes its own accesses to the environment.
+        unsafe { std::env::set_var(NAME, "a") };
+        let executor = Manual::default();
+        let var = EnvVar::with_executor(NAME, Duration::from_millis(1), executor.clone());
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            var.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        assert_eq!(var.get().as_deref(), Some("a"));
+
+        std::thread::sleep(Duration::from_millis(5));
+        executor.run();
+        assert!(seen.borrow().is_empty());
+
+        // SAFETY: as above.
+        unsafe { std::env::set_var(NAME, "b") };
+        run_until(&executor, || var.get().as_deref() == Some("b"));
+        // SAFETY: as above.
+        unsafe { std::env::remove_var(NAME) };
+        run_until(&executor, || var.get().is_none());
+        assert_eq!(*seen.borrow(), [Some("b".into()), None]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_os_signal_counts_deliveries() {
+        use signal_hook::{consts::SIGUSR1, low_level::raise};
+
+        let executor = Manual::default();
+        let usr1 = OsSignal::with_executor(&[SIGUSR1], executor.clone()).unwrap();
+        assert_eq!(usr1.get(), 0);
+
+        raise(SIGUSR1).unwrap();
+        run_until(&executor, || usr1.get() == 1);
+        raise(SIGUSR1).unwrap();
+        run_until(&executor, || usr1.get() == 2);
+    }
+}
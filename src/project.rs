@@ -1,4 +1,7 @@
-use crate::Binding;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+
+use crate::{Binding, NodeId, Signal, binding::CustomBinding, watcher::Context};
 
 /// Trait for projecting bindings into their component parts.
 ///
@@ -259,3 +262,131 @@ impl<T: Project> Binding<T> {
         T::project(self)
     }
 }
+
+impl<T: Clone + 'static> Binding<T> {
+    /// Returns a binding to the part of this binding's value selected by `get`
+    /// and `get_mut`, such as a field of a struct.
+    ///
+    /// Reading the lens returns the part, and setting it writes the part into
+    /// this binding, notifying its watchers unless the part is unchanged.
+    /// Watchers of the lens are only notified when the part changes, so a
+    /// lens on one field ignores writes to the others.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nami::{Binding, Signal, binding};
+    ///
+    /// #[derive(Clone)]
+    /// struct Config {
+    ///     theme: String,
+    ///     font_size: u32,
+    /// }
+    ///
+    /// let config: Binding<Config> = binding(Config {
+    ///     theme: "light".to_string(),
+    ///     font_size: 12,
+    /// });
+    /// let theme = config.lens(|config| &config.theme, |config| &mut config.theme);
+    /// let _guard = theme.watch(|ctx| println!("theme is now {}", ctx.value));
+    ///
+    /// config.handle(|config| config.font_size = 14); // `theme` is not notified.
+    /// theme.set("dark");
+    /// assert_eq!(config.get().theme, "dark");
+    /// ```
+    #[must_use]
+    pub fn lens<U: Clone + PartialEq + 'static>(
+        &self,
+        get: impl Fn(&T) -> &U + 'static,
+        get_mut: impl Fn(&mut T) -> &mut U + 'static,
+    ) -> Binding<U> {
+        Binding::custom(Lens {
+            binding: self.clone(),
+            get: Rc::new(get),
+            get_mut: Rc::new(get_mut),
+            id: NodeId::next(),
+        })
+    }
+}
+
+/// A binding to a part of another binding's value, created by
+/// [`Binding::lens`].
+struct Lens<T: 'static, U> {
+    binding: Binding<T>,
+    get: Rc<dyn Fn(&T) -> &U>,
+    get_mut: Rc<dyn Fn(&mut T) -> &mut U>,
+    id: NodeId,
+}
+
+impl<T, U> Clone for Lens<T, U> {
+    fn clone(&self) -> Self {
+        Self {
+            binding: self.binding.clone(),
+            get: self.get.clone(),
+            get_mut: self.get_mut.clone(),
+            id: self.id,
+        }
+    }
+}
+
+impl<T: Clone + 'static, U: Clone + PartialEq + 'static> Signal for Lens<T, U> {
+    type Output = U;
+    type Guard = <Binding<T> as Signal>::Guard;
+
+    fn get(&self) -> U {
+        (self.get)(&self.binding.get()).clone()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<U>) + 'static) -> Self::Guard {
+        let get = self.get.clone();
+        let last = RefCell::new(self.get());
+        self.binding.watch(move |context| {
+            let Context { value, metadata } = context;
+            let part = get(&value);
+            if *last.borrow() != *part {
+                last.replace(part.clone());
+                watcher(Context::new(part.clone(), metadata));
+            }
+        })
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        Some(self.id)
+    }
+}
+
+impl<T: Clone + 'static, U: Clone + PartialEq + 'static> CustomBinding for Lens<T, U> {
+    fn set(&self, value: U) {
+        if self.get() != value {
+            self.binding
+                .handle(|parent| *(self.get_mut)(parent) = value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_lens_notifies_only_when_its_part_changes() {
+        let pair: Binding<(i32, i32)> = binding((1, 2));
+        let first = pair.lens(|pair| &pair.0, |pair| &mut pair.0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            first.watch(move |ctx| seen.borrow_mut().push(ctx.value))
+        };
+
+        pair.set((1, 3));
+        first.set(5);
+        first.set(5);
+        pair.set((7, 3));
+
+        assert_eq!(*seen.borrow(), [5, 7]);
+        assert_eq!(pair.get(), (7, 3));
+        assert_eq!(first.get(), 7);
+    }
+}
@@ -0,0 +1,385 @@
+//! # Cooperative polling driver
+//!
+//! Without an async runtime, for example in a superloop or an RTOS task, a
+//! [`Pump`] drives the work that would otherwise be left to an executor. Call
+//! [`Pump::poll`] from the main loop with the current monotonic time. Each call
+//! does exactly the following, in order:
+//!
+//! 1. Applies the values queued through [`Sender`]s since the last poll to their
+//!    bindings, in the order they were sent, and the latest values stored in
+//!    [`Slots`], in key order. Senders and slots are `Send`, so values can come
+//!    from other threads or interrupt contexts. Sources are handled in the order
+//!    they were created.
+//! 2. Runs the callbacks scheduled with [`Pump::after`] that are due, earliest
+//!    first. Callbacks scheduled while polling are run by a later poll at the
+//!    earliest.
+//!
+//! Slots suit high-rate producers, such as encoder counts or mouse deltas: a
+//! value sent for a key replaces any value not applied yet, so memory and work
+//! per poll are bounded by the number of keys rather than the send rate.
+//!
+//! There is no separate flush: every write propagates to its watchers
+//! synchronously, so when `poll` returns, all derived values and watchers have
+//! caught up with the writes it applied. Nothing happens between polls, and
+//! [`Pump::next_deadline`] tells how long the loop may sleep.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use nami::{binding, Binding, Signal, pump::Pump};
+//!
+//! let temperature: Binding<f32> = binding(0.0);
+//! let pump = Pump::new();
+//! let sender = pump.sender(&temperature);
+//!
+//! std::thread::spawn(move || sender.send(21.5)).join().unwrap().unwrap();
+//! pump.after(Duration::from_millis(10), || println!("calibrated"));
+//!
+//! let work = pump.poll(Duration::from_millis(5));
+//! assert_eq!((work.writes, work.timers), (1, 0));
+//! assert_eq!(temperature.get(), 21.5);
+//! assert_eq!(pump.next_deadline(), Some(Duration::from_millis(10)));
+//! ```
+
+use alloc::{boxed::Box, collections::BTreeMap, rc::Rc, vec::Vec};
+use core::{cell::RefCell, fmt, time::Duration};
+
+use async_channel::{Receiver, unbounded};
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+use crate::Binding;
+
+/// Applies the values queued for one binding, returning how many it applied.
+type Source = Rc<dyn Fn() -> usize>;
+
+/// A callback scheduled with [`Pump::after`].
+type Callback = Box<dyn FnOnce()>;
+
+/// A driver applying queued writes and running timers when polled.
+///
+/// See the [module documentation](self) for what each poll does.
+#[derive(Default)]
+pub struct Pump {
+    inner: RefCell<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    sources: Vec<Source>,
+    /// Callbacks keyed by their deadline, and a sequence number keeping
+    /// callbacks with the same deadline in order.
+    timers: BTreeMap<(Duration, u64), Callback>,
+    sequence: u64,
+    now: Duration,
+}
+
+impl fmt::Debug for Pump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.borrow();
+        f.debug_struct("Pump")
+            .field("sources", &inner.sources.len())
+            .field("timers", &inner.timers.len())
+            .field("now", &inner.now)
+            .finish()
+    }
+}
+
+/// The work done by one [`Pump::poll`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Work {
+    /// The number of queued values applied to bindings.
+    pub writes: usize,
+    /// The number of timer callbacks run.
+    pub timers: usize,
+}
+
+impl Pump {
+    /// Creates a pump without senders or timers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a sender queueing values for `binding`, which are applied by the
+    /// next poll.
+    pub fn sender<T: Send + 'static>(&self, binding: &Binding<T>) -> Sender<T> {
+        let (sender, receiver) = unbounded();
+        let binding = binding.clone();
+        self.add_source(move || drain(&receiver, |value| binding.set(value)));
+        Sender(sender)
+    }
+
+    /// Returns slots storing the latest value per key, which the next poll
+    /// passes to `apply` in key order.
+    ///
+    /// Requires `std`.
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use nami::{binding, Binding, Signal, pump::Pump};
+    ///
+    /// let position: Binding<(i32, i32)> = binding((0, 0));
+    /// let pump = Pump::new();
+    /// let axes = {
+    ///     let position = position.clone();
+    ///     pump.slots(move |axis: usize, value: i32| {
+    ///         position.handle(|position| *[&mut position.0, &mut position.1][axis] = value);
+    ///     })
+    /// };
+    ///
+    /// for x in 0..100 {
+    ///     axes.send(0, x).unwrap();
+    /// }
+    /// axes.send(1, -5).unwrap();
+    /// assert_eq!(pump.poll(Duration::ZERO).writes, 2);
+    /// assert_eq!(position.get(), (99, -5));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn slots<K, T>(&self, apply: impl Fn(K, T) + 'static) -> Slots<K, T>
+    where
+        K: Ord + Send + 'static,
+        T: Send + 'static,
+    {
+        let values: Arc<Mutex<BTreeMap<K, T>>> = Arc::default();
+        let slots = Slots(Arc::downgrade(&values));
+        self.add_source(move || {
+            let latest = core::mem::take(&mut *lock(&values));
+            let count = latest.len();
+            for (key, value) in latest {
+                apply(key, value);
+            }
+            count
+        });
+        slots
+    }
+
+    /// Returns a slot storing the latest value for `binding`, which the next
+    /// poll applies to it.
+    ///
+    /// Requires `std`.
+    #[cfg(feature = "std")]
+    pub fn latest<T: Send + 'static>(&self, binding: &Binding<T>) -> Slots<(), T> {
+        let binding = binding.clone();
+        self.slots(move |(), value| binding.set(value))
+    }
+
+    fn add_source(&self, source: impl Fn() -> usize + 'static) {
+        self.inner.borrow_mut().sources.push(Rc::new(source));
+    }
+
+    /// Schedules `callback` to run by the first poll at least `delay` after the
+    /// time of the last poll.
+    pub fn after(&self, delay: Duration, callback: impl FnOnce() + 'static) {
+        let mut inner = self.inner.borrow_mut();
+        let key = (inner.now.saturating_add(delay), inner.sequence);
+        inner.sequence += 1;
+        inner.timers.insert(key, Box::new(callback));
+    }
+
+    /// Returns the deadline of the earliest scheduled callback, if any.
+    #[must_use]
+    pub fn next_deadline(&self) -> Option<Duration> {
+        self.inner
+            .borrow()
+            .timers
+            .first_key_value()
+            .map(|((deadline, _), _)| *deadline)
+    }
+
+    /// Applies queued writes and runs the callbacks due at `now`, the current
+    /// monotonic time.
+    ///
+    /// `now` never moves backwards: an earlier time than the last poll's is
+    /// treated as the same time.
+    pub fn poll(&self, now: Duration) -> Work {
+        let sources = {
+            let mut inner = self.inner.borrow_mut();
+            inner.now = inner.now.max(now);
+            inner.sources.clone()
+        };
+        let writes = sources.iter().map(|source| source()).sum();
+
+        let due = {
+            let mut inner = self.inner.borrow_mut();
+            let now = inner.now;
+            let pending = inner.timers.split_off(&(now, u64::MAX));
+            core::mem::replace(&mut inner.timers, pending)
+        };
+        let timers = due.len();
+        for callback in due.into_values() {
+            callback();
+        }
+
+        Work { writes, timers }
+    }
+}
+
+/// Applies every value queued in `receiver`, returning how many there were.
+fn drain<T>(receiver: &Receiver<T>, apply: impl Fn(T)) -> usize {
+    let mut count = 0;
+    while let Ok(value) = receiver.try_recv() {
+        apply(value);
+        count += 1;
+    }
+    count
+}
+
+/// An error returned when sending to a [`Pump`] that was dropped.
+///
+/// It hands back the value that was not sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disconnected<T>(pub T);
+
+impl<T> fmt::Display for Disconnected<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the pump was dropped")
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for Disconnected<T> {}
+
+/// A handle queueing values for a binding of a [`Pump`], created by
+/// [`Pump::sender`].
+///
+/// Senders can be cloned and sent to other threads.
+#[derive(Debug, Clone)]
+pub struct Sender<T>(async_channel::Sender<T>);
+
+impl<T> Sender<T> {
+    /// Queues `value` to be applied by the next poll.
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back as [`Disconnected`] if the pump was dropped.
+    pub fn send(&self, value: T) -> Result<(), Disconnected<T>> {
+        self.0
+            .try_send(value)
+            .map_err(|error| Disconnected(error.into_inner()))
+    }
+}
+
+/// Latest-wins slots of a [`Pump`], created by [`Pump::slots`] and
+/// [`Pump::latest`].
+///
+/// Slots can be cloned and sent to other threads.
+#[cfg(feature = "std")]
+pub struct Slots<K, T>(Weak<Mutex<BTreeMap<K, T>>>);
+
+#[cfg(feature = "std")]
+impl<K, T> Clone for Slots<K, T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, T> fmt::Debug for Slots<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Slots").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Ord, T> Slots<K, T> {
+    /// Stores `value` for `key`, replacing any value for it that the pump has
+    /// not applied yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns the value back as [`Disconnected`] if the pump was dropped.
+    pub fn send(&self, key: K, value: T) -> Result<(), Disconnected<T>> {
+        let Some(values) = self.0.upgrade() else {
+            return Err(Disconnected(value));
+        };
+        lock(&values).insert(key, value);
+        Ok(())
+    }
+}
+
+/// Locks `mutex`, ignoring poisoning: a panic while holding the lock cannot
+/// leave the map of latest values inconsistent.
+#[cfg(feature = "std")]
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_pump_applies_writes_then_timers() {
+        let pump = Rc::new(Pump::new());
+        let count: Binding<i32> = binding(0);
+        let sender = pump.sender(&count);
+        let fired = Rc::new(RefCell::new(Vec::new()));
+
+        for (delay, name) in [(20, "late"), (10, "first"), (10, "second")] {
+            let (fired, count) = (fired.clone(), count.clone());
+            pump.after(Duration::from_millis(delay), move || {
+                fired.borrow_mut().push((name, count.get()));
+            });
+        }
+        let rescheduled = Rc::new(Cell::new(false));
+        {
+            let (pump, inner, rescheduled) = (pump.clone(), pump.clone(), rescheduled.clone());
+            pump.after(Duration::ZERO, move || {
+                inner.after(Duration::ZERO, move || rescheduled.set(true));
+            });
+        }
+
+        assert_eq!(sender.send(1), Ok(()));
+        assert_eq!(sender.send(2), Ok(()));
+        let work = pump.poll(Duration::from_millis(10));
+        assert_eq!((work.writes, work.timers), (2, 3));
+        assert_eq!(*fired.borrow(), [("first", 2), ("second", 2)]);
+        assert!(!rescheduled.get());
+
+        assert_eq!(pump.poll(Duration::from_millis(5)).timers, 1);
+        assert!(rescheduled.get());
+        assert_eq!(pump.next_deadline(), Some(Duration::from_millis(20)));
+
+        drop(pump);
+        assert_eq!(sender.send(3), Err(Disconnected(3)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_slots_keep_latest_value_per_key() {
+        let pump = Pump::new();
+        let count: Binding<i32> = binding(0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let latest = pump.latest(&count);
+        let slots = {
+            let seen = seen.clone();
+            pump.slots(move |key: &'static str, value: i32| seen.borrow_mut().push((key, value)))
+        };
+
+        let producer = slots.clone();
+        std::thread::spawn(move || {
+            for value in 0..1000 {
+                assert_eq!(producer.send("b", value), Ok(()));
+            }
+        })
+        .join()
+        .unwrap_or_else(|_| panic!("producer panicked"));
+        assert_eq!(slots.send("a", 1), Ok(()));
+        assert_eq!(latest.send((), 5), Ok(()));
+        assert_eq!(latest.send((), 6), Ok(()));
+
+        assert_eq!(pump.poll(Duration::ZERO).writes, 3);
+        assert_eq!(*seen.borrow(), [("a", 1), ("b", 999)]);
+        assert_eq!(count.get(), 6);
+        assert_eq!(pump.poll(Duration::ZERO).writes, 0);
+
+        drop(pump);
+        assert_eq!(latest.send((), 7), Err(Disconnected(7)));
+    }
+}
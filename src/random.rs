@@ -0,0 +1,251 @@
+//! # Random sources
+//!
+//! This module provides signals of random values that are resampled on every
+//! tick of a clock signal, so simulations and demos can keep their noise sources
+//! inside the reactive graph.
+//!
+//! Sources are driven by a small seedable generator, so the same seed always
+//! produces the same sequence of values, which keeps tests deterministic.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, random::{Uniform, random_source}};
+//!
+//! let frame: Binding<u64> = binding(0u64);
+//! let jitter = random_source(42, Uniform::new(-1.0, 1.0), &frame);
+//!
+//! let first = jitter.get();
+//! frame.set(1u64);
+//! assert_ne!(jitter.get(), first);
+//! assert!((-1.0..1.0).contains(&jitter.get()));
+//! ```
+
+use alloc::rc::Rc;
+use core::{any::Any, cell::RefCell, fmt, ops::Range};
+
+use crate::{
+    Container, CustomBinding, NodeId, Signal,
+    watcher::{BoxWatcherGuard, Context},
+};
+
+/// A seedable pseudo-random number generator.
+///
+/// This is the `SplitMix64` generator: it is fast and statistically sound for
+/// simulations, but not suitable for cryptography.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a generator producing the sequence determined by `seed`.
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Returns the next 64 random bits.
+    pub const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number uniformly distributed in `[0, 1)`.
+    #[allow(clippy::cast_precision_loss)] // 53 bits fit in the mantissa.
+    pub const fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns an integer uniformly distributed in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "cannot sample an empty range");
+        let span = range.end - range.start;
+        // Rejects the top values that would make some results more likely.
+        let zone = u64::MAX - (u64::MAX - span + 1) % span;
+        loop {
+            let value = self.next_u64();
+            if value <= zone {
+                return range.start + value % span;
+            }
+        }
+    }
+}
+
+/// A distribution of random values of type `T`.
+///
+/// Closures taking the generator are distributions as well.
+pub trait Distribution<T> {
+    /// Draws a value from this distribution.
+    fn sample(&self, rng: &mut Rng) -> T;
+}
+
+impl<T, F: Fn(&mut Rng) -> T> Distribution<T> for F {
+    fn sample(&self, rng: &mut Rng) -> T {
+        self(rng)
+    }
+}
+
+/// The uniform distribution of numbers in `[low, high)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uniform {
+    low: f64,
+    high: f64,
+}
+
+impl Uniform {
+    /// Creates the uniform distribution of numbers in `[low, high)`.
+    #[must_use]
+    pub const fn new(low: f64, high: f64) -> Self {
+        Self { low, high }
+    }
+}
+
+impl Distribution<f64> for Uniform {
+    #[allow(clippy::suboptimal_flops)] // `mul_add` needs `std`.
+    fn sample(&self, rng: &mut Rng) -> f64 {
+        self.low + rng.next_f64() * (self.high - self.low)
+    }
+}
+
+/// The normal distribution with the given mean and standard deviation.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    std_dev: f64,
+}
+
+#[cfg(feature = "std")]
+impl Normal {
+    /// Creates the normal distribution with the given mean and standard deviation.
+    #[must_use]
+    pub const fn new(mean: f64, std_dev: f64) -> Self {
+        Self { mean, std_dev }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Distribution<f64> for Normal {
+    fn sample(&self, rng: &mut Rng) -> f64 {
+        // Box-Muller transform, with the first sample in `(0, 1]` to avoid `ln(0)`.
+        let u = 1.0 - rng.next_f64();
+        let v = rng.next_f64();
+        let z = (-2.0 * u.ln()).sqrt() * (core::f64::consts::TAU * v).cos();
+        self.std_dev.mul_add(z, self.mean)
+    }
+}
+
+/// A signal of random values, resampled on every tick of a clock signal.
+///
+/// Created by [`random_source`]. All clones share the same value and generator.
+#[derive(Clone)]
+pub struct RandomSource<T: Clone + 'static> {
+    container: Container<T>,
+    _tick: Rc<dyn Any>,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for RandomSource<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RandomSource")
+            .field("value", &self.container.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + 'static> Signal for RandomSource<T> {
+    type Output = T;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> Self::Output {
+        self.container.get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.container.watch(watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.container.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.container.watcher_count()
+    }
+}
+
+/// Creates a signal of values drawn from `distribution`, drawing a new value
+/// every time `tick` notifies its watchers.
+///
+/// The first value is drawn immediately. The values are determined by `seed`,
+/// so two sources with the same seed and distribution produce the same sequence.
+/// The source keeps watching `tick` for as long as any clone of it is alive.
+pub fn random_source<S, D, T>(seed: u64, distribution: D, tick: &S) -> RandomSource<T>
+where
+    S: Signal,
+    D: Distribution<T> + 'static,
+    T: Clone + 'static,
+{
+    let mut rng = Rng::new(seed);
+    let container = Container::new(distribution.sample(&mut rng));
+    let rng = RefCell::new(rng);
+    let guard = {
+        let container = container.clone();
+        tick.watch(move |_| {
+            let value = distribution.sample(&mut rng.borrow_mut());
+            container.set(value);
+        })
+    };
+    RandomSource {
+        container,
+        _tick: Rc::new(guard),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_random_source_is_deterministic() {
+        let tick: Binding<i32> = binding(0);
+        let dice = |seed| random_source(seed, |rng: &mut Rng| rng.range(1..7), &tick);
+        let (a, b, c) = (dice(7), dice(7), dice(8));
+
+        let (mut rolls, mut others) = (Vec::new(), Vec::new());
+        for frame in 1..=20 {
+            rolls.push(a.get());
+            others.push(c.get());
+            assert_eq!(a.get(), b.get());
+            tick.set(frame);
+        }
+        assert!(rolls.iter().all(|roll| (1..7).contains(roll)));
+        assert!(rolls.windows(2).any(|pair| pair[0] != pair[1]));
+        assert_ne!(rolls, others);
+
+        drop((a, b, c));
+        tick.set(21);
+        assert_eq!(tick.watcher_count(), Some(0));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_normal_distribution_moments() {
+        let mut rng = Rng::new(1);
+        let normal = Normal::new(10.0, 2.0);
+        let samples: Vec<f64> = (0..10_000).map(|_| normal.sample(&mut rng)).collect();
+        #[allow(clippy::cast_precision_loss)]
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / count;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / count;
+        assert!((mean - 10.0).abs() < 0.1);
+        assert!((variance.sqrt() - 2.0).abs() < 0.1);
+    }
+}
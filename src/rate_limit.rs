@@ -0,0 +1,209 @@
+//! # Rate limiting
+//!
+//! A [`TokenBucket`] limits how often an action may run, such as calls to an
+//! API triggered by the user. The bucket holds up to `burst` tokens and gains
+//! `rate` tokens per second, advanced by a clock signal of elapsed time like
+//! the one driving [animations](crate::animation). Each action takes a token
+//! with [`try_acquire`](TokenBucket::try_acquire), and is refused while the
+//! bucket is empty.
+//!
+//! The rate and burst are signals, so the limit can follow the graph, such as
+//! a quota fetched from the server. [`available`](TokenBucket::available) is a
+//! signal of the whole tokens left, for disabling a button while it is zero.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use nami::{binding, Binding, Signal, rate_limit::TokenBucket};
+//!
+//! let per_second: Binding<f64> = binding(1.0);
+//! let burst: Binding<u32> = binding(2u32);
+//! let clock: Binding<Duration> = binding(Duration::ZERO);
+//! let bucket = TokenBucket::new(per_second, burst, clock.clone());
+//! let available = bucket.available();
+//!
+//! assert!(bucket.try_acquire());
+//! assert!(bucket.try_acquire());
+//! assert!(!bucket.try_acquire());
+//! assert_eq!(available.get(), 0);
+//!
+//! clock.set(Duration::from_millis(1500));
+//! assert_eq!(available.get(), 1);
+//! assert!(bucket.try_acquire());
+//! ```
+
+use alloc::rc::Rc;
+use core::{any::Any, cell::RefCell, fmt, time::Duration};
+
+use crate::{Computed, Container, CustomBinding, Signal, SignalExt, watcher::Context};
+
+/// The tokens of a bucket, as of the last refill.
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    /// Tokens gained per second.
+    rate: f64,
+    burst: u32,
+    /// The clock time of the last refill.
+    time: Duration,
+}
+
+impl Bucket {
+    /// Adds the tokens gained from the last refill until `now`.
+    fn refill(&mut self, now: Duration) {
+        let elapsed = now.saturating_sub(self.time);
+        self.time = self.time.max(now);
+        // A negative or NaN rate gains nothing.
+        let gained = elapsed.as_secs_f64() * self.rate.max(0.0);
+        self.tokens = (self.tokens + gained).min(f64::from(self.burst));
+    }
+
+    /// Returns the number of whole tokens.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // At most the burst.
+    const fn available(&self) -> u32 {
+        self.tokens.max(0.0).floor() as u32
+    }
+}
+
+/// A rate limiter holding up to a burst of tokens, refilled at a rate over
+/// the time of a clock signal.
+///
+/// Created by [`TokenBucket::new`]. All clones share the same tokens. See the
+/// [module documentation](self) for how it behaves.
+#[derive(Clone)]
+pub struct TokenBucket {
+    bucket: Rc<RefCell<Bucket>>,
+    available: Container<u32>,
+    now: Rc<dyn Fn() -> Duration>,
+    _guard: Rc<dyn Any>,
+}
+
+impl fmt::Debug for TokenBucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenBucket")
+            .field("bucket", &self.bucket.borrow())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenBucket {
+    /// Creates a full bucket holding up to `burst` tokens and gaining `rate`
+    /// tokens per second of `clock`, a signal of elapsed time.
+    ///
+    /// A lower burst discards the tokens above it, while a new rate applies
+    /// from the current clock time on.
+    pub fn new<R, B, C>(rate: R, burst: B, clock: C) -> Self
+    where
+        R: Signal<Output = f64>,
+        B: Signal<Output = u32>,
+        C: Signal<Output = Duration>,
+    {
+        let bucket = Rc::new(RefCell::new(Bucket {
+            tokens: f64::from(burst.get()),
+            rate: rate.get(),
+            burst: burst.get(),
+            time: clock.get(),
+        }));
+        let available = Container::new(burst.get());
+
+        let clock_guard = {
+            let (bucket, available) = (bucket.clone(), available.clone());
+            clock.watch(move |context: Context<Duration>| {
+                bucket.borrow_mut().refill(context.value);
+                publish(&bucket, &available);
+            })
+        };
+        let rate_guard = {
+            let (bucket, clock) = (bucket.clone(), clock.clone());
+            rate.watch(move |context: Context<f64>| {
+                let mut bucket = bucket.borrow_mut();
+                // Tokens gained so far are counted at the previous rate.
+                bucket.refill(clock.get());
+                bucket.rate = context.value;
+            })
+        };
+        let burst_guard = {
+            let (bucket, available) = (bucket.clone(), available.clone());
+            burst.watch(move |context: Context<u32>| {
+                {
+                    let mut bucket = bucket.borrow_mut();
+                    bucket.burst = context.value;
+                    bucket.tokens = bucket.tokens.min(f64::from(context.value));
+                }
+                publish(&bucket, &available);
+            })
+        };
+
+        Self {
+            bucket,
+            available,
+            now: Rc::new(move || clock.get()),
+            _guard: Rc::new((clock_guard, rate_guard, burst_guard)),
+        }
+    }
+
+    /// Takes a token if one is available, returning whether it did.
+    #[must_use]
+    pub fn try_acquire(&self) -> bool {
+        let acquired = {
+            let mut bucket = self.bucket.borrow_mut();
+            bucket.refill((self.now)());
+            let acquired = bucket.tokens >= 1.0;
+            if acquired {
+                bucket.tokens -= 1.0;
+            }
+            acquired
+        };
+        publish(&self.bucket, &self.available);
+        acquired
+    }
+
+    /// Returns a signal of the number of whole tokens available.
+    #[must_use]
+    pub fn available(&self) -> Computed<u32> {
+        self.available.clone().computed()
+    }
+}
+
+/// Sets `available` to the whole tokens of `bucket`, if they changed.
+fn publish(bucket: &RefCell<Bucket>, available: &Container<u32>) {
+    let tokens = bucket.borrow().available();
+    if available.get() != tokens {
+        available.set(tokens);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding};
+
+    #[test]
+    fn test_token_bucket_follows_rate_and_burst() {
+        let rate: Binding<f64> = binding(2.0);
+        let burst: Binding<u32> = binding(3u32);
+        let clock: Binding<Duration> = binding(Duration::ZERO);
+        let bucket = TokenBucket::new(rate.clone(), burst.clone(), clock.clone());
+        let available = bucket.available();
+
+        for _ in 0..3 {
+            assert!(bucket.try_acquire());
+        }
+        assert!(!bucket.try_acquire());
+
+        // Half a second at two tokens per second, then a second at one.
+        clock.set(Duration::from_millis(500));
+        rate.set(1.0);
+        clock.set(Duration::from_millis(1500));
+        assert_eq!(available.get(), 2);
+
+        // The bucket never holds more than the burst.
+        clock.set(Duration::from_secs(10));
+        assert_eq!(available.get(), 3);
+        burst.set(1u32);
+        assert_eq!(available.get(), 1);
+        assert!(bucket.try_acquire());
+        assert!(!bucket.try_acquire());
+    }
+}
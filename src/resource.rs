@@ -0,0 +1,220 @@
+//! # Async resources
+//!
+//! [`resource`] derives reactive state from async work, such as search results
+//! fetched for a query binding. It runs a fetch for the current value of a
+//! source signal, and again each time the source changes, exposing the
+//! progress as a [`ResourceState`]:
+//!
+//! - While a fetch is running, the resource reads as
+//!   [`Loading`](ResourceState::Loading).
+//! - When it completes, the resource reads as [`Ready`](ResourceState::Ready)
+//!   or [`Error`](ResourceState::Error), depending on its result.
+//! - When the source changes again before a fetch completes, the stale fetch is
+//!   cancelled, so its result never replaces the one for the current value.
+//!
+//! [`Resource::refetch`] runs the fetch again for the current value, such as
+//! for a retry button.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use nami::{binding, Binding, Signal, resource::{ResourceState, resource}};
+//!
+//! async fn search(query: String) -> Result<Vec<String>, String> {
+//!     // Query a server...
+//!     # Ok(vec![query])
+//! }
+//!
+//! let query: Binding<String> = binding("cats");
+//! let results = resource(query.clone(), search);
+//!
+//! assert_eq!(results.get(), ResourceState::Loading);
+//! ```
+
+use alloc::rc::Rc;
+use core::{cell::RefCell, fmt, future::Future};
+
+use executor_core::{DefaultExecutor, LocalExecutor};
+
+use crate::{
+    Container, CustomBinding, Signal,
+    held::{Held, held_signal},
+    watcher::Context,
+};
+
+/// The state of a [`Resource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceState<T, E> {
+    /// The fetch for the current value of the source is running.
+    Loading,
+    /// The fetch completed with a value.
+    Ready(T),
+    /// The fetch failed.
+    Error(E),
+}
+
+impl<T, E> ResourceState<T, E> {
+    /// Returns `true` if the fetch is running.
+    #[must_use]
+    pub const fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+
+    /// Returns the fetched value, if the fetch completed with one.
+    #[must_use]
+    pub const fn value(&self) -> Option<&T> {
+        match self {
+            Self::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the error, if the fetch failed.
+    #[must_use]
+    pub const fn error(&self) -> Option<&E> {
+        match self {
+            Self::Error(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<T, E> From<Result<T, E>> for ResourceState<T, E> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => Self::Ready(value),
+            Err(error) => Self::Error(error),
+        }
+    }
+}
+
+/// A signal of the state of async work derived from a source signal.
+///
+/// Created by [`resource`]. All clones share the same state.
+#[derive(Clone)]
+pub struct Resource<T: Clone + 'static, E: Clone + 'static> {
+    held: Held<ResourceState<T, E>>,
+    refetch: Rc<dyn Fn()>,
+}
+
+impl<T, E> fmt::Debug for Resource<T, E>
+where
+    T: Clone + fmt::Debug + 'static,
+    E: Clone + fmt::Debug + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.held.debug(f, "Resource", "state")
+    }
+}
+
+held_signal!(Resource<T, E> => ResourceState<T, E>);
+
+impl<T: Clone + 'static, E: Clone + 'static> Resource<T, E> {
+    /// Runs the fetch again for the current value of the source, cancelling
+    /// the running one.
+    pub fn refetch(&self) {
+        (self.refetch)();
+    }
+}
+
+/// Returns a resource running `fetch` on the default executor for each value
+/// of `source`.
+///
+/// See the [module documentation](self) for how it behaves.
+pub fn resource<S, F, Fut, T, E>(source: S, fetch: F) -> Resource<T, E>
+where
+    S: Signal,
+    F: Fn(S::Output) -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    T: Clone + 'static,
+    E: Clone + 'static,
+{
+    resource_with_executor(DefaultExecutor, source, fetch)
+}
+
+/// Like [`resource`], but runs the fetches on `executor`.
+pub fn resource_with_executor<X, S, F, Fut, T, E>(
+    executor: X,
+    source: S,
+    fetch: F,
+) -> Resource<T, E>
+where
+    X: LocalExecutor + 'static,
+    S: Signal,
+    F: Fn(S::Output) -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    T: Clone + 'static,
+    E: Clone + 'static,
+{
+    let container = Container::new(ResourceState::Loading);
+    let running: RefCell<Option<X::Task<()>>> = RefCell::new(None);
+    let start = {
+        let container = container.clone();
+        Rc::new(move |input: S::Output| {
+            // Cancel the fetch for the previous value.
+            drop(running.take());
+            if !container.get().is_loading() {
+                container.set(ResourceState::Loading);
+            }
+            let fetched = fetch(input);
+            let container = container.clone();
+            let task = executor.spawn(async move {
+                container.set(fetched.await.into());
+            });
+            running.replace(Some(task));
+        })
+    };
+    start(source.get());
+    let refetch = {
+        let (source, start) = (source.clone(), start.clone());
+        Rc::new(move || start(source.get()))
+    };
+    let guard = source.watch(move |context: Context<S::Output>| start(context.value));
+    Resource {
+        held: Held::new(container, guard),
+        refetch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, binding, test_executor::Manual};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_resource_cancels_stale_fetches() {
+        let executor = Manual::default();
+        let id: Binding<i32> = binding(1);
+        let fetches = Rc::new(RefCell::new(Vec::new()));
+        let user = {
+            let fetches = fetches.clone();
+            resource_with_executor(executor.clone(), id.clone(), move |id: i32| {
+                fetches.borrow_mut().push(id);
+                async move {
+                    if id > 0 {
+                        Ok(id * 10)
+                    } else {
+                        Err("no such user")
+                    }
+                }
+            })
+        };
+
+        assert!(user.get().is_loading());
+        executor.run();
+        assert_eq!(user.get(), ResourceState::Ready(10));
+
+        // The fetch for 2 is cancelled before it completes.
+        id.set(2);
+        assert!(user.get().is_loading());
+        id.set(-1);
+        executor.run();
+        assert_eq!(user.get().error(), Some(&"no such user"));
+
+        user.refetch();
+        executor.run();
+        assert_eq!(*fetches.borrow(), [1, 2, -1, -1]);
+        assert_eq!(user.get(), ResourceState::Error("no such user"));
+    }
+}
@@ -0,0 +1,123 @@
+//! # Accumulating signals
+//!
+//! [`scan`] derives a signal that folds every new value of its source into an
+//! accumulator, such as a running total of a counter or a log of events. Unlike
+//! [`map`](crate::SignalExt::map), which is a function of the current value
+//! only, the accumulator remembers the history of the source.
+//!
+//! The accumulator starts as the given initial value, and only values the source
+//! notifies after the scan was created are folded into it: the current value at
+//! creation is not. Since the history cannot be replayed, the accumulator is
+//! computed once per notification, not on every read. It is updated in place,
+//! so folding into a large accumulator, such as a log, does not copy it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt};
+//!
+//! let count: Binding<i32> = binding(0);
+//! let total = count.clone().scan(0, |total, n| *total += n);
+//!
+//! count.set(2);
+//! count.set(3);
+//! assert_eq!(total.get(), 5);
+//! ```
+
+use core::fmt;
+
+use crate::{
+    Container, Signal,
+    held::{Held, held_signal},
+    watcher::Context,
+};
+
+/// A signal of an accumulator folding the values of a source.
+///
+/// Created by [`scan`] or [`SignalExt::scan`](crate::SignalExt::scan).
+#[derive(Clone)]
+pub struct Scan<A: Clone + 'static> {
+    held: Held<A>,
+}
+
+impl<A: Clone + fmt::Debug + 'static> fmt::Debug for Scan<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.held.debug(f, "Scan", "value")
+    }
+}
+
+held_signal!(Scan<A> => A);
+
+/// Returns a signal starting as `init`, then folding each new value of
+/// `source` into it in place with `f`.
+///
+/// See the [module documentation](self) for how it behaves.
+pub fn scan<S, A, F>(source: S, init: A, f: F) -> Scan<A>
+where
+    S: Signal,
+    A: Clone + 'static,
+    F: Fn(&mut A, S::Output) + 'static,
+{
+    let container = Container::new(init);
+    let guard = {
+        let container = container.clone();
+        source.watch(move |context: Context<S::Output>| {
+            container.modify(|accumulator| f(accumulator, context.value));
+        })
+    };
+    Scan {
+        held: Held::new(container, guard),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, SignalExt, binding};
+    use alloc::{rc::Rc, vec::Vec};
+    use core::cell::{Cell, RefCell};
+
+    #[test]
+    fn test_scan_collects_notified_values() {
+        let events: Binding<&'static str> = binding("start");
+        let log = events.clone().scan(Vec::new(), Vec::push);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            log.watch(move |context| seen.borrow_mut().push(context.value.len()))
+        };
+
+        assert!(log.get().is_empty());
+        events.set("click");
+        events.set("scroll");
+        assert_eq!(log.get(), ["click", "scroll"]);
+        assert_eq!(*seen.borrow(), [1, 2]);
+    }
+
+    #[test]
+    fn test_scan_folds_without_cloning() {
+        #[derive(Default)]
+        struct Counted(Rc<Cell<usize>>, i32);
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                self.0.set(self.0.get() + 1);
+                Self(self.0.clone(), self.1)
+            }
+        }
+
+        // A devtools hook would clone the accumulator to report it.
+        #[cfg(feature = "devtools")]
+        let _hook = crate::devtools::TestHook::install(None);
+        let count: Binding<i32> = binding(0);
+        let clones = Rc::new(Cell::new(0));
+        let total = count
+            .clone()
+            .scan(Counted(clones.clone(), 0), |total, n| total.1 += n);
+
+        count.set(2);
+        count.set(3);
+        assert_eq!(clones.get(), 0);
+        assert_eq!(total.get().1, 5);
+    }
+}
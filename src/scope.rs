@@ -0,0 +1,194 @@
+//! # Structured async effects
+//!
+//! A [`Scope`] owns the async effects spawned through it. Effects run on an
+//! executor like any task, but they cannot outlive their scope: dropping the
+//! scope cancels every effect that is still running. This ties background work,
+//! such as reacting to a binding with a network request, to the lifetime of the
+//! view or model that started it.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use nami::{binding, Binding, future::wait_until, scope::Scope};
+//!
+//! let query: Binding<String> = binding("");
+//! let scope = Scope::new();
+//! scope.spawn_effect({
+//!     let query = query.clone();
+//!     async move {
+//!         let query = wait_until(query, |query| !query.is_empty()).await;
+//!         println!("searching for {query}");
+//!     }
+//! });
+//!
+//! // Cancels the effect if it is still waiting.
+//! drop(scope);
+//! ```
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    any::Any,
+    cell::RefCell,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use executor_core::{DefaultExecutor, LocalExecutor};
+
+/// A spawned effect.
+type Effect<E> = Pin<Box<<E as LocalExecutor>::Task<()>>>;
+
+/// A group of async effects that are cancelled together when it is dropped.
+pub struct Scope<E: LocalExecutor = DefaultExecutor> {
+    executor: E,
+    effects: RefCell<Vec<Effect<E>>>,
+    guards: RefCell<Vec<Box<dyn Any>>>,
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: LocalExecutor> fmt::Debug for Scope<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scope")
+            .field("effects", &self.effects.borrow().len())
+            .field("guards", &self.guards.borrow().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scope {
+    /// Creates an empty scope spawning effects on the default executor.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_executor(DefaultExecutor)
+    }
+}
+
+impl<E: LocalExecutor> Scope<E> {
+    /// Creates an empty scope spawning effects on `executor`.
+    pub const fn with_executor(executor: E) -> Self {
+        Self {
+            executor,
+            effects: RefCell::new(Vec::new()),
+            guards: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Spawns `effect`, which is cancelled if it is still running when the
+    /// scope is dropped.
+    ///
+    /// The handles of the effects that completed are released first, so a
+    /// long-lived scope spawning many short effects does not accumulate them.
+    pub fn spawn_effect(&self, effect: impl Future<Output = ()> + 'static) {
+        let task = Box::pin(self.executor.spawn(effect));
+        let mut effects = self.effects.borrow_mut();
+        prune::<E>(&mut effects);
+        effects.push(task);
+    }
+
+    /// Keeps `guard`, such as a watcher guard, alive until the scope is
+    /// dropped.
+    pub fn keep(&self, guard: impl Any) {
+        self.guards.borrow_mut().push(Box::new(guard));
+    }
+
+    /// Returns the number of effects of this scope that are still running.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let mut effects = self.effects.borrow_mut();
+        prune::<E>(&mut effects);
+        effects.len()
+    }
+
+    /// Returns `true` if no effect of this scope is still running.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cancels every effect of this scope.
+    pub fn cancel_all(&self) {
+        let effects = self.effects.take();
+        drop(effects);
+    }
+
+    /// Cancels every effect of this scope and drops every guard it keeps,
+    /// leaving it empty for reuse.
+    pub fn clear(&self) {
+        self.cancel_all();
+        let guards = self.guards.take();
+        drop(guards);
+    }
+
+    /// Waits until every effect spawned so far has completed.
+    #[allow(clippy::future_not_send)] // Effects are local tasks.
+    pub async fn join(&self) {
+        let mut effects = self.effects.take();
+        core::future::poll_fn(|cx| {
+            effects.retain_mut(|effect| effect.as_mut().poll(cx).is_pending());
+            if effects.is_empty() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+
+    /// Waits until every effect spawned so far has completed, or `timeout`
+    /// elapses, whichever comes first.
+    ///
+    /// Returns `true` if all effects completed in time. Otherwise, the effects
+    /// still running are cancelled and `false` is returned.
+    #[cfg(feature = "io")]
+    #[allow(clippy::future_not_send)] // Effects are local tasks.
+    pub async fn join_timeout(&self, timeout: core::time::Duration) -> bool {
+        let mut join = core::pin::pin!(self.join());
+        let mut timer = async_io::Timer::after(timeout);
+        core::future::poll_fn(|cx| {
+            if join.as_mut().poll(cx).is_ready() {
+                Poll::Ready(true)
+            } else if Pin::new(&mut timer).poll(cx).is_ready() {
+                Poll::Ready(false)
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+/// Releases the handles of the effects that completed.
+fn prune<E: LocalExecutor>(effects: &mut Vec<Effect<E>>) {
+    let mut cx = Context::from_waker(Waker::noop());
+    effects.retain_mut(|effect| effect.as_mut().poll(&mut cx).is_pending());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_executor::Manual;
+
+    #[test]
+    fn test_spawn_releases_completed_effects() {
+        let executor = Manual::default();
+        let scope = Scope::with_executor(executor.clone());
+        scope.spawn_effect(async {});
+        scope.spawn_effect(core::future::pending());
+        assert_eq!(scope.len(), 2);
+
+        executor.run();
+        scope.spawn_effect(async {});
+        assert_eq!(scope.effects.borrow().len(), 2);
+        assert_eq!(scope.len(), 2);
+        executor.run();
+        assert_eq!(scope.len(), 1);
+        assert!(!scope.is_empty());
+    }
+}
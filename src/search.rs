@@ -0,0 +1,590 @@
+//! # Reactive search over lists
+//!
+//! A [`SearchIndex`] keeps an incremental prefix index of the text of every
+//! element of a list. [`SearchIndex::search`] turns a query signal into a live
+//! [`ListView`] of the matching elements, which follows both the query and the
+//! indexed list.
+//!
+//! Text is split into tokens by a tokenizer, [`words`] by default. The query is
+//! tokenized the same way, and an element matches if every query token is a
+//! prefix of one of its tokens. An empty query matches every element.
+//!
+//! For command palettes and other pickers where queries are abbreviations
+//! rather than words, [`fuzzy_filter`] keeps a list of the elements that
+//! contain the query as a subsequence, best [`fuzzy_score`] first.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, collection::List, search::SearchIndex};
+//!
+//! let commands = List::from(vec!["Open File", "Open Recent", "Close Window"]);
+//! let index = SearchIndex::new(&commands, |command: &&str| (*command).into());
+//!
+//! let query: Binding<String> = binding("op");
+//! let results = index.search(&query);
+//! assert_eq!(results.to_vec(), ["Open File", "Open Recent"]);
+//!
+//! query.set("op rec");
+//! assert_eq!(results.to_vec(), ["Open Recent"]);
+//!
+//! commands.push("Reopen Recent");
+//! query.set("recent");
+//! assert_eq!(results.to_vec(), ["Open Recent", "Reopen Recent"]);
+//! ```
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+    string::String,
+    vec::Vec,
+};
+use core::{cell::RefCell, cmp::Reverse, ops::Bound};
+
+use crate::{
+    Signal,
+    collection::{
+        Collection, List, ListChange, ListChanges, ListSource, ListView, filter_sorted_by_key,
+    },
+    watcher::{BoxWatcherGuard, Context},
+};
+
+/// Splits `text` into lowercase alphanumeric words.
+///
+/// This is the default tokenizer of a [`SearchIndex`].
+///
+/// ```rust
+/// assert_eq!(nami::search::words("Hello, World-2!"), ["hello", "world", "2"]);
+/// ```
+#[must_use]
+pub fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+type Text<T> = Rc<dyn Fn(&T) -> String>;
+type Tokenizer = Rc<dyn Fn(&str) -> Vec<String>>;
+
+/// The postings of a search index, keyed by stable element ids.
+struct Postings {
+    next_id: usize,
+    /// The id of the element at each index of the list.
+    ids: Vec<usize>,
+    tokens: BTreeMap<usize, Vec<String>>,
+    by_token: BTreeMap<String, BTreeSet<usize>>,
+}
+
+impl Postings {
+    fn add(&mut self, id: usize, tokens: Vec<String>) {
+        for token in &tokens {
+            self.by_token.entry(token.clone()).or_default().insert(id);
+        }
+        self.tokens.insert(id, tokens);
+    }
+
+    fn remove(&mut self, id: usize) {
+        for token in self.tokens.remove(&id).unwrap_or_default() {
+            if let Some(ids) = self.by_token.get_mut(&token) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.by_token.remove(&token);
+                }
+            }
+        }
+    }
+
+    fn insert(&mut self, index: usize, tokens: Vec<String>) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.ids.insert(index, id);
+        self.add(id, tokens);
+    }
+
+    /// Returns the ids of the elements with a token starting with `prefix`.
+    fn prefixed(&self, prefix: &str) -> BTreeSet<usize> {
+        self.by_token
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(|(token, _)| token.starts_with(prefix))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect()
+    }
+
+    /// Returns whether each element of the list matches `query`, in list order.
+    fn matches(&self, query: &[String]) -> Vec<bool> {
+        let mut matching: Option<BTreeSet<usize>> = None;
+        for token in query {
+            let ids = self.prefixed(token);
+            matching = Some(match matching {
+                Some(matching) => matching.intersection(&ids).copied().collect(),
+                None => ids,
+            });
+        }
+        self.ids
+            .iter()
+            .map(|id| {
+                matching
+                    .as_ref()
+                    .is_none_or(|matching| matching.contains(id))
+            })
+            .collect()
+    }
+}
+
+/// Returns `true` if every token of `query` is a prefix of one of `tokens`.
+fn matches(tokens: &[String], query: &[String]) -> bool {
+    query
+        .iter()
+        .all(|query| tokens.iter().any(|token| token.starts_with(query.as_str())))
+}
+
+/// An incremental prefix index over the text of the elements of a list.
+///
+/// The index follows the list it was created from, re-tokenizing only the
+/// elements that changed. Cloning an index shares it.
+pub struct SearchIndex<T> {
+    items: List<T>,
+    postings: Rc<RefCell<Postings>>,
+    text: Text<T>,
+    tokenizer: Tokenizer,
+    guard: Rc<BoxWatcherGuard>,
+}
+
+impl<T> Clone for SearchIndex<T> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+            postings: self.postings.clone(),
+            text: self.text.clone(),
+            tokenizer: self.tokenizer.clone(),
+            guard: self.guard.clone(),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for SearchIndex<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let postings = self.postings.borrow();
+        f.debug_struct("SearchIndex")
+            .field("len", &postings.ids.len())
+            .field("tokens", &postings.by_token.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + 'static> SearchIndex<T> {
+    /// Indexes the text returned by `text` for each element of `source`, split
+    /// into [`words`].
+    pub fn new<S>(source: &S, text: impl Fn(&T) -> String + 'static) -> Self
+    where
+        S: ListSource<Item = T>,
+    {
+        Self::with_tokenizer(source, text, words)
+    }
+
+    /// Indexes the text returned by `text` for each element of `source`, split
+    /// into tokens by `tokenizer`.
+    ///
+    /// Queries are split by the same tokenizer, so it should normalize case and
+    /// punctuation the way searches are expected to.
+    pub fn with_tokenizer<S>(
+        source: &S,
+        text: impl Fn(&T) -> String + 'static,
+        tokenizer: impl Fn(&str) -> Vec<String> + 'static,
+    ) -> Self
+    where
+        S: ListSource<Item = T>,
+    {
+        let text: Text<T> = Rc::new(text);
+        let tokenizer: Tokenizer = Rc::new(tokenizer);
+        let items = source.to_vec();
+        let mut postings = Postings {
+            next_id: 0,
+            ids: Vec::new(),
+            tokens: BTreeMap::new(),
+            by_token: BTreeMap::new(),
+        };
+        for (index, item) in items.iter().enumerate() {
+            postings.insert(index, tokenizer(&text(item)));
+        }
+
+        let items = List::from(items);
+        let postings = Rc::new(RefCell::new(postings));
+        let guard = {
+            let items = items.clone();
+            let postings = postings.clone();
+            let text = text.clone();
+            let tokenizer = tokenizer.clone();
+            source.watch_changes(move |changes: &ListChanges<T>| {
+                {
+                    let mut postings = postings.borrow_mut();
+                    for change in changes.changes() {
+                        match change {
+                            ListChange::Insert { index, value } => {
+                                postings.insert(*index, tokenizer(&text(value)));
+                            }
+                            ListChange::Remove { index } => {
+                                let id = postings.ids.remove(*index);
+                                postings.remove(id);
+                            }
+                            ListChange::Update { index, value } => {
+                                let id = postings.ids[*index];
+                                postings.remove(id);
+                                postings.add(id, tokenizer(&text(value)));
+                            }
+                            ListChange::Move { from, to } => {
+                                let id = postings.ids.remove(*from);
+                                postings.ids.insert(*to, id);
+                            }
+                        }
+                    }
+                }
+                items.apply_changes(changes.changes().to_vec());
+            })
+        };
+
+        Self {
+            items,
+            postings,
+            text,
+            tokenizer,
+            guard: Rc::new(guard),
+        }
+    }
+
+    /// Returns a live view of the elements matching `query`, in list order.
+    ///
+    /// Changed elements are re-tokenized to update the view incrementally, and a
+    /// changed query is looked up in the index.
+    pub fn search<Q>(&self, query: &Q) -> ListView<T>
+    where
+        Q: Signal,
+        Q::Output: AsRef<str>,
+    {
+        let tokens = (self.tokenizer)(query.get().as_ref());
+        let members = self.postings.borrow().matches(&tokens);
+        let list = List::from(
+            self.items
+                .to_vec()
+                .into_iter()
+                .zip(&members)
+                .filter_map(|(item, member)| member.then_some(item))
+                .collect::<Vec<_>>(),
+        );
+        let results = Rc::new(RefCell::new(Results {
+            query: tokens,
+            members,
+        }));
+
+        let items_guard = {
+            let list = list.clone();
+            let results = results.clone();
+            let text = self.text.clone();
+            let tokenizer = self.tokenizer.clone();
+            self.items.watch_changes(move |changes: &ListChanges<T>| {
+                let changes = results
+                    .borrow_mut()
+                    .follow(changes, |item| tokenizer(&text(item)));
+                list.apply_changes(changes);
+            })
+        };
+
+        let query_guard = {
+            let list = list.clone();
+            let index = self.clone();
+            query.watch(move |context: Context<Q::Output>| {
+                let tokens = (index.tokenizer)(context.value.as_ref());
+                let changes = {
+                    let mut results = results.borrow_mut();
+                    if results.query == tokens {
+                        return;
+                    }
+                    let members = index.postings.borrow().matches(&tokens);
+                    results.query = tokens;
+                    results.replace(members, |index_of| Collection::get(&index.items, index_of))
+                };
+                list.apply_changes(changes);
+            })
+        };
+
+        ListView::new(list, (self.clone(), items_guard, query_guard))
+    }
+}
+
+/// The current query of a search and which elements of the list match it.
+struct Results {
+    query: Vec<String>,
+    members: Vec<bool>,
+}
+
+impl Results {
+    /// Returns the position in the results of the element at `index` of the list.
+    fn position(&self, index: usize) -> usize {
+        self.members[..index]
+            .iter()
+            .filter(|member| **member)
+            .count()
+    }
+
+    /// Applies changes of the list, returning the changes of the results.
+    fn follow<T: Clone>(
+        &mut self,
+        changes: &ListChanges<T>,
+        tokenize: impl Fn(&T) -> Vec<String>,
+    ) -> Vec<ListChange<T>> {
+        let mut results = Vec::new();
+        for change in changes.changes() {
+            match change {
+                ListChange::Insert { index, value } => {
+                    let member = matches(&tokenize(value), &self.query);
+                    self.members.insert(*index, member);
+                    if member {
+                        results.push(ListChange::Insert {
+                            index: self.position(*index),
+                            value: value.clone(),
+                        });
+                    }
+                }
+                ListChange::Remove { index } => {
+                    let position = self.position(*index);
+                    if self.members.remove(*index) {
+                        results.push(ListChange::Remove { index: position });
+                    }
+                }
+                ListChange::Update { index, value } => {
+                    let member = matches(&tokenize(value), &self.query);
+                    let position = self.position(*index);
+                    match (self.members[*index], member) {
+                        (true, true) => results.push(ListChange::Update {
+                            index: position,
+                            value: value.clone(),
+                        }),
+                        (true, false) => results.push(ListChange::Remove { index: position }),
+                        (false, true) => results.push(ListChange::Insert {
+                            index: position,
+                            value: value.clone(),
+                        }),
+                        (false, false) => {}
+                    }
+                    self.members[*index] = member;
+                }
+                ListChange::Move { from, to } => {
+                    let position = self.position(*from);
+                    let member = self.members.remove(*from);
+                    self.members.insert(*to, member);
+                    let target = self.position(*to);
+                    if member && position != target {
+                        results.push(ListChange::Move {
+                            from: position,
+                            to: target,
+                        });
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    /// Replaces which elements match, returning the changes of the results.
+    fn replace<T>(
+        &mut self,
+        members: Vec<bool>,
+        item: impl Fn(usize) -> Option<T>,
+    ) -> Vec<ListChange<T>> {
+        let mut results = Vec::new();
+        let mut position = 0;
+        for (index, (was, is)) in self.members.iter().zip(&members).enumerate() {
+            match (*was, *is) {
+                (true, true) => position += 1,
+                (true, false) => results.push(ListChange::Remove { index: position }),
+                (false, true) => {
+                    if let Some(value) = item(index) {
+                        results.push(ListChange::Insert {
+                            index: position,
+                            value,
+                        });
+                        position += 1;
+                    }
+                }
+                (false, false) => {}
+            }
+        }
+        self.members = members;
+        results
+    }
+}
+
+/// Scores how well `text` matches `query` as a case-insensitive subsequence,
+/// or returns `None` if it does not contain every character of `query` in order.
+///
+/// Every matched character scores a point, with bonuses for characters that
+/// start a word and for runs of consecutive matches, so `"of"` scores higher
+/// for `"Open File"` than for `"Go forward"`. An empty query scores `0`.
+///
+/// ```rust
+/// use nami::search::fuzzy_score;
+///
+/// assert!(fuzzy_score("of", "Open File") > fuzzy_score("of", "Go forward"));
+/// assert_eq!(fuzzy_score("xyz", "Open File"), None);
+/// ```
+#[must_use]
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    const WORD_START: u32 = 8;
+    const CONSECUTIVE: u32 = 4;
+
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score: u32 = 0;
+    let mut previous: Option<char> = None;
+    let mut matched_previous = false;
+
+    for c in text.chars() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+        let matched = c.to_lowercase().eq(core::iter::once(wanted));
+        if matched {
+            query.next();
+            score += 1;
+            let word_start = previous.is_none_or(|previous| {
+                !previous.is_alphanumeric() || (previous.is_lowercase() && c.is_uppercase())
+            });
+            if word_start {
+                score += WORD_START;
+            }
+            if matched_previous {
+                score += CONSECUTIVE;
+            }
+        }
+        matched_previous = matched;
+        previous = Some(c);
+    }
+
+    query.peek().is_none().then_some(score)
+}
+
+/// Creates a live view of the elements of `source` that fuzzily match `query`,
+/// best match first.
+///
+/// Elements are ranked by [`fuzzy_score`], and equally scored elements keep
+/// their order in the source. Only elements that change in the source are
+/// scored again; a changed query re-scores every element and moves the
+/// results into their new order.
+///
+/// # Examples
+///
+/// ```rust
+/// use nami::{binding, Binding, collection::List, search::fuzzy_filter};
+///
+/// let commands = List::from(vec!["Go Forward", "Open File", "Close Folder"]);
+/// let query: Binding<String> = binding("of");
+/// let palette = fuzzy_filter(&commands, &query);
+/// assert_eq!(palette.to_vec(), ["Open File", "Go Forward", "Close Folder"]);
+///
+/// query.set("ofi");
+/// assert_eq!(palette.to_vec(), ["Open File"]);
+/// ```
+pub fn fuzzy_filter<S, Q, T>(source: &S, query: &Q) -> ListView<T>
+where
+    S: ListSource<Item = T>,
+    Q: Signal,
+    Q::Output: AsRef<str>,
+    T: AsRef<str> + Clone + 'static,
+{
+    filter_sorted_by_key(source, query, |query, item: &T| {
+        fuzzy_score(query.as_ref(), item.as_ref()).map(Reverse)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding;
+    use alloc::{format, vec};
+
+    #[test]
+    fn test_search_follows_list_and_query() {
+        let notes = List::from(vec!["apple pie", "banana bread", "apple tart"]);
+        let index = SearchIndex::new(&notes, |note: &&str| (*note).into());
+        let query = binding::<String>("apple");
+        let results = index.search(&query);
+
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let changes = changes.clone();
+            results.watch_changes(move |list_changes| {
+                changes
+                    .borrow_mut()
+                    .extend_from_slice(list_changes.changes());
+            })
+        };
+
+        notes.set(1, "apple bread");
+        let _ = notes.remove(0);
+        notes.set(1, "cherry tart");
+        assert_eq!(results.to_vec(), ["apple bread"]);
+        assert_eq!(
+            *changes.take(),
+            [
+                ListChange::Insert {
+                    index: 1,
+                    value: "apple bread"
+                },
+                ListChange::Remove { index: 0 },
+                ListChange::Remove { index: 1 },
+            ]
+        );
+
+        query.set("TART");
+        assert_eq!(results.to_vec(), ["cherry tart"]);
+        assert_eq!(
+            *changes.take(),
+            [
+                ListChange::Remove { index: 0 },
+                ListChange::Insert {
+                    index: 0,
+                    value: "cherry tart"
+                },
+            ]
+        );
+
+        query.set("");
+        assert_eq!(results.to_vec(), ["apple bread", "cherry tart"]);
+        assert_eq!(
+            format!("{index:?}"),
+            "SearchIndex { len: 2, tokens: 4, .. }"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_filter_changes() {
+        let commands = List::from(vec!["Go Forward", "Open File", "Close Folder"]);
+        let query = binding::<String>("of");
+        let palette = fuzzy_filter(&commands, &query);
+        let mirror = Rc::new(RefCell::new(palette.to_vec()));
+        let _guard = {
+            let mirror = mirror.clone();
+            palette.watch_changes(move |changes| changes.apply(&mut mirror.borrow_mut()))
+        };
+
+        commands.set(1, "Save File");
+        assert_eq!(palette.to_vec(), ["Go Forward", "Close Folder"]);
+        commands.push("Open Folder");
+        assert_eq!(
+            palette.to_vec(),
+            ["Open Folder", "Go Forward", "Close Folder"]
+        );
+        assert_eq!(*mirror.borrow(), palette.to_vec());
+
+        query.set("fi");
+        assert_eq!(palette.to_vec(), ["Save File"]);
+        assert_eq!(*mirror.borrow(), palette.to_vec());
+
+        query.set("");
+        assert_eq!(
+            palette.to_vec(),
+            ["Go Forward", "Save File", "Close Folder", "Open Folder"]
+        );
+        assert_eq!(*mirror.borrow(), palette.to_vec());
+    }
+}
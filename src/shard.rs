@@ -0,0 +1,268 @@
+//! # Graph shards
+//!
+//! A server hosting many independent reactive sessions, one per tenant or
+//! connection, keeps each of them in a [`Shard`] of a [`Shards`] map, keyed by
+//! the session:
+//!
+//! - A shard holds the state of its session, one value per type, such as the
+//!   struct of its bindings, and a [`Scope`] owning its async effects and
+//!   watcher guards. Nothing is shared between shards, so sessions cannot
+//!   observe each other's writes.
+//! - Every shard spawns its effects on the executor of the map, so sessions
+//!   share one scheduler instead of each starting its own.
+//! - [`dispose`](Shards::dispose) tears a session down in bulk: its effects are
+//!   cancelled, and its guards and state dropped, even while handles to the
+//!   shard are still held.
+//!
+//! Per-thread tooling, such as [`batch`](crate::batch) or the
+//! [`watchdog`](crate::watchdog), covers every shard on the thread.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, shard::Shards};
+//!
+//! #[derive(Clone)]
+//! struct Session {
+//!     cart: Binding<Vec<String>>,
+//! }
+//!
+//! let sessions = Shards::new();
+//! let session = |id| {
+//!     sessions
+//!         .shard(id)
+//!         .state(|| Session { cart: binding(Vec::new()) })
+//!         .cart
+//!         .clone()
+//! };
+//!
+//! session("alice").push("book".to_string());
+//! assert!(session("bob").get().is_empty());
+//!
+//! let alice = sessions.shard("alice");
+//! sessions.dispose(&"alice");
+//! assert!(alice.is_disposed());
+//! assert!(session("alice").get().is_empty());
+//! ```
+
+use alloc::{collections::BTreeMap, rc::Rc, vec::Vec};
+use core::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    fmt,
+};
+
+use executor_core::{DefaultExecutor, LocalExecutor};
+
+use crate::scope::Scope;
+
+/// The reactive state of one session, with the scope of its effects.
+///
+/// Created by [`Shards::shard`].
+pub struct Shard<E: LocalExecutor = DefaultExecutor> {
+    scope: Scope<E>,
+    state: RefCell<BTreeMap<TypeId, Rc<dyn Any>>>,
+    disposed: Cell<bool>,
+}
+
+impl<E: LocalExecutor> fmt::Debug for Shard<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shard")
+            .field("scope", &self.scope)
+            .field("state", &self.state.borrow().len())
+            .field("disposed", &self.disposed.get())
+            .finish()
+    }
+}
+
+impl<E: LocalExecutor> Shard<E> {
+    /// Returns the scope of this shard, which owns the effects and guards of
+    /// the session.
+    #[must_use]
+    pub const fn scope(&self) -> &Scope<E> {
+        &self.scope
+    }
+
+    /// Returns the state of type `T` of this shard, initializing it with
+    /// `init` on first access.
+    pub fn state<T: 'static>(&self, init: impl FnOnce() -> T) -> Rc<T> {
+        let existing = self.state.borrow().get(&TypeId::of::<T>()).cloned();
+        let state = existing.unwrap_or_else(|| {
+            let state: Rc<dyn Any> = Rc::new(init());
+            self.state
+                .borrow_mut()
+                .insert(TypeId::of::<T>(), state.clone());
+            state
+        });
+        state
+            .downcast()
+            .unwrap_or_else(|_| unreachable!("state is keyed by its type"))
+    }
+
+    /// Returns `true` once the shard has been disposed.
+    #[must_use]
+    pub const fn is_disposed(&self) -> bool {
+        self.disposed.get()
+    }
+
+    /// Cancels the effects of this shard, and drops its guards and state.
+    fn dispose(&self) {
+        self.disposed.set(true);
+        self.scope.clear();
+        let state = self.state.take();
+        drop(state);
+    }
+}
+
+/// A map of shards, one per session key, sharing one executor.
+///
+/// See the [module documentation](self) for how it behaves.
+pub struct Shards<K, E: LocalExecutor = DefaultExecutor> {
+    executor: E,
+    shards: RefCell<BTreeMap<K, Rc<Shard<E>>>>,
+}
+
+impl<K: fmt::Debug, E: LocalExecutor> fmt::Debug for Shards<K, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shards")
+            .field("keys", &self.shards.borrow().keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: Ord> Default for Shards<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord> Shards<K> {
+    /// Creates an empty map whose shards spawn effects on the default executor.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_executor(DefaultExecutor)
+    }
+}
+
+impl<K: Ord, E: LocalExecutor + Clone> Shards<K, E> {
+    /// Creates an empty map whose shards spawn effects on `executor`.
+    pub const fn with_executor(executor: E) -> Self {
+        Self {
+            executor,
+            shards: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the shard of `key`, creating an empty one if there is none.
+    pub fn shard(&self, key: K) -> Rc<Shard<E>> {
+        self.shards
+            .borrow_mut()
+            .entry(key)
+            .or_insert_with(|| {
+                Rc::new(Shard {
+                    scope: Scope::with_executor(self.executor.clone()),
+                    state: RefCell::new(BTreeMap::new()),
+                    disposed: Cell::new(false),
+                })
+            })
+            .clone()
+    }
+
+    /// Returns the shard of `key`, if there is one.
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<Rc<Shard<E>>> {
+        self.shards.borrow().get(key).cloned()
+    }
+
+    /// Disposes of the shard of `key`, returning whether there was one.
+    ///
+    /// Its effects are cancelled, and its guards and state dropped. A later
+    /// call to [`shard`](Self::shard) with the same key creates a new shard.
+    pub fn dispose(&self, key: &K) -> bool {
+        let shard = self.shards.borrow_mut().remove(key);
+        shard.map(|shard| shard.dispose()).is_some()
+    }
+
+    /// Disposes of every shard for which `keep` returns `false`, such as the
+    /// shards of expired sessions.
+    pub fn retain(&self, mut keep: impl FnMut(&K, &Shard<E>) -> bool) {
+        let mut disposed = Vec::new();
+        self.shards.borrow_mut().retain(|key, shard| {
+            let kept = keep(key, shard);
+            if !kept {
+                disposed.push(shard.clone());
+            }
+            kept
+        });
+        // Disposed outside the borrow, since dropping the state of a shard may
+        // run arbitrary code.
+        for shard in disposed {
+            shard.dispose();
+        }
+    }
+
+    /// Disposes of every shard.
+    pub fn dispose_all(&self) {
+        self.retain(|_, _| false);
+    }
+
+    /// Returns the number of shards.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shards.borrow().len()
+    }
+
+    /// Returns `true` if there are no shards.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.shards.borrow().is_empty()
+    }
+}
+
+/// Disposes of every shard, even those whose handles are still held.
+impl<K, E: LocalExecutor> Drop for Shards<K, E> {
+    fn drop(&mut self) {
+        let shards = core::mem::take(self.shards.get_mut());
+        for shard in shards.into_values() {
+            shard.dispose();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, Signal, binding};
+    use core::cell::Cell;
+
+    #[test]
+    fn test_shards_are_isolated_and_disposed_in_bulk() {
+        let shards = Shards::new();
+        let notified = Rc::new(Cell::new(0));
+        for tenant in 0..3 {
+            let shard = shards.shard(tenant);
+            let count = shard.state(|| -> Binding<i32> { binding(0) });
+            let notified = notified.clone();
+            shard
+                .scope()
+                .keep(count.watch(move |_| notified.set(notified.get() + 1)));
+        }
+
+        let first = shards.shard(0).state(|| -> Binding<i32> { binding(10) });
+        first.set(1);
+        assert_eq!(
+            shards
+                .shard(1)
+                .state(|| -> Binding<i32> { binding(10) })
+                .get(),
+            0
+        );
+        assert_eq!(notified.get(), 1);
+
+        shards.retain(|tenant, _| *tenant != 0);
+        first.set(2);
+        assert_eq!((shards.len(), notified.get()), (2, 1));
+        assert!(shards.get(&0).is_none() && shards.get(&1).is_some());
+        assert_eq!(first.watcher_count(), Some(0));
+    }
+}
@@ -0,0 +1,460 @@
+//! # Spreadsheets
+//!
+//! A [`Sheet`] is a grid of cells, each holding either a value or a formula
+//! computing its value from other cells. Formulas read cells through [`Cells`],
+//! which records what they read: when a cell changes, exactly the formulas
+//! depending on it, directly or not, are recalculated, each once, after the
+//! cells they read.
+//!
+//! A cell evaluates to a [`CellValue`], which is an error when its formula
+//! reads a cell outside of the sheet, or takes part in a cycle of formulas
+//! reading each other. Errors propagate to the formulas reading the failed
+//! cell with `?`, and a cycle is detected anew on every recalculation, so
+//! breaking it recovers the cells involved.
+//!
+//! Every cell is a signal, through [`Sheet::cell`], and
+//! [`Sheet::watch_region`] reports the cells of a region changed by each edit
+//! at once, to redraw the region of a view.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{Signal, sheet::{CellRef, Sheet, SheetError}};
+//!
+//! let a1 = CellRef::new(0, 0);
+//! let b1 = CellRef::new(0, 1);
+//! let c1 = CellRef::new(0, 2);
+//!
+//! let sheet = Sheet::<i32>::new(1, 3);
+//! sheet.set_value(a1, 2);
+//! sheet.set_formula(b1, move |cells| Ok(cells.get(a1)? * 10));
+//! let total = sheet.cell(c1);
+//! sheet.set_formula(c1, move |cells| Ok(cells.get(a1)? + cells.get(b1)?));
+//! assert_eq!(total.get(), Ok(22));
+//!
+//! sheet.set_value(a1, 3);
+//! assert_eq!(total.get(), Ok(33));
+//!
+//! sheet.set_formula(a1, move |cells| cells.get(c1));
+//! assert_eq!(total.get(), Err(SheetError::Cycle));
+//! ```
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+    vec,
+    vec::Vec,
+};
+use core::{cell::RefCell, fmt, ops::Range};
+
+use crate::{
+    Computed, Container, CustomBinding, Signal, SignalExt,
+    watcher::{BoxWatcherGuard, Context},
+};
+
+/// The position of a cell in a [`Sheet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellRef {
+    /// The row of the cell, from zero.
+    pub row: usize,
+    /// The column of the cell, from zero.
+    pub column: usize,
+}
+
+impl CellRef {
+    /// Creates a reference to the cell at `row` and `column`.
+    #[must_use]
+    pub const fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+}
+
+/// A rectangular region of a [`Sheet`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Region {
+    /// The rows of the region.
+    pub rows: Range<usize>,
+    /// The columns of the region.
+    pub columns: Range<usize>,
+}
+
+impl Region {
+    /// Creates a region spanning `rows` and `columns`.
+    #[must_use]
+    pub const fn new(rows: Range<usize>, columns: Range<usize>) -> Self {
+        Self { rows, columns }
+    }
+
+    /// Returns `true` if `cell` is in the region.
+    #[must_use]
+    pub fn contains(&self, cell: CellRef) -> bool {
+        self.rows.contains(&cell.row) && self.columns.contains(&cell.column)
+    }
+}
+
+/// An error a cell evaluates to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SheetError {
+    /// The cell's formula reads itself, directly or through other formulas.
+    Cycle,
+    /// The cell's formula reads a cell outside of the sheet.
+    OutOfBounds(CellRef),
+}
+
+impl fmt::Display for SheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle => f.write_str("circular reference between formulas"),
+            Self::OutOfBounds(cell) => write!(
+                f,
+                "cell at row {}, column {} is outside of the sheet",
+                cell.row, cell.column
+            ),
+        }
+    }
+}
+
+impl core::error::Error for SheetError {}
+
+/// The value of a cell, or the error it evaluates to.
+pub type CellValue<T> = Result<T, SheetError>;
+
+type Formula<T> = Rc<dyn Fn(&Cells<'_, T>) -> CellValue<T>>;
+
+enum Content<T: Clone + PartialEq + 'static> {
+    Value(T),
+    Formula(Formula<T>),
+}
+
+/// A grid of reactive cells holding values or formulas.
+///
+/// All clones share the same cells. See the [module documentation](self) for
+/// how it behaves.
+#[derive(Clone)]
+pub struct Sheet<T: Clone + PartialEq + 'static> {
+    inner: Rc<Inner<T>>,
+}
+
+struct Inner<T: Clone + PartialEq + 'static> {
+    rows: usize,
+    columns: usize,
+    contents: RefCell<Vec<Content<T>>>,
+    /// The cells read by each cell's formula in its last evaluation.
+    dependencies: RefCell<Vec<Vec<usize>>>,
+    values: Vec<Container<CellValue<T>>>,
+    /// The cells changed by the last edit.
+    changes: Container<Vec<CellRef>>,
+}
+
+impl<T: Clone + PartialEq + fmt::Debug + 'static> fmt::Debug for Sheet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sheet")
+            .field("rows", &self.inner.rows)
+            .field("columns", &self.inner.columns)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Sheet<T> {
+    /// Creates a sheet of `rows` by `columns` cells holding the default value.
+    #[must_use]
+    pub fn new(rows: usize, columns: usize) -> Self
+    where
+        T: Default,
+    {
+        let len = rows * columns;
+        Self {
+            inner: Rc::new(Inner {
+                rows,
+                columns,
+                contents: RefCell::new((0..len).map(|_| Content::Value(T::default())).collect()),
+                dependencies: RefCell::new(vec![Vec::new(); len]),
+                values: (0..len).map(|_| Container::new(Ok(T::default()))).collect(),
+                changes: Container::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Returns the number of rows.
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.inner.rows
+    }
+
+    /// Returns the number of columns.
+    #[must_use]
+    pub fn columns(&self) -> usize {
+        self.inner.columns
+    }
+
+    /// Returns the current value of `cell`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the cell evaluates to, or [`SheetError::OutOfBounds`]
+    /// if `cell` is outside of the sheet.
+    pub fn get(&self, cell: CellRef) -> CellValue<T> {
+        self.inner.values[self.inner.index(cell)?].get()
+    }
+
+    /// Returns a signal of the value of `cell`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell` is outside of the sheet.
+    #[must_use]
+    pub fn cell(&self, cell: CellRef) -> Computed<CellValue<T>> {
+        self.inner.values[self.index(cell)].clone().computed()
+    }
+
+    /// Sets `cell` to `value`, recalculating the formulas depending on it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell` is outside of the sheet.
+    pub fn set_value(&self, cell: CellRef, value: T) {
+        self.set(cell, Content::Value(value));
+    }
+
+    /// Sets `cell` to a formula computing its value from other cells, and
+    /// recalculates it with the formulas depending on it.
+    ///
+    /// The formula must only read the sheet through the given [`Cells`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell` is outside of the sheet.
+    pub fn set_formula(
+        &self,
+        cell: CellRef,
+        formula: impl Fn(&Cells<'_, T>) -> CellValue<T> + 'static,
+    ) {
+        self.set(cell, Content::Formula(Rc::new(formula)));
+    }
+
+    /// Registers a watcher notified with the cells of `region` changed by each
+    /// edit, in row-major order.
+    ///
+    /// Edits changing no cell of the region do not notify the watcher.
+    pub fn watch_region(
+        &self,
+        region: Region,
+        watcher: impl Fn(Context<Vec<CellRef>>) + 'static,
+    ) -> BoxWatcherGuard {
+        self.inner
+            .changes
+            .watch(move |context: Context<Vec<CellRef>>| {
+                let Context { value, metadata } = context;
+                let changed: Vec<CellRef> = value
+                    .into_iter()
+                    .filter(|cell| region.contains(*cell))
+                    .collect();
+                if !changed.is_empty() {
+                    watcher(Context::new(changed, metadata));
+                }
+            })
+    }
+
+    fn index(&self, cell: CellRef) -> usize {
+        let Ok(index) = self.inner.index(cell) else {
+            panic!("cell {cell:?} is outside of the sheet");
+        };
+        index
+    }
+
+    fn set(&self, cell: CellRef, content: Content<T>) {
+        let index = self.index(cell);
+        self.inner.contents.borrow_mut()[index] = content;
+        self.recalculate(index);
+    }
+
+    fn recalculate(&self, index: usize) {
+        let dirty = self.inner.dependents(index);
+        let (states, dependencies) = {
+            let contents = self.inner.contents.borrow();
+            let recalc = Recalc {
+                inner: &self.inner,
+                contents: &contents,
+                states: RefCell::new(dirty.iter().map(|&cell| (cell, State::Pending)).collect()),
+                dependencies: RefCell::default(),
+            };
+            for &cell in &dirty {
+                if matches!(recalc.states.borrow().get(&cell), Some(State::Pending)) {
+                    let _ = recalc.evaluate(cell);
+                }
+            }
+            (recalc.states.into_inner(), recalc.dependencies.into_inner())
+        };
+
+        {
+            let mut current = self.inner.dependencies.borrow_mut();
+            for (cell, reads) in dependencies {
+                current[cell] = reads;
+            }
+        }
+
+        // Borrows are released before notifying, so watchers may edit the sheet.
+        let mut changed = Vec::new();
+        for (cell, state) in states {
+            if let State::Done(value) = state {
+                let container = &self.inner.values[cell];
+                if container.get() != value {
+                    container.set(value);
+                    changed.push(self.inner.cell_ref(cell));
+                }
+            }
+        }
+        if !changed.is_empty() {
+            self.inner.changes.set(changed);
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Inner<T> {
+    const fn index(&self, cell: CellRef) -> Result<usize, SheetError> {
+        if cell.row < self.rows && cell.column < self.columns {
+            Ok(cell.row * self.columns + cell.column)
+        } else {
+            Err(SheetError::OutOfBounds(cell))
+        }
+    }
+
+    const fn cell_ref(&self, index: usize) -> CellRef {
+        CellRef::new(index / self.columns, index % self.columns)
+    }
+
+    /// Returns `index` and the cells depending on it, directly or not.
+    fn dependents(&self, index: usize) -> BTreeSet<usize> {
+        let dependencies = self.dependencies.borrow();
+        let mut dirty = BTreeSet::from([index]);
+        let mut queue = vec![index];
+        while let Some(changed) = queue.pop() {
+            for (cell, reads) in dependencies.iter().enumerate() {
+                if reads.contains(&changed) && dirty.insert(cell) {
+                    queue.push(cell);
+                }
+            }
+        }
+        dirty
+    }
+}
+
+enum State<T> {
+    Pending,
+    Evaluating,
+    Done(CellValue<T>),
+}
+
+/// A single recalculation, evaluating the dirty cells on demand.
+struct Recalc<'a, T: Clone + PartialEq + 'static> {
+    inner: &'a Inner<T>,
+    contents: &'a [Content<T>],
+    states: RefCell<BTreeMap<usize, State<T>>>,
+    dependencies: RefCell<BTreeMap<usize, Vec<usize>>>,
+}
+
+impl<T: Clone + PartialEq + 'static> Recalc<'_, T> {
+    fn value(&self, index: usize) -> CellValue<T> {
+        match self.states.borrow().get(&index) {
+            None => return self.inner.values[index].get(),
+            Some(State::Evaluating) => return Err(SheetError::Cycle),
+            Some(State::Done(value)) => return value.clone(),
+            Some(State::Pending) => {}
+        }
+        self.evaluate(index)
+    }
+
+    fn evaluate(&self, index: usize) -> CellValue<T> {
+        self.states.borrow_mut().insert(index, State::Evaluating);
+        let (value, reads) = match &self.contents[index] {
+            Content::Value(value) => (Ok(value.clone()), Vec::new()),
+            Content::Formula(formula) => {
+                let cells = Cells {
+                    recalc: self,
+                    reads: RefCell::default(),
+                };
+                let value = formula(&cells);
+                (value, cells.reads.into_inner())
+            }
+        };
+        self.dependencies.borrow_mut().insert(index, reads);
+        self.states
+            .borrow_mut()
+            .insert(index, State::Done(value.clone()));
+        value
+    }
+}
+
+/// Read access to the cells of a [`Sheet`] from a formula.
+pub struct Cells<'a, T: Clone + PartialEq + 'static> {
+    recalc: &'a Recalc<'a, T>,
+    reads: RefCell<Vec<usize>>,
+}
+
+impl<T: Clone + PartialEq + 'static> fmt::Debug for Cells<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cells").finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Cells<'_, T> {
+    /// Returns the value of `cell`, making the formula depend on it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error `cell` evaluates to, [`SheetError::OutOfBounds`] if it
+    /// is outside of the sheet, or [`SheetError::Cycle`] if it depends on the
+    /// formula reading it.
+    pub fn get(&self, cell: CellRef) -> CellValue<T> {
+        let index = self.recalc.inner.index(cell)?;
+        self.reads.borrow_mut().push(index);
+        self.recalc.value(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_sheet_recalculates_dependents_once_and_reports_regions() {
+        let sheet = Sheet::<i32>::new(2, 2);
+        let [a1, b1, a2, b2] =
+            [(0, 0), (0, 1), (1, 0), (1, 1)].map(|(row, column)| CellRef::new(row, column));
+        let evaluations = Rc::new(RefCell::new(0));
+        sheet.set_formula(b1, move |cells| Ok(cells.get(a1)? + 1));
+        sheet.set_formula(a2, move |cells| Ok(cells.get(a1)? * 2));
+        {
+            let evaluations = evaluations.clone();
+            sheet.set_formula(b2, move |cells| {
+                *evaluations.borrow_mut() += 1;
+                Ok(cells.get(b1)? + cells.get(a2)?)
+            });
+        }
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let changes = changes.clone();
+            sheet.watch_region(Region::new(1..2, 0..2), move |context| {
+                changes.borrow_mut().push(context.value);
+            })
+        };
+
+        *evaluations.borrow_mut() = 0;
+        sheet.set_value(a1, 5);
+        assert_eq!(sheet.get(b2), Ok(16));
+        assert_eq!(*evaluations.borrow(), 1);
+        assert_eq!(*changes.borrow(), [vec![a2, b2]]);
+
+        sheet.set_formula(a1, move |cells| cells.get(b2));
+        assert_eq!(sheet.get(a2), Err(SheetError::Cycle));
+        sheet.set_formula(a1, move |cells| cells.get(CellRef::new(5, 0)));
+        assert_eq!(
+            sheet.get(b2),
+            Err(SheetError::OutOfBounds(CellRef::new(5, 0)))
+        );
+        sheet.set_value(a1, 1);
+        assert_eq!(sheet.get(b2), Ok(4));
+    }
+}
@@ -13,9 +13,15 @@
 mod computed;
 pub use computed::*;
 
+use alloc::{boxed::Box, rc::Rc, sync::Arc};
+use core::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
 use crate::{
     map::{Map, map},
-    watcher::{Context, WatcherGuard},
+    watcher::{Context, Metadata, WatcherGuard},
 };
 
 /// The core trait for reactive system.
@@ -36,6 +42,306 @@ pub trait Signal: Clone + 'static {
     /// Returns a guard that, when dropped, will unregister the watcher.
     #[must_use]
     fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard;
+
+    /// Register a watcher that is only notified when `filter` accepts the
+    /// metadata of a change, such as changes not made by the user.
+    ///
+    /// Stateful signals check the filter before producing the value, so
+    /// rejected changes are neither computed nor cloned for this watcher. By
+    /// default, the filter is checked inside a regular watcher.
+    #[must_use]
+    fn watch_filtered(
+        &self,
+        filter: impl Fn(&Metadata) -> bool + 'static,
+        watcher: impl Fn(Context<Self::Output>) + 'static,
+    ) -> Self::Guard {
+        self.watch(move |ctx| {
+            if filter(&ctx.metadata) {
+                watcher(ctx);
+            }
+        })
+    }
+
+    /// Returns the identity of the reactive node behind this signal, if it has one.
+    ///
+    /// Clones of a stateful signal, such as a [`Binding`](crate::Binding), share
+    /// its node and return the same id, while independently created signals never
+    /// do. Stateless signals such as maps and constants return `None`.
+    fn node_id(&self) -> Option<NodeId> {
+        None
+    }
+
+    /// Returns the number of watchers registered with the node behind this
+    /// signal, if it keeps track of them.
+    ///
+    /// Like [`node_id`](Self::node_id), this is reported by stateful signals;
+    /// stateless signals forward their watchers to their sources and return `None`.
+    fn watcher_count(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A stable identifier of a reactive node, shared by all clones of a signal.
+///
+/// Ids are unique for the lifetime of the process, so they can key caches and
+/// deduplicate subscriptions to the same underlying node.
+///
+/// ```rust
+/// use nami::{binding, Binding, Signal};
+///
+/// let a: Binding<i32> = binding(0);
+/// let b: Binding<i32> = binding(0);
+/// assert_eq!(a.node_id(), a.clone().node_id());
+/// assert_ne!(a.node_id(), b.node_id());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(NonZeroUsize);
+
+impl NodeId {
+    /// Allocates a new, unique id.
+    pub(crate) fn next() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(1);
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+        Self(NonZeroUsize::new(id).unwrap_or_else(|| panic!("`NodeId` overflowed")))
+    }
+
+    /// Returns the id as an integer.
+    #[must_use]
+    pub const fn get(self) -> usize {
+        self.0.get()
+    }
+}
+
+impl core::fmt::Display for NodeId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// The number of writes to stateful signals on this thread so far.
+    static GENERATION: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+
+    /// The number of writes to stateful signals on this thread so far that did
+    /// not notify their watchers right away.
+    static SILENT_GENERATION: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+/// The number of writes to stateful signals so far.
+#[cfg(not(feature = "std"))]
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of writes to stateful signals so far that did not notify their
+/// watchers right away.
+#[cfg(not(feature = "std"))]
+static SILENT_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Records a write to a stateful signal, starting a new generation of values.
+pub(crate) fn advance_generation() {
+    #[cfg(feature = "std")]
+    GENERATION.with(|generation| generation.set(generation.get().wrapping_add(1)));
+    #[cfg(not(feature = "std"))]
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// Returns the current generation of values.
+///
+/// The generation advances with every write to a binding, including silent
+/// writes that do not notify watchers. Two reads made in the same generation
+/// therefore observe the same state. With the `std` feature, only writes made
+/// on the current thread count, since the signals of a thread cannot be
+/// written from another one.
+#[must_use]
+pub fn generation() -> usize {
+    #[cfg(feature = "std")]
+    {
+        GENERATION.with(core::cell::Cell::get)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        GENERATION.load(Ordering::Acquire)
+    }
+}
+
+/// Records a write whose watchers are not notified right away, such as a write
+/// inside a [`sandbox`](crate::what_if::sandbox) or a batch.
+pub(crate) fn advance_silent_generation() {
+    #[cfg(feature = "std")]
+    SILENT_GENERATION.with(|generation| generation.set(generation.get().wrapping_add(1)));
+    #[cfg(not(feature = "std"))]
+    SILENT_GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// Returns the number of writes so far that did not notify their watchers
+/// right away.
+///
+/// Unlike [`generation`], it stays the same across writes that notify their
+/// watchers, so a cache marked dirty by the watchers of its sources stays
+/// valid as long as it does.
+pub(crate) fn silent_generation() -> usize {
+    #[cfg(feature = "std")]
+    {
+        SILENT_GENERATION.with(core::cell::Cell::get)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        SILENT_GENERATION.load(Ordering::Acquire)
+    }
+}
+
+/// The last notification started, identifying each notification uniquely.
+static LAST_NOTIFICATION: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// The innermost running notification of this thread, or zero.
+    static NOTIFICATION: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+
+    /// The notification the next one started on this thread joins, or zero.
+    static JOINED: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+/// The innermost running notification, or zero.
+#[cfg(not(feature = "std"))]
+static NOTIFICATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Replaces the innermost running notification, returning the previous one.
+fn replace_notification(id: usize) -> usize {
+    #[cfg(feature = "std")]
+    {
+        NOTIFICATION.with(|notification| notification.replace(id))
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        NOTIFICATION.swap(id, Ordering::AcqRel)
+    }
+}
+
+/// Returns `true` while watchers are being notified.
+pub(crate) fn is_notifying() -> bool {
+    notification() != 0
+}
+
+/// Returns the innermost running notification, or zero.
+fn notification() -> usize {
+    #[cfg(feature = "std")]
+    {
+        NOTIFICATION.with(core::cell::Cell::get)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        NOTIFICATION.load(Ordering::Acquire)
+    }
+}
+
+/// Allocates the id of a new notification.
+fn next_notification() -> usize {
+    LAST_NOTIFICATION.fetch_add(1, Ordering::AcqRel) + 1
+}
+
+/// Runs `f`, which notifies the watchers of a node, as a new notification.
+///
+/// Inside [`joined`], the first notification started joins the shared one
+/// instead.
+pub(crate) fn notifying<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(usize);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            replace_notification(self.0);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    let id = match JOINED.with(|joined| joined.replace(0)) {
+        0 => next_notification(),
+        joined => joined,
+    };
+    #[cfg(not(feature = "std"))]
+    let id = next_notification();
+    let _restore = Restore(replace_notification(id));
+    f()
+}
+
+/// Runs each of `notifications` as part of one shared notification, so a
+/// signal combining several of the notified nodes notifies its watchers once.
+///
+/// Only the notifications started directly by each closure are shared: those
+/// started by watchers in turn, such as when a watcher writes a binding, are
+/// new ones as usual.
+#[cfg(feature = "std")]
+pub(crate) fn joined(notifications: impl IntoIterator<Item = impl FnOnce()>) {
+    struct Clear;
+
+    impl Drop for Clear {
+        fn drop(&mut self) {
+            JOINED.with(|joined| joined.set(0));
+        }
+    }
+
+    let id = next_notification();
+    for notify in notifications {
+        let _clear = Clear;
+        JOINED.with(|joined| joined.set(id));
+        notify();
+    }
+}
+
+/// Returns `true` unless a watcher already saw the innermost running
+/// notification, as recorded in `seen`.
+///
+/// A signal combining several sources, such as a [`Zip`](crate::zip::Zip),
+/// uses it to notify once when a change reaches it through several of them.
+pub(crate) fn first_in_notification(seen: &core::cell::Cell<usize>) -> bool {
+    let id = notification();
+    if id != 0 && seen.get() == id {
+        return false;
+    }
+    seen.set(id);
+    true
+}
+
+/// The number of times [`read_all`] reads its signals before giving up on a
+/// consistent read.
+const READ_ATTEMPTS: usize = 8;
+
+/// Reads several signals at once, returning values from a single generation.
+///
+/// Pass a tuple of signals to read them together. If a binding is written while
+/// they are being read, for example by a computation with side effects, the
+/// read is repeated, so the values never mix state from before and after a
+/// write.
+///
+/// A computation writing a binding on every read would tear every attempt, so
+/// the read is only repeated a few times. The last attempt runs in a
+/// [`batch`](crate::batch::batch), holding back the notifications of the
+/// writes it causes until it is done, and its values are returned as they are.
+///
+/// ```rust
+/// use nami::{binding, Binding, SignalExt, signal::read_all};
+///
+/// let width: Binding<i32> = binding(2);
+/// let height: Binding<i32> = binding(3);
+/// let area = (width.clone(), height.clone()).map(|(w, h)| w * h);
+/// assert_eq!(read_all((width, height, area)), (2, 3, 6));
+/// ```
+pub fn read_all<S: Signal>(signals: S) -> S::Output {
+    for _ in 1..READ_ATTEMPTS {
+        let generation = generation();
+        let values = signals.get();
+        if self::generation() == generation {
+            return values;
+        }
+    }
+    #[cfg(feature = "std")]
+    {
+        crate::batch::batch(|| signals.get())
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        signals.get()
+    }
 }
 
 /// A trait for converting a value into a computation.
@@ -125,3 +431,155 @@ impl<C: Signal, T: Clone + 'static> Signal for WithMetadata<C, T> {
             })
     }
 }
+
+/// Implements `Signal` for pointer types by delegating to the pointee.
+///
+/// This lets APIs taking `impl Signal` accept shared or boxed handles directly.
+///
+/// ```rust
+/// use std::{rc::Rc, sync::Arc};
+/// use nami::{binding, Binding, Signal};
+///
+/// fn current<S: Signal<Output = i32>>(signal: S) -> i32 {
+///     signal.get()
+/// }
+///
+/// let value: Binding<i32> = binding(1);
+/// assert_eq!(current(Rc::new(value.clone())), 1);
+/// assert_eq!(current(Box::new(value.clone())), 1);
+/// assert_eq!(current(Arc::new(value.clone())), 1);
+///
+/// let leaked: &'static Binding<i32> = Box::leak(Box::new(value));
+/// assert_eq!(current(leaked), 1);
+/// ```
+macro_rules! impl_delegate {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<C: Signal> Signal for $ty {
+                type Output = C::Output;
+                type Guard = C::Guard;
+
+                fn get(&self) -> Self::Output {
+                    C::get(self)
+                }
+
+                fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+                    C::watch(self, watcher)
+                }
+
+                fn watch_filtered(
+                    &self,
+                    filter: impl Fn(&Metadata) -> bool + 'static,
+                    watcher: impl Fn(Context<Self::Output>) + 'static,
+                ) -> Self::Guard {
+                    C::watch_filtered(self, filter, watcher)
+                }
+
+                fn node_id(&self) -> Option<NodeId> {
+                    C::node_id(self)
+                }
+
+                fn watcher_count(&self) -> Option<usize> {
+                    C::watcher_count(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_delegate!(&'static C, Rc<C>, Box<C>, Arc<C>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, SignalExt, binding};
+    use core::cell::Cell;
+
+    /// A signal counting its reads, which writes `count` on the first
+    /// `writes` of them.
+    #[derive(Clone)]
+    struct Tearing {
+        count: Binding<usize>,
+        reads: Rc<Cell<usize>>,
+        writes: usize,
+    }
+
+    impl Signal for Tearing {
+        type Output = ();
+        type Guard = ();
+
+        fn get(&self) {
+            let reads = self.reads.get() + 1;
+            self.reads.set(reads);
+            if reads <= self.writes {
+                self.count.set(reads);
+            }
+        }
+
+        fn watch(&self, _watcher: impl Fn(Context<Self::Output>)) {}
+    }
+
+    #[test]
+    fn test_read_all_retries_torn_reads() {
+        let count: Binding<usize> = binding(0usize);
+        let reads = Rc::new(Cell::new(0));
+        let tearing = Tearing {
+            count: count.clone(),
+            reads: reads.clone(),
+            writes: 1,
+        };
+
+        assert_eq!(read_all((count, tearing)), (1, ()));
+        assert_eq!(reads.get(), 2);
+    }
+
+    #[test]
+    fn test_read_all_gives_up_on_endless_writes() {
+        let count: Binding<usize> = binding(0usize);
+        let reads = Rc::new(Cell::new(0));
+        let tearing = Tearing {
+            count: count.clone(),
+            reads: reads.clone(),
+            writes: usize::MAX,
+        };
+
+        assert_eq!(read_all((count.clone(), tearing)), (READ_ATTEMPTS - 1, ()));
+        assert_eq!(reads.get(), READ_ATTEMPTS);
+        assert_eq!(count.get(), READ_ATTEMPTS);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_generation_ignores_other_threads() {
+        let generation = generation();
+        std::thread::spawn(|| binding::<i32>(0).set(1))
+            .join()
+            .unwrap_or_else(|_| panic!("the writing thread panicked"));
+        assert_eq!(self::generation(), generation);
+    }
+
+    #[test]
+    fn test_diamonds_notify_once_per_change() {
+        let a: Binding<i32> = binding(1);
+        let b: Binding<i32> = binding(10);
+        let diamond = (
+            a.clone().map(|n| n + 1),
+            a.clone().map(|n| n * 2),
+            b.clone(),
+        );
+        let seen = Rc::new(core::cell::RefCell::new(alloc::vec::Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            diamond.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        // A watcher of `a` writing `b` is a separate change.
+        let _forward = {
+            let b = b.clone();
+            a.watch(move |context| b.set(context.value * 10))
+        };
+
+        a.set(2);
+        b.set(30);
+        assert_eq!(*seen.borrow(), [(3, 4, 10), (3, 4, 20), (3, 4, 30)]);
+    }
+}
@@ -1,21 +1,47 @@
-use core::{any::Any, ops::Add};
+use core::any::Any;
 
 use alloc::boxed::Box;
 
 use crate::{
     SignalExt, constant,
-    map::Map,
-    utils::add,
-    watcher::{BoxWatcher, BoxWatcherGuard, Context},
-    zip::Zip,
+    watcher::{BoxFilter, BoxWatcher, BoxWatcherGuard, Context, Metadata},
 };
 
-use super::Signal;
+use super::{NodeId, Signal};
 
 /// A wrapper around a boxed implementation of the `ComputedImpl` trait.
 ///
 /// This type represents a computation that can be evaluated to produce a result of type `T`.
 /// The computation is stored as a boxed trait object, allowing for dynamic dispatch.
+///
+/// Since it erases the type of the signal it wraps, it can hold signals built
+/// from different combinators in struct fields and collections, where their
+/// concrete types would be impossible to name. Create one with
+/// [`computed`](SignalExt::computed).
+///
+/// # Examples
+///
+/// ```rust
+/// use nami::{binding, Binding, Computed, Signal, SignalExt};
+///
+/// struct Labels {
+///     labels: Vec<Computed<String>>,
+/// }
+///
+/// let count: Binding<i32> = binding(2);
+/// let name: Binding<String> = binding("items");
+/// let state = Labels {
+///     labels: vec![
+///         count.clone().map(|n| n.to_string()).computed(),
+///         count.clone().zip(name).map(|(n, name)| format!("{n} {name}")).computed(),
+///         "fixed".to_string().computed(),
+///     ],
+/// };
+///
+/// count.set(3);
+/// let labels: Vec<String> = state.labels.iter().map(Signal::get).collect();
+/// assert_eq!(labels, ["3", "3 items", "fixed"]);
+/// ```
 pub struct Computed<T>(pub(crate) Box<dyn ComputedImpl<Output = T>>);
 
 /// Internal trait that defines the interface for computed values.
@@ -33,7 +59,21 @@ pub(crate) trait ComputedImpl: Any {
     /// Registers a watcher that will be notified when the computed value changes
     fn add_watcher(&self, watcher: BoxWatcher<Self::Output>) -> BoxWatcherGuard;
 
+    /// Registers a watcher that is only notified of changes whose metadata
+    /// `filter` accepts
+    fn add_watcher_filtered(
+        &self,
+        filter: BoxFilter,
+        watcher: BoxWatcher<Self::Output>,
+    ) -> BoxWatcherGuard;
+
     fn cloned(&self) -> Computed<Self::Output>;
+
+    /// Returns the identity of the underlying node, if it has one
+    fn node_id(&self) -> Option<NodeId>;
+
+    /// Returns the number of watchers of the underlying node, if it tracks them
+    fn watcher_count(&self) -> Option<usize>;
 }
 
 /// Implements `ComputedImpl` for any type that implements `Compute`.
@@ -50,24 +90,25 @@ impl<C: Signal + 'static> ComputedImpl for C {
     fn add_watcher(&self, watcher: BoxWatcher<Self::Output>) -> BoxWatcherGuard {
         Box::new(<Self as Signal>::watch(self, watcher))
     }
+
+    fn add_watcher_filtered(
+        &self,
+        filter: BoxFilter,
+        watcher: BoxWatcher<Self::Output>,
+    ) -> BoxWatcherGuard {
+        Box::new(<Self as Signal>::watch_filtered(self, filter, watcher))
+    }
+
     fn cloned(&self) -> Computed<Self::Output> {
         self.clone().computed()
     }
-}
 
-impl<T, C2> Add<C2> for Computed<T>
-where
-    C2: Signal,
-    T: Add<C2::Output> + 'static,
-{
-    type Output = Map<
-        Zip<Self, C2>,
-        fn((T, <C2 as Signal>::Output)) -> <T as Add<<C2 as Signal>::Output>>::Output,
-        <T as Add<<C2 as Signal>::Output>>::Output,
-    >;
+    fn node_id(&self) -> Option<NodeId> {
+        <Self as Signal>::node_id(self)
+    }
 
-    fn add(self, rhs: C2) -> Self::Output {
-        add(self, rhs)
+    fn watcher_count(&self) -> Option<usize> {
+        <Self as Signal>::watcher_count(self)
     }
 }
 
@@ -82,10 +123,11 @@ impl<T: 'static + Clone + Default> Default for Computed<T> {
 
 /// Implements `Debug` for `Computed<T>`.
 ///
-/// This just outputs the type name rather than any internal details.
-impl<T> core::fmt::Debug for Computed<T> {
+/// This outputs the node id, output type and watcher count of the underlying
+/// signal. The value is left out, since computing it may be expensive.
+impl<T: 'static> core::fmt::Debug for Computed<T> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.write_str(core::any::type_name::<Self>())
+        crate::debug::fmt_node(f, "Computed", self, None)
     }
 }
 
@@ -103,6 +145,23 @@ impl<T: 'static> Signal for Computed<T> {
     fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
         self.0.add_watcher(Box::new(watcher))
     }
+
+    fn watch_filtered(
+        &self,
+        filter: impl Fn(&Metadata) -> bool + 'static,
+        watcher: impl Fn(Context<Self::Output>) + 'static,
+    ) -> Self::Guard {
+        self.0
+            .add_watcher_filtered(Box::new(filter), Box::new(watcher))
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.0.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.0.watcher_count()
+    }
 }
 
 impl<T: 'static> Clone for Computed<T> {
@@ -131,3 +190,15 @@ impl<T: 'static + Clone> Computed<T> {
         Self::new(constant(value))
     }
 }
+
+impl<T: 'static> Computed<T> {
+    /// Returns `true` if both computed values are handles to the same node.
+    ///
+    /// Computed values without a [`node_id`](Signal::node_id), such as maps, are
+    /// never equal.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        let id = self.0.node_id();
+        id.is_some() && id == other.0.node_id()
+    }
+}
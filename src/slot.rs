@@ -0,0 +1,162 @@
+//! # Binding slots
+//!
+//! A [`BindingSlot`] holds a replaceable binding, such as the binding of the
+//! title of whichever document is open. Watchers of the slot follow the binding
+//! currently assigned to it, so swapping the binding with
+//! [`assign`](BindingSlot::assign) does not require registering them again.
+//!
+//! Reading the slot reads the current binding, and setting the slot sets it.
+//! Assigning a binding stops following the previous one before notifying the
+//! watchers of the slot with the value of the new one.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::{cell::RefCell, rc::Rc};
+//! use nami::{binding, Binding, CustomBinding, Signal, slot::BindingSlot};
+//!
+//! let draft: Binding<String> = binding("Draft");
+//! let report: Binding<String> = binding("Report");
+//! let title = BindingSlot::new(draft.clone());
+//!
+//! let shown = Rc::new(RefCell::new(Vec::new()));
+//! let _guard = {
+//!     let shown = shown.clone();
+//!     title.watch(move |context| shown.borrow_mut().push(context.value))
+//! };
+//!
+//! title.assign(report.clone());
+//! draft.set("Old draft");
+//! title.set("Quarterly report".to_string());
+//!
+//! assert_eq!(*shown.borrow(), ["Report", "Quarterly report"]);
+//! assert_eq!(report.get(), "Quarterly report");
+//! ```
+
+use alloc::rc::Rc;
+use core::{cell::RefCell, fmt};
+
+use crate::{
+    Binding, Container, CustomBinding, NodeId, Signal,
+    watcher::{BoxWatcherGuard, Context},
+};
+
+/// A replaceable binding whose watchers follow the binding assigned to it.
+///
+/// All clones share the same assigned binding and watchers. See the
+/// [module documentation](self) for how it behaves.
+#[derive(Clone)]
+pub struct BindingSlot<T: Clone + 'static> {
+    container: Container<T>,
+    current: Rc<RefCell<Assigned<T>>>,
+}
+
+/// The binding assigned to a slot, with the watcher forwarding its values.
+struct Assigned<T: 'static> {
+    binding: Binding<T>,
+    _guard: BoxWatcherGuard,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for BindingSlot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BindingSlot")
+            .field("value", &self.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + 'static> BindingSlot<T> {
+    /// Creates a slot holding `binding`.
+    #[must_use]
+    pub fn new(binding: Binding<T>) -> Self {
+        let container = Container::new(binding.get());
+        let current = Rc::new(RefCell::new(follow(binding, &container)));
+        Self { container, current }
+    }
+
+    /// Assigns `binding` to the slot, notifying its watchers with the value of
+    /// `binding`.
+    pub fn assign(&self, binding: Binding<T>) {
+        let value = binding.get();
+        let assigned = follow(binding, &self.container);
+        // The previous binding stops notifying before the watchers are.
+        let previous = self.current.replace(assigned);
+        drop(previous);
+        self.container.set(value);
+    }
+
+    /// Returns the binding currently assigned to the slot.
+    #[must_use]
+    pub fn binding(&self) -> Binding<T> {
+        self.current.borrow().binding.clone()
+    }
+}
+
+/// Forwards the values of `binding` into `container`.
+fn follow<T: Clone + 'static>(binding: Binding<T>, container: &Container<T>) -> Assigned<T> {
+    let container = container.clone();
+    let guard = binding.watch(move |context: Context<T>| container.set(context.value));
+    Assigned {
+        binding,
+        _guard: guard,
+    }
+}
+
+impl<T: Clone + 'static> Signal for BindingSlot<T> {
+    type Output = T;
+    type Guard = <Container<T> as Signal>::Guard;
+
+    fn get(&self) -> Self::Output {
+        self.binding().get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.container.watch(watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.container.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.container.watcher_count()
+    }
+}
+
+impl<T: Clone + 'static> CustomBinding for BindingSlot<T> {
+    /// Sets the binding currently assigned to the slot.
+    fn set(&self, value: T) {
+        self.binding().set(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SignalExt, binding};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_slot_watchers_follow_the_assigned_binding() {
+        let first: Binding<i32> = binding(1);
+        let second: Binding<i32> = binding(2);
+        let slot = BindingSlot::new(first.clone());
+        let doubled = slot.clone().map(|n| n * 2);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            doubled.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        first.set(3);
+        slot.assign(second.clone());
+        first.set(4);
+        second.set(5);
+        let binding = Binding::custom(slot);
+        binding.set(6);
+
+        assert_eq!(*seen.borrow(), [6, 4, 10, 12]);
+        assert_eq!((first.get(), second.get(), doubled.get()), (4, 6, 12));
+        assert_eq!(first.watcher_count(), Some(0));
+    }
+}
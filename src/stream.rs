@@ -5,20 +5,25 @@
 //! - `StreamSignal<S>`: expose the latest `S::Item` from a stream as a
 //!   `Signal<Output = Option<S::Item>>`.
 //! - `SignalStream<S>`: expose a `Signal<Output = T>` as a
-//!   `Stream<Item = T>` that yields on updates.
+//!   `Stream<Item = T>` that yields on updates, coalescing the updates a slow
+//!   consumer missed, and ending once the signal is dropped. Create it with
+//!   `signal.to_stream()`.
 //!
 //! These adapters are useful when bridging async event sources with
 //! reactive computations, or when a consumer expects a `Stream` API.
 //!
 //! Note: the crate is `no_std` and relies on `alloc`.
 
-use core::pin::Pin;
+use core::{
+    cell::RefCell,
+    pin::Pin,
+    task::{Context as TaskContext, Poll, Waker},
+};
 
-use alloc::boxed::Box;
+use alloc::rc::Rc;
 use futures_core::Stream;
-use pin_project_lite::pin_project;
 
-use crate::{Container, Signal};
+use crate::{Container, Signal, watcher::Context};
 
 /// A `Signal` backed by a stream that holds the latest item.
 ///
@@ -66,41 +71,123 @@ where
     }
 }
 
-pin_project! {
-/// A `Stream` view over a `Signal`.
+/// A `Stream` of the values of a `Signal`, created by
+/// [`SignalExt::to_stream`](crate::SignalExt::to_stream) or
+/// [`SignalStream::new`].
 ///
-/// Produces the current value on each wake triggered by watcher notifications.
+/// The first poll yields the current value and starts watching the signal.
+/// Later polls yield the value of the latest notification, or wait for the
+/// next one: updates arriving faster than the stream is polled are coalesced,
+/// so a slow consumer only sees the most recent value.
+///
+/// Once watching, the stream no longer holds the signal, and ends when the
+/// signal drops its watcher, such as when every handle to a binding was
+/// dropped. Dropping the stream stops watching the signal.
+///
+/// ```rust
+/// use core::{pin::pin, task::{Context, Poll, Waker}};
+/// use futures_core::Stream;
+/// use nami::{binding, Binding, SignalExt};
+///
+/// let count: Binding<i32> = binding(0);
+/// let mut stream = pin!(count.clone().to_stream());
+/// let mut cx = Context::from_waker(Waker::noop());
+///
+/// assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(0)));
+/// assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Pending);
+/// count.set(1);
+/// count.set(2);
+/// assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(2)));
+/// drop(count);
+/// assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(None));
+/// ```
 pub struct SignalStream<S>
 where
     S: Signal,
 {
-    signal: S,
-    #[pin]
-    guard: Option<Pin<Box<S::Guard>>>,
+    signal: Option<S>,
+    shared: Rc<RefCell<Pending<S::Output>>>,
+    guard: Option<S::Guard>,
 }
 
+/// The latest notified value not yielded yet, and the task waiting for one.
+struct Pending<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    closed: bool,
 }
 
-impl<S: Signal> Stream for SignalStream<S> {
-    type Item = S::Output;
-    fn poll_next(
-        self: core::pin::Pin<&mut Self>,
-        cx: &mut core::task::Context<'_>,
-    ) -> core::task::Poll<Option<Self::Item>> {
-        let waker = cx.waker().clone();
+/// Held by the watcher of a [`SignalStream`], closing the stream once the
+/// signal drops the watcher.
+struct Close<T>(Rc<RefCell<Pending<T>>>);
+
+impl<T> Drop for Close<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut pending = self.0.borrow_mut();
+            pending.closed = true;
+            pending.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<S: Signal> core::fmt::Debug for SignalStream<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SignalStream")
+            .field("watching", &self.guard.is_some())
+            .field("closed", &self.shared.borrow().closed)
+            .finish_non_exhaustive()
+    }
+}
 
-        let project = self.project();
+impl<S: Signal> SignalStream<S> {
+    /// Creates a stream of the values of `signal`.
+    pub fn new(signal: S) -> Self {
+        Self {
+            signal: Some(signal),
+            shared: Rc::new(RefCell::new(Pending {
+                value: None,
+                waker: None,
+                closed: false,
+            })),
+            guard: None,
+        }
+    }
+}
 
-        let mut guard = project.guard;
+impl<S: Signal> Unpin for SignalStream<S> {}
 
-        let signal = project.signal;
+impl<S: Signal> Stream for SignalStream<S> {
+    type Item = S::Output;
 
-        guard.get_or_insert_with(|| {
-            Box::pin(signal.watch(move |_| {
-                waker.wake_by_ref();
-            }))
-        });
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(signal) = this.signal.take() {
+            let close = Close(this.shared.clone());
+            this.guard = Some(signal.watch(move |context: Context<S::Output>| {
+                let waker = {
+                    let mut pending = close.0.borrow_mut();
+                    pending.value = Some(context.value);
+                    pending.waker.take()
+                };
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            }));
+            return Poll::Ready(Some(signal.get()));
+        }
 
-        core::task::Poll::Ready(Some(signal.get()))
+        let mut pending = this.shared.borrow_mut();
+        if let Some(value) = pending.value.take() {
+            Poll::Ready(Some(value))
+        } else if pending.closed {
+            Poll::Ready(None)
+        } else {
+            pending.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
     }
 }
@@ -0,0 +1,844 @@
+//! # Thread-safe signals
+//!
+//! The signals of this crate are built on `Rc` for speed, so they cannot cross
+//! threads. This module is a smaller parallel API built on `Arc` and `Mutex`,
+//! for state shared between threads, such as in a multithreaded server or
+//! between async tasks running on a thread pool:
+//!
+//! - [`SyncSignal`] is the thread-safe counterpart of [`Signal`](crate::Signal).
+//!   Watchers receive the new value directly, and may be called from whichever
+//!   thread changed it.
+//! - [`SyncBinding`], created by [`binding`], is a mutable value. Concurrent
+//!   writers can coordinate through its
+//!   [`compare_and_set`](SyncBinding::compare_and_set) and
+//!   [`fetch_update`](SyncBinding::fetch_update).
+//! - [`SyncSignalExt`] provides [`map`](SyncSignalExt::map),
+//!   [`zip`](SyncSignalExt::zip) and [`computed`](SyncSignalExt::computed),
+//!   type-erasing a signal into a [`SyncComputed`].
+//!
+//! Watchers are called after the lock of the binding is released, so they may
+//! read and write bindings, including the one that notified them.
+//!
+//! # Flushes and snapshots
+//!
+//! Every write belongs to a flush, which publishes its writes to other threads
+//! all at once when it ends: a write on its own is a flush of one, and
+//! [`flush`] groups the writes of a closure. While a flush is running, other
+//! threads keep reading the values from before it, so they never observe some
+//! of its writes but not others, and reads never wait for it. Flushes run one
+//! at a time; a thread writing during another thread's flush waits for it.
+//!
+//! A [`Snapshot`] goes further and pins the values published when it was
+//! taken: reads in [`Snapshot::read`], including through derived signals, see
+//! a consistent view of every binding for as long as the snapshot is alive,
+//! whatever flushes complete in the meantime.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::sync::mpsc;
+//! use nami::sync::{self, SyncSignal, SyncSignalExt};
+//!
+//! let requests = sync::binding(0u32);
+//! let label = requests.clone().map(|n| format!("{n} requests"));
+//!
+//! let (sender, receiver) = mpsc::channel();
+//! let _guard = label.watch(move |label| sender.send(label).unwrap());
+//!
+//! std::thread::spawn(move || requests.set(3)).join().unwrap();
+//! assert_eq!(receiver.recv().unwrap(), "3 requests");
+//! assert_eq!(label.get(), "3 requests");
+//! ```
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{
+    cell::{Cell, RefCell},
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// The version of the last published flush.
+static PUBLISHED: AtomicU64 = AtomicU64::new(0);
+
+/// Held by the running flush, so flushes run one at a time.
+static FLUSH_LOCK: Mutex<()> = Mutex::new(());
+
+/// The versions pinned by live snapshots, with how many snapshots pin each.
+static SNAPSHOTS: Mutex<BTreeMap<u64, usize>> = Mutex::new(BTreeMap::new());
+
+/// The writes of the flush running on a thread.
+struct Flush {
+    /// The version the flush publishes.
+    version: u64,
+    /// How to notify the watchers of each write, in write order.
+    notify: Vec<Box<dyn FnOnce()>>,
+    /// How to undo the writes, if the flush panics.
+    rollback: Vec<Box<dyn FnOnce()>>,
+}
+
+std::thread_local! {
+    static FLUSH: RefCell<Option<Flush>> = const { RefCell::new(None) };
+    /// The version read by this thread, if it is in a flush or a snapshot.
+    static VIEW: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Returns the version read by the current thread.
+fn view() -> u64 {
+    VIEW.get()
+        .unwrap_or_else(|| PUBLISHED.load(Ordering::Acquire))
+}
+
+/// Restores the version read by the current thread when dropped.
+struct RestoreView(Option<u64>);
+
+impl Drop for RestoreView {
+    fn drop(&mut self) {
+        VIEW.set(self.0);
+    }
+}
+
+/// Runs `f` in a flush and returns its result.
+///
+/// The writes in `f` are published to other threads at once when it returns,
+/// and their watchers are notified then, in write order. Until then, other
+/// threads read the values from before the flush, while `f` reads its own
+/// writes. Nested flushes are part of the outermost one. If `f` panics, its
+/// writes are undone and no watcher is notified.
+///
+/// Writes from other threads wait for the flush to end, so `f` must not wait
+/// for them.
+///
+/// # Examples
+///
+/// ```rust
+/// use nami::sync::{self, SyncSignal};
+///
+/// let from = sync::binding(100);
+/// let to = sync::binding(0);
+/// sync::flush(|| {
+///     from.update(|balance| *balance -= 30);
+///     to.update(|balance| *balance += 30);
+/// });
+/// assert_eq!(from.get() + to.get(), 100);
+/// ```
+pub fn flush<R>(f: impl FnOnce() -> R) -> R {
+    /// Restores the view of the thread, and undoes the writes of the flush
+    /// unless they were published.
+    struct Abort(Option<u64>);
+
+    impl Drop for Abort {
+        fn drop(&mut self) {
+            VIEW.set(self.0);
+            if let Some(flush) = FLUSH.take() {
+                for rollback in flush.rollback.into_iter().rev() {
+                    rollback();
+                }
+            }
+        }
+    }
+
+    if FLUSH.with_borrow(Option::is_some) {
+        return f();
+    }
+    let lock = FLUSH_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let version = PUBLISHED.load(Ordering::Acquire) + 1;
+    FLUSH.set(Some(Flush {
+        version,
+        notify: Vec::new(),
+        rollback: Vec::new(),
+    }));
+    let abort = Abort(VIEW.replace(Some(version)));
+    let result = f();
+    let notify = FLUSH.take().map(|flush| flush.notify).unwrap_or_default();
+    PUBLISHED.store(version, Ordering::Release);
+    drop(abort);
+    drop(lock);
+    for notify in notify {
+        notify();
+    }
+    result
+}
+
+/// A consistent view of every thread-safe binding, as published when the
+/// snapshot was taken.
+///
+/// The values of the view are kept for as long as the snapshot is alive, so
+/// long-lived snapshots hold on to the values written since.
+#[derive(Debug)]
+pub struct Snapshot {
+    version: u64,
+}
+
+impl Default for Snapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Snapshot {
+    /// Takes a snapshot of the published values.
+    ///
+    /// Taken during a flush, on the thread running it, the snapshot does not
+    /// include the writes of the flush.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut snapshots = SNAPSHOTS.lock().unwrap_or_else(PoisonError::into_inner);
+        let version = PUBLISHED.load(Ordering::Acquire);
+        *snapshots.entry(version).or_default() += 1;
+        drop(snapshots);
+        Self { version }
+    }
+
+    /// Runs `f`, reading the values of this snapshot.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nami::sync::{self, Snapshot, SyncSignal};
+    ///
+    /// let count = sync::binding(1);
+    /// let snapshot = Snapshot::new();
+    /// count.set(2);
+    /// assert_eq!(snapshot.read(|| count.get()), 1);
+    /// assert_eq!(count.get(), 2);
+    /// ```
+    pub fn read<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _restore = RestoreView(VIEW.replace(Some(self.version)));
+        f()
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        let mut snapshots = SNAPSHOTS.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(count) = snapshots.get_mut(&self.version) {
+            *count -= 1;
+            if *count == 0 {
+                snapshots.remove(&self.version);
+            }
+        }
+    }
+}
+
+/// A thread-safe watcher, called with each new value.
+pub type SyncWatcher<T> = Arc<dyn Fn(T) + Send + Sync>;
+
+/// A guard unregistering a thread-safe watcher when dropped.
+#[must_use]
+pub struct SyncWatcherGuard(Option<Box<dyn FnOnce() + Send + Sync>>);
+
+impl SyncWatcherGuard {
+    /// Creates a guard running `cancel` when dropped.
+    pub fn new(cancel: impl FnOnce() + Send + Sync + 'static) -> Self {
+        Self(Some(Box::new(cancel)))
+    }
+
+    /// Creates a guard that does nothing when dropped.
+    pub const fn empty() -> Self {
+        Self(None)
+    }
+}
+
+impl fmt::Debug for SyncWatcherGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncWatcherGuard").finish_non_exhaustive()
+    }
+}
+
+impl Drop for SyncWatcherGuard {
+    fn drop(&mut self) {
+        if let Some(cancel) = self.0.take() {
+            cancel();
+        }
+    }
+}
+
+/// A thread-safe reactive value.
+///
+/// See the [module documentation](self) for how it differs from
+/// [`Signal`](crate::Signal).
+pub trait SyncSignal: Clone + Send + Sync + 'static {
+    /// The type of value produced by this signal.
+    type Output: Send + 'static;
+
+    /// Returns the current value.
+    fn get(&self) -> Self::Output;
+
+    /// Registers a watcher to be notified with each new value.
+    ///
+    /// The watcher may be called from any thread.
+    fn watch(&self, watcher: impl Fn(Self::Output) + Send + Sync + 'static) -> SyncWatcherGuard;
+}
+
+/// Extension trait providing combinators for all [`SyncSignal`] types.
+pub trait SyncSignalExt: SyncSignal + Sized {
+    /// Transforms the output of this signal using `f`.
+    fn map<F, Output>(self, f: F) -> SyncMap<Self, F>
+    where
+        F: Fn(Self::Output) -> Output + Send + Sync + 'static,
+        Output: Send + 'static,
+    {
+        SyncMap {
+            source: self,
+            f: Arc::new(f),
+        }
+    }
+
+    /// Combines this signal with another into a signal of both outputs.
+    fn zip<B: SyncSignal>(self, b: B) -> SyncZip<Self, B> {
+        SyncZip { a: self, b }
+    }
+
+    /// Type-erases this signal into a [`SyncComputed`].
+    fn computed(self) -> SyncComputed<Self::Output> {
+        SyncComputed(Arc::new(self))
+    }
+}
+
+impl<S: SyncSignal> SyncSignalExt for S {}
+
+/// Creates a thread-safe binding holding `value`.
+pub fn binding<T: Clone + Send + 'static>(value: T) -> SyncBinding<T> {
+    SyncBinding::new(value)
+}
+
+/// A thread-safe mutable value.
+///
+/// All clones share the same value and watchers.
+pub struct SyncBinding<T> {
+    inner: Arc<Mutex<Inner<T>>>,
+}
+
+struct Inner<T> {
+    value: T,
+    /// The version of the flush that wrote `value`.
+    version: u64,
+    /// The previous values still visible to a thread, oldest first.
+    history: VecDeque<(u64, T)>,
+    watchers: BTreeMap<u64, SyncWatcher<T>>,
+    conflicts: BTreeMap<u64, ConflictCallback<T>>,
+    next_id: u64,
+}
+
+/// A callback told about the failed compare-and-sets of a binding.
+type ConflictCallback<T> = Arc<dyn Fn(&Conflict<T>) + Send + Sync>;
+
+/// A compare-and-set on a [`SyncBinding`] that failed because another writer
+/// changed the value first.
+///
+/// Reported to the callbacks registered with [`SyncBinding::on_conflict`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Conflict<T> {
+    /// The value the writer expected.
+    pub expected: T,
+    /// The value the binding actually held.
+    pub actual: T,
+}
+
+impl<T> Clone for SyncBinding<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SyncBinding<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncBinding")
+            .field("value", self.lock().visible(view()))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + Send + Default + 'static> Default for SyncBinding<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> Inner<T> {
+    /// Returns the value as of `version`.
+    fn visible(&self, version: u64) -> &T {
+        if self.version <= version {
+            return &self.value;
+        }
+        // A plain read that loaded the published version just before a write
+        // pruned its value gets the oldest value left.
+        self.history
+            .iter()
+            .rev()
+            .find(|(written, _)| *written <= version)
+            .or_else(|| self.history.front())
+            .map_or(&self.value, |(_, value)| value)
+    }
+}
+
+impl<T> SyncBinding<T> {
+    fn lock(&self) -> MutexGuard<'_, Inner<T>> {
+        // A panicking watcher is called without the lock, so the value is
+        // never left half-written.
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Clone + Send + 'static> SyncBinding<T> {
+    /// Creates a binding holding `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                value,
+                version: 0,
+                history: VecDeque::new(),
+                watchers: BTreeMap::new(),
+                conflicts: BTreeMap::new(),
+                next_id: 0,
+            })),
+        }
+    }
+
+    /// Sets a new value, notifying watchers.
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+
+    /// Sets the value to `new` if it is equal to `expected`, notifying
+    /// watchers.
+    ///
+    /// The comparison and the write are atomic with respect to every other
+    /// write, since they run in one [`flush`]. On a mismatch, the binding is
+    /// left unchanged and the [conflict callbacks](Self::on_conflict) are
+    /// called.
+    ///
+    /// # Errors
+    ///
+    /// Returns the current value if it is not equal to `expected`.
+    pub fn compare_and_set(&self, expected: &T, new: T) -> Result<(), T>
+    where
+        T: PartialEq,
+    {
+        let result = flush(|| {
+            let current = self.get();
+            if current == *expected {
+                self.set(new);
+                Ok(())
+            } else {
+                Err(current)
+            }
+        });
+        if let Err(actual) = &result {
+            let conflict = Conflict {
+                expected: expected.clone(),
+                actual: actual.clone(),
+            };
+            let callbacks: Vec<ConflictCallback<T>> =
+                self.lock().conflicts.values().cloned().collect();
+            for callback in callbacks {
+                callback(&conflict);
+            }
+        }
+        result
+    }
+
+    /// Sets the value to `f(current)`, retrying with the new value whenever
+    /// another writer changed it while `f` was running.
+    ///
+    /// `f` runs without blocking other writers, so it may be called several
+    /// times, and each retry is reported as a [conflict](Self::on_conflict).
+    ///
+    /// # Errors
+    ///
+    /// Returns the current value if `f` returns `None`, leaving the binding
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use nami::sync::{self, SyncSignal};
+    ///
+    /// let stock = sync::binding(3u32);
+    /// let workers: Vec<_> = (0..5)
+    ///     .map(|_| {
+    ///         let stock = stock.clone();
+    ///         std::thread::spawn(move || stock.fetch_update(|n| n.checked_sub(1)).is_ok())
+    ///     })
+    ///     .collect();
+    /// let sold = workers
+    ///     .into_iter()
+    ///     .map(|worker| worker.join().unwrap())
+    ///     .filter(|&sold| sold)
+    ///     .count();
+    /// assert_eq!((sold, stock.get()), (3, 0));
+    /// ```
+    pub fn fetch_update(&self, mut f: impl FnMut(&T) -> Option<T>) -> Result<T, T>
+    where
+        T: PartialEq,
+    {
+        let mut current = self.get();
+        loop {
+            let Some(new) = f(&current) else {
+                return Err(current);
+            };
+            match self.compare_and_set(&current, new) {
+                Ok(()) => return Ok(current),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Registers `callback` to be called with every failed
+    /// [`compare_and_set`](Self::compare_and_set), including the retries of
+    /// [`fetch_update`](Self::fetch_update).
+    ///
+    /// The callback is called on the thread of the failed write, without the
+    /// lock of the binding held.
+    pub fn on_conflict(
+        &self,
+        callback: impl Fn(&Conflict<T>) + Send + Sync + 'static,
+    ) -> SyncWatcherGuard {
+        let id = {
+            let mut inner = self.lock();
+            let id = inner.next_id;
+            inner.next_id += 1;
+            inner.conflicts.insert(id, Arc::new(callback));
+            id
+        };
+        let inner = Arc::downgrade(&self.inner);
+        SyncWatcherGuard::new(move || {
+            if let Some(inner) = inner.upgrade() {
+                inner
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .conflicts
+                    .remove(&id);
+            }
+        })
+    }
+
+    /// Modifies the value in place with `f`, notifying watchers.
+    ///
+    /// The write is part of the running [`flush`], if any.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        flush(|| {
+            let Some(version) =
+                FLUSH.with_borrow(|flush| flush.as_ref().map(|flush| flush.version))
+            else {
+                return;
+            };
+            let (value, watchers, first_write) = {
+                let mut inner = self.lock();
+                let first_write = inner.version != version;
+                if first_write {
+                    let oldest = SNAPSHOTS
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .keys()
+                        .next()
+                        .copied()
+                        .unwrap_or(version - 1);
+                    let previous = (inner.version, inner.value.clone());
+                    inner.history.push_back(previous);
+                    // Only the newest value as of the oldest pinned version, and
+                    // those after it, can still be read.
+                    while inner
+                        .history
+                        .get(1)
+                        .is_some_and(|(written, _)| *written <= oldest)
+                    {
+                        inner.history.pop_front();
+                    }
+                    inner.version = version;
+                }
+                f(&mut inner.value);
+                let watchers: Vec<SyncWatcher<T>> = inner.watchers.values().cloned().collect();
+                (inner.value.clone(), watchers, first_write)
+            };
+            FLUSH.with_borrow_mut(|flush| {
+                let Some(flush) = flush else {
+                    return;
+                };
+                if first_write {
+                    let inner = self.inner.clone();
+                    flush.rollback.push(Box::new(move || {
+                        let mut inner = inner.lock().unwrap_or_else(PoisonError::into_inner);
+                        if let Some((version, value)) = inner.history.pop_back() {
+                            inner.version = version;
+                            inner.value = value;
+                        }
+                    }));
+                }
+                flush.notify.push(Box::new(move || {
+                    for watcher in watchers {
+                        watcher(value.clone());
+                    }
+                }));
+            });
+        });
+    }
+}
+
+impl<T: Clone + Send + 'static> SyncSignal for SyncBinding<T> {
+    type Output = T;
+
+    fn get(&self) -> T {
+        self.lock().visible(view()).clone()
+    }
+
+    fn watch(&self, watcher: impl Fn(T) + Send + Sync + 'static) -> SyncWatcherGuard {
+        let id = {
+            let mut inner = self.lock();
+            let id = inner.next_id;
+            inner.next_id += 1;
+            inner.watchers.insert(id, Arc::new(watcher));
+            id
+        };
+        let inner = Arc::downgrade(&self.inner);
+        SyncWatcherGuard::new(move || {
+            if let Some(inner) = inner.upgrade() {
+                inner
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .watchers
+                    .remove(&id);
+            }
+        })
+    }
+}
+
+/// A thread-safe signal transforming the output of another.
+///
+/// Created by [`SyncSignalExt::map`]. The function runs on every read and
+/// notification.
+pub struct SyncMap<S, F> {
+    source: S,
+    f: Arc<F>,
+}
+
+impl<S: Clone, F> Clone for SyncMap<S, F> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            f: self.f.clone(),
+        }
+    }
+}
+
+impl<S, F> fmt::Debug for SyncMap<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(core::any::type_name::<Self>())
+    }
+}
+
+impl<S, F, Output> SyncSignal for SyncMap<S, F>
+where
+    S: SyncSignal,
+    F: Fn(S::Output) -> Output + Send + Sync + 'static,
+    Output: Send + 'static,
+{
+    type Output = Output;
+
+    fn get(&self) -> Output {
+        (self.f)(self.source.get())
+    }
+
+    fn watch(&self, watcher: impl Fn(Output) + Send + Sync + 'static) -> SyncWatcherGuard {
+        let f = self.f.clone();
+        self.source.watch(move |value| watcher(f(value)))
+    }
+}
+
+/// A thread-safe signal combining the outputs of two others.
+///
+/// Created by [`SyncSignalExt::zip`].
+#[derive(Clone)]
+pub struct SyncZip<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> fmt::Debug for SyncZip<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(core::any::type_name::<Self>())
+    }
+}
+
+impl<A: SyncSignal, B: SyncSignal> SyncSignal for SyncZip<A, B>
+where
+    A::Output: Clone,
+    B::Output: Clone,
+{
+    type Output = (A::Output, B::Output);
+
+    fn get(&self) -> Self::Output {
+        (self.a.get(), self.b.get())
+    }
+
+    fn watch(&self, watcher: impl Fn(Self::Output) + Send + Sync + 'static) -> SyncWatcherGuard {
+        let watcher = Arc::new(watcher);
+        let guard_a = {
+            let (b, watcher) = (self.b.clone(), watcher.clone());
+            self.a.watch(move |a| watcher((a, b.get())))
+        };
+        let guard_b = {
+            let a = self.a.clone();
+            self.b.watch(move |b| watcher((a.get(), b)))
+        };
+        SyncWatcherGuard::new(move || drop((guard_a, guard_b)))
+    }
+}
+
+/// A type-erased thread-safe signal.
+///
+/// Created by [`SyncSignalExt::computed`].
+pub struct SyncComputed<T>(Arc<dyn ErasedSyncSignal<T>>);
+
+impl<T> Clone for SyncComputed<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for SyncComputed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(core::any::type_name::<Self>())
+    }
+}
+
+trait ErasedSyncSignal<T>: Send + Sync {
+    fn erased_get(&self) -> T;
+    fn erased_watch(&self, watcher: SyncWatcher<T>) -> SyncWatcherGuard;
+}
+
+impl<S: SyncSignal> ErasedSyncSignal<S::Output> for S {
+    fn erased_get(&self) -> S::Output {
+        SyncSignal::get(self)
+    }
+
+    fn erased_watch(&self, watcher: SyncWatcher<S::Output>) -> SyncWatcherGuard {
+        SyncSignal::watch(self, move |value| watcher(value))
+    }
+}
+
+impl<T: Send + 'static> SyncSignal for SyncComputed<T> {
+    type Output = T;
+
+    fn get(&self) -> T {
+        self.0.erased_get()
+    }
+
+    fn watch(&self, watcher: impl Fn(T) + Send + Sync + 'static) -> SyncWatcherGuard {
+        self.0.erased_watch(Arc::new(watcher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use std::{sync::mpsc, thread};
+
+    #[test]
+    fn test_sync_signals_notify_across_threads() {
+        let width = binding(2);
+        let height = binding(3);
+        let area = width
+            .clone()
+            .zip(height.clone())
+            .map(|(w, h)| w * h)
+            .computed();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let guard = {
+            let seen = seen.clone();
+            area.watch(move |area| {
+                seen.lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push(area);
+            })
+        };
+
+        let handles = vec![
+            thread::spawn(move || width.set(4)),
+            thread::spawn(move || height.update(|h| *h += 2)),
+        ];
+        for handle in handles {
+            assert!(handle.join().is_ok());
+        }
+        assert_eq!(area.get(), 20);
+
+        drop(guard);
+        let mut seen = seen.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        seen.sort_unstable();
+        assert_eq!(seen.last(), Some(&20));
+        assert_eq!(seen.len(), 2);
+    }
+
+    #[test]
+    fn test_readers_see_writes_of_a_flush_at_once() {
+        let a = binding(1);
+        let b = binding(1);
+        let total = a.clone().zip(b.clone()).map(|(a, b)| a + b);
+        let (started, wait_started) = mpsc::channel();
+        let (resume, wait_resume) = mpsc::channel::<()>();
+
+        let writer = thread::spawn(move || {
+            flush(|| {
+                a.set(2);
+                assert_eq!(a.get(), 2);
+                let _ = started.send(());
+                let _ = wait_resume.recv();
+                b.set(2);
+            });
+        });
+
+        assert!(wait_started.recv().is_ok());
+        assert_eq!(total.get(), 2);
+        let snapshot = Snapshot::new();
+        assert!(resume.send(()).is_ok());
+        assert!(writer.join().is_ok());
+
+        assert_eq!(total.get(), 4);
+        assert_eq!(snapshot.read(|| total.get()), 2);
+    }
+
+    #[test]
+    fn test_compare_and_set_reports_conflicts() {
+        let count = binding(0);
+        let conflicts = Arc::new(Mutex::new(Vec::new()));
+        let _guard = {
+            let conflicts = conflicts.clone();
+            count.on_conflict(move |conflict| {
+                conflicts
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push((conflict.expected, conflict.actual));
+            })
+        };
+
+        assert_eq!(count.compare_and_set(&0, 1), Ok(()));
+        assert_eq!(count.compare_and_set(&0, 2), Err(1));
+
+        // A write landing while `f` runs makes it retry with the new value.
+        let mut interfered = false;
+        let previous = count.fetch_update(|n| {
+            if !interfered {
+                interfered = true;
+                let writer = count.clone();
+                assert!(thread::spawn(move || writer.set(10)).join().is_ok());
+            }
+            Some(n + 1)
+        });
+        assert_eq!(previous, Ok(10));
+        assert_eq!(count.get(), 11);
+        assert_eq!(
+            *conflicts.lock().unwrap_or_else(PoisonError::into_inner),
+            [(0, 1), (1, 10)]
+        );
+    }
+}
@@ -0,0 +1,547 @@
+//! # Background tasks
+//!
+//! A [`TaskRunner`] runs the background work an application shows in its UI,
+//! such as imports, exports or uploads. Every job spawned through it returns a
+//! [`TaskHandle`] exposing the job's [`Status`], progress and result as
+//! signals, and the runner exposes how many jobs are queued and running, so a
+//! progress bar or an activity indicator is just a view of these signals.
+//!
+//! At most `limit` jobs run at once. Further jobs wait in a queue and start in
+//! the order they were spawned, as running jobs complete or are cancelled.
+//! Dropping the runner cancels every job that is queued or running.
+//!
+//! Jobs are futures run on a local executor. With the `std` feature,
+//! [`TaskRunner::spawn_blocking`] runs blocking work on a thread of its own,
+//! reporting its progress and result through a handle just the same.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use nami::{SignalExt, task::TaskRunner};
+//!
+//! let runner = TaskRunner::new(2);
+//! let upload = runner.spawn(|progress| async move {
+//!     for chunk in 0..10 {
+//!         // Upload the chunk...
+//!         progress.report((chunk + 1) as f32 / 10.0);
+//!     }
+//!     "uploaded"
+//! });
+//!
+//! let label = upload.progress().map(|progress| format!("{:.0}%", progress * 100.0));
+//! ```
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, VecDeque},
+    rc::{Rc, Weak},
+};
+use core::{cell::RefCell, fmt, future::Future, pin::Pin};
+
+use executor_core::{DefaultExecutor, LocalExecutor};
+
+use crate::{Computed, Container, CustomBinding, Signal, SignalExt};
+
+/// The status of a job spawned on a [`TaskRunner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The job waits for a running job to complete.
+    Queued,
+    /// The job is running.
+    Running,
+    /// The job completed, and its result is available.
+    Completed,
+    /// The job was cancelled before it completed.
+    Cancelled,
+    /// The job panicked. Only jobs spawned with
+    /// [`spawn_blocking`](TaskRunner::spawn_blocking) can report this status.
+    Failed,
+}
+
+impl Status {
+    /// Returns `true` if the job has stopped, whether it completed or not.
+    #[must_use]
+    pub const fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Cancelled | Self::Failed)
+    }
+}
+
+/// A job waiting in the queue.
+struct Queued {
+    id: u64,
+    status: Container<Status>,
+    start: Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()>>>>,
+}
+
+/// A job that was started, with its task once it was spawned.
+struct Running<E: LocalExecutor> {
+    status: Container<Status>,
+    task: Option<E::Task<()>>,
+}
+
+struct State<E: LocalExecutor> {
+    next_id: u64,
+    queue: VecDeque<Queued>,
+    running: BTreeMap<u64, Running<E>>,
+    /// The task of the last completed job, which cannot be dropped while it
+    /// is still finishing.
+    retired: Option<E::Task<()>>,
+}
+
+struct Inner<E: LocalExecutor> {
+    this: Weak<Self>,
+    executor: E,
+    limit: usize,
+    state: RefCell<State<E>>,
+    queued: Container<usize>,
+    running: Container<usize>,
+}
+
+/// Cancels jobs on behalf of their handles, whatever the executor.
+trait Cancel {
+    fn cancel(&self, id: u64);
+}
+
+/// Runs background jobs with a concurrency limit, exposing their progress as
+/// signals.
+///
+/// See the [module documentation](self) for more.
+pub struct TaskRunner<E: LocalExecutor + 'static = DefaultExecutor> {
+    inner: Rc<Inner<E>>,
+}
+
+impl<E: LocalExecutor + 'static> fmt::Debug for TaskRunner<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskRunner")
+            .field("limit", &self.inner.limit)
+            .field("queued", &self.inner.queued.get())
+            .field("running", &self.inner.running.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TaskRunner {
+    /// Creates a runner running up to `limit` jobs at once on the default
+    /// executor.
+    ///
+    /// A `limit` of zero is treated as one.
+    #[must_use]
+    pub fn new(limit: usize) -> Self {
+        Self::with_executor(DefaultExecutor, limit)
+    }
+}
+
+impl<E: LocalExecutor + 'static> TaskRunner<E> {
+    /// Creates a runner running up to `limit` jobs at once on `executor`.
+    ///
+    /// A `limit` of zero is treated as one.
+    pub fn with_executor(executor: E, limit: usize) -> Self {
+        Self {
+            inner: Rc::new_cyclic(|this| Inner {
+                this: this.clone(),
+                executor,
+                limit: limit.max(1),
+                state: RefCell::new(State {
+                    next_id: 0,
+                    queue: VecDeque::new(),
+                    running: BTreeMap::new(),
+                    retired: None,
+                }),
+                queued: Container::new(0),
+                running: Container::new(0),
+            }),
+        }
+    }
+
+    /// Returns the maximum number of jobs running at once.
+    #[must_use]
+    pub fn limit(&self) -> usize {
+        self.inner.limit
+    }
+
+    /// Returns a signal of the number of jobs waiting to start.
+    #[must_use]
+    pub fn queued(&self) -> Computed<usize> {
+        self.inner.queued.clone().computed()
+    }
+
+    /// Returns a signal of the number of jobs running.
+    #[must_use]
+    pub fn running(&self) -> Computed<usize> {
+        self.inner.running.clone().computed()
+    }
+
+    /// Queues the job created by `job`, which starts as soon as fewer than
+    /// [`limit`](Self::limit) jobs are running.
+    ///
+    /// `job` is called when the job starts, with a [`Progress`] to report how
+    /// far it got.
+    pub fn spawn<T, F, Fut>(&self, job: F) -> TaskHandle<T>
+    where
+        T: Clone + 'static,
+        F: FnOnce(Progress) -> Fut + 'static,
+        Fut: Future<Output = T> + 'static,
+    {
+        self.queue(move |progress| {
+            let job = job(progress);
+            async move { Some(job.await) }
+        })
+    }
+
+    /// Queues `job` to run on a thread of its own once it starts.
+    ///
+    /// If `job` panics, the job reports [`Status::Failed`]. Cancelling the
+    /// job discards its result, but cannot stop the thread: long jobs should
+    /// check [`ThreadProgress::is_cancelled`] and return early.
+    #[cfg(feature = "std")]
+    pub fn spawn_blocking<T, F>(&self, job: F) -> TaskHandle<T>
+    where
+        T: Clone + Send + 'static,
+        F: FnOnce(&ThreadProgress<T>) -> T + Send + 'static,
+    {
+        self.queue(move |progress| async move {
+            let (sender, receiver) = async_channel::unbounded();
+            std::thread::spawn(move || {
+                let reporter = ThreadProgress(sender);
+                let result = job(&reporter);
+                let _ = reporter.0.try_send(Message::Done(result));
+            });
+            while let Ok(message) = receiver.recv().await {
+                match message {
+                    Message::Progress(fraction) => progress.report(fraction),
+                    Message::Done(result) => return Some(result),
+                }
+            }
+            // The thread panicked before sending its result.
+            None
+        })
+    }
+
+    /// Cancels every job that is queued or running.
+    pub fn cancel_all(&self) {
+        self.inner.cancel_all();
+    }
+
+    fn queue<T, F, Fut>(&self, job: F) -> TaskHandle<T>
+    where
+        T: Clone + 'static,
+        F: FnOnce(Progress) -> Fut + 'static,
+        Fut: Future<Output = Option<T>> + 'static,
+    {
+        let handle = TaskHandle {
+            id: {
+                let mut state = self.inner.state.borrow_mut();
+                state.next_id += 1;
+                state.next_id
+            },
+            status: Container::new(Status::Queued),
+            progress: Container::new(0.0),
+            result: Container::new(None),
+            runner: self.inner.this.clone(),
+        };
+        let start = {
+            let handle = handle.clone();
+            Box::new(move || {
+                handle.status.set(Status::Running);
+                let job = job(Progress(handle.progress.clone()));
+                Box::pin(async move {
+                    let result = job.await;
+                    let status = if result.is_some() {
+                        Status::Completed
+                    } else {
+                        Status::Failed
+                    };
+                    handle.result.set(result);
+                    handle.status.set(status);
+                }) as Pin<Box<dyn Future<Output = ()>>>
+            })
+        };
+        self.inner.state.borrow_mut().queue.push_back(Queued {
+            id: handle.id,
+            status: handle.status.clone(),
+            start,
+        });
+        self.inner.schedule();
+        handle
+    }
+}
+
+impl<E: LocalExecutor + 'static> Drop for TaskRunner<E> {
+    fn drop(&mut self) {
+        self.inner.cancel_all();
+    }
+}
+
+impl<E: LocalExecutor + 'static> Inner<E> {
+    /// Starts queued jobs while fewer than `limit` jobs are running.
+    fn schedule(&self) {
+        loop {
+            let queued = {
+                let mut state = self.state.borrow_mut();
+                if state.running.len() >= self.limit {
+                    break;
+                }
+                let Some(queued) = state.queue.pop_front() else {
+                    break;
+                };
+                state.running.insert(
+                    queued.id,
+                    Running {
+                        status: queued.status.clone(),
+                        task: None,
+                    },
+                );
+                queued
+            };
+            let (id, this) = (queued.id, self.this.clone());
+            let job = (queued.start)();
+            let task = self.executor.spawn(async move {
+                job.await;
+                if let Some(this) = this.upgrade() {
+                    this.finish(id);
+                }
+            });
+            let mut state = self.state.borrow_mut();
+            // A job completing as it is spawned has already been removed.
+            if let Some(running) = state.running.get_mut(&id) {
+                running.task = Some(task);
+            }
+        }
+        self.update_counts();
+    }
+
+    /// Removes a completed job and starts the next one.
+    fn finish(&self, id: u64) {
+        let previous = {
+            let mut state = self.state.borrow_mut();
+            let task = state.running.remove(&id).and_then(|running| running.task);
+            // This task is still running the job's last poll, so it is kept
+            // until the next job completes.
+            core::mem::replace(&mut state.retired, task)
+        };
+        drop(previous);
+        self.schedule();
+    }
+
+    fn cancel_all(&self) {
+        let (queue, running) = {
+            let mut state = self.state.borrow_mut();
+            (
+                core::mem::take(&mut state.queue),
+                core::mem::take(&mut state.running),
+            )
+        };
+        for queued in queue {
+            queued.status.set(Status::Cancelled);
+        }
+        for running in running.into_values() {
+            drop(running.task);
+            running.status.set(Status::Cancelled);
+        }
+        self.update_counts();
+    }
+
+    fn update_counts(&self) {
+        let (queued, running) = {
+            let state = self.state.borrow();
+            (state.queue.len(), state.running.len())
+        };
+        if self.queued.get() != queued {
+            self.queued.set(queued);
+        }
+        if self.running.get() != running {
+            self.running.set(running);
+        }
+    }
+}
+
+impl<E: LocalExecutor + 'static> Cancel for Inner<E> {
+    fn cancel(&self, id: u64) {
+        let status = {
+            let mut state = self.state.borrow_mut();
+            if let Some(index) = state.queue.iter().position(|queued| queued.id == id) {
+                state.queue.remove(index).map(|queued| queued.status)
+            } else {
+                state.running.remove(&id).map(|running| {
+                    drop(running.task);
+                    running.status
+                })
+            }
+        };
+        if let Some(status) = status {
+            status.set(Status::Cancelled);
+            self.schedule();
+        }
+    }
+}
+
+/// A job spawned on a [`TaskRunner`], exposing its state as signals.
+///
+/// All clones refer to the same job. Dropping the handle does not cancel the
+/// job.
+pub struct TaskHandle<T: Clone + 'static> {
+    id: u64,
+    status: Container<Status>,
+    progress: Container<f32>,
+    result: Container<Option<T>>,
+    runner: Weak<dyn Cancel>,
+}
+
+impl<T: Clone + 'static> Clone for TaskHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            status: self.status.clone(),
+            progress: self.progress.clone(),
+            result: self.result.clone(),
+            runner: self.runner.clone(),
+        }
+    }
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for TaskHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskHandle")
+            .field("status", &self.status.get())
+            .field("progress", &self.progress.get())
+            .field("result", &self.result.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + 'static> TaskHandle<T> {
+    /// Returns a signal of the job's status.
+    #[must_use]
+    pub fn status(&self) -> Computed<Status> {
+        self.status.clone().computed()
+    }
+
+    /// Returns a signal of the progress last reported by the job, from `0.0`
+    /// to `1.0`.
+    #[must_use]
+    pub fn progress(&self) -> Computed<f32> {
+        self.progress.clone().computed()
+    }
+
+    /// Returns a signal of the job's result, which is `None` until the job
+    /// completes.
+    #[must_use]
+    pub fn result(&self) -> Computed<Option<T>> {
+        self.result.clone().computed()
+    }
+
+    /// Cancels the job if it is queued or running.
+    pub fn cancel(&self) {
+        if let Some(runner) = self.runner.upgrade() {
+            runner.cancel(self.id);
+        }
+    }
+}
+
+/// Reports the progress of a job spawned with [`TaskRunner::spawn`].
+#[derive(Debug, Clone)]
+pub struct Progress(Container<f32>);
+
+impl Progress {
+    /// Reports that the job is `fraction` done, from `0.0` to `1.0`.
+    pub fn report(&self, fraction: f32) {
+        self.0.set(fraction.clamp(0.0, 1.0));
+    }
+}
+
+#[cfg(feature = "std")]
+enum Message<T> {
+    Progress(f32),
+    Done(T),
+}
+
+/// Reports the progress of a job spawned with [`TaskRunner::spawn_blocking`]
+/// from its thread.
+#[cfg(feature = "std")]
+pub struct ThreadProgress<T>(async_channel::Sender<Message<T>>);
+
+#[cfg(feature = "std")]
+impl<T> fmt::Debug for ThreadProgress<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadProgress").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> ThreadProgress<T> {
+    /// Reports that the job is `fraction` done, from `0.0` to `1.0`.
+    pub fn report(&self, fraction: f32) {
+        let _ = self.0.try_send(Message::Progress(fraction));
+    }
+
+    /// Returns `true` if the job was cancelled, so its result would be
+    /// discarded.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_closed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_executor::Manual;
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn test_runner_limits_running_jobs() {
+        let executor = Manual::default();
+        let runner = TaskRunner::with_executor(executor.clone(), 2);
+        let started = Rc::new(RefCell::new(Vec::new()));
+        let handles: Vec<_> = (0..3)
+            .map(|index| {
+                let started = started.clone();
+                runner.spawn(move |progress| {
+                    started.borrow_mut().push(index);
+                    async move {
+                        progress.report(2.0);
+                        index * 10
+                    }
+                })
+            })
+            .collect();
+
+        assert_eq!(*started.borrow(), [0, 1]);
+        assert_eq!((runner.queued().get(), runner.running().get()), (1, 2));
+        assert_eq!(handles[2].status().get(), Status::Queued);
+
+        executor.run();
+        assert_eq!(handles[0].result().get(), Some(0));
+        assert!((handles[0].progress().get() - 1.0).abs() < f32::EPSILON);
+        assert_eq!(*started.borrow(), [0, 1, 2]);
+        assert_eq!((runner.queued().get(), runner.running().get()), (0, 1));
+
+        executor.run();
+        let statuses: Vec<_> = handles.iter().map(|handle| handle.status().get()).collect();
+        assert_eq!(statuses, vec![Status::Completed; 3]);
+        assert_eq!(handles[2].result().get(), Some(20));
+    }
+
+    #[test]
+    fn test_cancelling_a_job_starts_the_next() {
+        let executor = Manual::default();
+        let runner = TaskRunner::with_executor(executor.clone(), 1);
+        let first = runner.spawn(|_| core::future::pending::<()>());
+        let second = runner.spawn(|_| async {});
+        let third = runner.spawn(|_| async {});
+
+        third.cancel();
+        assert_eq!(third.status().get(), Status::Cancelled);
+        first.cancel();
+        assert_eq!(first.status().get(), Status::Cancelled);
+        assert_eq!(second.status().get(), Status::Running);
+
+        executor.run();
+        assert_eq!(second.status().get(), Status::Completed);
+
+        let queued = runner.spawn(|_| async {});
+        let _pending = runner.spawn(|_| async {});
+        drop(runner);
+        assert_eq!(queued.status().get(), Status::Cancelled);
+    }
+}
@@ -0,0 +1,80 @@
+//! An executor for unit tests, running its tasks only when asked to.
+
+use alloc::{
+    boxed::Box,
+    rc::{Rc, Weak},
+    vec::Vec,
+};
+use core::{
+    any::Any,
+    cell::RefCell,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use executor_core::LocalExecutor;
+
+type Job = RefCell<Pin<Box<dyn Future<Output = ()>>>>;
+
+/// An executor running its tasks when asked to, dropping tasks whose handle
+/// was dropped.
+#[derive(Clone, Default)]
+pub struct Manual(Rc<RefCell<Vec<Weak<Job>>>>);
+
+/// The handle of a task spawned on a [`Manual`] executor, which cancels the
+/// task when dropped, and is ready with its output once the task completed.
+pub struct Handle<T>(#[allow(dead_code)] Rc<Job>, Rc<RefCell<Option<T>>>);
+
+impl<T> Future for Handle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<T> {
+        self.1
+            .borrow_mut()
+            .take()
+            .map_or(Poll::Pending, Poll::Ready)
+    }
+}
+
+impl<T> executor_core::Task<T> for Handle<T> {
+    fn poll_result(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<T, Box<dyn Any + Send>>> {
+        self.poll(cx).map(Ok)
+    }
+
+    fn poll_cancel(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        Poll::Ready(())
+    }
+}
+
+impl LocalExecutor for Manual {
+    type Task<T: 'static> = Handle<T>;
+
+    fn spawn<Fut: Future + 'static>(&self, fut: Fut) -> Handle<Fut::Output> {
+        let output = Rc::new(RefCell::new(None));
+        let job: Rc<Job> = Rc::new(RefCell::new(Box::pin({
+            let output = output.clone();
+            async move {
+                output.replace(Some(fut.await));
+            }
+        })));
+        self.0.borrow_mut().push(Rc::downgrade(&job));
+        Handle(job, output)
+    }
+}
+
+impl Manual {
+    /// Polls every live task once, keeping the pending ones.
+    pub fn run(&self) {
+        let jobs = core::mem::take(&mut *self.0.borrow_mut());
+        let mut cx = Context::from_waker(Waker::noop());
+        for job in jobs {
+            let Some(live) = job.upgrade() else { continue };
+            if live.borrow_mut().as_mut().poll(&mut cx).is_pending() {
+                self.0.borrow_mut().push(job);
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! # Test doubles
+//!
+//! Code that accepts an `impl Signal` or a [`Binding`] can be unit tested
+//! without building a real reactive graph:
+//!
+//! - [`MockSignal`] plays back a scripted sequence of values, one per
+//!   [`emit`](MockSignal::emit), and counts how often it is read.
+//! - [`MockBinding`] records every write made through it, with assertion
+//!   helpers for the writes received.
+//!
+//! This module requires the `testing` feature, typically enabled only in
+//! `dev-dependencies`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{Binding, Signal, testing::{MockBinding, MockSignal}};
+//!
+//! // The code under test: mirrors a temperature into a label.
+//! fn show_temperature<S>(celsius: S, label: Binding<String>) -> S::Guard
+//! where
+//!     S: Signal<Output = i32>,
+//! {
+//!     label.set(format!("{}°C", celsius.get()));
+//!     celsius.watch(move |context| label.set(format!("{}°C", context.value)))
+//! }
+//!
+//! let celsius = MockSignal::scripted(20, [21, 23]);
+//! let label = MockBinding::new(String::new());
+//! let _guard = show_temperature(celsius.clone(), label.clone().into());
+//!
+//! while celsius.emit() {}
+//! label.assert_written(&["20°C".to_string(), "21°C".to_string(), "23°C".to_string()]);
+//! ```
+
+use alloc::{collections::VecDeque, rc::Rc, vec::Vec};
+use core::{
+    cell::{Cell, RefCell},
+    fmt,
+};
+
+use crate::{
+    Binding, Container, CustomBinding, NodeId, Signal,
+    watcher::{BoxWatcherGuard, Context},
+};
+
+/// A signal playing back a scripted sequence of values.
+///
+/// All clones share the same value, script and watchers.
+#[derive(Clone)]
+pub struct MockSignal<T: Clone + 'static> {
+    container: Container<T>,
+    script: Rc<RefCell<VecDeque<T>>>,
+    reads: Rc<Cell<usize>>,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for MockSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockSignal")
+            .field("value", &self.container.get())
+            .field("script", &self.script.borrow())
+            .field("reads", &self.reads.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + 'static> MockSignal<T> {
+    /// Creates a signal holding `value`, with an empty script.
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self::scripted(value, [])
+    }
+
+    /// Creates a signal holding `value`, then `script` one at a time as they
+    /// are emitted.
+    pub fn scripted(value: T, script: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            container: Container::new(value),
+            script: Rc::new(RefCell::new(script.into_iter().collect())),
+            reads: Rc::new(Cell::new(0)),
+        }
+    }
+
+    /// Appends `values` to the script.
+    pub fn push_script(&self, values: impl IntoIterator<Item = T>) {
+        self.script.borrow_mut().extend(values);
+    }
+
+    /// Emits the next scripted value, notifying watchers.
+    ///
+    /// Returns `false`, leaving the value unchanged, if the script is
+    /// exhausted.
+    #[allow(clippy::must_use_candidate)] // Emitting is the point, the result is optional.
+    pub fn emit(&self) -> bool {
+        let next = self.script.borrow_mut().pop_front();
+        next.map(|value| self.emit_value(value)).is_some()
+    }
+
+    /// Emits `value` out of script, notifying watchers.
+    pub fn emit_value(&self, value: T) {
+        self.container.set(value);
+    }
+
+    /// Returns the number of scripted values not emitted yet.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.script.borrow().len()
+    }
+
+    /// Returns the number of times the value was read with [`get`](Signal::get).
+    #[must_use]
+    pub fn reads(&self) -> usize {
+        self.reads.get()
+    }
+}
+
+impl<T: Clone + 'static> Signal for MockSignal<T> {
+    type Output = T;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> T {
+        self.reads.set(self.reads.get() + 1);
+        self.container.get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<T>) + 'static) -> Self::Guard {
+        self.container.watch(watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.container.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.container.watcher_count()
+    }
+}
+
+/// A binding recording the writes made through it.
+///
+/// All clones share the same value, watchers and recorded writes.
+#[derive(Clone)]
+pub struct MockBinding<T: Clone + 'static> {
+    container: Container<T>,
+    writes: Rc<RefCell<Vec<T>>>,
+}
+
+impl<T: Clone + fmt::Debug + 'static> fmt::Debug for MockBinding<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockBinding")
+            .field("value", &self.container.get())
+            .field("writes", &self.writes.borrow())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Clone + 'static> MockBinding<T> {
+    /// Creates a binding holding `value`, with no recorded writes.
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self {
+            container: Container::new(value),
+            writes: Rc::default(),
+        }
+    }
+
+    /// Changes the value from outside the code under test, notifying watchers
+    /// without recording a write.
+    pub fn emit(&self, value: T) {
+        self.container.set(value);
+    }
+
+    /// Returns the values written so far, oldest first.
+    #[must_use]
+    pub fn writes(&self) -> Vec<T> {
+        self.writes.borrow().clone()
+    }
+
+    /// Returns the last value written, if any.
+    #[must_use]
+    pub fn last_write(&self) -> Option<T> {
+        self.writes.borrow().last().cloned()
+    }
+
+    /// Forgets the recorded writes.
+    pub fn clear_writes(&self) {
+        self.writes.borrow_mut().clear();
+    }
+
+    /// Asserts that exactly `expected` were written, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the recorded writes differ from `expected`.
+    #[track_caller]
+    pub fn assert_written(&self, expected: &[T])
+    where
+        T: PartialEq + fmt::Debug,
+    {
+        assert_eq!(
+            self.writes.borrow().as_slice(),
+            expected,
+            "unexpected writes to the mock binding"
+        );
+    }
+
+    /// Asserts that nothing was written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any write was recorded.
+    #[track_caller]
+    pub fn assert_not_written(&self)
+    where
+        T: fmt::Debug,
+    {
+        let writes = self.writes.borrow();
+        assert!(
+            writes.is_empty(),
+            "expected no writes to the mock binding, got {writes:?}"
+        );
+    }
+}
+
+impl<T: Clone + 'static> Signal for MockBinding<T> {
+    type Output = T;
+    type Guard = BoxWatcherGuard;
+
+    fn get(&self) -> T {
+        self.container.get()
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<T>) + 'static) -> Self::Guard {
+        self.container.watch(watcher)
+    }
+
+    fn node_id(&self) -> Option<NodeId> {
+        self.container.node_id()
+    }
+
+    fn watcher_count(&self) -> Option<usize> {
+        self.container.watcher_count()
+    }
+}
+
+impl<T: Clone + 'static> CustomBinding for MockBinding<T> {
+    fn set(&self, value: T) {
+        self.writes.borrow_mut().push(value.clone());
+        self.container.set(value);
+    }
+}
+
+impl<T: Clone + 'static> From<MockBinding<T>> for Binding<T> {
+    fn from(mock: MockBinding<T>) -> Self {
+        Self::custom(mock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SignalExt;
+
+    #[test]
+    fn test_mocks_script_and_record() {
+        let source = MockSignal::scripted(1, [2, 3]);
+        let target = MockBinding::new(0);
+        let _guard = {
+            let target: Binding<i32> = target.clone().into();
+            source
+                .clone()
+                .map(|n| n * 10)
+                .watch(move |context| target.set(context.value))
+        };
+
+        target.assert_not_written();
+        assert!(source.emit());
+        target.emit(5);
+        assert!(source.emit());
+        assert!(!source.emit());
+        source.emit_value(4);
+        target.assert_written(&[20, 30, 40]);
+        assert_eq!((target.get(), target.last_write()), (40, Some(40)));
+        assert_eq!((source.remaining(), source.reads()), (0, 0));
+        assert_eq!(source.get(), 4);
+        assert_eq!(source.reads(), 1);
+
+        target.clear_writes();
+        assert!(target.writes().is_empty());
+    }
+}
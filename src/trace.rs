@@ -0,0 +1,313 @@
+//! # Causality tracing
+//!
+//! This module provides an opt-in tracer answering "why did this watcher fire?".
+//! While it is enabled, every binding write is recorded together with everything
+//! it caused: the watchers it notified, the transformations that ran to
+//! propagate the change, and further writes performed by those watchers.
+//!
+//! Each top-level write forms a flush, recorded as a tree of [`Trace`]s. The
+//! tracer keeps the last flushes up to a configured capacity, and like the
+//! [`watchdog`](crate::watchdog) it is configured per thread.
+//!
+//! Watchers and transformations are identified by the type name of their
+//! closure, which includes the path of the function it was defined in.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, trace::{self, Step}};
+//!
+//! trace::enable(16);
+//!
+//! let celsius: Binding<f64> = binding(0.0);
+//! let fahrenheit: Binding<f64> = binding(32.0);
+//! let _sync = {
+//!     let fahrenheit = fahrenheit.clone();
+//!     celsius.watch(move |context| fahrenheit.set(context.value * 1.8 + 32.0))
+//! };
+//!
+//! celsius.set(100.0);
+//!
+//! let flushes = trace::flushes();
+//! let cause = &flushes[0];
+//! assert!(matches!(cause.step, Step::Write { node, .. } if Some(node) == celsius.node_id()));
+//! let watcher = &cause.children[0];
+//! assert!(matches!(watcher.children[0].step, Step::Write { node, .. } if Some(node) == fahrenheit.node_id()));
+//! println!("{cause}");
+//!
+//! trace::disable();
+//! ```
+
+use alloc::{collections::VecDeque, vec::Vec};
+use core::{
+    any::type_name_of_val,
+    cell::RefCell,
+    fmt::{self, Display},
+};
+
+use crate::{NodeId, watcher::Context};
+
+/// A single step of a propagation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Step {
+    /// A node was written to.
+    Write {
+        /// The written node.
+        node: NodeId,
+        /// The type name of the written value.
+        value: &'static str,
+    },
+    /// A watcher was notified.
+    Watcher {
+        /// The type name of the watcher closure.
+        name: &'static str,
+    },
+    /// A transformation ran to propagate a change.
+    Compute {
+        /// The type name of the transformation closure.
+        name: &'static str,
+    },
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Write { node, value } => write!(f, "write {node}: {value}"),
+            Self::Watcher { name } => write!(f, "watcher {name}"),
+            Self::Compute { name } => write!(f, "compute {name}"),
+        }
+    }
+}
+
+/// A recorded step together with the steps it caused, in execution order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Trace {
+    /// The recorded step.
+    pub step: Step,
+    /// The steps caused by this one.
+    pub children: Vec<Self>,
+}
+
+impl Trace {
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(f, "{:indent$}{}", "", self.step, indent = depth * 2)?;
+        self.children
+            .iter()
+            .try_for_each(|child| child.fmt_indented(f, depth + 1))
+    }
+}
+
+/// Formats the trace as an indented tree, one step per line.
+impl Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+#[derive(Debug)]
+struct Tracer {
+    capacity: usize,
+    flushes: VecDeque<Trace>,
+    /// The steps currently running, outermost first.
+    stack: Vec<Trace>,
+}
+
+std::thread_local! {
+    static TRACER: RefCell<Option<Tracer>> = const { RefCell::new(None) };
+}
+
+/// Enables the tracer on the current thread, keeping the last `capacity` flushes.
+///
+/// Enabling the tracer again discards the flushes recorded so far.
+pub fn enable(capacity: usize) {
+    TRACER.with(|tracer| {
+        *tracer.borrow_mut() = Some(Tracer {
+            capacity,
+            flushes: VecDeque::with_capacity(capacity),
+            stack: Vec::new(),
+        });
+    });
+}
+
+/// Disables the tracer on the current thread, discarding the recorded flushes.
+pub fn disable() {
+    TRACER.with(|tracer| tracer.borrow_mut().take());
+}
+
+/// Returns `true` if the tracer is enabled on the current thread.
+#[must_use]
+pub fn is_enabled() -> bool {
+    TRACER.with(|tracer| tracer.borrow().is_some())
+}
+
+/// Returns the recorded flushes, oldest first.
+///
+/// A flush that is still running is not included.
+#[must_use]
+pub fn flushes() -> Vec<Trace> {
+    TRACER.with(|tracer| {
+        tracer
+            .borrow()
+            .as_ref()
+            .map(|tracer| tracer.flushes.iter().cloned().collect())
+            .unwrap_or_default()
+    })
+}
+
+/// Discards the recorded flushes, keeping the tracer enabled.
+pub fn clear() {
+    TRACER.with(|tracer| {
+        if let Some(tracer) = tracer.borrow_mut().as_mut() {
+            tracer.flushes.clear();
+        }
+    });
+}
+
+/// Records a step while it runs, closing it when dropped.
+struct Span(bool);
+
+impl Span {
+    fn enter(step: Step) -> Self {
+        Self(TRACER.with(|tracer| {
+            let mut tracer = tracer.borrow_mut();
+            let Some(tracer) = tracer.as_mut() else {
+                return false;
+            };
+            // Transformations only run for a cause inside a flush; outside of
+            // one they are plain reads.
+            if tracer.stack.is_empty() && matches!(step, Step::Compute { .. }) {
+                return false;
+            }
+            tracer.stack.push(Trace {
+                step,
+                children: Vec::new(),
+            });
+            true
+        }))
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if !self.0 {
+            return;
+        }
+        TRACER.with(|tracer| {
+            let mut tracer = tracer.borrow_mut();
+            // The tracer may have been reconfigured by the traced step.
+            let Some(tracer) = tracer.as_mut() else {
+                return;
+            };
+            let Some(trace) = tracer.stack.pop() else {
+                return;
+            };
+            if let Some(parent) = tracer.stack.last_mut() {
+                parent.children.push(trace);
+            } else if tracer.capacity > 0 {
+                if tracer.flushes.len() == tracer.capacity {
+                    tracer.flushes.pop_front();
+                }
+                tracer.flushes.push_back(trace);
+            }
+        });
+    }
+}
+
+/// Runs `f` as `step` of the running flush, measuring it against the
+/// [deadline](crate::deadline) when enabled.
+fn record<R>(step: Step, f: impl FnOnce() -> R) -> R {
+    let _span = Span::enter(step);
+    crate::deadline::measure(step, f)
+}
+
+/// Runs `f`, which notifies the watchers of `node` after a write.
+pub(crate) fn write<R>(node: NodeId, value: &'static str, f: impl FnOnce() -> R) -> R {
+    record(Step::Write { node, value }, f)
+}
+
+/// Runs the watcher named `name`.
+///
+/// Boxed watchers are not recorded, since the code boxing them records them
+/// under their own name.
+pub(crate) fn watcher<R>(name: &'static str, f: impl FnOnce() -> R) -> R {
+    if name.starts_with("alloc::boxed::Box<dyn ") {
+        return f();
+    }
+    record(Step::Watcher { name }, f)
+}
+
+/// Wraps `watcher` to record its runs under the type name of its closure.
+pub(crate) fn traced<T>(watcher: impl Fn(Context<T>) + 'static) -> impl Fn(Context<T>) + 'static {
+    let name = type_name_of_val(&watcher);
+    move |context| self::watcher(name, || watcher(context))
+}
+
+/// Runs the transformation named `name`.
+pub(crate) fn compute<R>(name: &'static str, f: impl FnOnce() -> R) -> R {
+    record(Step::Compute { name }, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, Signal, SignalExt, binding};
+    use alloc::string::ToString;
+
+    fn names(trace: &Trace) -> Vec<Step> {
+        core::iter::once(trace.step)
+            .chain(trace.children.iter().flat_map(names))
+            .collect()
+    }
+
+    #[test]
+    fn test_trace_records_causes() {
+        enable(2);
+
+        let source: Binding<i32> = binding(1);
+        let target: Binding<i32> = binding(0);
+        let doubled = source.clone().map(|n| n * 2);
+        let _forward = {
+            let target = target.clone();
+            doubled.watch(move |context| target.set(context.value))
+        };
+        let _log = target.watch(|_| {});
+
+        // Reads outside of a flush are not recorded.
+        assert_eq!(doubled.get(), 2);
+        assert!(flushes().is_empty());
+
+        source.set(2);
+        let flushes = flushes();
+        assert_eq!(flushes.len(), 1);
+        let steps = names(&flushes[0]);
+        assert!(matches!(steps[0], Step::Write { node, .. } if Some(node) == source.node_id()));
+        let kinds: Vec<_> = steps
+            .iter()
+            .map(|step| match step {
+                Step::Write { .. } => "write",
+                Step::Watcher { .. } => "watcher",
+                Step::Compute { .. } => "compute",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            ["write", "watcher", "compute", "watcher", "write", "watcher"]
+        );
+        assert!(matches!(steps[4], Step::Write { node, .. } if Some(node) == target.node_id()));
+        assert!(flushes[0].to_string().contains("test_trace_records_causes"));
+
+        source.set(3);
+        source.set(4);
+        source.set(5);
+        assert_eq!(super::flushes().len(), 2);
+
+        clear();
+        assert!(super::flushes().is_empty());
+        disable();
+        source.set(6);
+        assert!(!is_enabled());
+        assert!(super::flushes().is_empty());
+    }
+}
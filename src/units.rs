@@ -0,0 +1,231 @@
+//! # Units of measure
+//!
+//! This module converts a binding holding a quantity in a canonical unit into a
+//! binding in a display unit chosen by a signal, such as a user preference.
+//! Reading the converted binding converts from the canonical unit, and writing
+//! to it, for example from a text field, converts back:
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, units::{Unit, convert}};
+//!
+//! let distance: Binding<f64> = binding(1500.0); // in meters
+//! let unit: Binding<Unit> = binding(Unit::KILOMETER);
+//! let shown = convert(&distance, unit.clone());
+//! assert_eq!(shown.get(), 1.5);
+//!
+//! unit.set(Unit::METER);
+//! assert_eq!(shown.get(), 1500.0);
+//!
+//! unit.set(Unit::CENTIMETER);
+//! shown.set(25.0);
+//! assert_eq!(distance.get(), 0.25);
+//! ```
+//!
+//! The provided units use the SI base units as canonical units: meters,
+//! kilograms, seconds and kelvins. Other units can be defined with
+//! [`Unit::new`] and [`Unit::affine`].
+
+use core::fmt::{self, Display};
+
+use crate::{
+    Binding, CustomBinding, Signal,
+    watcher::{BoxWatcherGuard, Context},
+};
+
+/// A unit of measure, defined by its conversion to a canonical unit.
+///
+/// A value `v` in this unit is `v * scale + offset` in the canonical unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    symbol: &'static str,
+    scale: f64,
+    offset: f64,
+}
+
+impl Unit {
+    /// Meters, the canonical unit of length.
+    pub const METER: Self = Self::new("m", 1.0);
+    /// Kilometers.
+    pub const KILOMETER: Self = Self::new("km", 1000.0);
+    /// Centimeters.
+    pub const CENTIMETER: Self = Self::new("cm", 0.01);
+    /// Millimeters.
+    pub const MILLIMETER: Self = Self::new("mm", 0.001);
+    /// International miles.
+    pub const MILE: Self = Self::new("mi", 1609.344);
+    /// International feet.
+    pub const FOOT: Self = Self::new("ft", 0.3048);
+    /// International inches.
+    pub const INCH: Self = Self::new("in", 0.0254);
+
+    /// Kilograms, the canonical unit of mass.
+    pub const KILOGRAM: Self = Self::new("kg", 1.0);
+    /// Grams.
+    pub const GRAM: Self = Self::new("g", 0.001);
+    /// Avoirdupois pounds.
+    pub const POUND: Self = Self::new("lb", 0.453_592_37);
+
+    /// Seconds, the canonical unit of time.
+    pub const SECOND: Self = Self::new("s", 1.0);
+    /// Minutes.
+    pub const MINUTE: Self = Self::new("min", 60.0);
+    /// Hours.
+    pub const HOUR: Self = Self::new("h", 3600.0);
+
+    /// Kelvins, the canonical unit of temperature.
+    pub const KELVIN: Self = Self::new("K", 1.0);
+    /// Degrees Celsius.
+    pub const CELSIUS: Self = Self::affine("°C", 1.0, 273.15);
+    /// Degrees Fahrenheit.
+    pub const FAHRENHEIT: Self = Self::affine("°F", 5.0 / 9.0, 459.67 * 5.0 / 9.0);
+
+    /// Creates a unit worth `scale` canonical units.
+    #[must_use]
+    pub const fn new(symbol: &'static str, scale: f64) -> Self {
+        Self::affine(symbol, scale, 0.0)
+    }
+
+    /// Creates a unit whose zero is at `offset` canonical units, such as a
+    /// temperature scale.
+    #[must_use]
+    pub const fn affine(symbol: &'static str, scale: f64, offset: f64) -> Self {
+        Self {
+            symbol,
+            scale,
+            offset,
+        }
+    }
+
+    /// Returns the symbol of this unit.
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        self.symbol
+    }
+
+    /// Converts `value` in this unit to the canonical unit.
+    #[must_use]
+    #[allow(clippy::suboptimal_flops)] // `mul_add` needs `std`.
+    pub const fn to_canonical(self, value: f64) -> f64 {
+        value * self.scale + self.offset
+    }
+
+    /// Converts `value` in the canonical unit to this unit.
+    #[must_use]
+    pub const fn from_canonical(self, value: f64) -> f64 {
+        (value - self.offset) / self.scale
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol)
+    }
+}
+
+/// A unit is a constant signal of itself.
+impl Signal for Unit {
+    type Output = Self;
+    type Guard = ();
+
+    fn get(&self) -> Self::Output {
+        *self
+    }
+
+    fn watch(&self, _watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {}
+}
+
+/// A binding converting a canonical binding into a display unit.
+///
+/// Created by [`convert`].
+#[derive(Debug, Clone)]
+struct Converted<U> {
+    canonical: Binding<f64>,
+    unit: U,
+}
+
+impl<U: Signal<Output = Unit>> Signal for Converted<U> {
+    type Output = f64;
+    type Guard = (BoxWatcherGuard, U::Guard);
+
+    fn get(&self) -> Self::Output {
+        self.unit.get().from_canonical(self.canonical.get())
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let watcher = alloc::rc::Rc::new(watcher);
+        let on_value = {
+            let watcher = watcher.clone();
+            let unit = self.unit.clone();
+            self.canonical.watch(move |context: Context<f64>| {
+                let Context { value, metadata } = context;
+                watcher(Context::new(unit.get().from_canonical(value), metadata));
+            })
+        };
+        let canonical = self.canonical.clone();
+        let on_unit = self.unit.watch(move |context: Context<Unit>| {
+            let Context { value, metadata } = context;
+            watcher(Context::new(
+                value.from_canonical(canonical.get()),
+                metadata,
+            ));
+        });
+        (on_value, on_unit)
+    }
+}
+
+impl<U: Signal<Output = Unit>> CustomBinding for Converted<U> {
+    fn set(&self, value: f64) {
+        self.canonical.set(self.unit.get().to_canonical(value));
+    }
+}
+
+/// Creates a binding of `canonical` converted into the unit selected by `unit`.
+///
+/// The converted binding notifies its watchers when either the canonical value
+/// or the unit changes. Setting it converts the value back to the canonical unit
+/// and sets `canonical`. Pass a [`Unit`] directly for a fixed unit.
+pub fn convert<U>(canonical: &Binding<f64>, unit: U) -> Binding<f64>
+where
+    U: Signal<Output = Unit>,
+{
+    Binding::custom(Converted {
+        canonical: canonical.clone(),
+        unit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binding;
+    use alloc::{rc::Rc, vec::Vec};
+    use core::cell::RefCell;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_convert_follows_value_and_unit() {
+        let temperature: Binding<f64> = binding(293.15);
+        let unit: Binding<Unit> = binding(Unit::CELSIUS);
+        let shown = convert(&temperature, unit.clone());
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            shown.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+        unit.set(Unit::FAHRENHEIT);
+        shown.set(212.0);
+        unit.set(Unit::KELVIN);
+
+        let seen = seen.borrow();
+        assert_eq!(seen.len(), 3);
+        assert!(close(seen[0], 68.0));
+        assert!(close(seen[1], 212.0));
+        assert!(close(seen[2], 373.15));
+        assert!(close(temperature.get(), 373.15));
+        assert!(close(convert(&temperature, Unit::CELSIUS).get(), 100.0));
+    }
+}
@@ -1,20 +1,68 @@
-//! # Addition Operations for Signal Types
+//! # Arithmetic Operations for Signal Types
 //!
-//! This module provides functionality for adding two `Signal` values together.
+//! This module provides functionality for combining `Signal` values with arithmetic.
 //! It leverages the `zip` and `map` operations to combine computations and apply
-//! the addition operation to their results.
+//! the operation to their results.
 //!
-//! The addition is performed using the standard `Add` trait from Rust's core library,
-//! allowing for flexible addition semantics depending on the types involved.
+//! The operations are performed using the standard traits from Rust's core library,
+//! such as `Add`, allowing for flexible semantics depending on the types involved.
+//!
+//! The operators themselves are implemented for bindings, computed values, maps
+//! and constants, so arithmetic on signals reads like arithmetic on values. The
+//! right-hand side may be any signal, including a plain number:
+//!
+//! ```
+//! # use nami::{Signal, binding, Binding};
+//! let price: Binding<i32> = binding(4);
+//! let quantity: Binding<i32> = binding(3);
+//! let shipping: Binding<i32> = binding(5);
+//! let total = (price.clone() * quantity) + shipping;
+//! let refund = -(price.clone() / 2);
+//!
+//! price.set(10);
+//! assert_eq!(total.get(), 35);
+//! assert_eq!(refund.get(), -5);
+//! ```
 
-use core::ops::Add;
+use core::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
 use crate::{
-    Signal,
+    Binding, Computed, Signal,
+    constant::Constant,
     map::{Map, map},
     zip::{Zip, zip},
 };
 
+/// A signal applying a binary operation to the outputs of `A` and `B`,
+/// producing an `O`.
+pub type BinaryOp<A, B, O> =
+    Map<Zip<A, B>, fn((<A as Signal>::Output, <B as Signal>::Output)) -> O, O>;
+
+/// A signal applying a unary operation to the output of `A`, producing an `O`.
+pub type UnaryOp<A, O> = Map<A, fn(<A as Signal>::Output) -> O, O>;
+
+/// The signal returned by [`add`], such as `Sum<Binding<i32>, Binding<i32>>`.
+pub type Sum<A, B> = BinaryOp<A, B, <<A as Signal>::Output as Add<<B as Signal>::Output>>::Output>;
+
+/// The signal returned by [`sub`].
+pub type Difference<A, B> =
+    BinaryOp<A, B, <<A as Signal>::Output as Sub<<B as Signal>::Output>>::Output>;
+
+/// The signal returned by [`mul`].
+pub type Product<A, B> =
+    BinaryOp<A, B, <<A as Signal>::Output as Mul<<B as Signal>::Output>>::Output>;
+
+/// The signal returned by [`div`].
+pub type Quotient<A, B> =
+    BinaryOp<A, B, <<A as Signal>::Output as Div<<B as Signal>::Output>>::Output>;
+
+/// The signal returned by [`rem`].
+pub type Remainder<A, B> =
+    BinaryOp<A, B, <<A as Signal>::Output as Rem<<B as Signal>::Output>>::Output>;
+
+/// The signal returned by [`neg`].
+pub type Negation<A> = UnaryOp<A, <<A as Signal>::Output as Neg>::Output>;
+
 /// Adds two `Signal` values together.
 ///
 /// This function takes two values implementing the `Signal` trait and returns a new
@@ -44,15 +92,7 @@ use crate::{
 /// let sum = add(a, b);
 /// assert_eq!(sum.get(), 8);
 /// ```
-#[allow(clippy::type_complexity)]
-pub fn add<A, B>(
-    a: A,
-    b: B,
-) -> Map<
-    Zip<A, B>,
-    fn((A::Output, B::Output)) -> <A::Output as Add<B::Output>>::Output,
-    <A::Output as Add<B::Output>>::Output,
->
+pub fn add<A, B>(a: A, b: B) -> Sum<A, B>
 where
     A: Signal + 'static,
     B: Signal + 'static,
@@ -62,6 +102,151 @@ where
     map(zip, |(a, b)| a.add(b))
 }
 
+/// Subtracts the output of `b` from the output of `a`.
+///
+/// # Examples
+///
+/// ```
+/// # use nami::{Signal, utils::sub, binding, Binding};
+/// let a: Binding<i32> = binding(5);
+/// let b: Binding<i32> = binding(3);
+/// assert_eq!(sub(a, b).get(), 2);
+/// ```
+pub fn sub<A, B>(a: A, b: B) -> Difference<A, B>
+where
+    A: Signal,
+    B: Signal,
+    A::Output: Sub<B::Output>,
+{
+    map(zip(a, b), |(a, b)| a - b)
+}
+
+/// Multiplies the outputs of `a` and `b`.
+///
+/// # Examples
+///
+/// ```
+/// # use nami::{Signal, utils::mul, binding, Binding};
+/// let a: Binding<i32> = binding(5);
+/// let b: Binding<i32> = binding(3);
+/// assert_eq!(mul(a, b).get(), 15);
+/// ```
+pub fn mul<A, B>(a: A, b: B) -> Product<A, B>
+where
+    A: Signal,
+    B: Signal,
+    A::Output: Mul<B::Output>,
+{
+    map(zip(a, b), |(a, b)| a * b)
+}
+
+/// Divides the output of `a` by the output of `b`.
+///
+/// Dividing integers by zero panics when the value is computed, as it does
+/// for plain integers.
+///
+/// # Examples
+///
+/// ```
+/// # use nami::{Signal, utils::div, binding, Binding};
+/// let a: Binding<i32> = binding(6);
+/// let b: Binding<i32> = binding(3);
+/// assert_eq!(div(a, b).get(), 2);
+/// ```
+pub fn div<A, B>(a: A, b: B) -> Quotient<A, B>
+where
+    A: Signal,
+    B: Signal,
+    A::Output: Div<B::Output>,
+{
+    map(zip(a, b), |(a, b)| a / b)
+}
+
+/// Returns the remainder of dividing the output of `a` by the output of `b`.
+///
+/// Like [`div`], integer remainders by zero panic when the value is computed.
+///
+/// # Examples
+///
+/// ```
+/// # use nami::{Signal, utils::rem, binding, Binding};
+/// let a: Binding<i32> = binding(7);
+/// let b: Binding<i32> = binding(3);
+/// assert_eq!(rem(a, b).get(), 1);
+/// ```
+pub fn rem<A, B>(a: A, b: B) -> Remainder<A, B>
+where
+    A: Signal,
+    B: Signal,
+    A::Output: Rem<B::Output>,
+{
+    map(zip(a, b), |(a, b)| a % b)
+}
+
+/// Negates the output of `a`.
+///
+/// # Examples
+///
+/// ```
+/// # use nami::{Signal, utils::neg, binding, Binding};
+/// let a: Binding<i32> = binding(5);
+/// assert_eq!(neg(a).get(), -5);
+/// ```
+pub fn neg<A>(a: A) -> Negation<A>
+where
+    A: Signal,
+    A::Output: Neg,
+{
+    map(a, |a| -a)
+}
+
+/// Implements the arithmetic operators for a signal type, mapping its output,
+/// zipped with the output of the right-hand side, with the same operator.
+macro_rules! impl_arithmetic {
+    ($([$($generics:tt)*] $ty:ty),* $(,)?) => {
+        $(
+            impl_arithmetic!(@binary [$($generics)*] $ty, Add, add, Sum);
+            impl_arithmetic!(@binary [$($generics)*] $ty, Sub, sub, Difference);
+            impl_arithmetic!(@binary [$($generics)*] $ty, Mul, mul, Product);
+            impl_arithmetic!(@binary [$($generics)*] $ty, Div, div, Quotient);
+            impl_arithmetic!(@binary [$($generics)*] $ty, Rem, rem, Remainder);
+
+            impl<$($generics)*> Neg for $ty
+            where
+                Self: Signal,
+                <Self as Signal>::Output: Neg,
+            {
+                type Output = Negation<Self>;
+
+                fn neg(self) -> Self::Output {
+                    map(self, Neg::neg)
+                }
+            }
+        )*
+    };
+    (@binary [$($generics:tt)*] $ty:ty, $op:ident, $method:ident, $alias:ident) => {
+        impl<$($generics)*, Rhs> $op<Rhs> for $ty
+        where
+            Self: Signal,
+            Rhs: Signal,
+            <Self as Signal>::Output: $op<Rhs::Output>,
+        {
+            type Output = $alias<Self, Rhs>;
+
+            fn $method(self, rhs: Rhs) -> Self::Output {
+                map(zip(self, rhs), |(a, b)| $op::$method(a, b))
+            }
+        }
+    };
+}
+
+impl_arithmetic!(
+    [T] Binding<T>,
+    [T] Computed<T>,
+    [T] Constant<T>,
+    [C, F, O] Map<C, F, O>,
+);
+
 /// Returns the maximum value between two `Signal` values.
 ///
 /// This function takes two values implementing the `Signal` trait with the same output type
@@ -145,3 +330,82 @@ where
     let zip = zip(a, b);
     map(zip, |(a, b)| core::cmp::min(a, b))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SignalExt, binding};
+    use alloc::{rc::Rc, vec::Vec};
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_arithmetic_signals_can_be_named() {
+        struct Invoice {
+            total: Sum<Product<Binding<i32>, Binding<i32>>, Binding<i32>>,
+            leftover: Remainder<Binding<i32>, i32>,
+        }
+
+        let price: Binding<i32> = binding(4);
+        let quantity: Binding<i32> = binding(3);
+        let invoice = Invoice {
+            total: add(mul(price.clone(), quantity.clone()), binding(5)),
+            leftover: quantity.clone() % 2,
+        };
+
+        price.set(10);
+        quantity.set(5);
+        assert_eq!(invoice.total.get(), 55);
+        assert_eq!(invoice.leftover.get(), 1);
+        assert_eq!(neg(sub(invoice.total, div(price, 2))).get(), -50);
+    }
+
+    #[test]
+    fn test_operators_on_bindings_follow_their_operands() {
+        let a: Binding<i32> = binding(7);
+        let b: Binding<i32> = binding(2);
+        let sum = a.clone() + b.clone();
+        let difference = a.clone() - b.clone();
+        let product = a.clone() * b.clone();
+        let quotient = a.clone() / b.clone();
+        let remainder = a.clone() % b.clone();
+        let negation = -a.clone();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            product.watch(move |context| seen.borrow_mut().push(context.value))
+        };
+
+        assert_eq!((sum.get(), difference.get(), product.get()), (9, 5, 14));
+        assert_eq!(
+            (quotient.get(), remainder.get(), negation.get()),
+            (3, 1, -7)
+        );
+        a.set(9);
+        b.set(4);
+        assert_eq!((sum.get(), difference.get(), product.get()), (13, 5, 36));
+        assert_eq!(
+            (quotient.get(), remainder.get(), negation.get()),
+            (2, 1, -9)
+        );
+        assert_eq!(*seen.borrow(), [18, 36]);
+    }
+
+    #[test]
+    fn test_operators_on_computed_constants_and_maps() {
+        let count: Binding<i32> = binding(3);
+        let computed: Computed<i32> = Computed::new(count.clone());
+        let doubled = count.clone().map(|count| count * 2);
+        let ten = Constant::from(10);
+
+        assert_eq!((computed.clone() * 2).get(), 6);
+        assert_eq!((ten.clone() - computed.clone()).get(), 7);
+        assert_eq!((doubled.clone() + ten.clone()).get(), 16);
+        assert_eq!((-doubled.clone() % 4).get(), -2);
+        assert_eq!((-ten / 3).get(), -3);
+
+        // Operators chain through the maps they return.
+        let total = (computed * doubled) + count.clone();
+        count.set(4);
+        assert_eq!(total.get(), 36);
+    }
+}
@@ -0,0 +1,61 @@
+//! # Reactive enums
+//!
+//! State is often an enum, such as a request that is loading, loaded or failed.
+//! The [`Variants`] trait gives such an enum a fieldless *kind* naming its
+//! variants, and [`SignalExt::kind`](crate::SignalExt::kind) turns a signal of
+//! the enum into a signal of its kind, which only notifies when the variant
+//! changes. This suits views that switch on the variant, and should not be
+//! rebuilt when only the data inside it changes.
+//!
+//! With the `derive` feature, `#[derive(nami::Variants)]` implements the trait,
+//! generating the kind as `{Enum}Kind`, and an extension trait `{Enum}Signal`
+//! for signals of the enum with, for every variant:
+//!
+//! - `is_{variant}()`: a signal of whether the enum is that variant, notifying
+//!   only when that changes.
+//! - `as_{variant}()`, for variants with fields: a signal of the fields while
+//!   the enum is that variant, and `None` otherwise. Several fields are
+//!   returned as a tuple, in declaration order.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use nami::{binding, Binding, Signal, SignalExt};
+//!
+//! #[derive(Debug, Clone, nami::Variants)]
+//! enum Request {
+//!     Loading,
+//!     Loaded(String),
+//!     Failed { code: u16, message: String },
+//! }
+//!
+//! let request: Binding<Request> = binding(Request::Loading);
+//! let kind = request.clone().kind();
+//! let loading = request.is_loading();
+//! let body = request.as_loaded();
+//!
+//! request.set(Request::Loaded("hello".into()));
+//! assert_eq!(kind.get(), RequestKind::Loaded);
+//! assert!(!loading.get());
+//! assert_eq!(body.get().as_deref(), Some("hello"));
+//!
+//! request.set(Request::Failed { code: 404, message: "not found".into() });
+//! assert_eq!(request.as_failed().get(), Some((404, "not found".into())));
+//! # }
+//! ```
+
+use core::{fmt::Debug, hash::Hash};
+
+/// An enum whose variants are named by a fieldless kind.
+///
+/// Usually implemented with `#[derive(nami::Variants)]`. See the
+/// [module documentation](self) for more.
+pub trait Variants: Clone + 'static {
+    /// A fieldless enum with a variant for each variant of `Self`.
+    type Kind: Debug + Clone + Copy + PartialEq + Eq + Hash + 'static;
+
+    /// Returns the kind of this value's variant.
+    fn kind(&self) -> Self::Kind;
+}
@@ -0,0 +1,186 @@
+//! # Watchdog for long-running computations
+//!
+//! This module provides an opt-in diagnostic that measures how long each
+//! transformation closure takes to run, and reports any evaluation that exceeds
+//! a configured threshold.
+//!
+//! Every [`Map`](crate::map::Map) evaluation, whether triggered by `get()` or by a
+//! change propagating through a watcher, is measured while the watchdog is enabled.
+//! Nodes are identified by the type name of their closure, which includes the path
+//! of the function it was defined in.
+//!
+//! The watchdog is configured per thread, matching the single-threaded nature of
+//! the reactive graph.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use nami::{binding, Binding, Signal, SignalExt, watchdog};
+//!
+//! // Log a warning for every closure that runs longer than 16ms.
+//! watchdog::enable(Duration::from_millis(16));
+//!
+//! let value: Binding<i32> = binding(1);
+//! let doubled = value.map(|n| n * 2);
+//! assert_eq!(doubled.get(), 2);
+//!
+//! watchdog::disable();
+//! ```
+
+use alloc::rc::Rc;
+use core::{cell::RefCell, fmt::Debug, time::Duration};
+use std::time::Instant;
+
+/// A report describing a computation that exceeded the watchdog threshold.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Report {
+    /// The name of the node that ran too long.
+    pub name: &'static str,
+    /// How long the computation took.
+    pub elapsed: Duration,
+    /// The threshold that was exceeded.
+    pub threshold: Duration,
+}
+
+/// Type alias for a watchdog report hook.
+type Hook = Rc<dyn Fn(&Report)>;
+
+struct Watchdog {
+    threshold: Duration,
+    hook: Option<Hook>,
+}
+
+impl Debug for Watchdog {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("threshold", &self.threshold)
+            .field("hook", &self.hook.as_ref().map(|_| "<hook>"))
+            .finish()
+    }
+}
+
+std::thread_local! {
+    static WATCHDOG: RefCell<Option<Watchdog>> = const { RefCell::new(None) };
+}
+
+/// Enables the watchdog on the current thread, logging slow computations.
+///
+/// Every computation that takes longer than `threshold` is reported through
+/// `log::warn!`.
+pub fn enable(threshold: Duration) {
+    install(Watchdog {
+        threshold,
+        hook: None,
+    });
+}
+
+/// Enables the watchdog on the current thread with a custom report hook.
+///
+/// The hook is invoked instead of logging whenever a computation takes longer than `threshold`.
+pub fn enable_with_hook(threshold: Duration, hook: impl Fn(&Report) + 'static) {
+    install(Watchdog {
+        threshold,
+        hook: Some(Rc::new(hook)),
+    });
+}
+
+/// Disables the watchdog on the current thread.
+pub fn disable() {
+    WATCHDOG.with(|watchdog| watchdog.borrow_mut().take());
+}
+
+/// Returns `true` if the watchdog is enabled on the current thread.
+#[must_use]
+pub fn is_enabled() -> bool {
+    WATCHDOG.with(|watchdog| watchdog.borrow().is_some())
+}
+
+fn install(watchdog: Watchdog) {
+    WATCHDOG.with(|slot| *slot.borrow_mut() = Some(watchdog));
+}
+
+/// Runs `f`, reporting it under `name` if it exceeds the configured threshold.
+///
+/// When the watchdog is disabled, `f` is called directly without being timed.
+pub(crate) fn measure<R>(name: &'static str, f: impl FnOnce() -> R) -> R {
+    let threshold = WATCHDOG.with(|watchdog| watchdog.borrow().as_ref().map(|w| w.threshold));
+    let Some(threshold) = threshold else {
+        return f();
+    };
+
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+
+    if elapsed > threshold {
+        let report = Report {
+            name,
+            elapsed,
+            threshold,
+        };
+        // Clone the hook out so it may reconfigure the watchdog without a borrow conflict.
+        let hook =
+            WATCHDOG.with(|watchdog| watchdog.borrow().as_ref().and_then(|w| w.hook.clone()));
+        if let Some(hook) = hook {
+            hook(&report);
+        } else {
+            log::warn!(
+                "`{}` took {:?}, exceeding the watchdog threshold of {:?}",
+                report.name,
+                report.elapsed,
+                report.threshold
+            );
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Signal, SignalExt, binding};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_watchdog_reports_slow_map() {
+        let reports: Rc<RefCell<Vec<Report>>> = Rc::default();
+        {
+            let reports = reports.clone();
+            enable_with_hook(Duration::from_millis(5), move |report| {
+                reports.borrow_mut().push(report.clone());
+            });
+        }
+
+        let value = binding(1);
+        let fast = value.clone().map(|n: i32| n + 1);
+        let slow = value.clone().map(|n: i32| {
+            std::thread::sleep(Duration::from_millis(10));
+            n * 2
+        });
+
+        assert_eq!(fast.get(), 2);
+        assert!(reports.borrow().is_empty());
+
+        assert_eq!(slow.get(), 2);
+        assert_eq!(reports.borrow().len(), 1);
+
+        // Evaluations triggered through watchers are measured as well.
+        let _guard = slow.watch(|_| {});
+        value.set(2);
+        assert_eq!(reports.borrow().len(), 2);
+        assert!(reports.borrow()[1].elapsed > Duration::from_millis(5));
+        assert!(
+            reports.borrow()[1]
+                .name
+                .contains("test_watchdog_reports_slow_map")
+        );
+
+        disable();
+        assert!(!is_enabled());
+        assert_eq!(slow.get(), 4);
+        assert_eq!(reports.borrow().len(), 2);
+    }
+}
@@ -3,12 +3,19 @@
 //! This module provides the infrastructure for managing reactive value watchers,
 //! including metadata handling and notification systems.
 
-use alloc::{boxed::Box, collections::BTreeMap, rc::Rc};
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    rc::{Rc, Weak},
+    vec::Vec,
+};
 use core::{
     any::{Any, TypeId, type_name},
-    cell::RefCell,
+    cell::{Cell, RefCell},
     fmt::Debug,
     num::NonZeroUsize,
+    ops::ControlFlow,
+    task::Waker,
 };
 
 /// A type-erased container for metadata that can be associated with computation results.
@@ -48,6 +55,16 @@ impl MetadataInner {
 /// Type alias for a boxed watcher function.
 pub type BoxWatcher<T> = Box<dyn Fn(Context<T>) + 'static>;
 
+/// A registered watcher, which asks to be removed by returning [`ControlFlow::Break`].
+type Entry<T> = Rc<dyn Fn(Context<T>) -> ControlFlow<()> + 'static>;
+
+/// A predicate on the metadata of a notification, deciding whether a watcher
+/// is notified of it.
+pub type BoxFilter = Box<dyn Fn(&Metadata) -> bool + 'static>;
+
+/// A registered filter of a watcher.
+type Filter = Rc<dyn Fn(&Metadata) -> bool + 'static>;
+
 /// Context passed to watchers containing the value and associated metadata.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -72,6 +89,20 @@ impl<T> Context<T> {
     }
 }
 
+/// A change passed to watchers registered with
+/// [`SignalExt::watch_transitions`](crate::SignalExt::watch_transitions),
+/// holding the previous value along with the new one.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Transition<T> {
+    /// The value before the change.
+    pub old: T,
+    /// The value after the change.
+    pub new: T,
+    /// Associated metadata for this value change.
+    pub metadata: Metadata,
+}
+
 /// A guard that ensures proper cleanup of watchers when dropped.
 #[must_use]
 pub trait WatcherGuard: 'static {}
@@ -123,6 +154,65 @@ impl WatcherGuard for Rc<dyn WatcherGuard> {}
 
 impl<F: FnOnce() + 'static> WatcherGuard for OnDrop<F> {}
 
+/// A guard for a watcher that can stop itself, returned by
+/// [`SignalExt::watch_while`](crate::SignalExt::watch_while).
+///
+/// The watcher is removed when it returns [`ControlFlow::Break`], or when this
+/// guard is dropped, whichever comes first.
+#[must_use]
+pub struct WhileGuard<G: WatcherGuard>(Rc<WhileSlot<G>>);
+
+/// The guard of a self-stopping watcher, shared with the watcher itself.
+struct WhileSlot<G> {
+    guard: RefCell<Option<G>>,
+    stopped: Cell<bool>,
+}
+
+impl<G: WatcherGuard> WhileGuard<G> {
+    /// Registers `watcher` with `watch`, removing it once it returns
+    /// [`ControlFlow::Break`].
+    pub(crate) fn new<T>(
+        watch: impl FnOnce(Box<dyn Fn(Context<T>)>) -> G,
+        watcher: impl Fn(Context<T>) -> ControlFlow<()> + 'static,
+    ) -> Self {
+        let slot = Rc::new(WhileSlot {
+            guard: RefCell::new(None),
+            stopped: Cell::new(false),
+        });
+        let weak = Rc::downgrade(&slot);
+        let guard = watch(Box::new(move |context| {
+            let Some(slot) = weak.upgrade() else {
+                return;
+            };
+            if !slot.stopped.get() && watcher(context).is_break() {
+                slot.stopped.set(true);
+                let guard = slot.guard.borrow_mut().take();
+                drop(guard);
+            }
+        }));
+        if !slot.stopped.get() {
+            slot.guard.replace(Some(guard));
+        }
+        Self(slot)
+    }
+
+    /// Returns `true` once the watcher has stopped itself.
+    #[must_use]
+    pub fn is_stopped(&self) -> bool {
+        self.0.stopped.get()
+    }
+}
+
+impl<G: WatcherGuard> Debug for WhileGuard<G> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WhileGuard")
+            .field("stopped", &self.is_stopped())
+            .finish()
+    }
+}
+
+impl<G: WatcherGuard> WatcherGuard for WhileGuard<G> {}
+
 impl Metadata {
     /// Creates a new, empty metadata container.
     #[must_use]
@@ -206,9 +296,57 @@ impl<T: 'static> WatcherManager<T> {
         self.inner.borrow().is_empty()
     }
 
+    /// Returns the number of registered watchers.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.borrow().map.len()
+    }
+
     /// Registers a new watcher and returns its unique identifier.
     pub fn register(&self, watcher: impl Fn(Context<T>) + 'static) -> WatcherId {
-        self.inner.borrow_mut().register(watcher)
+        #[cfg(feature = "std")]
+        let watcher = crate::trace::traced(watcher);
+        self.inner.borrow_mut().register(move |context| {
+            watcher(context);
+            ControlFlow::Continue(())
+        })
+    }
+
+    /// Registers a watcher that is removed once it returns [`ControlFlow::Break`],
+    /// and returns a guard that will unregister it when dropped.
+    pub fn register_while(
+        &self,
+        watcher: impl Fn(Context<T>) -> ControlFlow<()> + 'static,
+    ) -> WatcherManagerGuard<T> {
+        #[cfg(feature = "std")]
+        let watcher = {
+            let name = core::any::type_name_of_val(&watcher);
+            move |context| crate::trace::watcher(name, || watcher(context))
+        };
+        let id = self.inner.borrow_mut().register(watcher);
+        WatcherManagerGuard {
+            manager: Rc::downgrade(&self.inner),
+            id,
+        }
+    }
+
+    /// Registers a watcher that is only notified when `filter` accepts the
+    /// metadata of a notification, and returns a guard that will unregister it
+    /// when dropped.
+    ///
+    /// The filter runs before the value is produced, so notifications it
+    /// rejects cost this watcher no computation or clone.
+    pub fn register_filtered(
+        &self,
+        filter: impl Fn(&Metadata) -> bool + 'static,
+        watcher: impl Fn(Context<T>) + 'static,
+    ) -> WatcherManagerGuard<T> {
+        let guard = self.register_as_guard(watcher);
+        self.inner
+            .borrow_mut()
+            .filters
+            .insert(guard.id, Rc::new(filter));
+        guard
     }
 
     /// Registers a watcher and returns a guard that will unregister it when dropped.
@@ -217,34 +355,105 @@ impl<T: 'static> WatcherManager<T> {
         watcher: impl Fn(Context<T>) + 'static,
     ) -> WatcherManagerGuard<T> {
         let id = self.register(watcher);
-        let this = self.clone();
-        WatcherManagerGuard { manager: this, id }
+        WatcherManagerGuard {
+            manager: Rc::downgrade(&self.inner),
+            id,
+        }
     }
 
-    /// Notifies all registered watchers with a value and specific metadata.
+    /// Registers `waker` to be woken by the next notification.
+    ///
+    /// Unlike a watcher, a waker is only stored until it is woken, and is not
+    /// given the value. Registering a waker that would wake the same task as an
+    /// already registered one replaces it.
+    pub fn add_waker(&self, waker: &Waker) {
+        let wakers = &mut self.inner.borrow_mut().wakers;
+        if let Some(registered) = wakers.iter_mut().find(|w| w.will_wake(waker)) {
+            registered.clone_from(waker);
+        } else {
+            wakers.push(waker.clone());
+        }
+    }
+
+    /// Notifies all registered watchers with a value and specific metadata, and
+    /// wakes all registered wakers.
+    ///
+    /// Watchers may register or cancel watchers of this manager while they are
+    /// notified. Watchers registered during a notification are first notified by
+    /// the next one, and cancelled watchers are skipped.
     pub fn notify(&self, value: impl Fn() -> T, metadata: &Metadata) {
-        let this = Rc::downgrade(&self.inner);
-        if let Some(this) = this.upgrade() {
-            this.borrow().notify(value, metadata);
+        crate::signal::notifying(|| self.notify_all(value, metadata));
+    }
+
+    /// Notifies all registered watchers and wakes all registered wakers, as
+    /// part of the running notification.
+    fn notify_all(&self, value: impl Fn() -> T, metadata: &Metadata) {
+        let wakers = core::mem::take(&mut self.inner.borrow_mut().wakers);
+        for waker in wakers {
+            waker.wake();
+        }
+        let watchers: Vec<(WatcherId, Entry<T>)> = self
+            .inner
+            .borrow()
+            .map
+            .iter()
+            .map(|(id, watcher)| (*id, watcher.clone()))
+            .collect();
+        #[cfg(feature = "std")]
+        let notification = crate::health::Notification::enter(watchers.len());
+        for (id, watcher) in watchers {
+            #[cfg(feature = "std")]
+            notification.next();
+            let filter = {
+                let inner = self.inner.borrow();
+                if !inner.map.contains_key(&id) {
+                    continue;
+                }
+                inner.filters.get(&id).cloned()
+            };
+            if filter.is_some_and(|filter| !filter(metadata)) {
+                continue;
+            }
+            if watcher(Context::new(value(), metadata.clone())).is_break() {
+                self.cancel(id);
+            }
         }
     }
 
     /// Clears all registered watchers.
     pub fn clear(&self) {
-        self.inner.borrow_mut().map.clear();
+        // Watchers are dropped after the borrow ends, since they may own guards
+        // of this manager.
+        let (map, filters) = {
+            let mut inner = self.inner.borrow_mut();
+            (
+                core::mem::take(&mut inner.map),
+                core::mem::take(&mut inner.filters),
+            )
+        };
+        #[cfg(feature = "std")]
+        crate::health::watchers_removed(map.len());
+        drop((map, filters));
     }
 
     /// Cancels a previously registered watcher by its identifier.
     pub fn cancel(&self, id: WatcherId) {
-        self.inner.borrow_mut().cancel(id);
+        let watcher = {
+            let mut inner = self.inner.borrow_mut();
+            (inner.cancel(id), inner.filters.remove(&id))
+        };
+        drop(watcher);
     }
 }
 
 /// A guard that ensures a watcher is unregistered when dropped.
+///
+/// The guard does not keep the manager alive: once the signal owning it is
+/// dropped, so are its watchers.
 #[must_use]
 #[derive(Debug)]
 pub struct WatcherManagerGuard<T: 'static> {
-    manager: WatcherManager<T>,
+    manager: Weak<RefCell<WatcherManagerInner<T>>>,
     id: WatcherId,
 }
 
@@ -252,7 +461,9 @@ impl<T> WatcherGuard for WatcherManagerGuard<T> {}
 
 impl<T: 'static> Drop for WatcherManagerGuard<T> {
     fn drop(&mut self) {
-        self.manager.cancel(self.id);
+        if let Some(inner) = self.manager.upgrade() {
+            WatcherManager { inner }.cancel(self.id);
+        }
     }
 }
 
@@ -261,7 +472,10 @@ impl<T: 'static> Drop for WatcherManagerGuard<T> {
 /// Maintains the collection of watchers and handles identifier assignment.
 struct WatcherManagerInner<T> {
     id: WatcherId,
-    map: BTreeMap<WatcherId, BoxWatcher<T>>,
+    map: BTreeMap<WatcherId, Entry<T>>,
+    /// The filters of the watchers registered with one.
+    filters: BTreeMap<WatcherId, Filter>,
+    wakers: Vec<Waker>,
 }
 
 impl<T> Debug for WatcherManagerInner<T> {
@@ -272,13 +486,24 @@ impl<T> Debug for WatcherManagerInner<T> {
 
 impl<T> Default for WatcherManagerInner<T> {
     fn default() -> Self {
+        #[cfg(feature = "std")]
+        crate::health::node_created();
         Self {
             id: WatcherId::MIN,
             map: BTreeMap::new(),
+            filters: BTreeMap::new(),
+            wakers: Vec::new(),
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl<T> Drop for WatcherManagerInner<T> {
+    fn drop(&mut self) {
+        crate::health::node_dropped(self.map.len());
+    }
+}
+
 impl<T: 'static> WatcherManagerInner<T> {
     /// Checks if there are any registered watchers.
     pub fn is_empty(&self) -> bool {
@@ -296,21 +521,220 @@ impl<T: 'static> WatcherManagerInner<T> {
     }
 
     /// Registers a watcher and returns its unique identifier.
-    pub fn register(&mut self, watcher: impl Fn(Context<T>) + 'static) -> WatcherId {
+    pub fn register(
+        &mut self,
+        watcher: impl Fn(Context<T>) -> ControlFlow<()> + 'static,
+    ) -> WatcherId {
         let id = self.assign();
-        self.map.insert(id, Box::new(watcher));
+        self.map.insert(id, Rc::new(watcher));
+        #[cfg(feature = "std")]
+        crate::health::watcher_registered();
         id
     }
 
-    /// Notifies all registered watchers with a value and metadata.
-    pub fn notify(&self, value: impl Fn() -> T, metadata: &Metadata) {
-        for watcher in self.map.values() {
-            watcher(Context::new(value(), metadata.clone()));
+    /// Cancels a watcher registration by its identifier, returning the watcher.
+    pub fn cancel(&mut self, id: WatcherId) -> Option<Entry<T>> {
+        let watcher = self.map.remove(&id);
+        #[cfg(feature = "std")]
+        if watcher.is_some() {
+            crate::health::watchers_removed(1);
+        }
+        watcher
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, Signal, SignalExt, binding};
+
+    #[test]
+    fn test_watchers_stop_themselves() {
+        let manager = WatcherManager::<i32>::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _stopping = {
+            let seen = seen.clone();
+            manager.register_while(move |ctx| {
+                seen.borrow_mut().push(ctx.value);
+                if ctx.value >= 2 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })
+        };
+        let _counting = {
+            let seen = seen.clone();
+            manager.register_as_guard(move |ctx| seen.borrow_mut().push(-ctx.value))
+        };
+
+        for value in 1..=3 {
+            manager.notify(|| value, &Metadata::new());
+        }
+        assert_eq!(*seen.borrow(), [1, -1, 2, -2, -3]);
+
+        let value: Binding<i32> = binding(0);
+        let guard = value.watch_while(|ctx| {
+            assert!(ctx.value < 2, "watcher ran after it stopped");
+            if ctx.value == 1 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        value.set(1);
+        value.set(2);
+        assert!(guard.is_stopped());
+    }
+
+    #[test]
+    fn test_filtered_watchers_skip_rejected_changes_at_the_source() {
+        #[derive(Clone)]
+        struct Programmatic;
+
+        let manager = WatcherManager::<i32>::new();
+        let (produced, seen) = (Rc::new(Cell::new(0)), Rc::new(RefCell::new(Vec::new())));
+        let _guard = {
+            let seen = seen.clone();
+            manager.register_filtered(
+                |metadata| metadata.try_get::<Programmatic>().is_none(),
+                move |ctx| seen.borrow_mut().push(ctx.value),
+            )
+        };
+        for (value, metadata) in [
+            (1, Metadata::new()),
+            (2, Metadata::new().with(Programmatic)),
+            (3, Metadata::new()),
+        ] {
+            let produced = produced.clone();
+            let value = move || {
+                produced.set(produced.get() + 1);
+                value
+            };
+            manager.notify(value, &metadata);
+        }
+        assert_eq!(*seen.borrow(), [1, 3]);
+        assert_eq!(produced.get(), 2);
+
+        // Maps and type-erased signals forward the filter to their source, so
+        // rejected changes are never mapped.
+        let count: Binding<i32> = binding(0);
+        let mapped = Rc::new(Cell::new(0));
+        let doubled = count
+            .clone()
+            .map({
+                let mapped = mapped.clone();
+                move |n| {
+                    mapped.set(mapped.get() + 1);
+                    n * 2
+                }
+            })
+            .computed();
+        let guard = doubled.watch_filtered(|_| false, |_| panic!("rejected change was delivered"));
+        count.set(1);
+        assert_eq!(mapped.get(), 0);
+        drop((guard, doubled));
+        assert_eq!(count.watcher_count(), Some(0));
+    }
+
+    #[test]
+    fn test_watch_first_fires_once_on_a_match() {
+        let value: Binding<i32> = binding(0);
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        let guard = {
+            let fired = fired.clone();
+            value.watch_first(
+                |n| n % 2 == 1,
+                move |ctx| fired.borrow_mut().push(ctx.value),
+            )
+        };
+        let cancelled = value.watch_once(|_| panic!("the watcher was dropped"));
+        drop(cancelled);
+
+        for n in 2..=5 {
+            value.set(n);
         }
+        assert_eq!(*fired.borrow(), [3]);
+        assert!(guard.is_stopped());
+        assert_eq!(value.watcher_count(), Some(0));
+    }
+
+    #[test]
+    fn test_transitions_keep_previous_values() {
+        let a: Binding<i32> = binding(1);
+        let b: Binding<i32> = binding(2);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _guard = {
+            let seen = seen.clone();
+            let sum = a.clone().zip(b.clone()).map(|(a, b)| a + b);
+            sum.with("animated").watch_transitions(move |change| {
+                let animated = change.metadata.try_get::<&str>() == Some("animated");
+                seen.borrow_mut().push((change.old, change.new, animated));
+            })
+        };
+
+        a.set(3);
+        b.set(4);
+        assert_eq!(*seen.borrow(), [(3, 5, true), (5, 7, true)]);
     }
 
-    /// Cancels a watcher registration by its identifier.
-    pub fn cancel(&mut self, id: WatcherId) {
-        self.map.remove(&id);
+    #[test]
+    fn test_wakers_are_woken_once() {
+        use alloc::{sync::Arc, task::Wake};
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Counter(AtomicUsize);
+
+        impl Wake for Counter {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        let manager = WatcherManager::<i32>::new();
+        manager.add_waker(&waker);
+        manager.add_waker(&waker);
+        assert!(manager.is_empty());
+
+        manager.notify(|| 1, &Metadata::new());
+        manager.notify(|| 2, &Metadata::new());
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_watchers_modify_manager_while_notified() {
+        let manager = WatcherManager::<()>::new();
+        let calls = Rc::new(Cell::new(0));
+        let later: Rc<RefCell<Vec<WatcherManagerGuard<()>>>> = Rc::default();
+        let _registering = {
+            let manager = manager.clone();
+            let calls = calls.clone();
+            manager.clone().register_as_guard(move |_| {
+                let calls = calls.clone();
+                let guard = manager.register_as_guard(move |_| calls.set(calls.get() + 1));
+                later.borrow_mut().push(guard);
+            })
+        };
+
+        manager.notify(|| (), &Metadata::new());
+        assert_eq!(calls.get(), 0);
+        manager.notify(|| (), &Metadata::new());
+        assert_eq!(calls.get(), 1);
+
+        let cancelled = Rc::new(Cell::new(false));
+        let slot: Rc<RefCell<Option<WatcherManagerGuard<()>>>> = Rc::default();
+        let _cancelling = {
+            let slot = slot.clone();
+            manager.register_as_guard(move |_| drop(slot.take()))
+        };
+        let guard = {
+            let cancelled = cancelled.clone();
+            manager.register_as_guard(move |_| cancelled.set(true))
+        };
+        slot.replace(Some(guard));
+        manager.notify(|| (), &Metadata::new());
+        assert!(!cancelled.get());
     }
 }
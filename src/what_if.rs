@@ -0,0 +1,306 @@
+//! # What-if evaluation
+//!
+//! This module evaluates a signal under hypothetical inputs without disturbing
+//! the rest of the reactive graph. An [`Evaluation`] temporarily overrides chosen
+//! bindings, reads its target signal, and restores the original values afterwards.
+//! Watchers are never notified of the overrides, so views and effects keep
+//! showing the real state while a model is probed for sensitivity analysis or
+//! Monte Carlo estimates.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt, random::Uniform, what_if::Evaluation};
+//!
+//! let price: Binding<f64> = binding(10.0);
+//! let quantity: Binding<f64> = binding(3.0);
+//! let total = price.clone().zip(quantity.clone()).map(|(p, q): (f64, f64)| p * q);
+//!
+//! // A single what-if question.
+//! let doubled = Evaluation::new(total.clone()).fix(&price, 20.0).eval();
+//! assert_eq!(doubled, 60.0);
+//!
+//! // A Monte Carlo estimate with varying prices.
+//! let totals = Evaluation::new(total.clone())
+//!     .vary(&price, Uniform::new(9.0, 11.0))
+//!     .run(7, 1000);
+//! assert!(totals.iter().all(|total| (27.0..33.0).contains(total)));
+//!
+//! // The real state is untouched.
+//! assert_eq!(total.get(), 30.0);
+//! ```
+//!
+//! For speculative changes made by ordinary code, such as previewing an invoice
+//! after applying a discount, [`sandbox`] runs a closure in which every binding
+//! write is silent and rolled back when the closure returns:
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt, what_if::sandbox};
+//!
+//! let subtotal: Binding<i32> = binding(100);
+//! let discount: Binding<i32> = binding(0);
+//! let total = subtotal.clone().zip(discount.clone()).map(|(s, d): (i32, i32)| s - d);
+//!
+//! let preview = sandbox(|| {
+//!     discount.set(15);
+//!     subtotal.set(subtotal.get() + 20);
+//!     total.get()
+//! });
+//! assert_eq!(preview, 105);
+//! assert_eq!(total.get(), 100);
+//! ```
+//!
+//! Overrides bypass notifications, so signals that cache their value between
+//! the overridden bindings and the target, such as [`cached`](crate::cache::cached),
+//! keep returning their cached value during the evaluation or sandbox.
+
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use crate::NodeId;
+use crate::{
+    Binding, Container, Signal,
+    random::{Distribution, Rng},
+};
+
+type Apply = Box<dyn Fn(&mut Rng) -> Box<dyn FnOnce()>>;
+
+/// An evaluation of a target signal with some bindings overridden.
+///
+/// Overrides are applied in the order they were added, and every evaluation
+/// restores the original values before returning, even if the target panics.
+pub struct Evaluation<S> {
+    target: S,
+    overrides: Vec<Apply>,
+}
+
+impl<S: fmt::Debug> fmt::Debug for Evaluation<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Evaluation")
+            .field("target", &self.target)
+            .field("overrides", &self.overrides.len())
+            .finish()
+    }
+}
+
+/// Restores overridden bindings when dropped, last override first.
+#[derive(Default)]
+struct Restore(Vec<Box<dyn FnOnce()>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        while let Some(restore) = self.0.pop() {
+            restore();
+        }
+    }
+}
+
+impl<S: Signal> Evaluation<S> {
+    /// Creates an evaluation of `target` without any overrides.
+    pub const fn new(target: S) -> Self {
+        Self {
+            target,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Overrides `binding` with `value` in every evaluation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `binding` does not hold its own value, that is, if it was not
+    /// created by [`binding`](crate::binding()) or [`Binding::container`].
+    #[must_use]
+    pub fn fix<T: Clone + 'static>(self, binding: &Binding<T>, value: T) -> Self {
+        self.vary(binding, move |_: &mut Rng| value.clone())
+    }
+
+    /// Overrides `binding` with a value drawn from `distribution` in every
+    /// evaluation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `binding` does not hold its own value, that is, if it was not
+    /// created by [`binding`](crate::binding()) or [`Binding::container`].
+    #[must_use]
+    pub fn vary<T, D>(mut self, binding: &Binding<T>, distribution: D) -> Self
+    where
+        T: Clone + 'static,
+        D: Distribution<T> + 'static,
+    {
+        let container: Container<T> = binding
+            .as_container()
+            .cloned()
+            .unwrap_or_else(|| panic!("only bindings holding their own value can be overridden"));
+        self.overrides.push(Box::new(move |rng| {
+            let original = container.replace_silently(distribution.sample(rng));
+            let container = container.clone();
+            Box::new(move || {
+                container.replace_silently(original);
+            })
+        }));
+        self
+    }
+
+    /// Evaluates the target once with the overrides applied.
+    ///
+    /// Random overrides are drawn from a generator seeded with zero.
+    pub fn eval(&self) -> S::Output {
+        self.sample(&mut Rng::new(0))
+    }
+
+    /// Evaluates the target `count` times, drawing new values for the
+    /// overrides every time, and returns the results in order.
+    ///
+    /// The values are determined by `seed`, so runs are reproducible.
+    pub fn run(&self, seed: u64, count: usize) -> Vec<S::Output> {
+        let mut rng = Rng::new(seed);
+        (0..count).map(|_| self.sample(&mut rng)).collect()
+    }
+
+    fn sample(&self, rng: &mut Rng) -> S::Output {
+        let _restore = Restore(self.overrides.iter().map(|apply| apply(rng)).collect());
+        self.target.get()
+    }
+}
+
+/// The bindings written in a sandbox, with the values to restore.
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct Frame {
+    written: Vec<NodeId>,
+    restore: Restore,
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static SANDBOXES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` in a sandbox and returns its result.
+///
+/// Inside the sandbox, setting a binding that holds its own value changes it
+/// without notifying watchers, so `f` can mutate state and read the derived
+/// results. When `f` returns, or panics, every binding it wrote is rolled back
+/// to the value it had before, again without notifying watchers.
+///
+/// Sandboxes only affect the current thread, and can be nested: an inner
+/// sandbox rolls back its own writes when it returns.
+#[cfg(feature = "std")]
+pub fn sandbox<R>(f: impl FnOnce() -> R) -> R {
+    struct Exit;
+
+    impl Drop for Exit {
+        fn drop(&mut self) {
+            // Release the borrow before restoring, since dropping the overridden
+            // values may write to bindings.
+            let frame = SANDBOXES.with(|sandboxes| sandboxes.borrow_mut().pop());
+            drop(frame);
+        }
+    }
+
+    SANDBOXES.with(|sandboxes| sandboxes.borrow_mut().push(Frame::default()));
+    let _exit = Exit;
+    f()
+}
+
+/// Records how to restore the binding identified by `id` before its first write
+/// in the innermost sandbox, returning whether a sandbox is active.
+///
+/// `snapshot` is only called for the first write, and returns a function
+/// restoring the current value silently.
+#[cfg(feature = "std")]
+pub(crate) fn record(id: NodeId, snapshot: impl FnOnce() -> Box<dyn FnOnce()>) -> bool {
+    SANDBOXES.with(|sandboxes| {
+        let mut sandboxes = sandboxes.borrow_mut();
+        let Some(frame) = sandboxes.last_mut() else {
+            return false;
+        };
+        if !frame.written.contains(&id) {
+            frame.written.push(id);
+            frame.restore.0.push(snapshot());
+        }
+        true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SignalExt, binding};
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_evaluation_restores_silently() {
+        let a: Binding<i32> = binding(1);
+        let b: Binding<i32> = binding(2);
+        let sum = crate::utils::add(a.clone(), b.clone()).map(|n: i32| n * 10);
+
+        let notified = Rc::new(Cell::new(0));
+        let _guard = {
+            let notified = notified.clone();
+            sum.watch(move |_| notified.set(notified.get() + 1))
+        };
+
+        let evaluation = Evaluation::new(sum.clone())
+            .fix(&a, 5)
+            .vary(&b, |rng: &mut Rng| {
+                i32::try_from(rng.range(0..3)).unwrap_or(0)
+            });
+        let results = evaluation.run(3, 50);
+        assert!(results.iter().all(|n| (50..=70).contains(n)));
+        assert!(results.windows(2).any(|pair| pair[0] != pair[1]));
+        assert_eq!(results, evaluation.run(3, 50));
+
+        assert_eq!(
+            Evaluation::new(sum.clone()).fix(&a, 4).fix(&a, 8).eval(),
+            100
+        );
+        assert_eq!((a.get(), b.get(), sum.get()), (1, 2, 30));
+        assert_eq!(notified.get(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_sandbox_rolls_back_silently() {
+        let a: Binding<i32> = binding(1);
+        let b: Binding<i32> = binding(2);
+        let sum = crate::utils::add(a.clone(), b.clone());
+
+        let notified = Rc::new(Cell::new(0));
+        let _guard = {
+            let notified = notified.clone();
+            sum.watch(move |_| notified.set(notified.get() + 1))
+        };
+
+        let (outer, inner) = sandbox(|| {
+            a.set(10);
+            a.increment(1);
+            let inner = sandbox(|| {
+                b.set(20);
+                a.set(0);
+                sum.get()
+            });
+            (sum.get(), inner)
+        });
+        assert_eq!((outer, inner), (13, 20));
+        assert_eq!((a.get(), b.get()), (1, 2));
+        assert_eq!(notified.get(), 0);
+
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            sandbox(|| {
+                a.set(5);
+                panic!("speculation failed");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(a.get(), 1);
+
+        a.set(3);
+        assert_eq!(notified.get(), 1);
+    }
+}
@@ -3,13 +3,40 @@
 //! This module contains:
 //! - `Zip`: A structure to combine two `Signal` instances into one computation
 //!   that produces a tuple of their results.
+//! - `zip_left` and `zip_right`: pair two signals like `Zip`, but only notify
+//!   when the chosen side changes.
 //! - `FlattenMap`: A trait for flattening and mapping nested tuple structures,
 //!   which simplifies working with multiple zipped computations.
+//! - `Part`, `unzip` and `nth!`: split a signal of a tuple into signals of its
+//!   elements, each notifying only when its own element changes.
+//! - `Signal` implementations for tuples of up to eight signals, which behave like
+//!   nested `Zip`s producing a flat tuple:
+//!
+//! ```rust
+//! use nami::{binding, Binding, Signal, SignalExt};
+//!
+//! let a: Binding<i32> = binding(1);
+//! let b: Binding<i32> = binding(2);
+//! let c: Binding<&'static str> = binding("sum");
+//!
+//! let label = (a.clone(), b.clone(), c).map(|(a, b, c)| format!("{c}: {}", a + b));
+//! assert_eq!(label.get(), "sum: 3");
+//!
+//! let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+//! let _guard = {
+//!     let seen = seen.clone();
+//!     label.watch(move |ctx| seen.borrow_mut().push(ctx.value))
+//! };
+//! a.set(10);
+//! b.set(5);
+//! assert_eq!(*seen.borrow(), ["sum: 12", "sum: 15"]);
+//! ```
 //!
 //! These utilities enable composition of reactive computations, making it easier
 //! to work with multiple interdependent values in a reactive context.
 
 use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
 
 use crate::{
     Signal,
@@ -19,7 +46,7 @@ use crate::{
 
 /// A structure that combines two `Signal` instances into a single computation
 /// that produces a tuple of their results.
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct Zip<A, B> {
     /// The first computation to be zipped.
     a: A,
@@ -119,6 +146,10 @@ impl<A: Signal, B: Signal> Signal for Zip<A, B> {
     /// This method sets up watchers for both `a` and `b` such that when either one
     /// changes, the watcher for the `Zip` is notified with the new tuple.
     ///
+    /// When a single change reaches both `a` and `b`, such as in
+    /// `zip(a.map(f), a.map(g))`, the watcher is only notified once, with both
+    /// new values.
+    ///
     /// # Parameters
     /// - `watcher`: The watcher to notify when either computation changes.
     ///
@@ -126,11 +157,15 @@ impl<A: Signal, B: Signal> Signal for Zip<A, B> {
     /// A `WatcherGuard` that, when dropped, will remove the watchers from both computations.
     fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
         let watcher = Rc::new(watcher);
+        let seen = Rc::new(Cell::new(0));
         let Self { a, b } = self;
         let guard_a = {
-            let watcher = watcher.clone();
+            let (watcher, seen) = (watcher.clone(), seen.clone());
             let b = b.clone();
             self.a.watch(move |context: Context<A::Output>| {
+                if !crate::signal::first_in_notification(&seen) {
+                    return;
+                }
                 let Context { value, metadata } = context;
                 let result = (value, b.get());
                 watcher(Context::new(result, metadata));
@@ -140,6 +175,9 @@ impl<A: Signal, B: Signal> Signal for Zip<A, B> {
         let guard_b = {
             let a = a.clone();
             self.b.watch(move |context: Context<B::Output>| {
+                if !crate::signal::first_in_notification(&seen) {
+                    return;
+                }
                 let Context { value, metadata } = context;
                 let result = (a.get(), value);
                 watcher(Context::new(result, metadata));
@@ -149,3 +187,443 @@ impl<A: Signal, B: Signal> Signal for Zip<A, B> {
         (guard_a, guard_b)
     }
 }
+
+/// A signal pairing two signals, notified only when the left one changes.
+///
+/// Created by [`zip_left`].
+#[derive(Debug, Clone)]
+pub struct ZipLeft<A, B> {
+    a: A,
+    b: B,
+}
+
+/// A signal pairing two signals, notified only when the right one changes.
+///
+/// Created by [`zip_right`].
+#[derive(Debug, Clone)]
+pub struct ZipRight<A, B> {
+    a: A,
+    b: B,
+}
+
+/// Combines two signals like [`zip`], but only notifies watchers when `a`
+/// changes, pairing its new value with the current value of `b`.
+///
+/// This suits a `b` that changes often but should not drive updates by
+/// itself, such as the current time.
+///
+/// Changes of `b` alone do not notify watchers, and therefore do not reach
+/// anything derived from the pair through them, although
+/// [`get`](Signal::get) always returns the current value of both sides.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::{binding, Binding, Signal, zip::zip_left};
+///
+/// let message: Binding<&'static str> = binding("saved");
+/// let now: Binding<u64> = binding(0u64);
+/// let stamped = zip_left(message.clone(), now.clone());
+///
+/// let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+/// let _guard = {
+///     let seen = seen.clone();
+///     stamped.watch(move |ctx| seen.borrow_mut().push(ctx.value))
+/// };
+/// now.set(1u64);
+/// now.set(2u64);
+/// message.set("synced");
+/// assert_eq!(*seen.borrow(), [("synced", 2)]);
+/// ```
+pub const fn zip_left<A, B>(a: A, b: B) -> ZipLeft<A, B>
+where
+    A: Signal,
+    B: Signal,
+{
+    ZipLeft { a, b }
+}
+
+/// Combines two signals like [`zip`], but only notifies watchers when `b`
+/// changes, pairing its new value with the current value of `a`.
+///
+/// Changes of `a` alone do not notify watchers. See [`zip_left`] for the
+/// mirrored case.
+pub const fn zip_right<A, B>(a: A, b: B) -> ZipRight<A, B>
+where
+    A: Signal,
+    B: Signal,
+{
+    ZipRight { a, b }
+}
+
+impl<A: Signal, B: Signal> Signal for ZipLeft<A, B> {
+    type Output = (A::Output, B::Output);
+    type Guard = A::Guard;
+
+    fn get(&self) -> Self::Output {
+        (self.a.get(), self.b.get())
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let b = self.b.clone();
+        self.a.watch(move |context: Context<A::Output>| {
+            let Context { value, metadata } = context;
+            watcher(Context::new((value, b.get()), metadata));
+        })
+    }
+}
+
+impl<A: Signal, B: Signal> Signal for ZipRight<A, B> {
+    type Output = (A::Output, B::Output);
+    type Guard = B::Guard;
+
+    fn get(&self) -> Self::Output {
+        (self.a.get(), self.b.get())
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let a = self.a.clone();
+        self.b.watch(move |context: Context<B::Output>| {
+            let Context { value, metadata } = context;
+            watcher(Context::new((a.get(), value), metadata));
+        })
+    }
+}
+
+/// A signal projecting one part of a source's output.
+///
+/// Watchers are only notified when the projected part actually changes, so a
+/// change affecting another part of the source does not reach them.
+pub struct Part<S: Signal, T> {
+    source: S,
+    project: fn(S::Output) -> T,
+}
+
+impl<S: Signal, T> Clone for Part<S, T> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            project: self.project,
+        }
+    }
+}
+
+impl<S: Signal, T> core::fmt::Debug for Part<S, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(core::any::type_name::<Self>())
+    }
+}
+
+impl<S: Signal, T> Part<S, T> {
+    /// Creates a signal of the part of `source` selected by `project`.
+    pub const fn new(source: S, project: fn(S::Output) -> T) -> Self {
+        Self { source, project }
+    }
+}
+
+impl<S, T> Signal for Part<S, T>
+where
+    S: Signal,
+    T: PartialEq + Clone + 'static,
+{
+    type Output = T;
+    type Guard = S::Guard;
+
+    fn get(&self) -> Self::Output {
+        (self.project)(self.source.get())
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        let project = self.project;
+        let last = RefCell::new(self.get());
+        self.source.watch(move |context: Context<S::Output>| {
+            let Context { value, metadata } = context;
+            let value = project(value);
+            if *last.borrow() != value {
+                last.replace(value.clone());
+                watcher(Context::new(value, metadata));
+            }
+        })
+    }
+}
+
+/// Splits a signal of a pair into a signal for each side.
+///
+/// Each side only notifies its watchers when its own element changes.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::{binding, Binding, Signal, zip::unzip};
+///
+/// let point: Binding<(i32, i32)> = binding((0, 0));
+/// let (x, y) = unzip(point.clone());
+///
+/// let _guard = y.watch(|_| panic!("y did not change"));
+/// point.set((1, 0));
+/// assert_eq!(x.get(), 1);
+/// ```
+pub fn unzip<S, A, B>(source: S) -> (Part<S, A>, Part<S, B>)
+where
+    S: Signal<Output = (A, B)>,
+    A: PartialEq + Clone + 'static,
+    B: PartialEq + Clone + 'static,
+{
+    (
+        Part::new(source.clone(), |(a, _)| a),
+        Part::new(source, |(_, b)| b),
+    )
+}
+
+/// Access to the leading elements of a tuple, used by [`SignalExt::first`] and
+/// [`SignalExt::second`].
+///
+/// [`SignalExt::first`]: crate::SignalExt::first
+/// [`SignalExt::second`]: crate::SignalExt::second
+pub trait Elements {
+    /// The type of the first element.
+    type First;
+    /// The type of the second element.
+    type Second;
+
+    /// Returns the first element.
+    fn first(self) -> Self::First;
+
+    /// Returns the second element.
+    fn second(self) -> Self::Second;
+}
+
+macro_rules! impl_elements {
+    ($first:ident, $second:ident $(, $rest:ident)*) => {
+        impl<$first, $second $(, $rest)*> Elements for ($first, $second $(, $rest)*) {
+            type First = $first;
+            type Second = $second;
+
+            fn first(self) -> Self::First {
+                self.0
+            }
+
+            fn second(self) -> Self::Second {
+                self.1
+            }
+        }
+    };
+}
+
+impl_elements!(A, B);
+impl_elements!(A, B, C);
+impl_elements!(A, B, C, D);
+impl_elements!(A, B, C, D, E);
+impl_elements!(A, B, C, D, E, F);
+impl_elements!(A, B, C, D, E, F, G);
+impl_elements!(A, B, C, D, E, F, G, H);
+
+/// Projects the element at a tuple index out of a signal of a tuple.
+///
+/// Expands to a [`Part`], so watchers are only notified when that element changes.
+///
+/// # Example
+///
+/// ```rust
+/// use nami::{binding, Binding, Signal, nth};
+///
+/// let row: Binding<(u32, String, bool)> = binding((1, "draft".to_string(), false));
+/// let published = nth!(row.clone(), 2);
+///
+/// let _guard = published.watch(|_| panic!("the flag did not change"));
+/// row.set((2, "edited".to_string(), false));
+/// assert!(!published.get());
+/// ```
+#[macro_export]
+macro_rules! nth {
+    ($signal:expr, $index:tt) => {
+        $crate::zip::Part::new($signal, |value| value.$index)
+    };
+}
+
+/// Implements `Signal` for the 1-tuple, the base case of the tuple impls below.
+impl<A: Signal> Signal for (A,) {
+    type Output = (A::Output,);
+    type Guard = A::Guard;
+
+    fn get(&self) -> Self::Output {
+        (self.0.get(),)
+    }
+
+    fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+        self.0.watch(move |context: Context<A::Output>| {
+            let Context { value, metadata } = context;
+            watcher(Context::new((value,), metadata));
+        })
+    }
+}
+
+/// Implements `Signal` for a tuple of signals by zipping its head with the tuple of
+/// the remaining elements, flattening the result.
+macro_rules! impl_tuple_signal {
+    ($first:ident, $($rest:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$first: Signal, $($rest: Signal),+> Signal for ($first, $($rest),+) {
+            type Output = ($first::Output, $($rest::Output),+);
+            type Guard = ($first::Guard, <($($rest,)+) as Signal>::Guard);
+
+            fn get(&self) -> Self::Output {
+                let ($first, $($rest),+) = self;
+                ($first.get(), $($rest.get()),+)
+            }
+
+            fn watch(&self, watcher: impl Fn(Context<Self::Output>) + 'static) -> Self::Guard {
+                let ($first, $($rest),+) = self;
+                Zip::new($first.clone(), ($($rest.clone(),)+)).watch(
+                    move |context: Context<($first::Output, ($($rest::Output,)+))>| {
+                        let Context { value: ($first, ($($rest,)+)), metadata } = context;
+                        watcher(Context::new(($first, $($rest),+), metadata));
+                    },
+                )
+            }
+        }
+    };
+}
+
+macro_rules! impl_tuple_signals {
+    ($first:ident) => {};
+    ($first:ident, $($rest:ident),+) => {
+        impl_tuple_signal!($first, $($rest),+);
+        impl_tuple_signals!($($rest),+);
+    };
+}
+
+impl_tuple_signals!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Binding, SignalExt, binding};
+    use alloc::vec::Vec;
+
+    /// Checks that a tuple of bindings reads all of them, and notifies its
+    /// watchers with the new values whenever any of them changes.
+    macro_rules! check_tuple {
+        ($($index:tt),+) => {{
+            let bindings = ($(binding::<i32>($index),)+);
+            let tuple = ($(bindings.$index.clone(),)+);
+            assert_eq!(tuple.get(), ($($index,)+));
+            let seen = Rc::new(RefCell::new(Vec::new()));
+            let _guard = {
+                let seen = seen.clone();
+                tuple.watch(move |ctx| seen.borrow_mut().push(ctx.value))
+            };
+
+            $(bindings.$index.set($index + 10);)+
+            assert_eq!(seen.borrow().len(), [$($index),+].len());
+            assert_eq!(seen.borrow().last(), Some(&($($index + 10,)+)));
+            assert_eq!(tuple.get(), ($($index + 10,)+));
+        }};
+    }
+
+    #[test]
+    fn test_tuples_of_two_to_eight_signals() {
+        check_tuple!(0, 1);
+        check_tuple!(0, 1, 2);
+        check_tuple!(0, 1, 2, 3);
+        check_tuple!(0, 1, 2, 3, 4);
+        check_tuple!(0, 1, 2, 3, 4, 5);
+        check_tuple!(0, 1, 2, 3, 4, 5, 6);
+        check_tuple!(0, 1, 2, 3, 4, 5, 6, 7);
+    }
+
+    #[test]
+    fn test_zip_left_is_driven_by_the_left_side_only() {
+        let value: Binding<i32> = binding(1);
+        let now: Binding<i32> = binding(0);
+        let pair = zip_left(value.clone(), now.clone());
+        let sum = pair.clone().map(|(value, now)| value + now);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let guard = {
+            let seen = seen.clone();
+            sum.watch(move |ctx| seen.borrow_mut().push(ctx.value))
+        };
+
+        now.set(5);
+        assert!(seen.borrow().is_empty());
+        assert_eq!((pair.get(), sum.get()), ((1, 5), 6));
+        value.set(2);
+        assert_eq!(*seen.borrow(), [7]);
+        assert_eq!(now.watcher_count(), Some(0));
+        drop(guard);
+        assert_eq!(value.watcher_count(), Some(0));
+    }
+
+    #[test]
+    fn test_zip_right_is_driven_by_the_right_side_only() {
+        let now: Binding<i32> = binding(0);
+        let value: Binding<i32> = binding(1);
+        let pair = zip_right(now.clone(), value.clone());
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let guard = {
+            let seen = seen.clone();
+            pair.watch(move |ctx| seen.borrow_mut().push(ctx.value))
+        };
+
+        now.set(5);
+        value.set(2);
+        assert_eq!(*seen.borrow(), [(5, 2)]);
+        assert_eq!(now.watcher_count(), Some(0));
+        drop(guard);
+        assert_eq!(value.watcher_count(), Some(0));
+    }
+
+    /// The values notified to a watcher, in order.
+    type Seen<T> = Rc<RefCell<Vec<T>>>;
+
+    /// Records the values notified to the watchers of `signal`.
+    fn record<S: Signal>(signal: &S) -> (Seen<S::Output>, S::Guard) {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let guard = {
+            let seen = seen.clone();
+            signal.watch(move |ctx| seen.borrow_mut().push(ctx.value))
+        };
+        (seen, guard)
+    }
+
+    #[test]
+    fn test_unzip_sides_notify_only_when_they_change() {
+        let pair: Binding<(i32, &str)> = binding((1, "a"));
+        let (left, right) = pair.clone().unzip();
+        let (lefts, _left_guard) = record(&left);
+        let (rights, _right_guard) = record(&right);
+
+        pair.set((2, "a"));
+        pair.set((2, "b"));
+        pair.set((2, "b"));
+        pair.set((3, "c"));
+        assert_eq!(*lefts.borrow(), [2, 3]);
+        assert_eq!(*rights.borrow(), ["b", "c"]);
+        assert_eq!((left.get(), right.get()), (3, "c"));
+    }
+
+    #[test]
+    fn test_projections_notify_only_when_their_element_changes() {
+        let row: Binding<(u32, &str, bool)> = binding((1, "draft", false));
+        let (id, title, published) = (
+            row.clone().first(),
+            row.clone().second(),
+            nth!(row.clone(), 2),
+        );
+        let (ids, _id_guard) = record(&id);
+        let (titles, _title_guard) = record(&title);
+        let (flags, _flag_guard) = record(&published);
+
+        row.set((1, "edited", false));
+        row.set((2, "edited", false));
+        row.set((2, "edited", true));
+        row.set((2, "edited", true));
+        assert_eq!(*ids.borrow(), [2]);
+        assert_eq!(*titles.borrow(), ["edited"]);
+        assert_eq!(*flags.borrow(), [true]);
+        assert_eq!(
+            (id.get(), title.get(), published.get()),
+            (2, "edited", true)
+        );
+    }
+}